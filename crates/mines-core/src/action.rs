@@ -0,0 +1,136 @@
+#![warn(missing_docs)]
+//! [Re-exported] The moves a user can make, and a log of them.
+//!
+//! Every change a user makes to a `Board` is one of the `Action`s, and
+//! can be applied with `Board::apply`. A `Board` can also keep a log of
+//! every `Action` that worked, stamped with its clock, as the basis for
+//! replays, post-game review, and keeping boards in sync over a
+//! network.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{Action, Board};
+//!
+//! let mut b = Board::from_layout("1*1\n111\n...").unwrap().with_action_log();
+//! b.apply(Action::Reveal(8)).unwrap();
+//! b.apply(Action::Flag(1)).unwrap();
+//! // Moves that fail aren't logged
+//! b.apply(Action::Reveal(1)).unwrap_err();
+//!
+//! let actions: Vec<Action> = b.action_log().unwrap().iter().map(|a| a.action).collect();
+//! assert_eq!(actions, vec![Action::Reveal(8), Action::Flag(1)]);
+//! ```
+
+use std::fmt;
+
+use board::Board;
+use clock::Timestamp;
+
+/// A move a user can make on a `Board`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Action {
+    /// Reveals the `Tile` at the index. See `Board::reveal_tile`.
+    Reveal(usize),
+    /// Flags or unflags the `Tile` at the index. See
+    /// `Board::flag_tile`.
+    Flag(usize),
+    /// Reveals around the number at the index. See
+    /// `Board::chord_tile`.
+    Chord(usize),
+}
+
+impl Action {
+    /// Returns the index of the `Tile` the action is on.
+    pub fn index(&self) -> usize {
+        match *self {
+            Action::Reveal(i) | Action::Flag(i) | Action::Chord(i) => i,
+        }
+    }
+}
+
+/// Writes `reveal 4`, `flag 4`, or `chord 4`.
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Action::Reveal(i) => write!(f, "reveal {}", i),
+            Action::Flag(i) => write!(f, "flag {}", i),
+            Action::Chord(i) => write!(f, "chord {}", i),
+        }
+    }
+}
+
+/// An `Action` that was applied to a `Board`, and when.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LoggedAction {
+    /// When the action was applied, according to the `Board`'s clock.
+    pub at: Timestamp,
+    /// What was done.
+    pub action: Action,
+}
+
+impl Board {
+    /// Turns on the action log, and returns the `Board`. Anything
+    /// logged before is cleared.
+    pub fn with_action_log(mut self) -> Board {
+        self.log = Some(Vec::new());
+        self
+    }
+
+    /// Returns every `Action` applied since the log was turned on,
+    /// oldest first, or `None` if it is off.
+    pub fn action_log(&self) -> Option<&[LoggedAction]> {
+        self.log.as_deref()
+    }
+
+    /// Applies `action` to the `Board`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// the method for the action. It is safe to discard this error; it
+    /// is only for the programmer.
+    pub fn apply(&mut self, action: Action) -> Result<(), &'static str> {
+        match action {
+            Action::Reveal(i) => self.reveal_tile(i),
+            Action::Flag(i) => self.flag_tile(i).map_err(Into::into),
+            Action::Chord(i) => self.chord_tile(i),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use clock::{GameClock, MockClock};
+
+    #[test]
+    fn test_action_log() {
+        let clock = MockClock::new();
+        let mut b = Board::restore("1*1\n111\n...", "1??\n???\n???")
+            .unwrap()
+            .with_clock(GameClock::new(clock.clone()))
+            .with_action_log();
+
+        let actions = [Action::Flag(1), Action::Chord(0), Action::Chord(0), Action::Reveal(8)];
+        for action in &actions {
+            clock.advance(Duration::from_millis(250));
+            let _ = b.apply(*action);
+        }
+
+        // The second chord reveals nothing new, but it still worked, so
+        // it is logged
+        let log = b.action_log().unwrap();
+        let expected: Vec<(u64, Action)> = vec![(250, Action::Flag(1)),
+                                                (500, Action::Chord(0)),
+                                                (750, Action::Chord(0)),
+                                                (1000, Action::Reveal(8))];
+        let found: Vec<(u64, Action)> = log.iter().map(|a| (a.at.as_millis(), a.action)).collect();
+        assert_eq!(found, expected);
+        assert_eq!(format!("{}", log[1].action), "chord 0");
+        assert!(Board::default().action_log().is_none());
+    }
+}
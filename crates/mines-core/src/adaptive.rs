@@ -0,0 +1,341 @@
+#![warn(missing_docs)]
+//! [Re-exported] Dynamic difficulty: choosing the next `Board` from how
+//! recent games went.
+//!
+//! An `AdaptiveDifficulty` keeps the last few games a player finished,
+//! and recommends the settings for the next one according to an
+//! `AdaptivePolicy`. The size of the `Board` stays the same as the last
+//! game's; only the number of bombs changes, by one `step` of density
+//! (and at least one bomb) at a time:
+//!
+//! * Harder, if the player has been winning more often than the
+//!   target, without leaning on guesses, and (if there is a target
+//!   time) fast enough.
+//! * Easier, if the player has been losing more often than the target,
+//!   or guessing too much.
+//! * The same, otherwise, or until there are enough games to judge by.
+//!
+//! Nothing is applied automatically; front-ends decide whether to take
+//! the `Recommendation`.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//! use mines_core::{AdaptiveDifficulty, AdaptivePolicy, Adjustment, PlayedGame};
+//!
+//! let mut adaptive = AdaptiveDifficulty::new(AdaptivePolicy::default());
+//! for _ in 0..5 {
+//!     adaptive.record(PlayedGame {
+//!         width: 9,
+//!         height: 9,
+//!         num_mines: 10,
+//!         won: true,
+//!         duration: Duration::from_secs(30),
+//!         guesses: 0,
+//!     });
+//! }
+//! let next = adaptive.recommend().unwrap();
+//! assert_eq!(next.adjustment, Adjustment::Harder);
+//! assert_eq!((next.width, next.height, next.num_mines), (9, 9, 11));
+//! ```
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use builder::{BoardBuilder, FirstClick};
+use stats::{GameRecord, Outcome};
+
+/// How an `AdaptiveDifficulty` judges recent games.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdaptivePolicy {
+    /// How many of the most recent games to judge by.
+    pub window: usize,
+    /// The fewest games to judge by before changing anything.
+    pub min_games: usize,
+    /// The fraction of games the player should win, from 0.0 to 1.0.
+    pub target_win_rate: f64,
+    /// How far the win rate can stray from the target before anything
+    /// changes.
+    pub tolerance: f64,
+    /// The most guesses per game, on average, that still counts as
+    /// playing well.
+    pub max_guesses: f64,
+    /// How quickly won games should be finished, on average, before
+    /// making things harder. `None` ignores time.
+    pub target_time: Option<Duration>,
+    /// How much the density of bombs changes at a time, as a fraction
+    /// of the `Tiles`.
+    pub step: f64,
+    /// The lowest density of bombs to recommend.
+    pub min_density: f64,
+    /// The highest density of bombs to recommend.
+    pub max_density: f64,
+}
+
+impl Default for AdaptivePolicy {
+    fn default() -> AdaptivePolicy {
+        AdaptivePolicy {
+            window: 10,
+            min_games: 3,
+            target_win_rate: 0.5,
+            tolerance: 0.15,
+            max_guesses: 1.0,
+            target_time: None,
+            step: 0.01,
+            // Between a little under beginner and a little over expert
+            min_density: 0.08,
+            max_density: 0.25,
+        }
+    }
+}
+
+/// A finished game, as far as an `AdaptiveDifficulty` is concerned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlayedGame {
+    /// The width of the `Board`.
+    pub width: usize,
+    /// The height of the `Board`.
+    pub height: usize,
+    /// The number of bombs on the `Board`.
+    pub num_mines: usize,
+    /// Whether the game was won.
+    pub won: bool,
+    /// How long the game took.
+    pub duration: Duration,
+    /// How many times the player had to guess, if the front-end knows.
+    pub guesses: usize,
+}
+
+impl<'a> From<&'a GameRecord> for PlayedGame {
+    /// Takes a game from a `GameRecord`, which doesn't know about
+    /// guesses.
+    fn from(record: &'a GameRecord) -> PlayedGame {
+        PlayedGame {
+            width: record.width,
+            height: record.height,
+            num_mines: record.num_mines,
+            won: record.outcome == Outcome::Won,
+            duration: record.duration,
+            guesses: 0,
+        }
+    }
+}
+
+/// Which way a `Recommendation` moves the difficulty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Adjustment {
+    /// More bombs than last time.
+    Harder,
+    /// Fewer bombs than last time.
+    Easier,
+    /// The same as last time.
+    Same,
+}
+
+/// The settings an `AdaptiveDifficulty` recommends for the next game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Recommendation {
+    /// The width of the next `Board`.
+    pub width: usize,
+    /// The height of the next `Board`.
+    pub height: usize,
+    /// The number of bombs on the next `Board`.
+    pub num_mines: usize,
+    /// How this compares with the last game.
+    pub adjustment: Adjustment,
+}
+
+impl Recommendation {
+    /// Returns a `BoardBuilder` with the recommended settings.
+    pub fn builder(&self) -> BoardBuilder {
+        BoardBuilder::new().width(self.width).height(self.height).mines(self.num_mines)
+    }
+}
+
+/// Recommends the next game's settings from how recent games went.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdaptiveDifficulty {
+    /// How recent games are judged.
+    pub policy: AdaptivePolicy,
+    recent: VecDeque<PlayedGame>,
+}
+
+impl AdaptiveDifficulty {
+    /// Creates an engine with no games played yet.
+    pub fn new(policy: AdaptivePolicy) -> AdaptiveDifficulty {
+        AdaptiveDifficulty {
+            policy,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Adds a finished game, forgetting the oldest one if there are more
+    /// than the policy's window.
+    pub fn record(&mut self, game: PlayedGame) {
+        self.recent.push_back(game);
+        while self.recent.len() > self.policy.window {
+            self.recent.pop_front();
+        }
+    }
+
+    /// Returns the games being judged by, oldest first.
+    pub fn recent(&self) -> &VecDeque<PlayedGame> {
+        &self.recent
+    }
+
+    /// Returns the recommended settings for the next game, or `None` if
+    /// no games have been recorded yet.
+    pub fn recommend(&self) -> Option<Recommendation> {
+        let last = *self.recent.back()?;
+        let tiles = last.width * last.height;
+        let step = (self.policy.step * tiles as f64).round() as usize;
+        let lowest = (self.policy.min_density * tiles as f64).ceil() as usize;
+        let highest = ((self.policy.max_density * tiles as f64).floor() as usize)
+            .min(FirstClick::default().max_mines(tiles));
+
+        // Always move by at least one bomb, but never past the limits
+        let num_mines = match self.judge() {
+            Adjustment::Harder => (last.num_mines + step.max(1)).min(highest).max(last.num_mines),
+            Adjustment::Easier => {
+                last.num_mines.saturating_sub(step.max(1)).max(lowest).min(last.num_mines)
+            }
+            Adjustment::Same => last.num_mines,
+        };
+        let adjustment = match num_mines {
+            n if n > last.num_mines => Adjustment::Harder,
+            n if n < last.num_mines => Adjustment::Easier,
+            _ => Adjustment::Same,
+        };
+        Some(Recommendation {
+            width: last.width,
+            height: last.height,
+            num_mines,
+            adjustment,
+        })
+    }
+
+    fn judge(&self) -> Adjustment {
+        let games = self.recent.len();
+        if games == 0 || games < self.policy.min_games {
+            return Adjustment::Same;
+        }
+        let wins: Vec<&PlayedGame> = self.recent.iter().filter(|g| g.won).collect();
+        let win_rate = wins.len() as f64 / games as f64;
+        let guesses = self.recent.iter().map(|g| g.guesses).sum::<usize>() as f64 / games as f64;
+        let fast_enough = match self.policy.target_time {
+            Some(target) if !wins.is_empty() => {
+                wins.iter().map(|g| g.duration).sum::<Duration>() / wins.len() as u32 <= target
+            }
+            _ => true,
+        };
+
+        if win_rate < self.policy.target_win_rate - self.policy.tolerance ||
+           guesses > self.policy.max_guesses {
+            Adjustment::Easier
+        } else if win_rate > self.policy.target_win_rate + self.policy.tolerance && fast_enough {
+            Adjustment::Harder
+        } else {
+            Adjustment::Same
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(num_mines: usize, won: bool, seconds: u64, guesses: usize) -> PlayedGame {
+        PlayedGame {
+            width: 16,
+            height: 16,
+            num_mines,
+            won,
+            duration: Duration::from_secs(seconds),
+            guesses,
+        }
+    }
+
+    #[test]
+    fn test_recommend() {
+        struct Test {
+            policy: AdaptivePolicy,
+            games: Vec<PlayedGame>,
+            expected: Option<(usize, Adjustment)>,
+        }
+
+        let timed = AdaptivePolicy {
+            target_time: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+        let tests = [Test {
+                         policy: AdaptivePolicy::default(),
+                         games: vec![],
+                         expected: None,
+                     },
+                     Test {
+                         // Too few games to judge
+                         policy: AdaptivePolicy::default(),
+                         games: vec![game(40, true, 50, 0), game(40, true, 50, 0)],
+                         expected: Some((40, Adjustment::Same)),
+                     },
+                     Test {
+                         policy: AdaptivePolicy::default(),
+                         games: vec![game(40, true, 50, 0); 4],
+                         expected: Some((43, Adjustment::Harder)),
+                     },
+                     Test {
+                         policy: AdaptivePolicy::default(),
+                         games: vec![game(40, false, 50, 0); 4],
+                         expected: Some((37, Adjustment::Easier)),
+                     },
+                     Test {
+                         // Winning, but by guessing
+                         policy: AdaptivePolicy::default(),
+                         games: vec![game(40, true, 50, 3); 4],
+                         expected: Some((37, Adjustment::Easier)),
+                     },
+                     Test {
+                         // Winning, but too slowly
+                         policy: timed.clone(),
+                         games: vec![game(40, true, 90, 0); 4],
+                         expected: Some((40, Adjustment::Same)),
+                     },
+                     Test {
+                         policy: timed,
+                         games: vec![game(40, true, 30, 0); 4],
+                         expected: Some((43, Adjustment::Harder)),
+                     },
+                     Test {
+                         // Already as dense as the policy allows
+                         policy: AdaptivePolicy::default(),
+                         games: vec![game(64, true, 50, 0); 4],
+                         expected: Some((64, Adjustment::Same)),
+                     }];
+
+        for test in &tests {
+            let mut adaptive = AdaptiveDifficulty::new(test.policy.clone());
+            for g in &test.games {
+                adaptive.record(*g);
+            }
+            let found = adaptive.recommend().map(|r| (r.num_mines, r.adjustment));
+            assert_eq!(found, test.expected);
+        }
+    }
+
+    #[test]
+    fn test_window() {
+        let mut adaptive = AdaptiveDifficulty::new(AdaptivePolicy {
+            window: 3,
+            ..Default::default()
+        });
+        for _ in 0..5 {
+            adaptive.record(game(40, false, 50, 0));
+        }
+        for _ in 0..3 {
+            adaptive.record(game(40, true, 50, 0));
+        }
+        assert_eq!(adaptive.recent().len(), 3);
+        assert_eq!(adaptive.recommend().unwrap().adjustment, Adjustment::Harder);
+    }
+}
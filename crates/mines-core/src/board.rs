@@ -0,0 +1,2359 @@
+#![warn(missing_docs)]
+/*! [Re-exported] Data structures and methods for creating and
+maniuplating Minesweeper boards.
+
+# Examples
+
+Instantiating a new `Board`:
+
+```
+use mines_core::Board;
+
+// A default 8x8 board
+let mut b: Board = Default::default();
+// Reveal the 8th tile using a linear index
+let result = b.reveal_tile(7);
+// Ensure the operation was a success
+assert_eq!(Ok(()), result);
+// Get the index of the tile in the bottom-right corner
+let index = b.linear_coords((7, 7));
+b.reveal_tile(index);
+// Print what the user should see to stdout
+println!("{}", b);
+```
+
+Example output:
+
+```text
+?????1.. <-- The revealed tile
+?????1..
+?????21.
+??????21
+????????
+????????
+????????
+???????* <-- Other revealed tile
+```
+
+Debug-printing a custom-sized `Board`:
+
+```
+# use mines_core::Board;
+// A 9x9 board with 20 mines
+let mut b: Board = Board::new(9, 9, 20);
+// The board will NOT be generated until
+// an initial tile is revealed
+b.reveal_tile(0);
+// See the debug output
+println!("{:?}", b);
+```
+
+Example output:
+
+```text
+.1*23*311
+.12*4*3*2
+.135*322*
+.1***2.11
+.12322121
+111111*3*
+*12*3214*
+112**223*
+..123*2*2
+```
+*/
+
+
+use std::default::Default;
+use std::error::Error;
+use std::fmt;
+use std::ops::Index;
+use std::collections::VecDeque;
+use std::time::Duration;
+use std::sync::Arc;
+
+use self::rand::Rng;
+
+use action::{Action, LoggedAction};
+use builder::{FirstClick, Pattern, Placement};
+use generate::{Generator, UniformGenerator};
+use clock::{GameClock, Timestamp};
+use observe::{BoardEvent, BoardListener, ListenerId, Listeners};
+use orientation::Orientation;
+use render::{DisplayOptions, GridFormat, NumberHook};
+use solver;
+use stats::Clicks;
+use tile::{Tile, TileState};
+use topology::{Kernel, Topology};
+use undo::UndoHistory;
+use util;
+
+extern crate rand;
+
+// How many Tiles a Board needs before its numbers are worth counting in
+// parallel
+#[cfg(feature = "rayon")]
+const PARALLEL_TILES: usize = 1 << 20;
+
+/// How a game on a `Board` is going.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GameState {
+    /// Nothing has been revealed yet.
+    Ready,
+    /// Some `Tiles` have been revealed, but not all of the safe ones.
+    Playing,
+    /// Every safe `Tile` has been revealed.
+    Won,
+    /// A bomb has been revealed.
+    Lost,
+}
+
+/// The ways flagging a `Tile` can fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FlagError {
+    /// The `Board` hasn't been generated yet.
+    NotGenerated,
+    /// The `Tile` can't be flagged, such as if it is already revealed.
+    NotFlaggable,
+    /// The flag limit is on, and every mine already has a flag.
+    LimitReached {
+        /// The most flags allowed: the number of mines.
+        limit: usize,
+    },
+}
+
+impl FlagError {
+    fn message(&self) -> &'static str {
+        match *self {
+            FlagError::NotGenerated => "Cannot flag Tile: The Board has not been generated yet.",
+            FlagError::NotFlaggable => "Tried to flag a Tile that can't be flagged!",
+            FlagError::LimitReached { .. } => "Tried to place more flags than there are mines!",
+        }
+    }
+}
+
+impl fmt::Display for FlagError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl Error for FlagError {}
+
+impl From<FlagError> for &'static str {
+    fn from(err: FlagError) -> &'static str {
+        err.message()
+    }
+}
+
+/// Representation of a standard Minesweeper board.
+///
+/// All mutation goes through `&mut self`, so a `Board` holds no
+/// interior mutability and can be shared between threads like any
+/// other plain value.
+///
+/// ```
+/// use std::sync::{Arc, RwLock};
+/// use std::thread;
+/// use mines_core::Board;
+///
+/// let board = Arc::new(RwLock::new(Board::new(9, 9, 10)));
+/// let player = Arc::clone(&board);
+/// thread::spawn(move || player.write().unwrap().reveal_tile(40).unwrap())
+///     .join()
+///     .unwrap();
+/// assert!(board.read().unwrap().is_generated());
+/// ```
+///
+/// With the `serde` feature enabled, a `Board` can be serialized and
+/// deserialized, including whether it has been generated yet. Its
+/// clock is not part of the serialized state: a deserialized `Board`
+/// starts with a fresh `GameClock`, and its timer restarts on the
+/// next reveal.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Board {
+    /// The total number of bombs (revealed or not) on the
+    /// `Board`, anti-mines included.
+    pub num_mines: usize,
+    /// Keeps track of whether the `Board` has been generated.
+    pub(crate) was_generated: bool,
+    /// The horizontal width.
+    pub width: usize,
+    /// The vertical height.
+    pub height: usize,
+    /// Collection of `Tiles` that make up the `Board`.
+    pub tiles: Vec<Tile>,
+    /// The clock used to timestamp everything that happens on the
+    /// `Board`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    clock: GameClock,
+    /// When the first `Tile` was revealed, according to `clock`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) started_at: Option<Timestamp>,
+    /// The seed used to place the bombs, if any.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) seed: Option<u64>,
+    /// Whether the `Board` is being shown as portrait or landscape.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) orientation: Orientation,
+    /// What is guaranteed about the first `Tile` revealed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) first_click: FirstClick,
+    /// Whether to refuse more flags than there are mines.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) flag_limit: bool,
+    /// How many bombs can be revealed without losing.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) extra_lives: usize,
+    /// The rare patterns the bombs must include.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) patterns: Vec<Pattern>,
+    /// Where the bombs may, must, and mustn't go.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) placement: Option<Placement>,
+    /// Which `Tiles` are adjacent to each other.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) topology: Topology,
+    /// The offsets of adjacent `Tiles`, in place of the `Topology`'s.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) kernel: Option<Kernel>,
+    /// Which `Tiles` are in play, if not all of them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) mask: Option<Vec<bool>>,
+    /// How many of the bombs are anti-mines.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) num_anti_mines: usize,
+    /// The moves that can be undone, if undo is on.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) undo: Option<UndoHistory>,
+    /// Every `Action` applied, if the log is on.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) log: Option<Vec<LoggedAction>>,
+    /// How many reveals, flags, and chords have worked so far.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) moves: usize,
+    /// Every click so far, including the ones that did nothing.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) clicks: Clicks,
+    /// Whatever is told about changes to the `Board`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) listeners: Listeners,
+    /// What decides the numbers shown on revealed `Tiles`, if not the
+    /// true numbers.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) number_hook: Option<Arc<dyn NumberHook>>,
+    /// What places the bombs, if not the `UniformGenerator`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) generator: Option<Arc<dyn Generator>>,
+}
+
+impl Default for Board {
+    fn default() -> Board {
+        const SIZE: usize = 8;
+
+        Board {
+            num_mines: 10,
+            was_generated: false,
+            width: SIZE,
+            height: SIZE,
+            tiles: vec![Tile::default(); SIZE * SIZE],
+            clock: GameClock::default(),
+            started_at: None,
+            seed: None,
+            orientation: Orientation::Landscape,
+            first_click: FirstClick::default(),
+            patterns: Vec::new(),
+            placement: None,
+            topology: Topology::Square,
+            kernel: None,
+            mask: None,
+            num_anti_mines: 0,
+            flag_limit: false,
+            extra_lives: 0,
+            undo: None,
+            log: None,
+            moves: 0,
+            clicks: Clicks::default(),
+            listeners: Listeners::default(),
+            number_hook: None,
+            generator: None,
+        }
+    }
+}
+
+impl fmt::Debug for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cells = self.cells(|t| format!("{:?}", t), " ");
+        write!(f, "{}", GridFormat::for_cells(&cells).render(&cells, self.width))
+    }
+}
+
+// The indices returned by `Board::neighbors`, kept in an array, or in
+// a Vec for Kernels too big for it
+#[derive(Default)]
+struct Neighbors {
+    inline: [usize; 8],
+    len: usize,
+    next: usize,
+    spilled: Option<::std::vec::IntoIter<usize>>,
+}
+
+impl Neighbors {
+    fn push(&mut self, index: usize) {
+        self.inline[self.len] = index;
+        self.len += 1;
+    }
+
+    // Sorts the indices, drops repeats, and keeps only those in play on
+    // a Board with `mask`, or none if the Tile at `index` isn't
+    fn finish(&mut self, mask: Option<&[bool]>, index: usize) {
+        let in_play = |i: usize| mask.is_none_or(|mask| mask[i]);
+        if let Some(ref mut spilled) = self.spilled {
+            let indices: Vec<usize> = spilled.filter(|&i| in_play(i) && in_play(index)).collect();
+            *spilled = indices.into_iter();
+            return;
+        }
+        if !in_play(index) {
+            self.len = 0;
+            return;
+        }
+        self.inline[..self.len].sort_unstable();
+        let mut kept = 0;
+        for k in 0..self.len {
+            let i = self.inline[k];
+            if in_play(i) && (kept == 0 || self.inline[kept - 1] != i) {
+                self.inline[kept] = i;
+                kept += 1;
+            }
+        }
+        self.len = kept;
+    }
+}
+
+impl Iterator for Neighbors {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if let Some(ref mut spilled) = self.spilled {
+            return spilled.next();
+        }
+        if self.next == self.len {
+            return None;
+        }
+        self.next += 1;
+        Some(self.inline[self.next - 1])
+    }
+}
+
+/// An iterator over the `Tiles` of a `Board`, with their indices and
+/// (x, y) coordinates. See `Board::iter_tiles`.
+#[derive(Clone, Debug)]
+pub struct TileIter<'a> {
+    tiles: ::std::iter::Enumerate<::std::slice::Iter<'a, Tile>>,
+    width: usize,
+}
+
+impl<'a> Iterator for TileIter<'a> {
+    type Item = (usize, (usize, usize), &'a Tile);
+
+    fn next(&mut self) -> Option<(usize, (usize, usize), &'a Tile)> {
+        self.tiles.next().map(|(i, tile)| (i, cartesian_coords(i, self.width), tile))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.tiles.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for TileIter<'a> {}
+
+impl<'a> IntoIterator for &'a Board {
+    type Item = (usize, (usize, usize), &'a Tile);
+    type IntoIter = TileIter<'a>;
+
+    fn into_iter(self) -> TileIter<'a> {
+        self.iter_tiles()
+    }
+}
+
+/// Looks up a `Tile` by its index, as `tiles[index]` does.
+///
+/// # Examples
+///
+/// ```
+/// use mines_core::Board;
+///
+/// let b = Board::from_layout("1*1\n111\n...").unwrap();
+/// assert!(b[1].is_bomb);
+/// assert_eq!(b[(1, 1)].adjacent_bombs, 1);
+/// ```
+///
+/// # Panics
+///
+/// Indexing will panic if `index` is not within the bounds of the grid.
+impl Index<usize> for Board {
+    type Output = Tile;
+
+    fn index(&self, index: usize) -> &Tile {
+        &self.tiles[index]
+    }
+}
+
+/// Looks up a `Tile` by its (x, y) coordinates. See `linear_coords`.
+///
+/// # Panics
+///
+/// Indexing will panic if the coordinates are not within the bounds of
+/// the grid.
+impl Index<(usize, usize)> for Board {
+    type Output = Tile;
+
+    fn index(&self, p: (usize, usize)) -> &Tile {
+        &self.tiles[self.linear_coords(p)]
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(&DisplayOptions::default()))
+    }
+}
+
+impl Board {
+    /// Creates a new `Board`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the programmer attempts to make a
+    /// `Board` smaller than 3x3, or if there are too many mines to
+    /// make a functioning `Board`. There must be fewer than (`width`
+    /// * `height` - 9) mines.
+    pub fn new(width: usize, height: usize, num_mines: usize) -> Board {
+        if width * height <= 9 {
+            panic!("Tried to make too small of a board!");
+        }
+        if num_mines >= (width * height) - 9 {
+            panic!("Too many mines to make a functioning board! Mines passed: {}, Maximum mines: \
+                    {}",
+                   num_mines,
+                   (width * height) - 10);
+        }
+
+        Board::unchecked(width, height, num_mines)
+    }
+
+    // Creates a new, ungenerated Board without checking the arguments
+    pub(crate) fn unchecked(width: usize, height: usize, num_mines: usize) -> Board {
+        Board {
+            num_mines,
+            was_generated: false,
+            width,
+            height,
+            tiles: vec![Tile::default(); width * height],
+            clock: GameClock::default(),
+            started_at: None,
+            seed: None,
+            orientation: Orientation::for_size(width, height),
+            first_click: FirstClick::default(),
+            patterns: Vec::new(),
+            placement: None,
+            topology: Topology::Square,
+            kernel: None,
+            mask: None,
+            num_anti_mines: 0,
+            flag_limit: false,
+            extra_lives: 0,
+            undo: None,
+            log: None,
+            moves: 0,
+            clicks: Clicks::default(),
+            listeners: Listeners::default(),
+            number_hook: None,
+            generator: None,
+        }
+    }
+
+    /// Creates an already generated `Board` from a complete set of
+    /// `Tiles`, counting its mines and filling in the adjacent bomb
+    /// counts from where the bombs are.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there isn't exactly one `Tile`, and
+    /// one entry of `mask` if there is one, for every position on a
+    /// `width`x`height` grid.
+    pub fn from_tiles(width: usize,
+                      height: usize,
+                      topology: Topology,
+                      kernel: Option<Kernel>,
+                      mask: Option<Vec<bool>>,
+                      tiles: Vec<Tile>)
+                      -> Board {
+        let length = width * height;
+        if tiles.len() != length || mask.as_ref().is_some_and(|mask| mask.len() != length) {
+            panic!("Tried to make a Board from Tiles that don't fill the grid! Tiles passed: {}, \
+                    Grid length: {}",
+                   tiles.len(),
+                   length);
+        }
+        let mut board = Board {
+            num_mines: tiles.iter().filter(|t| t.is_bomb).count(),
+            num_anti_mines: tiles.iter().filter(|t| t.is_anti_mine).count(),
+            was_generated: true,
+            width,
+            height,
+            tiles,
+            clock: GameClock::default(),
+            started_at: None,
+            seed: None,
+            orientation: Orientation::for_size(width, height),
+            first_click: FirstClick::default(),
+            patterns: Vec::new(),
+            placement: None,
+            topology,
+            kernel,
+            mask,
+            flag_limit: false,
+            extra_lives: 0,
+            undo: None,
+            log: None,
+            moves: 0,
+            clicks: Clicks::default(),
+            listeners: Listeners::default(),
+            number_hook: None,
+            generator: None,
+        };
+        board.count_adjacent_bombs();
+        board
+    }
+
+    /// Creates a generated `Board` from `tiles`, as `Board::from_tiles`
+    /// does, with the same size, `Topology`, `Kernel`, mask, seed, and
+    /// first-click rule as this one.
+    pub fn with_tiles(&self, tiles: Vec<Tile>) -> Board {
+        let mut board = Board::from_tiles(self.width,
+                                          self.height,
+                                          self.topology,
+                                          self.kernel.clone(),
+                                          self.mask.clone(),
+                                          tiles);
+        board.seed = self.seed;
+        board.first_click = self.first_click;
+        board
+    }
+
+    /// Stops the clock and clears the move and click counts, so that
+    /// whatever has been revealed so far doesn't count against the
+    /// user.
+    pub fn forget_moves(&mut self) {
+        self.started_at = None;
+        self.moves = 0;
+        self.clicks = Clicks::default();
+    }
+
+    /// Registers `listener` to be told about every change to the
+    /// `Board` from now on (see the `observe` module), returning an id
+    /// to remove it with.
+    pub fn add_listener<L>(&mut self, listener: L) -> ListenerId
+        where L: BoardListener + Send + Sync + 'static
+    {
+        self.listeners.add(Box::new(listener))
+    }
+
+    /// Removes the listener with the given id, returning whether there
+    /// was one.
+    pub fn remove_listener(&mut self, id: ListenerId) -> bool {
+        self.listeners.remove(id)
+    }
+
+    /// Turns this `Board` into a freshly generated `width`x`height`
+    /// `Board` with bombs wherever `is_mine` says, and every `Tile`
+    /// hidden. The existing allocation for `tiles` is reused where
+    /// possible, and the clock is kept.
+    pub(crate) fn reset_with<F>(&mut self, width: usize, height: usize, is_mine: F)
+        where F: Fn(usize) -> bool
+    {
+        self.tiles.clear();
+        self.tiles.resize(width * height, Tile::default());
+        for (i, tile) in self.tiles.iter_mut().enumerate() {
+            tile.is_bomb = is_mine(i);
+        }
+        self.num_mines = self.tiles.iter().filter(|t| t.is_bomb).count();
+        self.num_anti_mines = 0;
+        self.width = width;
+        self.height = height;
+        self.was_generated = true;
+        self.forget_moves();
+        self.seed = None;
+        self.orientation = Orientation::for_size(width, height);
+        self.count_adjacent_bombs();
+        self.check_invariants();
+    }
+
+    // Panics with everything that is wrong with the `Board`, and a dump
+    // of it, if it has got into a state it never should
+    #[cfg(feature = "debug-invariants")]
+    pub(crate) fn check_invariants(&self) {
+        if let Err(report) = self.validate() {
+            panic!("Board invariants violated!\n{}\nlayout:\n{}visible:\n{}",
+                   report,
+                   self.layout_string(),
+                   self.visible_string());
+        }
+    }
+
+    #[cfg(not(feature = "debug-invariants"))]
+    #[inline]
+    pub(crate) fn check_invariants(&self) {}
+
+    /// Replaces the clock used to timestamp this `Board`, returning
+    /// the `Board`. Mainly useful for supplying a `MockClock` in
+    /// tests, or a custom `Clock` on platforms without
+    /// `std::time::Instant`.
+    pub fn with_clock(mut self, clock: GameClock) -> Board {
+        self.clock = clock;
+        self
+    }
+
+    /// Sets the seed used to place the bombs, returning the `Board`.
+    ///
+    /// Two `Boards` with the same dimensions, number of mines, and
+    /// seed will place their bombs identically when first revealed at
+    /// the same `Tile`, on any platform. See the `util` module for the
+    /// algorithms involved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// let mut a = Board::new(16, 16, 40).with_seed(2024);
+    /// let mut b = Board::new(16, 16, 40).with_seed(2024);
+    /// a.reveal_tile(17).unwrap();
+    /// b.reveal_tile(17).unwrap();
+    /// assert_eq!(a.layout_string(), b.layout_string());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `Board` has already been
+    /// generated.
+    pub fn with_seed(mut self, seed: u64) -> Board {
+        if self.was_generated {
+            panic!("Tried to seed a Board that has already been generated!");
+        }
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Returns the seed used to place the bombs, if the `Board` was
+    /// seeded.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Returns which `Tiles` of the `Board` are adjacent to each other.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Returns the `Kernel` that replaces the `Topology`'s neighbours,
+    /// if there is one.
+    pub fn kernel(&self) -> Option<&Kernel> {
+        self.kernel.as_ref()
+    }
+
+    /// Returns how many of the `Board`'s bombs are anti-mines. See
+    /// `BoardBuilder::anti_mines`.
+    pub fn num_anti_mines(&self) -> usize {
+        self.num_anti_mines
+    }
+
+    /// Returns which `Tiles` are in play, if the `Board` was made with
+    /// a mask. See `BoardBuilder::mask`.
+    pub fn mask(&self) -> Option<&[bool]> {
+        self.mask.as_deref()
+    }
+
+    /// Returns whether the `Tile` at `index` is part of the playing
+    /// field. Every `Tile` is, unless the `Board` was made with a mask;
+    /// those that aren't stay hidden, are never bombs, and are nobody's
+    /// neighbours.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `index` is not within the bounds of
+    /// the grid.
+    pub fn is_in_play(&self, index: usize) -> bool {
+        self.mask.as_ref().is_none_or(|mask| mask[index])
+    }
+
+    // Each Tile as written by `cell`, with `hole` in place of the ones
+    // that aren't in play
+    pub(crate) fn cells<F: Fn(&Tile) -> String>(&self, cell: F, hole: &str) -> Vec<String> {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(|(i, t)| if self.is_in_play(i) { cell(t) } else { hole.to_string() })
+            .collect()
+    }
+
+    /// Returns the clock used to timestamp this `Board`.
+    pub fn clock(&self) -> &GameClock {
+        &self.clock
+    }
+
+    /// Returns the current time on this `Board`'s clock.
+    pub fn now(&self) -> Timestamp {
+        self.clock.now()
+    }
+
+    /// Returns the time at which the first `Tile` was revealed (which
+    /// is also when the `Board` is generated), or `None` if nothing has
+    /// been revealed yet.
+    pub fn started_at(&self) -> Option<Timestamp> {
+        self.started_at
+    }
+
+    /// Returns how long the game has been running: the time since the
+    /// first `Tile` was revealed, or zero if nothing has been revealed
+    /// yet.
+    pub fn elapsed(&self) -> Duration {
+        match self.started_at {
+            Some(start) => self.now().since(start),
+            None => Duration::from_secs(0),
+        }
+    }
+
+    /// Returns the 3BV of this `Board`: the fewest clicks that clear it
+    /// without flagging. Each opening counts as one click, as does each
+    /// safe `Tile` that isn't on the edge of an opening. Returns 0 if
+    /// the `Board` hasn't been generated yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// // One opening, and the two top corners
+    /// let b = Board::from_layout("1*1\n111\n...").unwrap();
+    /// assert_eq!(b.bbbv(), 3);
+    /// ```
+    pub fn bbbv(&self) -> usize {
+        ::stats::bbbv_progress(self).1
+    }
+
+    /// Returns how many reveals, flags, and chords have worked on this
+    /// `Board` so far.
+    pub fn move_count(&self) -> usize {
+        self.moves
+    }
+
+    /// Returns how many `Tiles` are still hidden, not counting flagged
+    /// ones.
+    pub fn hidden_count(&self) -> usize {
+        self.count_tiles(|t| matches!(t.state, TileState::Hidden))
+    }
+
+    /// Returns how many `Tiles` have been revealed, including a
+    /// detonated bomb.
+    pub fn revealed_count(&self) -> usize {
+        self.count_tiles(|t| matches!(t.state, TileState::Revealed | TileState::Detonated))
+    }
+
+    /// Returns how many `Tiles` are flagged.
+    pub fn flagged_count(&self) -> usize {
+        self.count_tiles(|t| matches!(t.state, TileState::Flagged))
+    }
+
+    // How many bombs have been revealed without losing, each using up a
+    // life. Once the game is lost every bomb is shown, so this is 0
+    fn exploded_count(&self) -> usize {
+        if self.state() == GameState::Lost {
+            return 0;
+        }
+        self.count_tiles(|t| matches!(t.state, TileState::Exposed))
+    }
+
+    /// Returns how many bombs are left to flag: the number of mines less
+    /// the number of flags and of bombs revealed without losing, for the
+    /// mine counter. This is negative if there are more flags than
+    /// mines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// let b = Board::restore("1*1\n111\n...", "!!!\n???\n???").unwrap();
+    /// assert_eq!(b.mines_remaining(), -2);
+    /// ```
+    pub fn mines_remaining(&self) -> i64 {
+        self.num_mines as i64 - (self.flagged_count() + self.exploded_count()) as i64
+    }
+
+    /// Returns how much of the `Board` has been cleared, from 0.0 to
+    /// 1.0: the fraction of safe `Tiles` that have been revealed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+    /// assert_eq!(b.progress(), 0.0);
+    /// b.reveal_tile(8).unwrap();
+    /// assert_eq!(b.progress(), 0.75);
+    /// ```
+    pub fn progress(&self) -> f32 {
+        let safe = self.count_tiles(|_| true) - self.num_mines;
+        if safe == 0 {
+            return 1.0;
+        }
+        let cleared = self.count_tiles(|t| !t.is_bomb && matches!(t.state, TileState::Revealed));
+        cleared as f32 / safe as f32
+    }
+
+    fn count_tiles<F: Fn(&Tile) -> bool>(&self, predicate: F) -> usize {
+        self.tiles.iter().enumerate().filter(|&(i, t)| self.is_in_play(i) && predicate(t)).count()
+    }
+
+    /// Returns whether the bombs and tile values have been placed
+    /// yet. This happens the first time a `Tile` is revealed.
+    pub fn is_generated(&self) -> bool {
+        self.was_generated
+    }
+
+    /// Returns how the game on this `Board` is going, judging only by
+    /// its `Tiles`.
+    pub fn state(&self) -> GameState {
+        game_state(self.tiles
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| self.is_in_play(i))
+            .map(|(_, t)| (t.is_bomb, &t.state)))
+    }
+
+    /// Renders what the user should see, as with `Display`, but using
+    /// the given `format`.
+    ///
+    /// `Display` and `Debug` fall back to space-separated columns on
+    /// their own when a `Tile` needs more than one character; use this
+    /// to control the separators and padding yourself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::{Board, GridFormat};
+    ///
+    /// let b = Board::new(4, 3, 2);
+    /// let format = GridFormat {
+    ///     separator: String::from(" "),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(b.render_visible(&format), "? ? ? ?\n? ? ? ?\n? ? ? ?\n");
+    /// ```
+    pub fn render_visible(&self, format: &GridFormat) -> String {
+        let cells = self.shown_cells();
+        format.render(&cells, self.width)
+    }
+
+    /// Renders the full layout of the `Board`, as with `Debug`, but
+    /// using the given `format`.
+    pub fn render_layout(&self, format: &GridFormat) -> String {
+        let cells = self.cells(|t| format!("{:?}", t), " ");
+        format.render(&cells, self.width)
+    }
+
+    /// Returns the indices of any adjacent tiles.
+    ///
+    /// `Board` represents its grid of tiles as a one-dimensional
+    /// `Vec<Tile>`. Given the `index` of a `Tile`, this function will
+    /// return an `Vec<usize>` of the indices of all the tiles
+    /// surrounding it.
+    ///
+    /// # Examples
+    ///
+    /// Finding the indices of the `Tiles` surrounding the first
+    /// `Tile`:
+    ///
+    /// ```
+    /// use mines_core::board::Board;
+    ///
+    /// // An 8x8 Board
+    /// let b: Board = Default::default();
+    /// let indices: Vec<usize> = b.adjacent_tile_indices(0);
+    /// assert_eq!(indices, vec![1, 8, 9]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the programmer passes an `index`
+    /// that is not within the bounds of the grid, or if the
+    /// dimensions of the `Board` are not at least 3x3.
+    pub fn adjacent_tile_indices(&self, index: usize) -> Vec<usize> {
+        self.neighbors(index).collect()
+    }
+
+    /// Returns the indices of the `Tiles` surrounding the `Tile` at
+    /// `index`, in ascending order, as `adjacent_tile_indices` does but
+    /// without allocating (except for a `Kernel` of more than eight
+    /// offsets).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// let b = Board::from_layout("1*1\n111\n...").unwrap();
+    /// let bombs = b.neighbors(4).filter(|&i| b.tiles[i].is_bomb).count();
+    /// assert_eq!(bombs, 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the same conditions as
+    /// `adjacent_tile_indices`.
+    pub fn neighbors(&self, index: usize) -> impl Iterator<Item = usize> {
+        let mut neighbors = Neighbors::default();
+        let length = self.tiles.len();
+        match self.kernel {
+            Some(ref kernel) if kernel.offsets().len() > neighbors.inline.len() => {
+                let indices = kernel.adjacent_indices(index, self.width, length, self.topology);
+                neighbors.spilled = Some(indices.into_iter());
+            }
+            Some(ref kernel) => {
+                kernel.for_each_adjacent(index, self.width, length, self.topology, |i| {
+                    neighbors.push(i)
+                })
+            }
+            None => {
+                self.topology.for_each_adjacent(index, self.width, length, |i| neighbors.push(i))
+            }
+        }
+        neighbors.finish(self.mask.as_deref(), index);
+        neighbors
+    }
+
+    /// Flood-reveals any available `Tiles`, allowing the user to see
+    /// their values. Returns a `Result` indicating whether the
+    /// reveals were successful.
+    ///
+    /// The first time this function is called, the `Board` will place
+    /// its bombs and generate values for its `Tiles`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any `Tile` was not in a
+    /// revealable `TileState`, such as if it was already revealed. It
+    /// is safe to discard this error; it is only for the programmer.
+    pub fn reveal_tile(&mut self, index: usize) -> Result<(), &'static str> {
+        self.reveal_tile_layered(index).map(|_| ())
+    }
+
+    /// Reveals a `Tile` as with `reveal_tile`, returning the `Tiles`
+    /// that were revealed in wavefront layers, so that front-ends can
+    /// animate an opening spreading out from the click.
+    ///
+    /// The first layer is just `index`, and each layer after that holds
+    /// the `Tiles` that the flood fill reached one step further out, in
+    /// ascending order. Revealing a bomb gives just the one layer, and
+    /// revealing a `Tile` that was already revealed gives none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// let mut b = Board::from_layout("....\n....\n11..\n*1..").unwrap();
+    /// let layers = b.reveal_tile_layered(0).unwrap();
+    /// assert_eq!(layers[0], vec![0]);
+    /// assert_eq!(layers[1], vec![1, 4, 5]);
+    /// assert_eq!(layers.concat().len(), 15);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error in the same cases as
+    /// `reveal_tile`. It is safe to discard this error; it is only for
+    /// the programmer.
+    pub fn reveal_tile_layered(&mut self, index: usize) -> Result<Vec<Vec<usize>>, &'static str> {
+        let before = self.undo_snapshot();
+        let did_nothing = matches!(self.tiles[index].state, TileState::Revealed);
+        let (layers, result) = match self.reveal_unrecorded(index) {
+            Ok(_) if did_nothing => (Vec::new(), Ok(())),
+            Ok(layers) => (layers, Ok(())),
+            Err(e) => (Vec::new(), Err(e)),
+        };
+        self.record_move(Action::Reveal(index), before, result, did_nothing)?;
+        Ok(layers)
+    }
+
+    // Reveals `index` and floods out from it, returning the layers as
+    // for `reveal_tile_layered`
+    fn reveal_unrecorded(&mut self, index: usize) -> Result<Vec<Vec<usize>>, &'static str> {
+        if !self.is_in_play(index) {
+            return Err("Tried to reveal a Tile that isn't in play!");
+        }
+        if !self.was_generated {
+            self.generate(index);
+        }
+        if self.started_at.is_none() {
+            self.started_at = Some(self.now());
+        }
+        let survives = self.lives_left() > 1;
+        // Then flood-fill reveal, starting with the tile at index.
+        self.tiles[index].reveal()?;
+        if self.tiles[index].is_bomb {
+            // Nothing floods out of a bomb; the game is over, unless
+            // there is a life to spare
+            if survives {
+                self.tiles[index].state = TileState::Exposed;
+            } else {
+                self.resolve_loss();
+            }
+            Ok(vec![vec![index]])
+        } else {
+            self.flood_reveal(index)
+        }
+    }
+
+    /// Shows how a lost game really stood: every bomb the user hadn't
+    /// flagged becomes `TileState::Exposed`, and every flag on a safe
+    /// `Tile` becomes `TileState::WrongFlag`. Correct flags are left as
+    /// they are.
+    ///
+    /// `reveal_tile` does this automatically when it reveals a bomb.
+    /// Does nothing unless the game has been lost.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// let mut b = Board::restore("1*1\n221\n*1.", "?!?\n?!?\n???").unwrap();
+    /// b.reveal_tile(0).unwrap();
+    /// b.reveal_tile(1).unwrap_err();
+    /// b.reveal_tile(6).unwrap();
+    /// assert_eq!(format!("{}", b), "1!?\n?X?\n*??\n");
+    /// ```
+    pub fn resolve_loss(&mut self) {
+        if self.state() != GameState::Lost {
+            return;
+        }
+        for tile in &mut self.tiles {
+            tile.state = match (tile.is_bomb, &tile.state) {
+                (true, &TileState::Hidden) => TileState::Exposed,
+                (false, &TileState::Flagged) => TileState::WrongFlag,
+                _ => continue,
+            };
+        }
+        self.check_invariants();
+    }
+
+
+    /// Toggles this `Tile` as flagged. If it is flagged, the user
+    /// will not be able to reveal it (and uncover a bomb). Returns a
+    /// `Result` indicating whether the flag was successful.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the `Board` has not been
+    /// generated yet, if the `Tile` was not in a flaggable `TileState`
+    /// (such as if it is already revealed), or if the flag limit is on
+    /// and there are already as many flags as mines. It is safe to
+    /// discard this error; it is only for the programmer.
+    pub fn flag_tile(&mut self, index: usize) -> Result<(), FlagError> {
+        if !self.was_generated {
+            // NOTE: gnome-mines allows pre-generation flagging, it
+            // just removes the ones it encounters during the flood fill
+            return Err(FlagError::NotGenerated);
+        }
+        if !self.is_in_play(index) {
+            return Err(FlagError::NotFlaggable);
+        }
+        let placing = matches!(self.tiles[index].state, TileState::Hidden);
+        let limit = self.num_mines - self.exploded_count();
+        if self.flag_limit && placing && self.flagged_count() >= limit {
+            self.clicks.count(Action::Flag(index), true);
+            return Err(FlagError::LimitReached { limit });
+        }
+        let before = self.undo_snapshot();
+        let result = self.tiles[index].flag();
+        // Removing a flag is a wasted click
+        let did_nothing = !matches!(self.tiles[index].state, TileState::Flagged);
+        self.record_move(Action::Flag(index), before, result, did_nothing)
+            .map_err(|_| FlagError::NotFlaggable)
+    }
+
+    /// Turns on the flag limit: `flag_tile` refuses to place more flags
+    /// than there are mines, as in several classic versions of the
+    /// game. Removing a flag is always allowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::{Board, FlagError};
+    ///
+    /// let mut b = Board::from_layout("1*1\n111\n...").unwrap().with_flag_limit();
+    /// b.flag_tile(0).unwrap();
+    /// assert_eq!(b.flag_tile(1), Err(FlagError::LimitReached { limit: 1 }));
+    /// b.flag_tile(0).unwrap();
+    /// b.flag_tile(1).unwrap();
+    /// ```
+    pub fn with_flag_limit(mut self) -> Board {
+        self.flag_limit = true;
+        self
+    }
+
+    /// Gives the user `lives` lives, returning the `Board`. Revealing a
+    /// bomb uses one up: the bomb stays on show as `TileState::Exposed`,
+    /// counting as flagged from then on, and the game carries on. The
+    /// game is only lost when the last life goes. A `Board` has one
+    /// life unless given more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::{Board, GameState};
+    ///
+    /// let mut b = Board::from_layout("1*1\n221\n*1.").unwrap().with_lives(2);
+    /// b.reveal_tile(1).unwrap();
+    /// assert_ne!(b.state(), GameState::Lost);
+    /// assert_eq!(b.lives_left(), 1);
+    /// b.reveal_tile(6).unwrap();
+    /// assert_eq!((b.state(), b.lives_left()), (GameState::Lost, 0));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `lives` is 0.
+    pub fn with_lives(mut self, lives: usize) -> Board {
+        if lives == 0 {
+            panic!("Tried to give a Board no lives!");
+        }
+        self.extra_lives = lives - 1;
+        self
+    }
+
+    /// Returns how many lives the user started with. See `with_lives`.
+    pub fn lives(&self) -> usize {
+        self.extra_lives + 1
+    }
+
+    /// Returns how many lives the user has left: 0 once the game is
+    /// lost, and one less for each bomb revealed before then.
+    pub fn lives_left(&self) -> usize {
+        if self.state() == GameState::Lost {
+            return 0;
+        }
+        self.lives().saturating_sub(self.exploded_count())
+    }
+
+    /// Reveals every hidden, unflagged `Tile` around the revealed
+    /// number at `index`, as long as it has exactly as many flags
+    /// around it as its number. If a flag was wrong, this reveals a
+    /// bomb.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// let mut b = Board::restore("1*1\n111\n...", "1!?\n???\n???").unwrap();
+    /// b.chord_tile(0).unwrap();
+    /// assert_eq!(b.visible_string(), "1!?\n11?\n???\n");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the `Tile` is not a
+    /// revealed number, or if the number of flags around it doesn't
+    /// match. It is safe to discard this error; it is only for the
+    /// programmer.
+    pub fn chord_tile(&mut self, index: usize) -> Result<(), &'static str> {
+        match self.tiles[index].state {
+            TileState::Revealed if !self.tiles[index].is_bomb &&
+                                   self.tiles[index].adjacent_bombs > 0 => {}
+            _ => {
+                return self.waste(Action::Chord(index),
+                                  Err("Tried to chord a Tile that isn't a revealed number!"))
+            }
+        }
+        let adjacent = self.adjacent_tile_indices(index);
+        // A bomb revealed without losing counts as flagged
+        let lost = self.state() == GameState::Lost;
+        let flags = adjacent.iter()
+            .filter(|&&i| match self.tiles[i].state {
+                TileState::Flagged => true,
+                TileState::Exposed => !lost,
+                _ => false,
+            })
+            .count();
+        if flags as isize != self.tiles[index].adjacent_bombs {
+            return self.waste(Action::Chord(index),
+                              Err("Tried to chord a Tile without the right number of flags!"));
+        }
+
+        let before = self.undo_snapshot();
+        let mut result = Ok(());
+        let mut revealed_any = false;
+        for i in adjacent {
+            // A wrong flag can end the game part way through
+            if matches!(self.tiles[i].state, TileState::Hidden) {
+                result = result.and(self.reveal_unrecorded(i).map(|_| ()));
+                revealed_any = true;
+            }
+        }
+        self.record_move(Action::Chord(index), before, result, !revealed_any)
+    }
+
+    // Counts the click for `action`, then logs it and remembers the
+    // Tiles as they were `before` it, if it worked
+    fn record_move(&mut self,
+                   action: Action,
+                   before: Option<Vec<TileState>>,
+                   result: Result<(), &'static str>,
+                   did_nothing: bool)
+                   -> Result<(), &'static str> {
+        self.clicks.count(action, did_nothing || result.is_err());
+        self.check_invariants();
+        result?;
+        self.moves += 1;
+        if let Some(before) = before {
+            self.notify_move(&before);
+            if let Some(ref mut history) = self.undo {
+                history.push(before);
+            }
+        }
+        let at = self.now();
+        if let Some(ref mut log) = self.log {
+            log.push(LoggedAction { at, action });
+        }
+        Ok(())
+    }
+
+    // Tells the listeners which Tiles a move changed from `before`, and
+    // whether it ended the game
+    fn notify_move(&mut self, before: &[TileState]) {
+        if self.listeners.is_empty() {
+            return;
+        }
+        let at = self.now();
+        let after: Vec<TileState> = self.tiles.iter().map(|t| t.state.clone()).collect();
+        self.listeners.notify_changes(at, before, &after);
+        let was = game_state(self.tiles.iter().zip(before).map(|(t, s)| (t.is_bomb, s)));
+        match self.state() {
+            GameState::Won if was != GameState::Won => {
+                self.listeners.notify(&BoardEvent::Won { at })
+            }
+            GameState::Lost if was != GameState::Lost => {
+                self.listeners.notify(&BoardEvent::Lost { at })
+            }
+            _ => {}
+        }
+    }
+
+    // Counts `action` as a click that did nothing, and passes `result` on
+    fn waste(&mut self,
+             action: Action,
+             result: Result<(), &'static str>)
+             -> Result<(), &'static str> {
+        self.clicks.count(action, true);
+        result
+    }
+
+    /// Reveals a hidden `Tile` that is guaranteed not to be a bomb,
+    /// returning its index.
+    ///
+    /// Where possible, the hint is a `Tile` that the user could have
+    /// deduced was safe from the numbers already revealed (see the
+    /// `solver` module). Otherwise it
+    /// is a safe `Tile` next to the revealed area, or failing that, any
+    /// safe `Tile`. Flagged `Tiles` are never hinted.
+    ///
+    /// Returns `None` if the `Board` hasn't been generated yet (the
+    /// first reveal is always safe anyway), or if there are no hidden
+    /// safe `Tiles` left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// let mut b: Board = "1*1\n111\n...".parse().unwrap();
+    /// b.reveal_tile(6).unwrap();
+    /// // The 1s prove that the top corners are safe
+    /// let index = b.hint().unwrap();
+    /// assert!(index == 0 || index == 2);
+    /// assert_eq!(b.hint(), Some(2 - index));
+    /// assert_eq!(b.hint(), None);
+    /// ```
+    pub fn hint(&mut self) -> Option<usize> {
+        if !self.was_generated {
+            return None;
+        }
+
+        let is_candidate = |tile: &Tile| {
+            if let TileState::Hidden = tile.state {
+                !tile.is_bomb
+            } else {
+                false
+            }
+        };
+        let deduced = solver::solve(self).safe;
+        let index = deduced.into_iter()
+            .find(|&i| is_candidate(&self.tiles[i]))
+            .or_else(|| {
+                (0..self.tiles.len()).find(|&i| {
+                    is_candidate(&self.tiles[i]) && self.tile_touches_revealed(i)
+                })
+            })
+            .or_else(|| (0..self.tiles.len()).find(|&i| is_candidate(&self.tiles[i])));
+
+        if let Some(index) = index {
+            // The tile is hidden and not a bomb, so this can't fail
+            let _ = self.reveal_tile(index);
+        }
+        index
+    }
+
+    /// Flags every hidden `Tile` that the numbers already revealed
+    /// prove to be a bomb (see the `solver` module), returning their
+    /// indices in ascending order. Existing flags are left alone and
+    /// play no part in the proof.
+    ///
+    /// Each flag is placed with `flag_tile`, so it is counted, logged,
+    /// and undone like any other, and stops at the flag limit if it is
+    /// on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// let mut b = Board::restore("1*1\n111\n...", "???\n111\n...").unwrap();
+    /// assert_eq!(b.auto_flag(), vec![1]);
+    /// assert_eq!(b.visible_string(), "?!?\n111\n...\n");
+    /// assert!(b.auto_flag().is_empty());
+    /// ```
+    pub fn auto_flag(&mut self) -> Vec<usize> {
+        let mut flagged = Vec::new();
+        if !self.was_generated {
+            return flagged;
+        }
+        let mut mines = solver::solve(self).mines;
+        mines.sort_unstable();
+        for i in mines {
+            if matches!(self.tiles[i].state, TileState::Hidden) && self.flag_tile(i).is_ok() {
+                flagged.push(i);
+            }
+        }
+        flagged
+    }
+
+    fn tile_touches_revealed(&self, index: usize) -> bool {
+        self.neighbors(index)
+            .any(|i| matches!(self.tiles[i].state, TileState::Revealed))
+    }
+
+    /// Places the bombs as if the `Tile` at `index` were the first one
+    /// revealed, but doesn't reveal anything. A seeded `Board` places
+    /// them from its seed, and any other from system randomness, just as
+    /// its first reveal would.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `Board` has already been
+    /// generated.
+    pub fn generate(&mut self, index: usize) {
+        if self.was_generated {
+            panic!("Tried to generate a Board that has already been generated!");
+        }
+        // A seeded Board draws everything from its seed, and so never
+        // needs system randomness, which a browser can't give it
+        match self.seed {
+            Some(seed) => self.place_bombs(index, &mut util::SeededRng::new(seed)),
+            None => self.place_bombs(index, &mut rand::thread_rng()),
+        }
+    }
+
+    /// Places the bombs as if the `Tile` at `index` were the first one
+    /// revealed, drawing from `rng`, but doesn't reveal anything. The
+    /// same `rng` in the same state always places the same bombs, so a
+    /// seeded generator (such as `util::SeededRng`) gives deterministic
+    /// `Boards` without `Board::with_seed`. A `Board` that already has
+    /// a seed uses it instead of `rng`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    /// use mines_core::util::SeededRng;
+    ///
+    /// let mut a = Board::new(16, 16, 40);
+    /// let mut b = Board::new(16, 16, 40);
+    /// a.generate_with_rng(100, &mut SeededRng::new(9));
+    /// b.generate_with_rng(100, &mut SeededRng::new(9));
+    /// assert_eq!(a.layout_string(), b.layout_string());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `Board` has already been
+    /// generated.
+    pub fn generate_with_rng<R: Rng>(&mut self, index: usize, rng: &mut R) {
+        if self.was_generated {
+            panic!("Tried to generate a Board that has already been generated!");
+        }
+        self.place_bombs(index, rng);
+    }
+
+    fn place_bombs<R: Rng>(&mut self, index: usize, rng: &mut R) {
+        self.was_generated = true;
+        match self.generator.clone() {
+            Some(generator) => generator.place_mines(self, index, rng),
+            None => UniformGenerator.place_mines(self, index, rng),
+        }
+        self.place_anti_mines(rng);
+        self.count_adjacent_bombs();
+        let at = self.now();
+        self.listeners.notify(&BoardEvent::Generated { at });
+    }
+
+    // Turns `num_anti_mines` of the bombs just placed into anti-mines,
+    // drawing from the seed if there is one, or `rng`
+    fn place_anti_mines<R: Rng>(&mut self, rng: &mut R) {
+        if self.num_anti_mines == 0 {
+            return;
+        }
+        let mut bombs: Vec<usize> =
+            (0..self.tiles.len()).filter(|&i| self.tiles[i].is_bomb).collect();
+        if let Some(seed) = self.seed {
+            // A different stream from the one that placed the bombs
+            for pick in util::seeded_sample(bombs.len(), self.num_anti_mines, !seed) {
+                self.tiles[bombs[pick]].is_anti_mine = true;
+            }
+        } else {
+            for k in 0..self.num_anti_mines {
+                let j = rng.gen_range(k, bombs.len());
+                bombs.swap(k, j);
+                self.tiles[bombs[k]].is_anti_mine = true;
+            }
+        }
+    }
+
+    // Moves the bomb at `from` to the (safe) Tile at `to`, keeping the
+    // numbers around both up to date
+    pub(crate) fn move_bomb(&mut self, from: usize, to: usize) {
+        debug_assert!(self.tiles[from].is_bomb && !self.tiles[to].is_bomb);
+        let is_anti_mine = self.tiles[from].is_anti_mine;
+        self.tiles[from].is_bomb = false;
+        self.tiles[from].is_anti_mine = false;
+        self.tiles[to].is_bomb = true;
+        self.tiles[to].is_anti_mine = is_anti_mine;
+        self.tiles[to].adjacent_bombs = 0;
+        let sign = if is_anti_mine { -1 } else { 1 };
+        for i in self.neighbors(from) {
+            if !self.tiles[i].is_bomb {
+                self.tiles[i].adjacent_bombs -= sign;
+            }
+        }
+        for i in self.neighbors(to) {
+            if !self.tiles[i].is_bomb {
+                self.tiles[i].adjacent_bombs += sign;
+            }
+        }
+        self.tiles[from].adjacent_bombs = self.adjacent_count(from);
+    }
+
+    /// Counts the adjacent bombs of every `Tile` again, for after
+    /// bombs have been moved through `Board::tiles` by hand.
+    pub fn count_adjacent_bombs(&mut self) {
+        #[cfg(feature = "rayon")]
+        {
+            if self.tiles.len() >= PARALLEL_TILES {
+                use rayon::prelude::*;
+
+                let counts: Vec<isize> =
+                    (0..self.tiles.len()).into_par_iter().map(|i| self.adjacent_count(i)).collect();
+                for (tile, count) in self.tiles.iter_mut().zip(counts) {
+                    if !tile.is_bomb {
+                        tile.adjacent_bombs = count;
+                    }
+                }
+                return;
+            }
+        }
+        for index in 0..self.tiles.len() {
+            if !self.tiles[index].is_bomb {
+                self.tiles[index].adjacent_bombs = self.adjacent_count(index);
+            }
+        }
+    }
+
+    // The number the Tile at `index` should show: its adjacent bombs,
+    // less its adjacent anti-mines
+    pub(crate) fn adjacent_count(&self, index: usize) -> isize {
+        self.neighbors(index)
+            .map(|i| {
+                match self.tiles[i] {
+                    Tile { is_anti_mine: true, .. } => -1,
+                    Tile { is_bomb: true, .. } => 1,
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
+
+    // Whether the Tile at `index` has no bombs of either kind next to
+    // it, so that revealing it reveals its neighbours too. A 0 can
+    // still hide a bomb and an anti-mine cancelling out
+    pub(crate) fn is_blank(&self, index: usize) -> bool {
+        self.tiles[index].adjacent_bombs == 0 &&
+        (self.num_anti_mines == 0 ||
+         self.neighbors(index).all(|i| !self.tiles[i].is_bomb))
+    }
+
+    // Reveals everything the opening at `index` (already revealed)
+    // spreads to, breadth first: every empty Tile reveals all of its
+    // hidden neighbours. A flagged neighbour is left flagged, and the
+    // flood goes around it
+    fn flood_reveal(&mut self, index: usize) -> Result<Vec<Vec<usize>>, &'static str> {
+        let mut layers = vec![vec![index]];
+        let mut queued = vec![false; self.tiles.len()];
+        queued[index] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back((index, 0));
+
+        while let Some((i, layer)) = queue.pop_front() {
+            if layer > 0 {
+                self.tiles[i].reveal()?;
+                if layers.len() == layer {
+                    layers.push(Vec::new());
+                }
+                layers[layer].push(i);
+            }
+            if !self.is_blank(i) {
+                continue;
+            }
+            for n in self.neighbors(i) {
+                if !queued[n] && matches!(self.tiles[n].state, TileState::Hidden) {
+                    queued[n] = true;
+                    queue.push_back((n, layer + 1));
+                }
+            }
+        }
+
+        for layer in &mut layers {
+            layer.sort_unstable();
+        }
+        Ok(layers)
+    }
+
+    /// Converts an (x, y) coordinate pair to a 1D index.
+    ///
+    /// `Board` represents its grid of tiles as a one-dimensional
+    /// `Vec<Tile>`. Given a coordinate pair (x, y) referring to the
+    /// location of `Tile`, this function will return the 1D index
+    /// that corresponds to that `Tile`.
+    ///
+    /// # Examples
+    ///
+    /// Finding the index from coordinates of the `Tile` at (3, 4):
+    ///
+    /// ```
+    /// use mines_core::board::Board;
+    ///
+    /// // An 8x8 Board
+    /// let b: Board = Default::default();
+    /// let tile_index = b.linear_coords((3, 4));
+    /// assert_eq!(tile_index, 35);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the programmer passes a coordinate
+    /// pair that is not within the bounds of the grid.
+    pub fn linear_coords(&self, p: (usize, usize)) -> usize {
+        if p.0 >= self.width || p.1 >= self.height {
+            panic!("Tried to get the index of a Tile that wasn't within the bounds of the grid! \
+                    Coordinates passed: ({}, {}), Grid bounds: {}x{}",
+                   p.0,
+                   p.1,
+                   self.width,
+                   self.height);
+        }
+        linear_coords(p, self.width)
+    }
+
+    /// Converts a 1D index to an (x, y) coordinate pair.
+    ///
+    /// `Board` represents its grid of tiles as a one-dimensional
+    /// `Vec<Tile>`. Given the `index` of a `Tile`, this function will
+    /// return an (x, y) coordinate pair (zero indexed) that refers to
+    /// that `Tile`.
+    ///
+    /// # Examples
+    ///
+    /// Finding the coordinates of the first `Tile`:
+    ///
+    /// ```
+    /// use mines_core::board::Board;
+    ///
+    /// // An 8x8 Board
+    /// let b: Board = Default::default();
+    /// let first_tile_coords = b.cartesian_coords(0);
+    /// assert_eq!(first_tile_coords, (0, 0));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the programmer passes an `index`
+    /// that is not within the bounds of the grid.
+    pub fn cartesian_coords(&self, index: usize) -> (usize, usize) {
+        if index >= self.tiles.len() {
+            panic!("Tried to get the coordinates of a Tile that wasn't within the bounds of the \
+                    grid! Index passed: {}, Grid length: {}",
+                   index,
+                   self.tiles.len());
+        }
+        cartesian_coords(index, self.width)
+    }
+
+    /// Returns an iterator over every `Tile`, row by row, along with
+    /// its index and (x, y) coordinates. Iterating over `&Board` does
+    /// the same. `Tiles` that aren't in play are included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// let b = Board::from_layout("1*1\n111\n...").unwrap();
+    /// for (index, (x, y), tile) in &b {
+    ///     assert_eq!(index, y * 3 + x);
+    ///     assert_eq!(tile.is_bomb, (x, y) == (1, 0));
+    /// }
+    /// assert_eq!(b.iter_tiles().count(), 9);
+    /// ```
+    pub fn iter_tiles(&self) -> TileIter<'_> {
+        TileIter {
+            tiles: self.tiles.iter().enumerate(),
+            width: self.width,
+        }
+    }
+
+    /// Returns the `Tile` at `index`, or `None` if `index` is not
+    /// within the bounds of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// let b = Board::from_layout("1*1\n111\n...").unwrap();
+    /// assert!(b.get_tile(1).unwrap().is_bomb);
+    /// assert!(b.get_tile(9).is_none());
+    /// assert_eq!(b.get_tile_at(1, 1).map(|t| t.adjacent_bombs), Some(1));
+    /// assert!(b.get_tile_at(3, 0).is_none());
+    /// ```
+    pub fn get_tile(&self, index: usize) -> Option<&Tile> {
+        self.tiles.get(index)
+    }
+
+    /// Returns the `Tile` at `index` to change, or `None` if `index` is
+    /// not within the bounds of the grid. Changing whether a `Tile` is
+    /// a bomb doesn't update the numbers around it.
+    pub fn get_tile_mut(&mut self, index: usize) -> Option<&mut Tile> {
+        self.tiles.get_mut(index)
+    }
+
+    /// Returns the `Tile` at (`x`, `y`), or `None` if that is not
+    /// within the bounds of the grid.
+    pub fn get_tile_at(&self, x: usize, y: usize) -> Option<&Tile> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.tiles.get(linear_coords((x, y), self.width))
+    }
+
+    /// Returns the `Tile` at (`x`, `y`) to change, or `None` if that is
+    /// not within the bounds of the grid. Changing whether a `Tile` is
+    /// a bomb doesn't update the numbers around it.
+    pub fn get_tile_at_mut(&mut self, x: usize, y: usize) -> Option<&mut Tile> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.tiles.get_mut(linear_coords((x, y), self.width))
+    }
+}
+
+fn linear_coords(p: (usize, usize), width: usize) -> usize {
+    let (x, y) = p;
+    (width * y) + x
+}
+
+fn cartesian_coords(index: usize, width: usize) -> (usize, usize) {
+    (index % width, index / width)
+}
+
+pub(crate) fn adjacent_indices(index: usize, width: usize, length: usize) -> Vec<usize> {
+    // In an actual array, Rust will enforce whether the index is out
+    // of bounds.
+    if index >= length {
+        panic!("Tried to find adjacent indices using an index greater than the length of the \
+                grid! Passed index: {}, Grid length: {}",
+               index,
+               length);
+    }
+
+    let mut indices: Vec<usize> = Default::default();
+
+    // NOTE: This will behave badly with grids of length < 9
+    // (although WHY would you even do that?)
+    // Definitely do not use with grids that are not at least 3x3
+    if length < 9 {
+        panic!("Tried to find adjacent indices in a grid that is not at least 3x3! Grid length: \
+                {}",
+               length);
+    }
+
+    // Lots of literal edge cases to detect.
+    // The corners must be checked first, THEN the sides.
+    // HOW TO DERIVE THE MAGIC NUMBERS:
+    // 1. Make an arbitrary grid, I did a 5x4 one.
+    // 2. Number the tiles sequentially, [0, length)
+    // 3. For each corner or edge, sketch out what the 3x3 section would look like
+    //    if it were a whole piece (it's not, because it's on an edge/corner)
+    // 4. Figure out the relationship between the index and the indices of the
+    //    expected/desired tiles that are surrounding it. Do not include tiles that
+    //    are not actually part of the grid.
+    if index == 0 {
+        // top-left corner
+        indices.push(index + 1);
+        indices.push(index + width);
+        indices.push(index + width + 1);
+    } else if index == (width - 1) {
+        // top-right corner
+        indices.push(width - 2);
+        indices.push(2 * width - 2);
+        indices.push(2 * width - 1);
+    } else if index == (length - width) {
+        // bottom-left corner
+        indices.push((length - width) - width);
+        indices.push((length - width) - width + 1);
+        indices.push(index + 1);
+    } else if index == (length - 1) {
+        // bottom-right corner
+        indices.push(index - width - 1);
+        indices.push(index - width);
+        indices.push(index - 1);
+    } else if index.is_multiple_of(width) {
+        // left side
+        indices.push(index - width);
+        indices.push(index - width + 1);
+        indices.push(index + 1);
+        indices.push(index + width);
+        indices.push(index + width + 1);
+    } else if (index + 1).is_multiple_of(width) {
+        // right side
+        indices.push(index - width - 1);
+        indices.push(index - width);
+        indices.push(index - 1);
+        indices.push(index + width - 1);
+        indices.push(index + width);
+    } else {
+        // Assume it's a well-behaved index
+        // Attempt to push all 8 surrounding indices
+        let index = index as i32;
+        let width = width as i32;
+        let length = length as i32;
+
+        let is_valid = |x: i32| x >= 0 && x < length;
+
+        let surrounding_eight = [(index - width - 1),
+                                 (index - width),
+                                 (index - width + 1),
+                                 (index - 1),
+                                 (index + 1),
+                                 (index + width - 1),
+                                 (index + width),
+                                 (index + width + 1)];
+        for i in &surrounding_eight {
+            if is_valid(*i) {
+                indices.push(*i as usize);
+            }
+        }
+    }
+
+    indices
+}
+
+// Works out the `GameState` from whether each `Tile` is a bomb and its
+// state
+fn game_state<'a, I: Iterator<Item = (bool, &'a TileState)>>(tiles: I) -> GameState {
+    let mut playing = false;
+    let mut cleared = true;
+    for tile in tiles {
+        match tile {
+            (_, &TileState::Detonated) | (true, &TileState::Revealed) => {
+                return GameState::Lost
+            }
+            (false, &TileState::Revealed) => playing = true,
+            (false, _) => cleared = false,
+            _ => {}
+        }
+    }
+    if !playing {
+        GameState::Ready
+    } else if cleared {
+        GameState::Won
+    } else {
+        GameState::Playing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_coords() {
+        const WIDTH: usize = 5;
+
+        struct Test {
+            p: (usize, usize),
+            expected: usize,
+        }
+
+        let tests = [Test {
+                         p: (2, 1),
+                         expected: 7,
+                     },
+                     Test {
+                         p: (4, 0),
+                         expected: 4,
+                     },
+                     Test {
+                         p: (0, 2),
+                         expected: 10,
+                     }];
+
+        for test in &tests {
+            assert_eq!(test.expected, linear_coords(test.p, WIDTH))
+        }
+    }
+
+    #[test]
+    fn test_cartesian_coords() {
+        const WIDTH: usize = 5;
+
+        struct Test {
+            index: usize,
+            expected: (usize, usize),
+        }
+
+        let tests = [Test {
+                         index: 4,
+                         expected: (4, 0),
+                     },
+                     Test {
+                         index: 6,
+                         expected: (1, 1),
+                     },
+                     Test {
+                         index: 10,
+                         expected: (0, 2),
+                     }];
+
+        for test in &tests {
+            assert_eq!(test.expected, cartesian_coords(test.index, WIDTH))
+        }
+    }
+
+    #[test]
+    fn test_adjacent_indices() {
+        // Mock grid of 5x4
+        const LENGTH: usize = 20;
+        const WIDTH: usize = 5;
+
+        struct Test {
+            index: usize,
+            expected: Vec<usize>,
+        }
+
+        let tests = [Test {
+                         // top-left
+                         index: 0,
+                         expected: vec![1, 5, 6],
+                     },
+                     Test {
+                         // top-middle
+                         index: 2,
+                         expected: vec![1, 3, 6, 7, 8],
+                     },
+                     Test {
+                         // top-right
+                         index: 4,
+                         expected: vec![3, 8, 9],
+                     },
+                     Test {
+                         // left
+                         index: 10,
+                         expected: vec![5, 6, 11, 15, 16],
+                     },
+                     Test {
+                         // middle
+                         index: 7,
+                         expected: vec![1, 2, 3, 6, 8, 11, 12, 13],
+                     },
+                     Test {
+                         // right
+                         index: 14,
+                         expected: vec![8, 9, 13, 18, 19],
+                     },
+                     Test {
+                         // bottom-left
+                         index: 15,
+                         expected: vec![10, 11, 16],
+                     },
+                     Test {
+                         // bottom-middle
+                         index: 17,
+                         expected: vec![11, 12, 13, 16, 18],
+                     },
+                     Test {
+                         // bottom-right
+                         index: 19,
+                         expected: vec![13, 14, 18],
+                     }];
+
+        for test in &tests {
+            assert_eq!(adjacent_indices(test.index, WIDTH, LENGTH), test.expected);
+        }
+    }
+
+    #[test]
+    fn test_wide_cells_stay_aligned() {
+        let mut b = Board::new(4, 3, 2);
+        b.tiles[1].adjacent_bombs = 12;
+        b.tiles[2].adjacent_bombs = 3;
+
+        assert_eq!(format!("{:?}", b), " . 12  3  .\n .  .  .  .\n .  .  .  .\n");
+        assert_eq!(b.render_layout(&GridFormat::compact()), " .12 3 .\n . . . .\n . . . .\n");
+        // Nothing wide is visible yet, so Display stays compact
+        assert_eq!(format!("{}", b), "????\n????\n????\n");
+    }
+
+    #[test]
+    fn test_hint() {
+        let mut b = Board::default();
+        assert_eq!(b.hint(), None);
+
+        b.reveal_tile(0).unwrap();
+        let mut hints = 0;
+        while let Some(index) = b.hint() {
+            assert!(!b.tiles[index].is_bomb);
+            hints += 1;
+        }
+        assert!(hints > 0);
+
+        // Only bombs are left hidden
+        for tile in &b.tiles {
+            assert_eq!(tile.is_bomb, !matches!(tile.state, TileState::Revealed));
+        }
+    }
+
+    #[test]
+    fn test_hint_prefers_deductions() {
+        for seed in 0..20 {
+            let mut b = Board::new(16, 16, 40).with_seed(seed);
+            b.reveal_tile(0).unwrap();
+            let deduced = solver::solve(&b).safe;
+            if !deduced.is_empty() {
+                assert!(deduced.contains(&b.hint().unwrap()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_auto_flag() {
+        for seed in 0..20 {
+            let mut b = Board::new(16, 16, 40).with_seed(seed);
+            b.reveal_tile(0).unwrap();
+            let flagged = b.auto_flag();
+            assert_eq!(flagged.len(), b.flagged_count());
+            assert!(flagged.iter().all(|&i| b.tiles[i].is_bomb));
+        }
+
+        // A wrong flag uses up the only one allowed
+        let mut b = Board::restore("1*1\n111\n...", "???\n111\n...").unwrap().with_flag_limit();
+        b.flag_tile(0).unwrap();
+        assert!(b.auto_flag().is_empty());
+    }
+
+    #[test]
+    fn test_seeded_generation() {
+        let mut a = Board::new(30, 16, 99).with_seed(7);
+        let mut b = Board::new(30, 16, 99).with_seed(7);
+        let mut c = Board::new(30, 16, 99).with_seed(8);
+        for board in &mut [&mut a, &mut b, &mut c] {
+            board.reveal_tile(200).unwrap();
+        }
+
+        assert_eq!(a.seed(), Some(7));
+        assert_eq!(format!("{:?}", a), format!("{:?}", b));
+        assert_ne!(format!("{:?}", a), format!("{:?}", c));
+        assert_eq!(a.tiles.iter().filter(|t| t.is_bomb).count(), 99);
+        for i in a.adjacent_tile_indices(200) {
+            assert!(!a.tiles[i].is_bomb);
+        }
+    }
+
+    #[test]
+    fn test_anti_mines() {
+        // The bomb and the anti-mine cancel out around the 0 between them
+        let mut b = Board::from_layout("* . -\n1 . -1\n. . .").unwrap();
+        assert_eq!((b.num_mines, b.num_anti_mines()), (2, 1));
+        assert_eq!(b.tiles[5].adjacent_bombs, -1);
+        assert_eq!(b.layout_string(), " *  .  -\n 1  . -1\n .  .  .\n");
+
+        // A 0 next to bombs doesn't flood
+        b.reveal_tile(4).unwrap();
+        assert_eq!(b.revealed_count(), 1);
+        b.reveal_tile(7).unwrap();
+        assert_eq!(b.to_string(), " ?  ?  ?\n 1  . -1\n .  .  .\n");
+        b.reveal_tile(1).unwrap();
+        assert_eq!(b.state(), GameState::Won);
+
+        let mut b = Board::from_layout("* . -\n1 . -1\n. . .").unwrap();
+        b.reveal_tile(2).unwrap();
+        assert_eq!(b.state(), GameState::Lost);
+        assert_eq!(b.visible_string(), "#?-\n???\n???\n");
+        let restored = Board::restore(&b.layout_string(), &b.visible_string()).unwrap();
+        assert_eq!(restored.visible_string(), b.visible_string());
+
+        for seed in 0..10 {
+            let mut b = Board::builder()
+                .width(9)
+                .height(9)
+                .mines(8)
+                .anti_mines(3)
+                .seed(seed)
+                .build()
+                .unwrap();
+            b.reveal_tile(40).unwrap();
+            assert_eq!(b.tiles.iter().filter(|t| t.is_bomb).count(), 11);
+            assert_eq!(b.tiles.iter().filter(|t| t.is_anti_mine).count(), 3);
+            b.check_invariants();
+            for i in b.adjacent_tile_indices(40) {
+                assert!(!b.tiles[i].is_bomb);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_with_rng() {
+        let mut a = Board::new(30, 16, 99);
+        a.generate_with_rng(200, &mut util::SeededRng::new(3));
+        assert!(a.is_generated());
+        assert_eq!(a.tiles.iter().filter(|t| t.is_bomb).count(), 99);
+        for i in a.adjacent_tile_indices(200) {
+            assert!(!a.tiles[i].is_bomb);
+        }
+        // Nothing is revealed, and revealing doesn't move the bombs
+        let layout = a.layout_string();
+        assert_eq!(a.revealed_count(), 0);
+        a.reveal_tile(200).unwrap();
+        assert_eq!(a.layout_string(), layout);
+
+        // The seed wins over the generator
+        let mut seeded = Board::new(30, 16, 99).with_seed(7);
+        let mut revealed = Board::new(30, 16, 99).with_seed(7);
+        seeded.generate_with_rng(200, &mut util::SeededRng::new(3));
+        revealed.reveal_tile(200).unwrap();
+        assert_eq!(seeded.layout_string(), revealed.layout_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to generate a Board that has already been generated!")]
+    fn test_generate_twice() {
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+        b.generate_with_rng(0, &mut util::SeededRng::new(0));
+    }
+
+    #[test]
+    fn test_dense_generation() {
+        // As many bombs as fit, which leaves one safe Tile outside the
+        // opening
+        let mut b = Board::new(30, 16, 470);
+        b.reveal_tile(200).unwrap();
+        assert_eq!(b.tiles.iter().filter(|t| t.is_bomb).count(), 470);
+        let mut opening = b.adjacent_tile_indices(200);
+        opening.push(200);
+        let safe = (0..b.tiles.len()).filter(|i| !b.tiles[*i].is_bomb && !opening.contains(i));
+        assert_eq!(safe.count(), 1);
+    }
+
+    #[test]
+    fn test_reveal_tile_layered() {
+        let mut b = Board::from_layout("....\n....\n11..\n*1..").unwrap();
+        assert_eq!(b.reveal_tile_layered(0).unwrap(),
+                   vec![vec![0], vec![1, 4, 5], vec![2, 6, 8, 9, 10], vec![3, 7, 11, 13, 14, 15]]);
+        assert!(b.reveal_tile_layered(5).unwrap().is_empty());
+        assert_eq!(b.reveal_tile_layered(12).unwrap(), vec![vec![12]]);
+
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+        assert_eq!(b.reveal_tile_layered(0).unwrap(), vec![vec![0]]);
+        b.flag_tile(1).unwrap();
+        assert!(b.reveal_tile_layered(1).is_err());
+
+        // The flood goes around a wrong flag, and is still a move
+        let mut b = Board::restore("....\n....\n11..\n*1..", "????\n?!??\n????\n????")
+            .unwrap()
+            .with_undo(1);
+        assert_eq!(b.reveal_tile_layered(0).unwrap(),
+                   vec![vec![0],
+                        vec![1, 4],
+                        vec![2, 6, 8, 9],
+                        vec![3, 7, 10, 11],
+                        vec![13, 14, 15]]);
+        assert!(matches!(b.tiles[5].state, TileState::Flagged));
+        assert_eq!(b.move_count(), 1);
+        b.undo().unwrap();
+        assert!(b.tiles.iter().all(|t| !matches!(t.state, TileState::Revealed)));
+    }
+
+    #[cfg(feature = "debug-invariants")]
+    #[test]
+    #[should_panic(expected = "(1, 1) counts 3 bombs around it, but there are 1")]
+    fn test_invariants() {
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+        b.reveal_tile(8).unwrap();
+        b.tiles[4].adjacent_bombs = 3;
+        b.flag_tile(1).unwrap();
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_count() {
+        let mut b = Board::new(1024, 1024, 100_000);
+        assert!(b.tiles.len() >= PARALLEL_TILES);
+        b.reveal_tile(0).unwrap();
+        assert!(b.validate().is_ok());
+    }
+
+    #[test]
+    fn test_board_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Board>();
+        assert_send_sync::<::game::Game>();
+        assert_send_sync::<::view::BoardView>();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        extern crate serde_json;
+
+        let mut b = Board::default();
+        b.reveal_tile(0).unwrap();
+
+        let json = serde_json::to_string(&b).unwrap();
+        let mut restored: Board = serde_json::from_str(&json).unwrap();
+        assert!(restored.is_generated());
+        assert_eq!(format!("{:?}", restored), format!("{:?}", b));
+        assert_eq!(format!("{}", restored), format!("{}", b));
+
+        // A restored board must not be regenerated on the next reveal
+        let index = (0..restored.tiles.len())
+            .find(|&i| !restored.tiles[i].is_bomb)
+            .unwrap();
+        let _ = restored.reveal_tile(index);
+        assert_eq!(format!("{:?}", restored), format!("{:?}", b));
+    }
+
+    #[test]
+    fn test_started_at() {
+        use clock::MockClock;
+
+        let mock = MockClock::new();
+        let mut b = Board::default().with_clock(GameClock::new(mock.clone()));
+        assert_eq!(b.started_at(), None);
+        assert_eq!(b.elapsed(), Duration::from_secs(0));
+
+        mock.advance(Duration::from_millis(40));
+        b.reveal_tile(0).unwrap();
+        mock.advance(Duration::from_millis(60));
+        assert_eq!(b.started_at(), Some(Timestamp::from_millis(40)));
+        assert_eq!(b.now(), Timestamp::from_millis(100));
+        assert_eq!(b.elapsed(), Duration::from_millis(60));
+    }
+
+    #[test]
+    fn test_bbbv() {
+        struct Test {
+            layout: &'static str,
+            expected: usize,
+        }
+
+        let tests = [Test {
+                         layout: "*2*\n242\n*2*",
+                         expected: 5,
+                     },
+                     Test {
+                         // Two openings either side of a wall of bombs
+                         layout: ".2*2.\n.3*3.\n.2*2.",
+                         expected: 2,
+                     },
+                     Test {
+                         layout: "....\n....\n....",
+                         expected: 1,
+                     }];
+
+        for test in &tests {
+            assert_eq!(Board::from_layout(test.layout).unwrap().bbbv(), test.expected);
+        }
+        assert_eq!(Board::new(9, 9, 10).bbbv(), 0);
+    }
+
+    #[test]
+    fn test_flag_errors() {
+        let mut b = Board::new(9, 9, 10).with_flag_limit();
+        assert_eq!(b.flag_tile(0), Err(FlagError::NotGenerated));
+
+        let mut b = Board::restore("1*1\n111\n...", "1??\n???\n???").unwrap().with_flag_limit();
+        assert_eq!(b.flag_tile(0), Err(FlagError::NotFlaggable));
+        b.flag_tile(2).unwrap();
+        assert_eq!(b.flag_tile(1), Err(FlagError::LimitReached { limit: 1 }));
+        assert_eq!(b.mines_remaining(), 0);
+    }
+
+    #[test]
+    fn test_lives() {
+        let mut b = Board::from_layout("1*1\n221\n*1.").unwrap().with_lives(2);
+        assert_eq!((b.lives(), b.lives_left()), (2, 2));
+        b.reveal_tile(0).unwrap();
+        b.reveal_tile(1).unwrap();
+        assert_eq!(b.state(), GameState::Playing);
+        assert_eq!((b.lives_left(), b.mines_remaining()), (1, 1));
+        assert_eq!(b.visible_string(), "1#?\n???\n???\n");
+
+        // The exploded bomb counts as a flag for chording, and doesn't
+        // stop the game being won
+        b.chord_tile(0).unwrap();
+        assert_eq!(b.visible_string(), "1#?\n22?\n???\n");
+        for &i in &[2, 5, 7, 8] {
+            b.reveal_tile(i).unwrap();
+        }
+        assert_eq!(b.state(), GameState::Won);
+        assert_eq!(b.lives_left(), 1);
+        b.check_invariants();
+    }
+
+    #[test]
+    fn test_index() {
+        let b = Board::from_layout("1*1\n221\n*1.").unwrap();
+        for i in 0..9 {
+            assert_eq!(b[i].is_bomb, b.tiles[i].is_bomb);
+            assert_eq!(b[b.cartesian_coords(i)].adjacent_bombs, b.tiles[i].adjacent_bombs);
+        }
+        assert!(b[(0, 2)].is_bomb);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let builder = Board::builder().width(5).height(4).mines(1).first_click(FirstClick::Safe);
+        let boards = [builder.clone().topology(Topology::Torus),
+                      builder.clone().topology(Topology::Hex),
+                      builder.clone().kernel(Kernel::knight()),
+                      builder.clone().kernel(Kernel::window(2)),
+                      builder.clone().mask((0..20).map(|i| i % 3 != 0).collect())];
+        for builder in &boards {
+            let b = builder.build().unwrap();
+            for i in 0..20 {
+                let mut expected = match b.kernel {
+                    Some(ref kernel) => kernel.adjacent_indices(i, 5, 20, b.topology),
+                    None if b.topology == Topology::Square => adjacent_indices(i, 5, 20),
+                    None => b.topology.adjacent_indices(i, 5, 20),
+                };
+                if let Some(mask) = b.mask() {
+                    expected.retain(|&n| mask[i] && mask[n]);
+                }
+                assert_eq!(b.neighbors(i).collect::<Vec<usize>>(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_iter_tiles() {
+        let b = Board::from_layout("1*1\n221\n*1.").unwrap();
+        let bombs: Vec<(usize, (usize, usize))> =
+            b.iter_tiles().filter(|&(_, _, t)| t.is_bomb).map(|(i, p, _)| (i, p)).collect();
+        assert_eq!(bombs, vec![(1, (1, 0)), (6, (0, 2))]);
+        assert_eq!(b.iter_tiles().len(), 9);
+        for (i, p, tile) in &b {
+            assert_eq!(p, b.cartesian_coords(i));
+            assert_eq!(tile.adjacent_bombs, b.tiles[i].adjacent_bombs);
+        }
+    }
+
+    #[test]
+    fn test_get_tile() {
+        let mut b = Board::from_layout("1*1\n221\n*1.").unwrap();
+        assert!(b.get_tile(9).is_none() && b.get_tile_mut(9).is_none());
+        assert!(b.get_tile_at(0, 3).is_none() && b.get_tile_at_mut(3, 0).is_none());
+        b.get_tile_at_mut(2, 2).unwrap().state = TileState::Flagged;
+        assert!(matches!(b.get_tile(8).unwrap().state, TileState::Flagged));
+        b.get_tile_mut(8).unwrap().state = TileState::Hidden;
+        assert!(matches!(b.get_tile_at(2, 2).unwrap().state, TileState::Hidden));
+    }
+
+    #[test]
+    fn test_counts() {
+        let mut b = Board::restore("1*1\n111\n...", "1!?\n???\n???").unwrap();
+        assert_eq!((b.hidden_count(), b.revealed_count(), b.flagged_count()), (7, 1, 1));
+        assert_eq!(b.mines_remaining(), 0);
+        assert_eq!(b.progress(), 0.125);
+        b.reveal_tile(8).unwrap();
+        assert_eq!((b.hidden_count(), b.revealed_count(), b.flagged_count()), (1, 7, 1));
+        b.reveal_tile(2).unwrap();
+        assert_eq!(b.progress(), 1.0);
+        assert_eq!(Board::new(9, 9, 10).progress(), 0.0);
+    }
+
+    #[test]
+    fn test_state() {
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+        assert_eq!(b.state(), GameState::Ready);
+        b.reveal_tile(8).unwrap();
+        assert_eq!(b.state(), GameState::Playing);
+        let mut lost = b.clone();
+        lost.reveal_tile(1).unwrap();
+        assert_eq!(lost.state(), GameState::Lost);
+        b.reveal_tile(0).unwrap();
+        b.reveal_tile(2).unwrap();
+        assert_eq!(b.state(), GameState::Won);
+    }
+
+    #[test]
+    fn test_resolve_loss() {
+        let mut b = Board::restore("1*1\n221\n*1.", "!?!\n???\n!?.").unwrap();
+
+        // Nothing happens until the game is lost
+        b.resolve_loss();
+        assert_eq!(b.visible_string(), "!?!\n???\n!?.\n");
+
+        b.reveal_tile(1).unwrap();
+        assert_eq!(b.state(), GameState::Lost);
+        assert_eq!(b.visible_string(), "X*X\n???\n!?.\n");
+
+        // The visible format keeps the result
+        let restored = Board::restore(&b.layout_string(), &b.visible_string()).unwrap();
+        assert_eq!(restored.visible_string(), b.visible_string());
+        assert!(Board::restore("1*1\n221\n*1.", "#??\n???\n???").is_err());
+    }
+}
@@ -0,0 +1,936 @@
+#![warn(missing_docs)]
+//! [Re-exported] Assembling a `Board` from configuration that is only
+//! known piece by piece.
+//!
+//! `Board::new` panics on a bad configuration, which suits hard-coded
+//! sizes but not values typed into a form. A `BoardBuilder` collects the
+//! same settings (and a few more) one at a time, and only checks them
+//! when the `Board` is built, returning a `BuildError` that can be shown
+//! to the user.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{BoardBuilder, BuildError, FirstClick};
+//!
+//! let mut builder = BoardBuilder::new().width(30).height(16);
+//! builder = builder.mines(99).first_click(FirstClick::Safe);
+//! let b = builder.build().unwrap();
+//! assert_eq!((b.width, b.height, b.num_mines), (30, 16, 99));
+//!
+//! let builder = BoardBuilder::new().width(3).height(3).mines(9);
+//! let err = builder.first_click(FirstClick::Safe).build().unwrap_err();
+//! assert_eq!(err, BuildError::TooManyMines { mines: 9, max: 8 });
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use board::Board;
+use clock::GameClock;
+use generate;
+use topology::{Kernel, Topology};
+
+/// The most `Tiles` a `BoardBuilder` will build a `Board` with, so that
+/// sizes from untrusted input can't exhaust memory.
+pub const MAX_TILES: usize = 1 << 24;
+
+/// What is guaranteed about the first `Tile` revealed on a `Board`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FirstClick {
+    /// The first `Tile` and all of its neighbours are safe, so the
+    /// first reveal always opens up an area. This is the default.
+    #[default]
+    Opening,
+    /// Only the first `Tile` itself is safe.
+    Safe,
+    /// No guarantee; the first `Tile` may be a bomb.
+    Unprotected,
+}
+
+impl FirstClick {
+    /// Returns the most bombs a `Board` with `num_tiles` `Tiles` can
+    /// have under this policy.
+    pub fn max_mines(&self, num_tiles: usize) -> usize {
+        match *self {
+            // Matches the limit imposed by `Board::new`
+            FirstClick::Opening => num_tiles.saturating_sub(10),
+            FirstClick::Safe => num_tiles.saturating_sub(1),
+            FirstClick::Unprotected => num_tiles,
+        }
+    }
+
+    // The Tiles that must not be bombs when `index`, next to
+    // `adjacent`, is revealed first
+    pub(crate) fn protected(&self, index: usize, adjacent: Vec<usize>) -> Vec<usize> {
+        match *self {
+            FirstClick::Opening => {
+                let mut protected = adjacent;
+                protected.push(index);
+                protected
+            }
+            FirstClick::Safe => vec![index],
+            FirstClick::Unprotected => Vec::new(),
+        }
+    }
+}
+
+/// A rare pattern that a `Board` can be made to include, for novelty
+/// and achievement hunting. See `BoardBuilder::pattern`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Pattern {
+    /// A safe `Tile` with a bomb on all eight sides.
+    Eight,
+    /// An opening walled in by bombs: an empty `Tile` whose neighbours
+    /// are all safe, with a bomb on every `Tile` two steps away. The
+    /// edge of the `Board` can stand in for part of the wall.
+    EnclosedOpening,
+}
+
+impl Pattern {
+    // Every way of placing the pattern on a `width`x`height` grid, as
+    // the Tiles that must be bombs and the Tiles that must be safe
+    pub(crate) fn sites(&self, width: usize, height: usize) -> Vec<(Vec<usize>, Vec<usize>)> {
+        let ring = |x: usize, y: usize, distance: usize| {
+            let mut ring = Vec::new();
+            for ny in y.saturating_sub(distance)..(y + distance + 1).min(height) {
+                for nx in x.saturating_sub(distance)..(x + distance + 1).min(width) {
+                    if nx.abs_diff(x).max(ny.abs_diff(y)) == distance {
+                        ring.push(ny * width + nx);
+                    }
+                }
+            }
+            ring
+        };
+
+        let mut sites = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let centre = y * width + x;
+                match *self {
+                    Pattern::Eight => {
+                        if x > 0 && y > 0 && x + 1 < width && y + 1 < height {
+                            sites.push((ring(x, y, 1), vec![centre]));
+                        }
+                    }
+                    Pattern::EnclosedOpening => {
+                        let mut safe = ring(x, y, 1);
+                        safe.push(centre);
+                        sites.push((ring(x, y, 2), safe));
+                    }
+                }
+            }
+        }
+        sites
+    }
+
+    // The fewest bombs the pattern can be placed with
+    fn min_mines(&self, width: usize, height: usize) -> usize {
+        self.sites(width, height).iter().map(|site| site.0.len()).min().unwrap_or(0)
+    }
+}
+
+/// Where a `Board`'s bombs may, must, and mustn't go, for levels that
+/// are designed rather than left to chance. See
+/// `BoardBuilder::placement`.
+///
+/// Every `Tile` is named by its index. The required bombs are placed
+/// first, even around the first click, so a level should keep its
+/// first click well away from them. The rest of the bombs are then
+/// spread over the other `Tiles` the first-click policy allows, never
+/// on a forbidden `Tile`, and with each `Tile` as likely to get one as
+/// its weight says.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Placement {
+    /// The `Tiles` that are always bombs.
+    pub required: Vec<usize>,
+    /// The `Tiles` that are never bombs.
+    pub forbidden: Vec<usize>,
+    /// The weights of parts of the `Board`. A `Tile` outside every
+    /// region has a weight of 1, and a `Tile` in more than one has the
+    /// weight of the last. A `Tile` with a weight of 0 only gets a bomb
+    /// once there is nowhere else left.
+    pub regions: Vec<Region>,
+}
+
+/// A part of the `Board` whose `Tiles` are more or less likely to get a
+/// bomb than the rest. See `Placement`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Region {
+    /// The indices of the `Tiles` in the region.
+    pub tiles: Vec<usize>,
+    /// How likely each `Tile` is to get a bomb, relative to the others:
+    /// a `Tile` with a weight of 3 is three times as likely as one with
+    /// a weight of 1.
+    pub weight: u32,
+}
+
+impl Placement {
+    // The first Tile named that isn't in play on a `num_tiles` Board
+    // (whose mask is `mask`), or that is both required and forbidden
+    fn bad_tile(&self, num_tiles: usize, mask: Option<&[bool]>) -> Option<usize> {
+        let in_play = |i: usize| i < num_tiles && mask.is_none_or(|mask| mask[i]);
+        let regions = self.regions.iter().flat_map(|r| r.tiles.iter());
+        self.required
+            .iter()
+            .chain(&self.forbidden)
+            .chain(regions)
+            .cloned()
+            .find(|&i| !in_play(i))
+            .or_else(|| self.required.iter().cloned().find(|i| self.forbidden.contains(i)))
+    }
+
+    // The weight of each of a `num_tiles` Board's Tiles
+    pub(crate) fn weights(&self, num_tiles: usize) -> Vec<u64> {
+        let mut weights = vec![1; num_tiles];
+        for region in &self.regions {
+            for &i in &region.tiles {
+                weights[i] = u64::from(region.weight);
+            }
+        }
+        weights
+    }
+}
+
+/// The ways a `BoardBuilder` configuration can be invalid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BuildError {
+    /// The `Board` is narrower or shorter than 3x3.
+    TooSmall {
+        /// The requested width.
+        width: usize,
+        /// The requested height.
+        height: usize,
+    },
+    /// The `Board` would have more than `MAX_TILES` `Tiles`.
+    TooLarge {
+        /// The requested width.
+        width: usize,
+        /// The requested height.
+        height: usize,
+    },
+    /// There are more bombs than the first-click policy leaves room
+    /// for.
+    TooManyMines {
+        /// The requested number of bombs.
+        mines: usize,
+        /// The most bombs that fit.
+        max: usize,
+    },
+    /// There are fewer bombs than the required patterns (or `Tiles`)
+    /// need.
+    TooFewMines {
+        /// The requested number of bombs.
+        mines: usize,
+        /// The fewest bombs the patterns can be placed with.
+        min: usize,
+    },
+    /// One of the patterns can't be placed on the `Board` at all.
+    /// Patterns only fit on a `Topology::Square` `Board` without a
+    /// `Kernel`, a mask, anti-mines, or a `Placement`.
+    PatternUnsupported,
+    /// The mask doesn't have one entry per `Tile`.
+    MaskSize {
+        /// The number of `Tiles` on the `Board`.
+        expected: usize,
+        /// The number of entries in the mask.
+        found: usize,
+    },
+    /// The `Placement` names a `Tile` that isn't in play, or makes one
+    /// both required and forbidden.
+    PlacementTile {
+        /// The index of the `Tile`.
+        index: usize,
+    },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BuildError::TooSmall { width, height } => {
+                write!(f,
+                       "The board is too small: {}x{}, but it must be at least 3x3.",
+                       width,
+                       height)
+            }
+            BuildError::TooLarge { width, height } => {
+                write!(f,
+                       "The board is too large: {}x{}, but it can have at most {} tiles.",
+                       width,
+                       height,
+                       MAX_TILES)
+            }
+            BuildError::TooManyMines { mines, max } => {
+                write!(f,
+                       "There are too many mines: {}, but at most {} fit.",
+                       mines,
+                       max)
+            }
+            BuildError::TooFewMines { mines, min } => {
+                write!(f,
+                       "There are too few mines for the patterns: {}, but they need at least {}.",
+                       mines,
+                       min)
+            }
+            BuildError::PatternUnsupported => {
+                write!(f, "The patterns can't be placed on a board like this.")
+            }
+            BuildError::MaskSize { expected, found } => {
+                write!(f,
+                       "The mask has {} entries, but the board has {} tiles.",
+                       found,
+                       expected)
+            }
+            BuildError::PlacementTile { index } => {
+                write!(f,
+                       "Tile {} can't be placed as asked: it isn't in play, or is both required \
+                        and forbidden.",
+                       index)
+            }
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+/// The settings that decide how a `Board` plays, short of where its
+/// bombs are.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BoardConfig {
+    /// The horizontal width.
+    pub width: usize,
+    /// The vertical height.
+    pub height: usize,
+    /// The number of bombs.
+    pub num_mines: usize,
+    /// What is guaranteed about the first `Tile` revealed.
+    pub first_click: FirstClick,
+    /// Which `Tiles` are adjacent to each other.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub topology: Topology,
+}
+
+impl BoardConfig {
+    /// Returns a `BoardBuilder` with these settings.
+    pub fn builder(&self) -> BoardBuilder {
+        BoardBuilder::new()
+            .width(self.width)
+            .height(self.height)
+            .mines(self.num_mines)
+            .first_click(self.first_click)
+            .topology(self.topology)
+    }
+}
+
+/// Collects the settings for a new `Board`. Every setting has a
+/// default, matching `Board::default()`.
+#[derive(Clone, Debug)]
+pub struct BoardBuilder {
+    width: usize,
+    height: usize,
+    num_mines: usize,
+    seed: Option<u64>,
+    first_click: FirstClick,
+    topology: Topology,
+    kernel: Option<Kernel>,
+    mask: Option<Vec<bool>>,
+    anti_mines: usize,
+    clock: Option<GameClock>,
+    patterns: Vec<Pattern>,
+    placement: Option<Placement>,
+}
+
+impl Default for BoardBuilder {
+    fn default() -> BoardBuilder {
+        let board = Board::default();
+        BoardBuilder {
+            width: board.width,
+            height: board.height,
+            num_mines: board.num_mines,
+            seed: None,
+            first_click: FirstClick::default(),
+            topology: Topology::default(),
+            kernel: None,
+            mask: None,
+            anti_mines: 0,
+            clock: None,
+            patterns: Vec::new(),
+            placement: None,
+        }
+    }
+}
+
+impl BoardBuilder {
+    /// Creates a builder with the default settings.
+    pub fn new() -> BoardBuilder {
+        BoardBuilder::default()
+    }
+
+    /// Sets the width of the `Board`.
+    pub fn width(mut self, width: usize) -> BoardBuilder {
+        self.width = width;
+        self
+    }
+
+    /// Sets the height of the `Board`.
+    pub fn height(mut self, height: usize) -> BoardBuilder {
+        self.height = height;
+        self
+    }
+
+    /// Sets the number of bombs on the `Board`.
+    pub fn mines(mut self, num_mines: usize) -> BoardBuilder {
+        self.num_mines = num_mines;
+        self
+    }
+
+    /// Places the bombs deterministically from `seed`. See
+    /// `Board::with_seed`.
+    pub fn seed(mut self, seed: u64) -> BoardBuilder {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets what is guaranteed about the first `Tile` revealed.
+    pub fn first_click(mut self, first_click: FirstClick) -> BoardBuilder {
+        self.first_click = first_click;
+        self
+    }
+
+    /// Sets which `Tiles` are adjacent to each other, such as
+    /// `Topology::Torus` to wrap around the edges. See the `topology`
+    /// module.
+    pub fn topology(mut self, topology: Topology) -> BoardBuilder {
+        self.topology = topology;
+        self
+    }
+
+    /// Sets the offsets of adjacent `Tiles`, in place of the
+    /// `Topology`'s, for variants such as knight's-move Minesweeper.
+    /// See the `topology` module.
+    pub fn kernel(mut self, kernel: Kernel) -> BoardBuilder {
+        self.kernel = Some(kernel);
+        self
+    }
+
+    /// Leaves the `Tiles` whose entry in `mask` is `false` out of play,
+    /// for boards that aren't rectangular. `mask` has one entry per
+    /// `Tile`, row by row. See `Board::is_in_play`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// // A donut, with a hole in the middle
+    /// let mask = (0..25).map(|i| i != 12).collect();
+    /// let b = Board::builder().width(5).height(5).mines(4).mask(mask).build().unwrap();
+    /// assert!(!b.is_in_play(12));
+    /// ```
+    pub fn mask(mut self, mask: Vec<bool>) -> BoardBuilder {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Adds `anti_mines` anti-mines to the `Board`, on top of the
+    /// bombs set by `mines`. An anti-mine is as deadly as any other
+    /// bomb, but takes one away from the numbers around it, so numbers
+    /// can be 0 next to bombs, or negative. The `Board`'s `num_mines`
+    /// counts both kinds, and `Board::config` doesn't say how many are
+    /// anti-mines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// let mut b = Board::builder().width(9).height(9).mines(8).anti_mines(2).build().unwrap();
+    /// b.reveal_tile(40).unwrap();
+    /// assert_eq!(b.num_mines, 10);
+    /// assert_eq!(b.tiles.iter().filter(|t| t.is_anti_mine).count(), 2);
+    /// ```
+    pub fn anti_mines(mut self, anti_mines: usize) -> BoardBuilder {
+        self.anti_mines = anti_mines;
+        self
+    }
+
+    /// Makes sure the `Board` includes `pattern`. Patterns are placed
+    /// first, around the first click, and the rest of the bombs are
+    /// placed as usual. `build` fails if the patterns wouldn't all fit
+    /// around some first click.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::{Board, Pattern};
+    ///
+    /// let mut b = Board::builder()
+    ///     .width(9)
+    ///     .height(9)
+    ///     .mines(10)
+    ///     .pattern(Pattern::Eight)
+    ///     .build()
+    ///     .unwrap();
+    /// b.reveal_tile(0).unwrap();
+    /// assert!(b.tiles.iter().any(|t| !t.is_bomb && t.adjacent_bombs == 8));
+    /// ```
+    pub fn pattern(mut self, pattern: Pattern) -> BoardBuilder {
+        self.patterns.push(pattern);
+        self
+    }
+
+    /// Sets where the bombs may, must, and mustn't go. See
+    /// `Placement`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::{Board, Placement, Region};
+    ///
+    /// // Bombs in the corners, none down the middle column, and the
+    /// // rest crowded towards the right
+    /// let placement = Placement {
+    ///     required: vec![0, 8, 72, 80],
+    ///     forbidden: (0..9).map(|y| y * 9 + 4).collect(),
+    ///     regions: vec![Region {
+    ///         tiles: (0..81).filter(|i| i % 9 > 4).collect(),
+    ///         weight: 5,
+    ///     }],
+    /// };
+    /// let builder = Board::builder().width(9).height(9).mines(10);
+    /// let mut b = builder.placement(placement).build().unwrap();
+    /// b.reveal_tile(38).unwrap();
+    /// assert!(b.tiles[0].is_bomb && b.tiles[80].is_bomb);
+    /// assert!(!b.tiles[4].is_bomb);
+    /// ```
+    pub fn placement(mut self, placement: Placement) -> BoardBuilder {
+        self.placement = Some(placement);
+        self
+    }
+
+    /// Sets the clock used to time the game. See `Board::with_clock`.
+    pub fn clock(mut self, clock: GameClock) -> BoardBuilder {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Checks the settings and creates the `Board`. The builder can be
+    /// used again afterwards.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the `Board` would be
+    /// smaller than 3x3 or have more than `MAX_TILES` `Tiles`, if the
+    /// mask is the wrong size, if the `Placement` names a `Tile` it
+    /// can't, if there are more bombs (of either kind) than fit in play
+    /// outside the forbidden `Tiles`, if there are too few for the
+    /// patterns or required `Tiles`, or if the patterns can't be placed
+    /// on a `Board` like this one at all.
+    pub fn build(&self) -> Result<Board, BuildError> {
+        if self.width < 3 || self.height < 3 {
+            return Err(BuildError::TooSmall {
+                width: self.width,
+                height: self.height,
+            });
+        }
+        let mut in_play = match self.width.checked_mul(self.height) {
+            Some(num_tiles) if num_tiles <= MAX_TILES => num_tiles,
+            _ => {
+                return Err(BuildError::TooLarge {
+                    width: self.width,
+                    height: self.height,
+                })
+            }
+        };
+        if let Some(ref mask) = self.mask {
+            if mask.len() != in_play {
+                return Err(BuildError::MaskSize {
+                    expected: in_play,
+                    found: mask.len(),
+                });
+            }
+            in_play = mask.iter().filter(|&&b| b).count();
+        }
+        let mut max = self.first_click.max_mines(in_play);
+        if let (Some(ref kernel), FirstClick::Opening) = (&self.kernel, self.first_click) {
+            // The opening may be bigger than the usual nine Tiles
+            max = max.min(in_play.saturating_sub(kernel.offsets().len() + 1));
+        }
+        if let Some(ref placement) = self.placement {
+            let num_tiles = self.width * self.height;
+            if let Some(index) = placement.bad_tile(num_tiles, self.mask.as_deref()) {
+                return Err(BuildError::PlacementTile { index });
+            }
+            let mut forbidden = placement.forbidden.clone();
+            forbidden.sort_unstable();
+            forbidden.dedup();
+            // The first click may protect none of them
+            max = max.saturating_sub(forbidden.len());
+        }
+        let num_mines = self.num_mines + self.anti_mines;
+        if num_mines > max {
+            return Err(BuildError::TooManyMines {
+                mines: num_mines,
+                max,
+            });
+        }
+        let mut min = 0;
+        for pattern in &self.patterns {
+            if self.topology != Topology::Square || self.kernel.is_some() || self.mask.is_some() ||
+               self.anti_mines > 0 || self.placement.is_some() ||
+               pattern.sites(self.width, self.height).is_empty() {
+                return Err(BuildError::PatternUnsupported);
+            }
+            min += pattern.min_mines(self.width, self.height);
+        }
+        if let Some(ref placement) = self.placement {
+            let mut required = placement.required.clone();
+            required.sort_unstable();
+            required.dedup();
+            min = min.max(required.len());
+        }
+        if self.num_mines < min {
+            return Err(BuildError::TooFewMines {
+                mines: self.num_mines,
+                min,
+            });
+        }
+
+        let mut board = Board::unchecked(self.width, self.height, num_mines);
+        board.num_anti_mines = self.anti_mines;
+        board.first_click = self.first_click;
+        board.patterns = self.patterns.clone();
+        board.topology = self.topology;
+        board.kernel = self.kernel.clone();
+        board.mask = self.mask.clone();
+        board.placement = self.placement.clone();
+        if let Some(seed) = self.seed {
+            board = board.with_seed(seed);
+        }
+        if let Some(ref clock) = self.clock {
+            board = board.with_clock(clock.clone());
+        }
+        if !self.patterns.is_empty() && !self.patterns_always_fit(&board) {
+            return Err(BuildError::PatternUnsupported);
+        }
+        Ok(board)
+    }
+
+    // Whether the patterns fit on `board` wherever its first click is.
+    // Any one placement of them that leaves room for the rest of the
+    // bombs works for every first click that keeps clear of it, so
+    // only the others need a search of their own
+    fn patterns_always_fit(&self, board: &Board) -> bool {
+        let length = board.tiles.len();
+        let fit = |forced: &mut [Option<bool>]| {
+            generate::fit_patterns(&self.patterns,
+                                   self.width,
+                                   self.height,
+                                   forced,
+                                   self.num_mines,
+                                   &mut |_| {})
+        };
+        let mut anywhere = vec![None; length];
+        let remaining = match fit(&mut anywhere) {
+            Some(remaining) => remaining,
+            None => return false,
+        };
+        let free = anywhere.iter().filter(|f| f.is_none()).count();
+        (0..length).all(|first_click| {
+            let protected = board.protected_tiles(first_click);
+            let clear = protected.iter().all(|&i| anywhere[i] != Some(true)) &&
+                        free - protected.iter().filter(|&&i| anywhere[i].is_none()).count() >=
+                        remaining;
+            if clear {
+                return true;
+            }
+            let mut forced = vec![None; length];
+            for &i in &protected {
+                forced[i] = Some(false);
+            }
+            fit(&mut forced).is_some()
+        })
+    }
+}
+
+impl Board {
+    /// Returns a `BoardBuilder` with the default settings.
+    pub fn builder() -> BoardBuilder {
+        BoardBuilder::new()
+    }
+
+    /// Returns the settings this `Board` was made with.
+    pub fn config(&self) -> BoardConfig {
+        BoardConfig {
+            width: self.width,
+            height: self.height,
+            num_mines: self.num_mines,
+            first_click: self.first_click,
+            topology: self.topology,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tile::TileState;
+
+    #[test]
+    fn test_build_errors() {
+        struct Test {
+            width: usize,
+            height: usize,
+            mines: usize,
+            first_click: FirstClick,
+            expected: Option<BuildError>,
+        }
+
+        let tests = [Test {
+                         width: 2,
+                         height: 9,
+                         mines: 1,
+                         first_click: FirstClick::Opening,
+                         expected: Some(BuildError::TooSmall {
+                             width: 2,
+                             height: 9,
+                         }),
+                     },
+                     Test {
+                         width: 4,
+                         height: 4,
+                         mines: 6,
+                         first_click: FirstClick::Opening,
+                         expected: None,
+                     },
+                     Test {
+                         width: 4,
+                         height: 4,
+                         mines: 7,
+                         first_click: FirstClick::Opening,
+                         expected: Some(BuildError::TooManyMines { mines: 7, max: 6 }),
+                     },
+                     Test {
+                         width: 4,
+                         height: 4,
+                         mines: 15,
+                         first_click: FirstClick::Safe,
+                         expected: None,
+                     },
+                     Test {
+                         width: 4,
+                         height: 4,
+                         mines: 16,
+                         first_click: FirstClick::Unprotected,
+                         expected: None,
+                     },
+                     Test {
+                         width: MAX_TILES,
+                         height: 2 * MAX_TILES,
+                         mines: 1,
+                         first_click: FirstClick::Opening,
+                         expected: Some(BuildError::TooLarge {
+                             width: MAX_TILES,
+                             height: 2 * MAX_TILES,
+                         }),
+                     },
+                     Test {
+                         // Would overflow
+                         width: usize::MAX / 2,
+                         height: usize::MAX / 2,
+                         mines: 1,
+                         first_click: FirstClick::Opening,
+                         expected: Some(BuildError::TooLarge {
+                             width: usize::MAX / 2,
+                             height: usize::MAX / 2,
+                         }),
+                     }];
+
+        for test in &tests {
+            let result = BoardBuilder::new()
+                .width(test.width)
+                .height(test.height)
+                .mines(test.mines)
+                .first_click(test.first_click)
+                .build();
+            assert_eq!(result.err(), test.expected);
+        }
+    }
+
+    #[test]
+    fn test_first_click_policy() {
+        let mut b = Board::builder()
+            .width(4)
+            .height(4)
+            .mines(15)
+            .first_click(FirstClick::Safe)
+            .build()
+            .unwrap();
+        b.reveal_tile(5).unwrap();
+        assert!(!b.tiles[5].is_bomb);
+        assert!(matches!(b.tiles[5].state, TileState::Revealed));
+        assert_eq!(b.tiles[5].adjacent_bombs, 8);
+
+        let mut b = Board::builder()
+            .width(4)
+            .height(4)
+            .mines(16)
+            .first_click(FirstClick::Unprotected)
+            .build()
+            .unwrap();
+        let _ = b.reveal_tile(5);
+        assert!(b.tiles[5].is_bomb);
+    }
+
+    #[test]
+    fn test_mask() {
+        // A 5x5 donut, with 24 Tiles in play
+        let donut = || Board::builder().width(5).height(5).mask((0..25).map(|i| i != 12).collect());
+        assert_eq!(donut().mines(15).build().err(),
+                   Some(BuildError::TooManyMines { mines: 15, max: 14 }));
+        assert_eq!(donut().mask(vec![true; 24]).build().err(),
+                   Some(BuildError::MaskSize {
+                       expected: 25,
+                       found: 24,
+                   }));
+        assert_eq!(donut().pattern(Pattern::Eight).build().err(),
+                   Some(BuildError::PatternUnsupported));
+
+        for seed in 0..10 {
+            let mut b = donut().mines(14).seed(seed).build().unwrap();
+            b.reveal_tile(0).unwrap();
+            assert!(!b.tiles[12].is_bomb);
+            assert_eq!(b.tiles.iter().filter(|t| t.is_bomb).count(), 14);
+            assert_eq!(b.mask().map(|mask| mask.iter().filter(|&&m| m).count()), Some(24));
+        }
+    }
+
+    #[test]
+    fn test_placement() {
+        let builder = Board::builder().width(9).height(9).mines(10);
+        let placed = |placement: Placement| builder.clone().placement(placement).build();
+        assert_eq!(placed(Placement {
+                           required: vec![81],
+                           ..Default::default()
+                       })
+                       .err(),
+                   Some(BuildError::PlacementTile { index: 81 }));
+        assert_eq!(placed(Placement {
+                           required: vec![3],
+                           forbidden: vec![3],
+                           ..Default::default()
+                       })
+                       .err(),
+                   Some(BuildError::PlacementTile { index: 3 }));
+        assert_eq!(placed(Placement {
+                           forbidden: (0..65).collect(),
+                           ..Default::default()
+                       })
+                       .err(),
+                   Some(BuildError::TooManyMines { mines: 10, max: 6 }));
+        assert_eq!(placed(Placement {
+                           required: (0..11).collect(),
+                           ..Default::default()
+                       })
+                       .err(),
+                   Some(BuildError::TooFewMines { mines: 10, min: 11 }));
+
+        // Every bomb on the top three rows, most of them on the left
+        let placement = Placement {
+            required: vec![0],
+            forbidden: (27..81).collect(),
+            regions: vec![Region {
+                              tiles: (0..27).filter(|i| i % 9 < 3).collect(),
+                              weight: 100,
+                          },
+                          Region {
+                              tiles: vec![1, 2],
+                              weight: 0,
+                          }],
+        };
+        let mut left = 0;
+        for seed in 0..10 {
+            let mut b = placed(placement.clone()).unwrap().with_seed(seed);
+            b.reveal_tile(80).unwrap();
+            assert!(b.tiles[0].is_bomb && !b.tiles[1].is_bomb && !b.tiles[2].is_bomb);
+            assert!(b.tiles[27..].iter().all(|t| !t.is_bomb));
+            assert_eq!(b.tiles.iter().filter(|t| t.is_bomb).count(), 10);
+            left += (0..27).filter(|&i| i % 9 < 3 && b.tiles[i].is_bomb).count();
+        }
+        // Only 7 of the 9 Tiles on the left can be filled each time
+        assert!(left > 60);
+    }
+
+    #[test]
+    fn test_patterns() {
+        // Whether there is a safe Tile with every Tile one step away
+        // safe and every Tile two steps away a bomb
+        let enclosed = |b: &Board| {
+            Pattern::EnclosedOpening.sites(b.width, b.height).iter().any(|(bombs, safe)| {
+                bombs.iter().all(|&i| b.tiles[i].is_bomb) &&
+                safe.iter().all(|&i| !b.tiles[i].is_bomb)
+            })
+        };
+
+        for seed in 0..20 {
+            let mut b = Board::builder()
+                .width(16)
+                .height(16)
+                .mines(40)
+                .seed(seed)
+                .pattern(Pattern::Eight)
+                .pattern(Pattern::EnclosedOpening)
+                .build()
+                .unwrap();
+            b.reveal_tile(120).unwrap();
+            assert_eq!(b.tiles.iter().filter(|t| t.is_bomb).count(), 40);
+            assert!(b.tiles.iter().any(|t| !t.is_bomb && t.adjacent_bombs == 8));
+            assert!(enclosed(&b));
+            assert!(matches!(b.tiles[120].state, TileState::Revealed));
+        }
+
+        let result = Board::builder().width(9).height(9).mines(10).pattern(Pattern::Eight);
+        assert_eq!(result.pattern(Pattern::Eight).build().err(),
+                   Some(BuildError::TooFewMines { mines: 10, min: 16 }));
+        // Every site for an 8 is next to the middle, and would be
+        // cleared by an opening there
+        for &(size, mines) in &[(5, 10), (6, 26)] {
+            let crowded = Board::builder().width(size).height(size).mines(mines);
+            assert_eq!(crowded.pattern(Pattern::Eight).build().err(),
+                       Some(BuildError::PatternUnsupported));
+        }
+        for seed in 0..50 {
+            let mut b = Board::builder()
+                .width(5)
+                .height(5)
+                .mines(10)
+                .first_click(FirstClick::Safe)
+                .seed(seed)
+                .pattern(Pattern::Eight)
+                .build()
+                .unwrap();
+            b.reveal_tile(12).unwrap();
+            assert!(b.tiles.iter().any(|t| !t.is_bomb && t.adjacent_bombs == 8), "seed {}", seed);
+        }
+        let hex = Board::builder().width(9).height(9).mines(10).topology(Topology::Hex);
+        assert_eq!(hex.pattern(Pattern::Eight).build().err(),
+                   Some(BuildError::PatternUnsupported));
+    }
+
+    #[test]
+    fn test_seed_and_clock() {
+        let clock = GameClock::new(::clock::MockClock::new());
+        let builder = Board::builder().width(9).height(9).mines(10).seed(3).clock(clock);
+        let mut a = builder.build().unwrap();
+        let mut b = builder.build().unwrap();
+        assert_eq!(a.seed(), Some(3));
+        a.reveal_tile(40).unwrap();
+        b.reveal_tile(40).unwrap();
+        assert_eq!(a.layout_string(), b.layout_string());
+    }
+}
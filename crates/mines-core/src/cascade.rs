@@ -0,0 +1,217 @@
+#![warn(missing_docs)]
+//! Tracing how a reveal cascades, for debugging.
+//!
+//! `Board::reveal_tile` floods out from the `Tile` that was clicked,
+//! and it isn't always obvious why a particular `Tile` was (or wasn't)
+//! revealed. `trace` replays a click on a copy of the `Board` and
+//! records every `Tile` the flood fill took in, with the rule that let
+//! it in and what happened when it was revealed, as a log that can be
+//! printed or walked as a tree.
+//!
+//! The flood fill works breadth first, and takes in every hidden `Tile`
+//! next to an empty `Tile` (one with no bombs around it) that it has
+//! revealed. Flagged `Tiles` are left out, so the cascade goes around a
+//! flag next to an opening. The trace takes the `Tiles` in the same
+//! order as the real thing, and only fails where it does: on a click
+//! that can't be revealed.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::Board;
+//! use mines_core::cascade;
+//!
+//! let b = Board::from_layout("1*1\n111\n...").unwrap();
+//! let trace = cascade::trace(&b, 8);
+//! assert_eq!(trace.children(8), vec![4, 5, 7]);
+//! println!("{}", trace);
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use board::Board;
+use tile::TileState;
+
+/// Why the flood fill took in a `Tile`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Admitted {
+    /// It is the `Tile` that was clicked.
+    Clicked,
+    /// It is next to `from`, an empty `Tile` the flood fill revealed.
+    Flooded {
+        /// The `Tile` the flood fill came from.
+        from: usize,
+    },
+}
+
+/// A `Tile` the flood fill took in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CascadeStep {
+    /// The index of the `Tile`.
+    pub index: usize,
+    /// How many steps out from the click it is.
+    pub layer: usize,
+    /// Why it was taken in.
+    pub admitted: Admitted,
+    /// What happened when it was revealed.
+    pub result: Result<(), &'static str>,
+}
+
+/// The full record of a cascade, from `trace`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cascade {
+    /// The width of the `Board`, for showing coordinates.
+    pub width: usize,
+    /// Every `Tile` taken in, in the order they were revealed.
+    pub steps: Vec<CascadeStep>,
+}
+
+impl Cascade {
+    /// Returns the step for the `Tile` at `index`, if the cascade took
+    /// it in.
+    pub fn step(&self, index: usize) -> Option<&CascadeStep> {
+        self.steps.iter().find(|s| s.index == index)
+    }
+
+    /// Returns the `Tiles` the flood fill took in from the one at
+    /// `index`, in the order they were revealed.
+    pub fn children(&self, index: usize) -> Vec<usize> {
+        self.steps
+            .iter()
+            .filter(|s| matches!(s.admitted, Admitted::Flooded { from, .. } if from == index))
+            .map(|s| s.index)
+            .collect()
+    }
+
+    /// Returns the first reveal that failed, which is where the cascade
+    /// stopped, if any did.
+    pub fn error(&self) -> Option<&CascadeStep> {
+        self.steps.iter().find(|s| s.result.is_err())
+    }
+
+    fn coords(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+}
+
+impl fmt::Display for Cascade {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for step in &self.steps {
+            write!(f, "layer {}: {:?} ", step.layer, self.coords(step.index))?;
+            match step.admitted {
+                Admitted::Clicked => write!(f, "clicked")?,
+                Admitted::Flooded { from } => write!(f, "from {:?}", self.coords(from))?,
+            }
+            match step.result {
+                Ok(()) => writeln!(f)?,
+                Err(e) => writeln!(f, ", failed: {}", e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replays revealing the `Tile` at `index` on a copy of `board`, and
+/// returns what the flood fill did.
+///
+/// If `board` hasn't been generated yet, the copy is generated first,
+/// so unless it is seeded, its bombs won't be where the real game's
+/// will be.
+///
+/// # Panics
+///
+/// This function will panic if `index` is not within the bounds of the
+/// grid.
+pub fn trace(board: &Board, index: usize) -> Cascade {
+    let mut board = board.clone();
+    if !board.is_generated() {
+        board.generate(index);
+    }
+
+    let mut steps = Vec::new();
+    let mut queued = vec![false; board.tiles.len()];
+    queued[index] = true;
+    let mut queue = VecDeque::new();
+    queue.push_back((index, 0, Admitted::Clicked));
+    while let Some((i, layer, admitted)) = queue.pop_front() {
+        let result = board.tiles[i].reveal();
+        steps.push(CascadeStep {
+            index: i,
+            layer,
+            admitted,
+            result,
+        });
+        // Nothing floods out of a bomb or a number, and a click that
+        // can't be revealed stops the cascade
+        if result.is_err() {
+            break;
+        }
+        if board.tiles[i].is_bomb || !board.is_blank(i) {
+            continue;
+        }
+        for n in board.adjacent_tile_indices(i) {
+            if !queued[n] && matches!(board.tiles[n].state, TileState::Hidden) {
+                queued[n] = true;
+                queue.push_back((n, layer + 1, Admitted::Flooded { from: i }));
+            }
+        }
+    }
+
+    Cascade {
+        width: board.width,
+        steps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace() {
+        let b = Board::from_layout("....\n....\n11..\n*1..").unwrap();
+        let cascade = trace(&b, 0);
+        assert!(cascade.error().is_none());
+        assert_eq!(cascade.steps[0].admitted, Admitted::Clicked);
+        assert_eq!(cascade.step(5).unwrap().admitted, Admitted::Flooded { from: 0 });
+
+        // The same layers as the real thing
+        let mut layers: Vec<Vec<usize>> = Vec::new();
+        for step in &cascade.steps {
+            if layers.len() == step.layer {
+                layers.push(Vec::new());
+            }
+            layers[step.layer].push(step.index);
+        }
+        for layer in &mut layers {
+            layer.sort_unstable();
+        }
+        let mut real = b.clone();
+        assert_eq!(layers, real.reveal_tile_layered(0).unwrap());
+    }
+
+    #[test]
+    fn test_trace_stops() {
+        // A flag next to the opening is left out, just as for real
+        let mut b = Board::from_layout("....\n....\n11..\n*1..").unwrap();
+        b.flag_tile(10).unwrap();
+        let cascade = trace(&b, 0);
+        assert!(cascade.error().is_none());
+        assert!(cascade.step(10).is_none());
+        let mut real = b.clone();
+        let revealed = real.reveal_tile_layered(0).unwrap().concat();
+        assert_eq!(cascade.steps.len(), revealed.len());
+        assert!(matches!(real.tiles[10].state, TileState::Flagged));
+
+        // A click on the flag itself fails
+        let cascade = trace(&b, 10);
+        assert_eq!(cascade.error().unwrap().index, 10);
+        assert!(cascade.to_string().ends_with("layer 0: (2, 2) clicked, failed: Tried to reveal a \
+                                             Tile that can't be revealed!\n"));
+
+        let b = Board::from_layout("1*1\n111\n...").unwrap();
+        assert_eq!(trace(&b, 1).steps.len(), 1);
+        assert_eq!(trace(&b, 1).to_string(), "layer 0: (1, 0) clicked\n");
+    }
+}
@@ -0,0 +1,254 @@
+#![warn(missing_docs)]
+//! [Re-exported] The monotonic clock that a `Board` uses to timestamp
+//! everything that happens during a game.
+//!
+//! Front-ends should not have to agree on how they measure time for
+//! replays, statistics, and the like to line up. Instead, each `Board`
+//! owns a `GameClock`, and anything it records is stamped with a
+//! `Timestamp` read from that clock.
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+use std::time::Instant;
+
+/// A point in time on a `GameClock`, measured from the moment the
+/// clock was created.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Timestamp(Duration);
+
+impl Timestamp {
+    /// Creates a `Timestamp` that lies `elapsed` after the start of
+    /// the clock.
+    pub fn from_duration(elapsed: Duration) -> Timestamp {
+        Timestamp(elapsed)
+    }
+
+    /// Creates a `Timestamp` that lies `millis` milliseconds after the
+    /// start of the clock.
+    pub fn from_millis(millis: u64) -> Timestamp {
+        Timestamp(Duration::from_millis(millis))
+    }
+
+    /// Returns the time elapsed between the start of the clock and
+    /// this `Timestamp`.
+    pub fn as_duration(&self) -> Duration {
+        self.0
+    }
+
+    /// Returns the time elapsed between the start of the clock and
+    /// this `Timestamp`, in whole milliseconds.
+    pub fn as_millis(&self) -> u64 {
+        self.0.as_secs() * 1000 + u64::from(self.0.subsec_millis())
+    }
+
+    /// Returns the time elapsed between `earlier` and this
+    /// `Timestamp`, or a zero `Duration` if `earlier` is actually
+    /// later.
+    pub fn since(&self, earlier: Timestamp) -> Duration {
+        self.0.checked_sub(earlier.0).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{:03}s", self.0.as_secs(), self.0.subsec_millis())
+    }
+}
+
+/// A source of monotonic time.
+///
+/// Anything in the crate that needs to know the time reads it through
+/// a `Clock`, so that time-dependent behaviour can be tested
+/// deterministically with a `MockClock`, and so that targets without
+/// `std::time::Instant` (such as wasm in a browser) can supply their
+/// own source, e.g. `performance.now()`.
+///
+/// Any `Fn() -> Duration` closure can be used as a `Clock`.
+///
+/// # Examples
+///
+/// Supplying time from a closure:
+///
+/// ```
+/// use std::time::Duration;
+/// use mines_core::GameClock;
+///
+/// // Pretend this reads performance.now() in a browser
+/// let clock = GameClock::new(|| Duration::from_millis(5000));
+/// assert_eq!(clock.now().as_millis(), 0);
+/// ```
+pub trait Clock: Send + Sync {
+    /// Returns the time elapsed since an arbitrary, fixed origin. The
+    /// returned value must never decrease between calls.
+    fn elapsed(&self) -> Duration;
+}
+
+impl<F> Clock for F
+    where F: Fn() -> Duration + Send + Sync
+{
+    fn elapsed(&self) -> Duration {
+        self()
+    }
+}
+
+/// The default `Clock`, which follows real time using
+/// `std::time::Instant`, or `performance.now()` in a browser with the
+/// `wasm` feature.
+#[derive(Clone, Copy, Debug)]
+pub struct SystemClock {
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    start: Instant,
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    start: Duration,
+}
+
+impl SystemClock {
+    /// Creates a `SystemClock` whose origin is now.
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    pub fn new() -> SystemClock {
+        SystemClock { start: Instant::now() }
+    }
+
+    /// Creates a `SystemClock` whose origin is now.
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    pub fn new() -> SystemClock {
+        SystemClock { start: ::wasm::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    fn elapsed(&self) -> Duration {
+        ::wasm::now() - self.start
+    }
+}
+
+/// A `Clock` that stands still until it is explicitly advanced.
+///
+/// Cloning a `MockClock` produces a handle to the *same* clock, so a
+/// test can keep a clone and advance it after handing the original to
+/// a `Board`.
+#[derive(Clone, Debug, Default)]
+pub struct MockClock {
+    // Nanoseconds elapsed, shared between every handle to the clock
+    nanos: Arc<AtomicU64>,
+}
+
+impl MockClock {
+    /// Creates a `MockClock` that reads zero.
+    pub fn new() -> MockClock {
+        MockClock::default()
+    }
+
+    /// Moves the clock forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        let by = by.as_secs() * 1_000_000_000 + u64::from(by.subsec_nanos());
+        self.nanos.fetch_add(by, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+}
+
+/// The clock owned by a game, which hands out `Timestamps` relative
+/// to the moment it was created.
+///
+/// A `GameClock` wraps any `Clock`, defaulting to a `SystemClock`.
+/// Cloning a `GameClock` produces a handle to the same underlying
+/// `Clock` with the same origin.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use mines_core::{Board, GameClock, MockClock, Timestamp};
+///
+/// let mock = MockClock::new();
+/// let b = Board::default().with_clock(GameClock::new(mock.clone()));
+/// assert_eq!(b.now(), Timestamp::from_millis(0));
+///
+/// mock.advance(Duration::from_millis(1500));
+/// assert_eq!(b.now(), Timestamp::from_millis(1500));
+/// ```
+#[derive(Clone)]
+pub struct GameClock {
+    clock: Arc<dyn Clock>,
+    origin: Duration,
+}
+
+impl GameClock {
+    /// Creates a `GameClock` that reads time from `clock`, starting
+    /// now.
+    pub fn new<C: Clock + 'static>(clock: C) -> GameClock {
+        let origin = clock.elapsed();
+        GameClock {
+            clock: Arc::new(clock),
+            origin,
+        }
+    }
+
+    /// Returns the current time on this clock.
+    pub fn now(&self) -> Timestamp {
+        let elapsed = self.clock.elapsed();
+        Timestamp(elapsed.checked_sub(self.origin).unwrap_or_default())
+    }
+}
+
+impl Default for GameClock {
+    fn default() -> GameClock {
+        GameClock::new(SystemClock::new())
+    }
+}
+
+impl fmt::Debug for GameClock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "GameClock({})", self.now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock() {
+        let mock = MockClock::new();
+        mock.advance(Duration::from_secs(10));
+
+        // The GameClock starts counting from when it was created
+        let clock = GameClock::new(mock.clone());
+        assert_eq!(clock.now(), Timestamp::from_millis(0));
+
+        mock.advance(Duration::from_millis(250));
+        mock.advance(Duration::from_secs(2));
+        assert_eq!(clock.now(), Timestamp::from_millis(2250));
+        assert_eq!(format!("{}", clock.now()), "2.250s");
+    }
+
+    #[test]
+    fn test_system_clock() {
+        let clock = GameClock::default();
+        let first = clock.now();
+        let second = clock.now();
+        assert!(second >= first);
+        assert_eq!(first.since(second), Duration::from_secs(0));
+    }
+}
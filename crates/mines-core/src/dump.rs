@@ -0,0 +1,207 @@
+#![warn(missing_docs)]
+//! Self-contained dumps of a `Board`, for bug reports.
+//!
+//! `Board::dump_debug` writes everything needed to reproduce a problem
+//! as one block of text that can be pasted into an issue: the version
+//! of the crate, the full save from `Board::save` (settings, seed,
+//! layout, and the state of every `Tile`), and the most recent moves
+//! from the action log, if it is on. `Board::from_dump` reads it back.
+//!
+//! # Format
+//!
+//! The first line is `mines-dump` and the version of the format, and
+//! the second is the version of the crate that wrote it. Then comes the
+//! save, unchanged, and then `actions` with the number of moves that
+//! follow (or `-` if the log is off), one per line, each the time in
+//! milliseconds and the `Action`:
+//!
+//! ```text
+//! mines-dump 1
+//! crate 0.1.0
+//! mines-save 5
+//! ...
+//! actions 2
+//! 250 reveal 8
+//! 500 flag 1
+//! ```
+//!
+//! Only the last `RECENT_ACTIONS` moves are kept, so a `Board` read
+//! from a dump of a long game has just those in its log.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{Action, Board};
+//!
+//! let mut b = Board::new(9, 9, 10).with_seed(3).with_action_log();
+//! b.apply(Action::Reveal(40)).unwrap();
+//!
+//! let dump = b.dump_debug();
+//! let reproduced = Board::from_dump(&dump).unwrap();
+//! assert_eq!(reproduced.visible_string(), b.visible_string());
+//! assert_eq!(reproduced.action_log().unwrap().len(), 1);
+//! ```
+
+use action::{Action, LoggedAction};
+use board::Board;
+use clock::Timestamp;
+use save::LoadError;
+
+const HEADER: &str = "mines-dump";
+const VERSION: u32 = 1;
+
+/// The most moves from the action log that a dump keeps.
+pub const RECENT_ACTIONS: usize = 100;
+
+// The lines before the save starts
+const PREAMBLE: usize = 2;
+
+impl Board {
+    /// Returns a dump of the `Board` for a bug report. See the `dump`
+    /// module documentation for the format.
+    pub fn dump_debug(&self) -> String {
+        let mut save = Vec::new();
+        self.save(&mut save).expect("Tried to save a Board to memory and failed!");
+
+        let mut dump = format!("{} {}\ncrate {}\n", HEADER, VERSION, env!("CARGO_PKG_VERSION"));
+        dump.push_str(&String::from_utf8_lossy(&save));
+        match self.log {
+            Some(ref log) => {
+                let recent = &log[log.len().saturating_sub(RECENT_ACTIONS)..];
+                dump.push_str(&format!("actions {}\n", recent.len()));
+                for logged in recent {
+                    dump.push_str(&format!("{} {}\n", logged.at.as_millis(), logged.action));
+                }
+            }
+            None => dump.push_str("actions -\n"),
+        }
+        dump
+    }
+
+    /// Reads a `Board` back from a dump written by `dump_debug`.
+    ///
+    /// The clock is not part of a dump, so the `Board` has a fresh one,
+    /// and its action log holds only the moves that were dumped.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `dump` isn't a valid dump.
+    /// Line numbers in the error count from the start of the dump.
+    pub fn from_dump(dump: &str) -> Result<Board, LoadError> {
+        let lines: Vec<&str> = dump.lines().collect();
+        let version = match lines.first().and_then(|l| l.strip_prefix(HEADER)) {
+            Some(rest) => rest.trim().parse::<u32>().map_err(|_| LoadError::NotASave)?,
+            None => return Err(LoadError::NotASave),
+        };
+        if version > VERSION {
+            return Err(LoadError::UnsupportedVersion(version));
+        }
+        if !lines.get(1).is_some_and(|l| l.starts_with("crate ")) {
+            return Err(LoadError::BadLine { line: 2 });
+        }
+
+        let (mut board, save_lines) = Board::load_lines(&lines[PREAMBLE..]).map_err(|err| {
+            match err {
+                LoadError::BadLine { line } => LoadError::BadLine { line: line + PREAMBLE },
+                err => err,
+            }
+        })?;
+
+        let start = PREAMBLE + save_lines;
+        let count = match lines.get(start).and_then(|l| l.strip_prefix("actions ")) {
+            Some("-") => return Ok(board),
+            Some(count) => {
+                count.parse::<usize>().map_err(|_| LoadError::BadLine { line: start + 1 })?
+            }
+            None => return Err(LoadError::BadLine { line: start + 1 }),
+        };
+        // The count is only trusted as far as there are lines to back it
+        let mut log = Vec::new();
+        for number in (start + 1..).take(count) {
+            let line = lines.get(number).ok_or(LoadError::BadLine { line: number + 1 })?;
+            log.push(parse_action(line).ok_or(LoadError::BadLine { line: number + 1 })?);
+        }
+        board.log = Some(log);
+        Ok(board)
+    }
+}
+
+// Reads a line like `250 reveal 8`
+fn parse_action(line: &str) -> Option<LoggedAction> {
+    let mut parts = line.split(' ');
+    let at = Timestamp::from_millis(parts.next()?.parse().ok()?);
+    let kind = parts.next()?;
+    let index = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    let action = match kind {
+        "reveal" => Action::Reveal(index),
+        "flag" => Action::Flag(index),
+        "chord" => Action::Chord(index),
+        _ => return None,
+    };
+    Some(LoggedAction { at, action })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use clock::{GameClock, MockClock};
+
+    #[test]
+    fn test_round_trip() {
+        let clock = MockClock::new();
+        let mut b = Board::restore("1*1\n111\n...", "???\n???\n???")
+            .unwrap()
+            .with_clock(GameClock::new(clock.clone()))
+            .with_action_log();
+        for action in &[Action::Flag(1), Action::Reveal(0), Action::Chord(0)] {
+            clock.advance(Duration::from_millis(250));
+            b.apply(*action).unwrap();
+        }
+
+        let dump = b.dump_debug();
+        assert!(dump.ends_with("actions 3\n250 flag 1\n500 reveal 0\n750 chord 0\n"));
+        let reproduced = Board::from_dump(&dump).unwrap();
+        assert_eq!(reproduced.layout_string(), b.layout_string());
+        assert_eq!(reproduced.visible_string(), b.visible_string());
+        assert_eq!(reproduced.action_log(), b.action_log());
+        assert_eq!(reproduced.dump_debug(), dump);
+
+        // Without the log
+        let b = Board::new(9, 9, 10).with_seed(7);
+        let reproduced = Board::from_dump(&b.dump_debug()).unwrap();
+        assert!(reproduced.action_log().is_none());
+        assert_eq!(reproduced.seed(), Some(7));
+    }
+
+    #[test]
+    fn test_recent_only() {
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap().with_action_log();
+        for _ in 0..RECENT_ACTIONS + 5 {
+            b.apply(Action::Flag(1)).unwrap();
+        }
+        let reproduced = Board::from_dump(&b.dump_debug()).unwrap();
+        assert_eq!(reproduced.action_log().unwrap().len(), RECENT_ACTIONS);
+    }
+
+    #[test]
+    fn test_errors() {
+        let good = Board::from_layout("1*1\n111\n...").unwrap().with_action_log().dump_debug();
+        assert!(Board::from_dump(&good).is_ok());
+
+        assert!(matches!(Board::from_dump("mines-save 4"), Err(LoadError::NotASave)));
+        let newer = good.replace("mines-dump 1", "mines-dump 2");
+        assert!(matches!(Board::from_dump(&newer), Err(LoadError::UnsupportedVersion(2))));
+        let bad_moves = good.replacen("moves 0", "moves x", 1);
+        assert!(matches!(Board::from_dump(&bad_moves), Err(LoadError::BadLine { line: 6 })));
+        let missing = good.replace("actions 0", "actions 1");
+        assert!(matches!(Board::from_dump(&missing), Err(LoadError::BadLine { line: 24 })));
+        let huge = good.replace("actions 0", &format!("actions {}", usize::MAX));
+        assert!(matches!(Board::from_dump(&huge), Err(LoadError::BadLine { line: 24 })));
+        let bad_action = good.replace("actions 0\n", "actions 1\n0 dig 3\n");
+        assert!(matches!(Board::from_dump(&bad_action), Err(LoadError::BadLine { line: 24 })));
+    }
+}
@@ -0,0 +1,522 @@
+#![warn(missing_docs)]
+//! [Re-exported] Running a game on a `Board` over time.
+//!
+//! A `Board` only changes when the user does something to it. A `Game`
+//! wraps a `Board` together with rules that also act on their own as
+//! time passes. Front-ends call `Game::tick` regularly (every frame, or
+//! on a timer), and get back an `Event` for everything that happened
+//! since the last tick, stamped with the `Board`'s clock.
+//!
+//! # Moving mines
+//!
+//! **Experimental.** With `MovingMines`, some of the hidden bombs
+//! relocate at a fixed interval once the game has started. A bomb only
+//! ever moves to a hidden `Tile` with exactly the same revealed
+//! neighbours as the one it left, so every number the user has seen
+//! stays true; what they have not seen yet can change under them.
+//!
+//! # Blitz
+//!
+//! With `Blitz`, the game is played against a countdown that starts on
+//! the first reveal. Each safe reveal, and each flag placed on a bomb,
+//! can add time back. When the countdown runs out the game is lost:
+//! `Game::state` says so, and no more moves are allowed.
+//!
+//! ```
+//! use std::time::Duration;
+//! use mines_core::{Blitz, Board, EventKind, Game, GameClock, GameState, MockClock};
+//!
+//! let clock = MockClock::new();
+//! let b = Board::from_layout("1*1\n111\n...").unwrap().with_clock(GameClock::new(clock.clone()));
+//! let mut game = Game::new(b).with_blitz(Blitz::new(Duration::from_secs(10)));
+//!
+//! game.reveal(8).unwrap();
+//! clock.advance(Duration::from_secs(10));
+//! assert_eq!(game.tick()[0].kind, EventKind::TimeUp);
+//! assert_eq!(game.state(), GameState::Lost);
+//! assert!(game.reveal(0).is_err());
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//! use mines_core::{Board, EventKind, Game, GameClock, MockClock, MovingMines};
+//!
+//! let clock = MockClock::new();
+//! let b = Board::new(16, 16, 40).with_clock(GameClock::new(clock.clone()));
+//! let mut game = Game::new(b).with_moving_mines(MovingMines::new(Duration::from_secs(5), 3, 42));
+//!
+//! game.reveal(0).unwrap();
+//! clock.advance(Duration::from_secs(11));
+//! // Two rounds of up to three moves each
+//! let events = game.tick();
+//! assert!(events.len() <= 6);
+//! for event in &events {
+//!     if let EventKind::MineMoved { from, to } = event.kind {
+//!         println!("{}: a mine moved from {} to {}", event.at, from, to);
+//!     }
+//! }
+//! assert_eq!(game.board().tiles.iter().filter(|t| t.is_bomb).count(), 40);
+//! ```
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use board::{Board, GameState};
+use clock::Timestamp;
+use stats::Splits;
+use tile::TileState;
+use util::{self, SeededRng};
+
+/// Something that happened during a `Game` without the user doing it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    /// When it happened, according to the `Board`'s clock.
+    pub at: Timestamp,
+    /// What happened.
+    pub kind: EventKind,
+}
+
+/// The kinds of `Event` a `Game` can produce.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    /// A bomb moved from one hidden `Tile` to another.
+    MineMoved {
+        /// The index the bomb left.
+        from: usize,
+        /// The index the bomb moved to.
+        to: usize,
+    },
+    /// The blitz countdown ran out, and the game was lost.
+    TimeUp,
+}
+
+/// **Experimental.** The moving mines rule: every `interval` after the
+/// first reveal, up to `count` hidden bombs relocate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MovingMines {
+    /// How often the bombs move.
+    pub interval: Duration,
+    /// How many bombs try to move each time.
+    pub count: usize,
+    rng: SeededRng,
+}
+
+impl MovingMines {
+    /// Creates the rule. Which bombs move, and where, is decided by
+    /// `seed`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `interval` is zero.
+    pub fn new(interval: Duration, count: usize, seed: u64) -> MovingMines {
+        if interval == Duration::from_secs(0) {
+            panic!("Tried to move mines with no time between moves!");
+        }
+        MovingMines {
+            interval,
+            count,
+            rng: SeededRng::new(seed),
+        }
+    }
+
+    // Moves up to `count` bombs on `board`, returning (from, to) pairs
+    fn relocate(&mut self, board: &mut Board) -> Vec<(usize, usize)> {
+        let mut moved = Vec::new();
+        let length = board.tiles.len();
+        let hidden = |board: &Board, i: usize| matches!(board.tiles[i].state, TileState::Hidden);
+
+        let bombs: Vec<usize> = (0..length)
+            .filter(|&i| board.tiles[i].is_bomb && hidden(board, i))
+            .collect();
+        let picks = util::sample(bombs.len(), self.count.min(bombs.len()), &mut self.rng);
+        for pick in picks {
+            let from = bombs[pick];
+            let seen = revealed_neighbors(board, from);
+            let targets: Vec<usize> = (0..length)
+                .filter(|&i| {
+                    i != from && !board.tiles[i].is_bomb && hidden(board, i) &&
+                    revealed_neighbors(board, i) == seen
+                })
+                .collect();
+            if targets.is_empty() {
+                continue;
+            }
+            let to = targets[self.rng.below(targets.len())];
+            board.move_bomb(from, to);
+            moved.push((from, to));
+        }
+        moved
+    }
+}
+
+/// The blitz rule: the game must be won within `time_limit` of the
+/// first reveal, plus `increment` for every safe reveal and every bomb
+/// flagged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Blitz {
+    /// The time on the countdown when the game starts.
+    pub time_limit: Duration,
+    /// The time added back for each safe reveal and each bomb flagged.
+    pub increment: Duration,
+}
+
+impl Blitz {
+    /// Creates the rule with no increments.
+    pub fn new(time_limit: Duration) -> Blitz {
+        Blitz {
+            time_limit,
+            increment: Duration::from_secs(0),
+        }
+    }
+
+    /// Sets the time added back for each correct move, returning the
+    /// rule.
+    pub fn with_increment(mut self, increment: Duration) -> Blitz {
+        self.increment = increment;
+        self
+    }
+}
+
+/// **Experimental.** What one player is given to even out a `Versus`
+/// match in `mines-net`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Handicap {
+    /// Extra time, on the blitz countdown and off the finishing time.
+    pub time_bonus: Duration,
+    /// Whether to reveal the biggest opening before the match starts.
+    pub opening: bool,
+    /// How many bombs to take off the player's `Board`.
+    pub fewer_mines: usize,
+}
+
+/// A `Board` together with the rules that act on it over time.
+#[derive(Clone, Debug)]
+pub struct Game {
+    board: Board,
+    moving_mines: Option<MovingMines>,
+    blitz: Option<Blitz>,
+    // Time added to the countdown so far
+    pub(crate) bonus: Duration,
+    // The handicap time bonus, taken off the finishing time
+    handicap_time: Duration,
+    // Tiles already flagged for a bonus, so re-flagging earns nothing
+    credited: HashSet<usize>,
+    timed_out: bool,
+    // How many rounds of moves have happened so far
+    moves_done: u32,
+    splits: Splits,
+}
+
+impl Game {
+    /// Starts a game on `board`, with no timed rules.
+    pub fn new(board: Board) -> Game {
+        Game {
+            board,
+            moving_mines: None,
+            blitz: None,
+            bonus: Duration::from_secs(0),
+            handicap_time: Duration::from_secs(0),
+            credited: HashSet::new(),
+            timed_out: false,
+            moves_done: 0,
+            splits: Splits::new(),
+        }
+    }
+
+    /// **Experimental.** Turns on the moving mines rule.
+    pub fn with_moving_mines(mut self, rule: MovingMines) -> Game {
+        self.moving_mines = Some(rule);
+        self
+    }
+
+    /// Turns on the blitz rule.
+    pub fn with_blitz(mut self, rule: Blitz) -> Game {
+        self.blitz = Some(rule);
+        self
+    }
+
+    /// **Experimental.** Applies the parts of `handicap` that change the
+    /// `Game` rather than the `Board`: the time bonus. Use `Versus` to
+    /// apply the rest.
+    pub fn with_handicap(mut self, handicap: Handicap) -> Game {
+        self.bonus += handicap.time_bonus;
+        self.handicap_time = handicap.time_bonus;
+        self
+    }
+
+    /// Returns the time the game has taken, less any handicap time
+    /// bonus.
+    pub fn adjusted_time(&self) -> Duration {
+        self.board().elapsed().checked_sub(self.handicap_time).unwrap_or_default()
+    }
+
+    /// Returns how the game is going. This is the `Board`'s state,
+    /// except that running out of time loses the game.
+    pub fn state(&self) -> GameState {
+        if self.timed_out {
+            GameState::Lost
+        } else {
+            self.board.state()
+        }
+    }
+
+    /// Returns how long is left on the blitz countdown. The countdown
+    /// doesn't run until the first reveal. Returns `None` without the
+    /// blitz rule, or once the game has been won or lost on the
+    /// `Board`.
+    pub fn time_left(&self) -> Option<Duration> {
+        self.deadline()
+            .map(|deadline| deadline.checked_sub(self.board.elapsed()).unwrap_or_default())
+    }
+
+    /// Returns the `Board` being played.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns when the game reached each of the 3BV `SPLITS`.
+    pub fn splits(&self) -> &Splits {
+        &self.splits
+    }
+
+    /// Ends the game, returning the `Board`.
+    pub fn into_board(self) -> Board {
+        self.board
+    }
+
+    /// Reveals the `Tile` at `index`. See `Board::reveal_tile`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `Board::reveal_tile`. It is safe to discard this error; it is
+    /// only for the programmer.
+    pub fn reveal(&mut self, index: usize) -> Result<(), &'static str> {
+        self.check_time()?;
+        // Revealing a revealed Tile succeeds, but does nothing to earn time
+        let was_revealed = matches!(self.board.tiles[index].state, TileState::Revealed);
+        self.board.reveal_tile(index)?;
+        self.splits.observe(&self.board);
+        if !was_revealed && !self.board.tiles[index].is_bomb {
+            self.add_bonus();
+        }
+        Ok(())
+    }
+
+    /// Flags the `Tile` at `index`. See `Board::flag_tile`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `Board::flag_tile`. It is safe to discard this error; it is only
+    /// for the programmer.
+    pub fn flag(&mut self, index: usize) -> Result<(), &'static str> {
+        self.check_time()?;
+        self.board.flag_tile(index)?;
+        let flagged = matches!(self.board.tiles[index].state, TileState::Flagged);
+        if flagged && self.board.tiles[index].is_bomb && self.credited.insert(index) {
+            self.add_bonus();
+        }
+        Ok(())
+    }
+
+    /// Applies every timed rule that has come due since the last tick,
+    /// returning what happened in the order it happened.
+    ///
+    /// Nothing happens before the first reveal or after the game has
+    /// been lost. If ticks are late, every round that was missed is
+    /// applied, each stamped with the time it was due, up until the
+    /// blitz countdown ran out.
+    pub fn tick(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        let started_at = match self.board.started_at() {
+            Some(t) => t,
+            None => return events,
+        };
+        if self.timed_out {
+            return events;
+        }
+        let mut elapsed = self.board.now().since(started_at);
+        let deadline = self.deadline().filter(|&d| d <= elapsed);
+        if let Some(deadline) = deadline {
+            elapsed = deadline;
+        }
+
+        if let Some(ref mut rule) = self.moving_mines {
+            loop {
+                let due = rule.interval * (self.moves_done + 1);
+                if due > elapsed || self.board.state() == GameState::Lost {
+                    break;
+                }
+                self.moves_done += 1;
+                let at = Timestamp::from_duration(started_at.as_duration() + due);
+                for (from, to) in rule.relocate(&mut self.board) {
+                    events.push(Event {
+                        at,
+                        kind: EventKind::MineMoved { from, to },
+                    });
+                }
+            }
+        }
+
+        if let Some(deadline) = deadline {
+            self.timed_out = true;
+            events.push(Event {
+                at: Timestamp::from_duration(started_at.as_duration() + deadline),
+                kind: EventKind::TimeUp,
+            });
+        }
+        events
+    }
+
+    // The time since the first reveal at which the blitz countdown runs
+    // out, or None once the game is over anyway
+    fn deadline(&self) -> Option<Duration> {
+        let rule = self.blitz?;
+        match self.board.state() {
+            GameState::Won | GameState::Lost if !self.timed_out => None,
+            _ => Some(rule.time_limit + self.bonus),
+        }
+    }
+
+    // Fails if the blitz countdown has run out, noticing it if no tick
+    // has yet
+    fn check_time(&mut self) -> Result<(), &'static str> {
+        if !self.timed_out && self.board.started_at().is_some() &&
+           self.time_left() == Some(Duration::from_secs(0)) {
+            self.timed_out = true;
+        }
+        if self.timed_out {
+            return Err("Tried to make a move after the time ran out!");
+        }
+        Ok(())
+    }
+
+    fn add_bonus(&mut self) {
+        if let Some(rule) = self.blitz {
+            self.bonus += rule.increment;
+        }
+    }
+}
+
+// The revealed Tiles next to `index`, in order
+fn revealed_neighbors(board: &Board, index: usize) -> Vec<usize> {
+    board.adjacent_tile_indices(index)
+        .into_iter()
+        .filter(|&i| matches!(board.tiles[i].state, TileState::Revealed))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clock::{GameClock, MockClock};
+
+    #[test]
+    fn test_blitz() {
+        let clock = MockClock::new();
+        let b = Board::from_layout("1*1\n111\n...\n...")
+            .unwrap()
+            .with_clock(GameClock::new(clock.clone()));
+        let rule = Blitz::new(Duration::from_secs(5)).with_increment(Duration::from_secs(2));
+        let mut game = Game::new(b).with_blitz(rule);
+
+        // The countdown waits for the first reveal
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(game.time_left(), Some(Duration::from_secs(5)));
+        assert!(game.tick().is_empty());
+
+        game.reveal(11).unwrap();
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(game.time_left(), Some(Duration::from_secs(4)));
+
+        // Revealing it again earns no time
+        for _ in 0..100 {
+            game.reveal(11).unwrap();
+        }
+        assert_eq!(game.time_left(), Some(Duration::from_secs(4)));
+
+        // Only the first flag on a bomb earns time
+        game.flag(1).unwrap();
+        game.flag(1).unwrap();
+        game.flag(1).unwrap();
+        game.flag(0).unwrap();
+        assert_eq!(game.time_left(), Some(Duration::from_secs(6)));
+        game.flag(0).unwrap();
+
+        // Running out is noticed by a late tick, at the right time
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(game.state(), GameState::Playing);
+        let events = game.tick();
+        assert_eq!(events,
+                   vec![Event {
+                            at: Timestamp::from_millis(69_000),
+                            kind: EventKind::TimeUp,
+                        }]);
+        assert_eq!(game.state(), GameState::Lost);
+        assert!(game.tick().is_empty());
+        assert!(game.reveal(0).is_err());
+
+        // Or by the next move, without a tick
+        let clock = MockClock::new();
+        let b = Board::from_layout("1*1\n111\n...")
+            .unwrap()
+            .with_clock(GameClock::new(clock.clone()));
+        let mut game = Game::new(b).with_blitz(Blitz::new(Duration::from_secs(1)));
+        game.reveal(8).unwrap();
+        clock.advance(Duration::from_secs(1));
+        assert!(game.reveal(0).is_err());
+        assert_eq!(game.state(), GameState::Lost);
+    }
+
+    #[test]
+    fn test_blitz_stops_when_won() {
+        let clock = MockClock::new();
+        let b = Board::from_layout("1*1\n111\n...")
+            .unwrap()
+            .with_clock(GameClock::new(clock.clone()));
+        let mut game = Game::new(b).with_blitz(Blitz::new(Duration::from_secs(1)));
+        game.reveal(8).unwrap();
+        game.reveal(0).unwrap();
+        game.reveal(2).unwrap();
+        clock.advance(Duration::from_secs(5));
+        assert!(game.tick().is_empty());
+        assert_eq!(game.state(), GameState::Won);
+        assert_eq!(game.time_left(), None);
+    }
+
+    #[test]
+    fn test_moving_mines() {
+        let clock = MockClock::new();
+        let b = Board::new(16, 16, 40).with_seed(9).with_clock(GameClock::new(clock.clone()));
+        let rule = MovingMines::new(Duration::from_secs(2), 5, 1);
+        let mut game = Game::new(b).with_moving_mines(rule);
+
+        // Nothing moves before the game starts
+        clock.advance(Duration::from_secs(10));
+        assert!(game.tick().is_empty());
+
+        game.reveal(0).unwrap();
+        let visible = game.board().visible_string();
+        let before = game.board().layout_string();
+        clock.advance(Duration::from_millis(1999));
+        assert!(game.tick().is_empty());
+
+        clock.advance(Duration::from_millis(4001));
+        let events = game.tick();
+        assert!(!events.is_empty());
+        assert!(events.iter().all(|e| e.at.as_millis() % 2000 == 0));
+        assert!(events.windows(2).all(|w| w[0].at <= w[1].at));
+        assert_eq!(events.last().unwrap().at.as_millis(), 16000);
+
+        // The bombs moved, but nothing the user has seen changed, and
+        // the numbers still add up
+        assert_ne!(game.board().layout_string(), before);
+        assert_eq!(game.board().visible_string(), visible);
+        assert_eq!(game.board().tiles.iter().filter(|t| t.is_bomb).count(), 40);
+        let layout = game.board().layout_string();
+        assert_eq!(Board::from_layout(&layout).unwrap().layout_string(), layout);
+    }
+}
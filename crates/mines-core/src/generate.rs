@@ -0,0 +1,543 @@
+#![warn(missing_docs)]
+//! Generating `Boards` with guarantees about how they can be played.
+//!
+//! # No-guess boards
+//!
+//! `Board::new_no_guess` makes a `Board` that can be cleared from its
+//! first click by deduction alone: at every point, the `solver` can
+//! prove at least one more `Tile` to be safe, until every safe `Tile`
+//! has been revealed.
+//!
+//! Layouts are generated as usual and then played through with the
+//! solver. When the solver gets stuck, one of the bombs it could not
+//! pin down is moved somewhere that has not been reached yet, and the
+//! layout is played through again from the start. A layout that cannot
+//! be repaired this way is thrown away and a fresh one is generated, up
+//! to `NO_GUESS_ATTEMPTS` of them.
+//!
+//! # Rare patterns
+//!
+//! A `Board` built with `BoardBuilder::pattern` places its patterns
+//! before anything else. Each pattern goes on a random site that keeps
+//! clear of the first click and of the patterns already placed, and the
+//! remaining bombs are then spread over the rest of the `Board` as
+//! usual. Seeded `Boards` choose their sites from the seed too.
+//!
+//! # Placement
+//!
+//! A `Board` built with `BoardBuilder::placement` places its required
+//! bombs first, then the rest one at a time, each on a `Tile` chosen
+//! with odds in proportion to its weight. The weights are integers, so
+//! a seeded `Board` comes out the same everywhere.
+//!
+//! # Generators
+//!
+//! All of the above is the work of the `UniformGenerator`, which places
+//! the bombs unless a `Board` is given another `Generator` with
+//! `Board::with_generator`. A `Generator` only decides which `Tiles`
+//! are bombs; the `Board` then picks its anti-mines from among them and
+//! counts the numbers.
+//!
+//! ```
+//! extern crate mines_core;
+//! extern crate rand;
+//!
+//! use mines_core::Board;
+//! use mines_core::generate::Generator;
+//! use rand::Rng;
+//!
+//! // Places pairs of bombs at random, mirrored left to right
+//! struct Mirrored;
+//!
+//! impl Generator for Mirrored {
+//!     fn place_mines(&self, board: &mut Board, first_click: usize, rng: &mut dyn Rng) {
+//!         let protected = board.protected_tiles(first_click);
+//!         let mut placed = 0;
+//!         while placed < board.num_mines {
+//!             let i = rng.next_u32() as usize % board.tiles.len();
+//!             let mirror = i - i % board.width + board.width - 1 - i % board.width;
+//!             if i < mirror && !board.tiles[i].is_bomb && !protected.contains(&i) &&
+//!                !protected.contains(&mirror) {
+//!                 board.tiles[i].is_bomb = true;
+//!                 board.tiles[mirror].is_bomb = true;
+//!                 placed += 2;
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let mut b = Board::new(10, 10, 20).with_generator(Mirrored);
+//! b.reveal_tile(0).unwrap();
+//! for (i, tile) in b.tiles.iter().enumerate() {
+//!     assert_eq!(tile.is_bomb, b.tiles[i - i % 10 + 9 - i % 10].is_bomb);
+//! }
+//! # }
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+use self::rand::Rng;
+
+use board::{self, Board};
+use builder::{Pattern, Placement};
+use solver;
+use tile::TileState;
+use util::{self, SeededRng};
+
+extern crate rand;
+
+/// How many layouts `Board::new_no_guess` generates, and tries to
+/// repair, before it gives up.
+pub const NO_GUESS_ATTEMPTS: usize = 100;
+
+/// The error from `Board::new_no_guess` when none of its layouts could
+/// be made to solve without guessing. Usually the `Board` is too dense.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NoGuessError;
+
+impl fmt::Display for NoGuessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "No board that can be solved without guessing was found in {} attempts.",
+               NO_GUESS_ATTEMPTS)
+    }
+}
+
+impl Error for NoGuessError {}
+
+/// A way of placing the bombs on a `Board`. See the module
+/// documentation.
+///
+/// Any `Fn(&mut Board, usize, &mut dyn Rng)` closure, taking the same
+/// arguments as `place_mines`, can be used as a `Generator`.
+pub trait Generator: Send + Sync {
+    /// Makes `board.num_mines` of the `Tiles` of `board`, which has no
+    /// bombs yet, into bombs, drawing from `rng` (or from the `Board`'s
+    /// seed, if it has one and the result should be reproducible).
+    /// `first_click` is the `Tile` about to be revealed first; the
+    /// bombs should keep clear of `board.protected_tiles(first_click)`,
+    /// and off any `Tile` that isn't in play.
+    fn place_mines(&self, board: &mut Board, first_click: usize, rng: &mut dyn Rng);
+}
+
+impl<F> Generator for F
+    where F: Fn(&mut Board, usize, &mut dyn Rng) + Send + Sync
+{
+    fn place_mines(&self, board: &mut Board, first_click: usize, rng: &mut dyn Rng) {
+        self(board, first_click, rng)
+    }
+}
+
+/// The `Generator` every `Board` uses unless given another: it spreads
+/// the bombs uniformly at random over the `Tiles` the first-click
+/// policy allows, after placing any patterns (see
+/// `BoardBuilder::pattern`) or following the `Board`'s `Placement`
+/// (see `BoardBuilder::placement`). A seeded `Board` is placed from its
+/// seed alone.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UniformGenerator;
+
+impl Generator for UniformGenerator {
+    fn place_mines(&self, board: &mut Board, first_click: usize, mut rng: &mut dyn Rng) {
+        // Depending on the first-click policy, we must not put a bomb
+        // on the original tile, and maybe not on the adjacent 8 tiles
+        let invalid_locations = board.protected_tiles(first_click);
+        if !board.patterns.is_empty() {
+            let seed = board.seed.unwrap_or_else(|| rng.next_u64());
+            board.generate_with_patterns(&invalid_locations, seed);
+        } else if let Some(placement) = board.placement.clone() {
+            match board.seed {
+                Some(seed) => {
+                    board.place_by_rules(&placement, &invalid_locations, &mut SeededRng::new(seed))
+                }
+                None => board.place_by_rules(&placement, &invalid_locations, &mut rng),
+            }
+        } else {
+            let mut candidates: Vec<usize> = (0..board.tiles.len())
+                .filter(|&i| !invalid_locations.contains(&i) && board.is_in_play(i))
+                .collect();
+            if let Some(seed) = board.seed {
+                // Seeded boards must come out the same everywhere, so they
+                // are placed with the crate's deterministic sampling
+                for pick in util::seeded_sample(candidates.len(), board.num_mines, seed) {
+                    board.tiles[candidates[pick]].is_bomb = true;
+                }
+            } else {
+                // A partial Fisher-Yates shuffle, so that placing the bombs
+                // takes the same time however densely they are packed
+                for k in 0..board.num_mines {
+                    let j = Rng::gen_range(&mut rng, k, candidates.len());
+                    candidates.swap(k, j);
+                    board.tiles[candidates[k]].is_bomb = true;
+                }
+            }
+        }
+    }
+}
+
+impl Board {
+    /// Creates a new `Board` that can be solved without guessing,
+    /// starting from the `Tile` at `first_click`. That `Tile` has
+    /// already been revealed on the returned `Board`.
+    ///
+    /// Dense `Boards` need many more attempts to generate than sparse
+    /// ones; expect beginner, intermediate and expert sized `Boards` to
+    /// take milliseconds, but anything much denser than expert to take
+    /// far longer, or to fail.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::{Board, TileState};
+    /// use mines_core::solver;
+    ///
+    /// let mut b = Board::new_no_guess(9, 9, 10, 40).unwrap();
+    /// assert!(matches!(b.tiles[40].state, TileState::Revealed));
+    ///
+    /// // The solver can always find a safe Tile to reveal next
+    /// loop {
+    ///     let deductions = solver::solve(&b);
+    ///     if deductions.safe.is_empty() {
+    ///         break;
+    ///     }
+    ///     for i in deductions.safe {
+    ///         let _ = b.reveal_tile(i);
+    ///     }
+    /// }
+    /// assert!(b.tiles.iter().all(|t| t.is_bomb || matches!(t.state, TileState::Revealed)));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if none of the
+    /// `NO_GUESS_ATTEMPTS` layouts it tried could be solved without
+    /// guessing.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the same conditions as
+    /// `Board::new`, or if `first_click` is not within the bounds of
+    /// the grid.
+    pub fn new_no_guess(width: usize,
+                        height: usize,
+                        num_mines: usize,
+                        first_click: usize)
+                        -> Result<Board, NoGuessError> {
+        let mut board = Board::new(width, height, num_mines);
+        let length = width * height;
+        if first_click >= length {
+            panic!("Tried to start a Board on a Tile that wasn't within the bounds of the grid! \
+                    Index passed: {}, Number of tiles: {}",
+                   first_click,
+                   length);
+        }
+
+        // As with any other Board, nothing may be placed on or around
+        // the first click
+        let mut opening = board::adjacent_indices(first_click, width, length);
+        opening.push(first_click);
+        let candidates: Vec<usize> = (0..length).filter(|i| !opening.contains(i)).collect();
+
+        let mut rng = SeededRng::new(rand::thread_rng().next_u64());
+        let mut mines = vec![false; length];
+        for _ in 0..NO_GUESS_ATTEMPTS {
+            for mine in mines.iter_mut() {
+                *mine = false;
+            }
+            for pick in util::sample(candidates.len(), num_mines, &mut rng) {
+                mines[candidates[pick]] = true;
+            }
+
+            for _ in 0..length {
+                board.reset_with(width, height, |i| mines[i]);
+                if play_out(&mut board, first_click) {
+                    board.reset_with(width, height, |i| mines[i]);
+                    let _ = board.reveal_tile(first_click);
+                    return Ok(board);
+                }
+                if !repair(&board, &mut mines, &opening, &mut rng) {
+                    break;
+                }
+            }
+        }
+        Err(NoGuessError)
+    }
+}
+
+impl Board {
+    /// Makes `generator` place the bombs when the `Board` is generated,
+    /// returning the `Board`. See the module documentation.
+    pub fn with_generator<G: Generator + 'static>(mut self, generator: G) -> Board {
+        self.generator = Some(Arc::new(generator));
+        self
+    }
+
+    /// Returns the `Tiles` that the first-click policy keeps clear of
+    /// bombs when the `Tile` at `first_click` is revealed first.
+    pub fn protected_tiles(&self, first_click: usize) -> Vec<usize> {
+        self.first_click.protected(first_click, self.adjacent_tile_indices(first_click))
+    }
+
+    // Places the bombs as `placement` says: the required ones first, then
+    // the rest one at a time, each on a Tile picked with odds in
+    // proportion to its weight
+    pub(crate) fn place_by_rules<R: Rng>(&mut self,
+                                         placement: &Placement,
+                                         invalid: &[usize],
+                                         rng: &mut R) {
+        for &i in &placement.required {
+            self.tiles[i].is_bomb = true;
+        }
+        let mut weights = placement.weights(self.tiles.len());
+        let mut allowed = vec![false; self.tiles.len()];
+        for (i, is_allowed) in allowed.iter_mut().enumerate() {
+            *is_allowed = self.is_in_play(i) && !self.tiles[i].is_bomb && !invalid.contains(&i) &&
+                          !placement.forbidden.contains(&i);
+        }
+        let mut placed = self.tiles.iter().filter(|t| t.is_bomb).count();
+        while placed < self.num_mines {
+            let mut total: u64 =
+                (0..weights.len()).filter(|&i| allowed[i]).map(|i| weights[i]).sum();
+            if total == 0 {
+                // Only Tiles with a weight of 0 are left
+                for i in 0..weights.len() {
+                    if allowed[i] {
+                        weights[i] = 1;
+                        total += 1;
+                    }
+                }
+            }
+            let mut pick = rng.gen_range(0, total);
+            for i in 0..weights.len() {
+                if !allowed[i] {
+                    continue;
+                }
+                if pick < weights[i] {
+                    self.tiles[i].is_bomb = true;
+                    allowed[i] = false;
+                    break;
+                }
+                pick -= weights[i];
+            }
+            placed += 1;
+        }
+    }
+
+    // Places the required patterns, then the rest of the bombs, keeping
+    // clear of `protected`, choosing everything from `seed`
+    pub(crate) fn generate_with_patterns(&mut self, protected: &[usize], seed: u64) {
+        let mut rng = SeededRng::new(seed);
+        let length = self.tiles.len();
+        // None: free, Some(true): bomb, Some(false): safe
+        let mut forced: Vec<Option<bool>> = vec![None; length];
+        for &i in protected {
+            forced[i] = Some(false);
+        }
+
+        // `build` checked that the patterns fit wherever the first click
+        // is, so they are only left out of a Board made some other way
+        let height = length / self.width;
+        let remaining = fit_patterns(&self.patterns,
+                                     self.width,
+                                     height,
+                                     &mut forced,
+                                     self.num_mines,
+                                     &mut |sites| util::shuffle(sites, &mut rng))
+            .unwrap_or(self.num_mines);
+
+        // Without that check, the patterns can take up room the first
+        // click policy counted on, so fall back to any Tile that isn't
+        // already a bomb
+        let mut candidates: Vec<usize> = (0..length).filter(|&i| forced[i].is_none()).collect();
+        if candidates.len() < remaining {
+            candidates = (0..length).filter(|&i| forced[i] != Some(true)).collect();
+        }
+        for pick in util::sample(candidates.len(), remaining, &mut rng) {
+            forced[candidates[pick]] = Some(true);
+        }
+        for (tile, mine) in self.tiles.iter_mut().zip(forced) {
+            tile.is_bomb = mine == Some(true);
+        }
+        self.count_adjacent_bombs();
+    }
+}
+
+// Where a pattern can go: the Tiles that must be bombs, and the Tiles
+// that must be safe
+type Site = (Vec<usize>, Vec<usize>);
+
+// Fits every one of `patterns` onto a `width`x`height` grid, marking
+// `forced` (None: free, Some(true): bomb, Some(false): safe) with at
+// most `mines` new bombs and leaving room for the rest, and trying the
+// sites of each pattern in the order `arrange` puts them in. Returns
+// the bombs left to place, or None, with `forced` as it was, if the
+// patterns can't all fit
+pub(crate) fn fit_patterns<F>(patterns: &[Pattern],
+                              width: usize,
+                              height: usize,
+                              forced: &mut [Option<bool>],
+                              mines: usize,
+                              arrange: &mut F)
+                              -> Option<usize>
+    where F: FnMut(&mut Vec<Site>)
+{
+    let (pattern, rest) = match patterns.split_first() {
+        Some(split) => split,
+        None => {
+            let free = forced.iter().filter(|f| f.is_none()).count();
+            return if free >= mines { Some(mines) } else { None };
+        }
+    };
+    let mut sites = pattern.sites(width, height);
+    arrange(&mut sites);
+    for (bombs, safe) in &sites {
+        let new = bombs.iter().filter(|&&i| forced[i].is_none()).count();
+        if new > mines || bombs.iter().any(|&i| forced[i] == Some(false)) ||
+           safe.iter().any(|&i| forced[i] == Some(true)) {
+            continue;
+        }
+        let before: Vec<(usize, Option<bool>)> =
+            bombs.iter().chain(safe).map(|&i| (i, forced[i])).collect();
+        for &i in bombs {
+            forced[i] = Some(true);
+        }
+        for &i in safe {
+            forced[i] = Some(false);
+        }
+        if let Some(left) = fit_patterns(rest, width, height, forced, mines - new, arrange) {
+            return Some(left);
+        }
+        for (i, f) in before {
+            forced[i] = f;
+        }
+    }
+    None
+}
+
+// Reveals everything the solver can prove safe, starting from
+// `first_click`. Returns whether that cleared the Board.
+fn play_out(board: &mut Board, first_click: usize) -> bool {
+    let _ = board.reveal_tile(first_click);
+    loop {
+        let deductions = solver::solve(board);
+        if deductions.safe.is_empty() {
+            break;
+        }
+        for i in deductions.safe {
+            // Tiles earlier in the list may have flooded into this one
+            let _ = board.reveal_tile(i);
+        }
+    }
+    board.tiles
+        .iter()
+        .enumerate()
+        .all(|(i, t)| t.is_bomb || !board.is_in_play(i) || matches!(t.state, TileState::Revealed))
+}
+
+// Moves one of the bombs the solver got stuck on to a Tile that hasn't
+// been reached yet. Returns false if there is no such bomb or Tile.
+fn repair(board: &Board, mines: &mut [bool], opening: &[usize], rng: &mut SeededRng) -> bool {
+    let deductions = solver::solve(board);
+    let touches_revealed = |i: usize| {
+        board.adjacent_tile_indices(i)
+            .iter()
+            .any(|&n| matches!(board.tiles[n].state, TileState::Revealed))
+    };
+
+    let stuck: Vec<usize> = (0..mines.len())
+        .filter(|&i| {
+            mines[i] && !deductions.mines.contains(&i) &&
+            matches!(board.tiles[i].state, TileState::Hidden) && touches_revealed(i)
+        })
+        .collect();
+    let targets: Vec<usize> = (0..mines.len())
+        .filter(|&i| {
+            !mines[i] && !opening.contains(&i) &&
+            matches!(board.tiles[i].state, TileState::Hidden) && !touches_revealed(i)
+        })
+        .collect();
+    if stuck.is_empty() || targets.is_empty() {
+        return false;
+    }
+
+    mines[stuck[rng.below(stuck.len())]] = false;
+    mines[targets[rng.below(targets.len())]] = true;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_no_guess() {
+        struct Test {
+            width: usize,
+            height: usize,
+            num_mines: usize,
+            first_click: usize,
+        }
+
+        let tests = [Test {
+                         width: 9,
+                         height: 9,
+                         num_mines: 10,
+                         first_click: 0,
+                     },
+                     Test {
+                         width: 16,
+                         height: 16,
+                         num_mines: 40,
+                         first_click: 100,
+                     },
+                     Test {
+                         width: 30,
+                         height: 16,
+                         num_mines: 99,
+                         first_click: 479,
+                     }];
+
+        for test in &tests {
+            let b = Board::new_no_guess(test.width, test.height, test.num_mines, test.first_click)
+                .unwrap();
+            assert_eq!(b.num_mines, test.num_mines);
+            assert_eq!(b.tiles.iter().filter(|t| t.is_bomb).count(), test.num_mines);
+            assert!(matches!(b.tiles[test.first_click].state, TileState::Revealed));
+            assert!(b.started_at().is_some());
+
+            let mut played = b.clone();
+            assert!(play_out(&mut played, test.first_click));
+        }
+    }
+
+    #[test]
+    fn test_generator() {
+        // Fills the Board from the bottom right, ignoring the rng
+        let packed = |board: &mut Board, first_click: usize, _: &mut dyn Rng| {
+            let protected = board.protected_tiles(first_click);
+            let mut left = board.num_mines;
+            for i in (0..board.tiles.len()).rev() {
+                if left > 0 && !protected.contains(&i) {
+                    board.tiles[i].is_bomb = true;
+                    left -= 1;
+                }
+            }
+        };
+        let mut b = Board::builder()
+            .width(4)
+            .height(4)
+            .mines(5)
+            .anti_mines(1)
+            .build()
+            .unwrap()
+            .with_generator(packed);
+        b.reveal_tile(0).unwrap();
+        let bombs: Vec<usize> = (0..16).filter(|&i| b.tiles[i].is_bomb).collect();
+        assert_eq!(bombs, vec![10, 11, 12, 13, 14, 15]);
+        assert_eq!(b.tiles.iter().filter(|t| t.is_anti_mine).count(), 1);
+        b.check_invariants();
+    }
+}
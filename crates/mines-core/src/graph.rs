@@ -0,0 +1,428 @@
+#![warn(missing_docs)]
+//! [Re-exported] A `Board` of any shape, given as a graph.
+//!
+//! A `GraphBoard` has no grid at all: its cells are numbered from 0,
+//! and which of them are adjacent is given as a list of neighbours for
+//! each one. That covers geometries no `Topology` or `Kernel` can, such
+//! as pentagon tilings or the countries of a world map. The cells are
+//! ordinary `Tiles`, and revealing, flagging, chording, and the
+//! first-click opening work as on a `Board`, with each number counting
+//! the bombs among its own neighbours.
+//!
+//! Neighbour lists are taken as given, so adjacency is usually
+//! symmetric, but doesn't have to be. Duplicates are left out.
+//!
+//! Drawing a `GraphBoard` is up to the front-end, since only it knows
+//! where the cells go; `visible_cell` gives each one's symbol in the
+//! visible format of the `layout` module.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{GameState, GraphBoard};
+//!
+//! // A ring of ten cells, each next to the two on either side
+//! let ring: Vec<Vec<usize>> = (0..10).map(|i| vec![(i + 9) % 10, (i + 1) % 10]).collect();
+//!
+//! let mut b = GraphBoard::from_mines(ring.clone(), &[3]);
+//! b.reveal_tile(7).unwrap();
+//! assert_eq!(b.visible_cell(2), "1");
+//! assert_eq!(b.visible_cell(3), "?");
+//! assert_eq!(b.state(), GameState::Won);
+//!
+//! let mut b = GraphBoard::new(ring, 2).with_seed(4);
+//! b.reveal_tile(0).unwrap();
+//! assert_eq!(b.tiles.iter().filter(|t| t.is_bomb).count(), 2);
+//! ```
+
+use std::collections::VecDeque;
+
+use board::{FlagError, GameState};
+use builder::FirstClick;
+use layout;
+use tile::{Tile, TileState};
+use util;
+
+extern crate rand;
+
+use self::rand::Rng;
+
+/// A `Board` whose cells and neighbours are given as a graph. See the
+/// `graph` module documentation.
+#[derive(Clone, Debug)]
+pub struct GraphBoard {
+    /// The total number of bombs (revealed or not).
+    pub num_mines: usize,
+    /// The cells, in the order their neighbours were given.
+    pub tiles: Vec<Tile>,
+    neighbors: Vec<Vec<usize>>,
+    was_generated: bool,
+    seed: Option<u64>,
+    // Whether a bomb has been revealed, and how many safe Tiles have
+    lost: bool,
+    revealed: usize,
+}
+
+impl GraphBoard {
+    /// Creates a `GraphBoard` with `num_mines` bombs, which are placed
+    /// on the first reveal, away from the cell revealed and its
+    /// neighbours.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a neighbour isn't one of the cells,
+    /// or a cell is its own neighbour, or if there are too many mines
+    /// to leave room for the opening around any cell.
+    pub fn new(neighbors: Vec<Vec<usize>>, num_mines: usize) -> GraphBoard {
+        let neighbors = check_neighbors(neighbors);
+        let max = neighbors.iter().map(|n| neighbors.len() - n.len() - 1).min().unwrap_or(0);
+        if num_mines > max {
+            panic!("Too many mines to make a functioning board! Mines passed: {}, Maximum mines: \
+                    {}",
+                   num_mines,
+                   max);
+        }
+        GraphBoard {
+            num_mines,
+            tiles: vec![Tile::default(); neighbors.len()],
+            neighbors,
+            was_generated: false,
+            seed: None,
+            lost: false,
+            revealed: 0,
+        }
+    }
+
+    /// Creates an already generated `GraphBoard` with bombs on the cells
+    /// in `mine_indices`, and every cell hidden.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a neighbour or a mine isn't one of
+    /// the cells, or a cell is its own neighbour.
+    pub fn from_mines(neighbors: Vec<Vec<usize>>, mine_indices: &[usize]) -> GraphBoard {
+        let neighbors = check_neighbors(neighbors);
+        let mut tiles = vec![Tile::default(); neighbors.len()];
+        for &i in mine_indices {
+            tiles[i].is_bomb = true;
+        }
+        let mut board = GraphBoard {
+            num_mines: tiles.iter().filter(|t| t.is_bomb).count(),
+            tiles,
+            neighbors,
+            was_generated: true,
+            seed: None,
+            lost: false,
+            revealed: 0,
+        };
+        board.count_adjacent_bombs();
+        board
+    }
+
+    /// Places the bombs deterministically from `seed`, and returns the
+    /// `GraphBoard`. It has no effect once the bombs are placed.
+    pub fn with_seed(mut self, seed: u64) -> GraphBoard {
+        if !self.was_generated {
+            self.seed = Some(seed);
+        }
+        self
+    }
+
+    /// Returns the seed used to place the bombs, if any.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Returns the number of cells.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Returns whether there are no cells at all.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Returns whether the bombs have been placed yet.
+    pub fn is_generated(&self) -> bool {
+        self.was_generated
+    }
+
+    /// Returns how the game is going.
+    pub fn state(&self) -> GameState {
+        if self.lost {
+            GameState::Lost
+        } else if self.was_generated && self.revealed == self.len() - self.num_mines {
+            GameState::Won
+        } else if self.revealed > 0 {
+            GameState::Playing
+        } else {
+            GameState::Ready
+        }
+    }
+
+    /// Returns the neighbours of the cell at `index`, in ascending
+    /// order.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `index` isn't one of the cells.
+    pub fn neighbors(&self, index: usize) -> &[usize] {
+        &self.neighbors[index]
+    }
+
+    /// Returns what the user can see of the cell at `index`, in the
+    /// visible format of the `layout` module.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `index` isn't one of the cells.
+    pub fn visible_cell(&self, index: usize) -> String {
+        layout::visible_cell(&self.tiles[index])
+    }
+
+    /// Reveals the cell at `index`, placing the bombs first if this is
+    /// the first reveal, and floods out from it as `Board::reveal_tile`
+    /// does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any `Tile` was not in a
+    /// revealable `TileState`, such as if it was flagged. It is safe to
+    /// discard this error; it is only for the programmer.
+    pub fn reveal_tile(&mut self, index: usize) -> Result<(), &'static str> {
+        if !self.was_generated {
+            self.place_bombs(index);
+        }
+        if matches!(self.tiles[index].state, TileState::Revealed) {
+            return Ok(());
+        }
+        self.reveal_one(index)?;
+        if self.tiles[index].is_bomb {
+            // Nothing floods out of a bomb; the game is over
+            return Ok(());
+        }
+
+        let mut queued = vec![false; self.len()];
+        queued[index] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back(index);
+        while let Some(i) = queue.pop_front() {
+            if i != index {
+                self.reveal_one(i)?;
+            }
+            if self.tiles[i].adjacent_bombs != 0 {
+                continue;
+            }
+            for k in 0..self.neighbors[i].len() {
+                let n = self.neighbors[i][k];
+                if !queued[n] && !matches!(self.tiles[n].state, TileState::Revealed) {
+                    queued[n] = true;
+                    queue.push_back(n);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Toggles a flag on the cell at `index`. See `Board::flag_tile`;
+    /// there is no flag limit.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the `GraphBoard` has not
+    /// been generated yet, or if the `Tile` was not in a flaggable
+    /// `TileState`. It is safe to discard this error; it is only for
+    /// the programmer.
+    pub fn flag_tile(&mut self, index: usize) -> Result<(), FlagError> {
+        if !self.was_generated {
+            return Err(FlagError::NotGenerated);
+        }
+        let tile = &mut self.tiles[index];
+        tile.state = match tile.state {
+            TileState::Hidden => TileState::Flagged,
+            TileState::Flagged => TileState::Hidden,
+            _ => return Err(FlagError::NotFlaggable),
+        };
+        Ok(())
+    }
+
+    /// Reveals every hidden, unflagged neighbour of the revealed number
+    /// at `index`, as `Board::chord_tile` does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the `Tile` is not a
+    /// revealed number, or if the number of flags around it doesn't
+    /// match. It is safe to discard this error; it is only for the
+    /// programmer.
+    pub fn chord_tile(&mut self, index: usize) -> Result<(), &'static str> {
+        let tile = &self.tiles[index];
+        if !matches!(tile.state, TileState::Revealed) || tile.is_bomb || tile.adjacent_bombs == 0 {
+            return Err("Tried to chord a Tile that isn't a revealed number!");
+        }
+        let adjacent = self.neighbors[index].clone();
+        let flags =
+            adjacent.iter().filter(|&&i| matches!(self.tiles[i].state, TileState::Flagged)).count();
+        if flags as isize != tile.adjacent_bombs {
+            return Err("Tried to chord a Tile without the right number of flags!");
+        }
+        let mut result = Ok(());
+        for i in adjacent {
+            if matches!(self.tiles[i].state, TileState::Hidden) {
+                result = result.and(self.reveal_tile(i));
+            }
+        }
+        result
+    }
+
+    fn place_bombs(&mut self, index: usize) {
+        self.was_generated = true;
+        let protected = FirstClick::Opening.protected(index, self.neighbors[index].clone());
+        let mut candidates: Vec<usize> =
+            (0..self.len()).filter(|i| !protected.contains(i)).collect();
+        match self.seed {
+            Some(seed) => {
+                for pick in util::seeded_sample(candidates.len(), self.num_mines, seed) {
+                    self.tiles[candidates[pick]].is_bomb = true;
+                }
+            }
+            None => {
+                let mut rng = rand::thread_rng();
+                for k in 0..self.num_mines {
+                    let j = rng.gen_range(k, candidates.len());
+                    candidates.swap(k, j);
+                    self.tiles[candidates[k]].is_bomb = true;
+                }
+            }
+        }
+        self.count_adjacent_bombs();
+    }
+
+    fn count_adjacent_bombs(&mut self) {
+        for index in 0..self.len() {
+            let count =
+                self.neighbors[index].iter().filter(|&&i| self.tiles[i].is_bomb).count() as isize;
+            self.tiles[index].adjacent_bombs = if self.tiles[index].is_bomb { 0 } else { count };
+        }
+    }
+
+    fn reveal_one(&mut self, index: usize) -> Result<(), &'static str> {
+        match self.tiles[index].state {
+            TileState::Hidden => {}
+            TileState::Revealed => return Ok(()),
+            _ => return Err("Tried to reveal a Tile that can't be revealed!"),
+        }
+        if self.tiles[index].is_bomb {
+            self.tiles[index].state = TileState::Detonated;
+            self.resolve_loss();
+        } else {
+            self.tiles[index].state = TileState::Revealed;
+            self.revealed += 1;
+        }
+        Ok(())
+    }
+
+    // Shows every unflagged bomb and every wrong flag, as
+    // `Board::resolve_loss` does
+    fn resolve_loss(&mut self) {
+        self.lost = true;
+        for tile in &mut self.tiles {
+            tile.state = match (tile.is_bomb, &tile.state) {
+                (true, &TileState::Hidden) => TileState::Exposed,
+                (false, &TileState::Flagged) => TileState::WrongFlag,
+                _ => continue,
+            };
+        }
+    }
+}
+
+// Sorts and dedups every neighbour list, panicking on any that don't
+// make sense
+fn check_neighbors(mut neighbors: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+    let len = neighbors.len();
+    for (index, list) in neighbors.iter_mut().enumerate() {
+        list.sort_unstable();
+        list.dedup();
+        if let Some(&bad) = list.iter().find(|&&n| n >= len || n == index) {
+            panic!("Tried to make a GraphBoard with a bad neighbour! Cell: {}, Neighbour: {}, \
+                    Number of cells: {}",
+                   index,
+                   bad,
+                   len);
+        }
+    }
+    neighbors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 2x3 grid of hexagon-ish cells, plus one cell next to every other
+    fn hub() -> Vec<Vec<usize>> {
+        vec![vec![1, 3, 6],
+             vec![0, 2, 4, 6],
+             vec![1, 5, 6],
+             vec![0, 4, 6],
+             vec![1, 3, 5, 6],
+             vec![2, 4, 6],
+             vec![0, 1, 2, 3, 4, 5]]
+    }
+
+    #[test]
+    fn test_moves() {
+        let mut b = GraphBoard::from_mines(hub(), &[2]);
+        let numbers: Vec<isize> = b.tiles.iter().map(|t| t.adjacent_bombs).collect();
+        assert_eq!(numbers, vec![0, 1, 0, 0, 0, 1, 1]);
+
+        // The opening stops at the numbers
+        b.reveal_tile(3).unwrap();
+        let visible: Vec<String> = (0..b.len()).map(|i| b.visible_cell(i)).collect();
+        assert_eq!(visible, vec![".", "1", "?", ".", ".", "1", "1"]);
+        assert_eq!(b.state(), GameState::Won);
+
+        // Chording
+        let mut b = GraphBoard::from_mines(hub(), &[2, 3]);
+        b.reveal_tile(5).unwrap();
+        assert!(b.chord_tile(5).is_err());
+        b.flag_tile(2).unwrap();
+        b.chord_tile(5).unwrap();
+        assert!(matches!(b.tiles[4].state, TileState::Revealed));
+        assert_eq!(b.state(), GameState::Playing);
+
+        // And losing
+        b.reveal_tile(3).unwrap();
+        assert_eq!(b.state(), GameState::Lost);
+        assert!(matches!(b.tiles[3].state, TileState::Detonated));
+    }
+
+    #[test]
+    fn test_generation() {
+        let ring: Vec<Vec<usize>> = (0..20).map(|i| vec![(i + 19) % 20, (i + 1) % 20]).collect();
+        for seed in 0..10 {
+            let mut a = GraphBoard::new(ring.clone(), 6).with_seed(seed);
+            let mut b = GraphBoard::new(ring.clone(), 6).with_seed(seed);
+            assert!(a.flag_tile(0).is_err());
+            a.reveal_tile(0).unwrap();
+            b.reveal_tile(0).unwrap();
+            assert_eq!(a.tiles.iter().filter(|t| t.is_bomb).count(), 6);
+            assert!([19, 0, 1].iter().all(|&i| !a.tiles[i].is_bomb));
+            let bombs = |g: &GraphBoard| g.tiles.iter().map(|t| t.is_bomb).collect::<Vec<bool>>();
+            assert_eq!(bombs(&a), bombs(&b));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bad_neighbor() {
+        GraphBoard::new(vec![vec![1], vec![0, 2]], 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_too_many_mines() {
+        // The hub's opening covers everything
+        GraphBoard::new(hub(), 1);
+    }
+}
@@ -0,0 +1,395 @@
+#![warn(missing_docs)]
+//! [Re-exported] A persistent history of games, kept in a single file.
+//!
+//! This module is only available with the `history` feature.
+//!
+//! A `HistoryStore` appends one `GameRecord` per line to a plain text
+//! file, so front-ends get a game history that survives restarts
+//! without having to pick a database. Records are never rewritten; the
+//! file only grows.
+//!
+//! # Format
+//!
+//! The first line of the file is `mines-history`. Every line after that
+//! is one record, starting with the version of the record format it was
+//! written in, so that old files keep working as the format grows. In
+//! version 1, the fields are those of `stats::write_csv`, in the same
+//! order and separated by commas:
+//!
+//! ```text
+//! mines-history
+//! 1,1500000000,9,9,10,won,61250,7,71
+//! ```
+//!
+//! Durations are stored in whole milliseconds.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//! use mines_core::{Difficulty, GameRecord, HistoryStore, Outcome, Query};
+//!
+//! let path = std::env::temp_dir().join("mines-history-doctest.txt");
+//! # let _ = std::fs::remove_file(&path);
+//! let store = HistoryStore::open(&path).unwrap();
+//! store.append(&GameRecord {
+//!     played_at: 1_500_000_000,
+//!     width: 9,
+//!     height: 9,
+//!     num_mines: 10,
+//!     outcome: Outcome::Won,
+//!     duration: Duration::from_millis(61_250),
+//!     seed: None,
+//!     revealed: 71,
+//! }).unwrap();
+//!
+//! let wins = store.query(&Query::new().difficulty(Difficulty::Beginner).outcome(Outcome::Won));
+//! assert_eq!(wins.unwrap().len(), 1);
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use stats::{Difficulty, GameRecord, Outcome};
+
+const HEADER: &str = "mines-history";
+const VERSION: u32 = 1;
+
+/// The ways reading a history file can fail.
+#[derive(Debug)]
+pub enum HistoryError {
+    /// The file could not be read or written.
+    Io(io::Error),
+    /// The file does not start with the history header.
+    NotAHistory,
+    /// A record was written by a newer version of this crate.
+    UnsupportedVersion {
+        /// The one-indexed line number of the record.
+        line: usize,
+        /// The version it was written in.
+        version: u32,
+    },
+    /// A record could not be read.
+    BadRecord {
+        /// The one-indexed line number of the record.
+        line: usize,
+    },
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HistoryError::Io(ref err) => write!(f, "Couldn't access the history: {}", err),
+            HistoryError::NotAHistory => write!(f, "The file is not a game history."),
+            HistoryError::UnsupportedVersion { line, version } => {
+                write!(f,
+                       "The record on line {} is version {}, but only up to version {} is \
+                        supported.",
+                       line,
+                       version,
+                       VERSION)
+            }
+            HistoryError::BadRecord { line } => {
+                write!(f, "The record on line {} is malformed.", line)
+            }
+        }
+    }
+}
+
+impl Error for HistoryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            HistoryError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for HistoryError {
+    fn from(err: io::Error) -> HistoryError {
+        HistoryError::Io(err)
+    }
+}
+
+/// Which records to pick out of a history. Every condition left unset
+/// matches everything.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Query {
+    difficulty: Option<Difficulty>,
+    from: Option<u64>,
+    until: Option<u64>,
+    outcome: Option<Outcome>,
+}
+
+impl Query {
+    /// Creates a query that matches every record.
+    pub fn new() -> Query {
+        Query::default()
+    }
+
+    /// Only matches games played at `difficulty`.
+    pub fn difficulty(mut self, difficulty: Difficulty) -> Query {
+        self.difficulty = Some(difficulty);
+        self
+    }
+
+    /// Only matches games played at or after `from` and before `until`,
+    /// both in seconds since the Unix epoch.
+    pub fn between(mut self, from: u64, until: u64) -> Query {
+        self.from = Some(from);
+        self.until = Some(until);
+        self
+    }
+
+    /// Only matches games that ended in `outcome`.
+    pub fn outcome(mut self, outcome: Outcome) -> Query {
+        self.outcome = Some(outcome);
+        self
+    }
+
+    /// Returns whether `record` matches every condition.
+    pub fn matches(&self, record: &GameRecord) -> bool {
+        self.difficulty.is_none_or(|d| record.difficulty() == d) &&
+        self.from.is_none_or(|from| record.played_at >= from) &&
+        self.until.is_none_or(|until| record.played_at < until) &&
+        self.outcome.is_none_or(|o| record.outcome == o)
+    }
+}
+
+/// An append-only history of games, kept in a single file.
+#[derive(Clone, Debug)]
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    /// Opens the history at `path`, creating it if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file can't be read or
+    /// created, or if it exists but isn't a history.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<HistoryStore, HistoryError> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{}", HEADER)?;
+        } else {
+            let mut first = String::new();
+            BufReader::new(file).read_line(&mut first)?;
+            if first.trim_end() != HEADER {
+                return Err(HistoryError::NotAHistory);
+            }
+        }
+        Ok(HistoryStore { path })
+    }
+
+    /// Returns the path of the history file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Adds `record` to the end of the history.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file can't be written.
+    pub fn append(&self, record: &GameRecord) -> io::Result<()> {
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        writeln!(file,
+                 "{},{},{},{},{},{},{},{},{}",
+                 VERSION,
+                 record.played_at,
+                 record.width,
+                 record.height,
+                 record.num_mines,
+                 record.outcome,
+                 record.duration_millis(),
+                 record.seed.map(|s| s.to_string()).unwrap_or_default(),
+                 record.revealed)
+    }
+
+    /// Returns every record in the history, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file can't be read, or
+    /// if any record in it is malformed or too new.
+    pub fn records(&self) -> Result<Vec<GameRecord>, HistoryError> {
+        self.query(&Query::new())
+    }
+
+    /// Returns the records that match `query`, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `records`.
+    pub fn query(&self, query: &Query) -> Result<Vec<GameRecord>, HistoryError> {
+        let mut lines = BufReader::new(File::open(&self.path)?).lines();
+        let first = match lines.next() {
+            Some(first) => first?,
+            None => return Err(HistoryError::NotAHistory),
+        };
+        if first.trim_end() != HEADER {
+            return Err(HistoryError::NotAHistory);
+        }
+
+        let mut records = Vec::new();
+        for (i, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // The header is line 1
+            let record = parse_record(&line, i + 2)?;
+            if query.matches(&record) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+}
+
+fn parse_record(line: &str, number: usize) -> Result<GameRecord, HistoryError> {
+    let bad = || HistoryError::BadRecord { line: number };
+    let fields: Vec<&str> = line.trim_end().split(',').collect();
+    let version: u32 = match fields[0].parse() {
+        Ok(v) => v,
+        Err(_) => return Err(bad()),
+    };
+    if version > VERSION {
+        return Err(HistoryError::UnsupportedVersion {
+            line: number,
+            version,
+        });
+    }
+    if fields.len() != 9 {
+        return Err(bad());
+    }
+
+    let number_at = |i: usize| fields[i].parse::<u64>().ok();
+    let outcome = match fields[5] {
+        "won" => Outcome::Won,
+        "lost" => Outcome::Lost,
+        "abandoned" => Outcome::Abandoned,
+        _ => return Err(bad()),
+    };
+    let seed = if fields[7].is_empty() {
+        None
+    } else {
+        Some(number_at(7).ok_or_else(bad)?)
+    };
+    match (number_at(1), number_at(2), number_at(3), number_at(4), number_at(6), number_at(8)) {
+        (Some(played_at), Some(width), Some(height), Some(num_mines), Some(millis),
+         Some(revealed)) => {
+            Ok(GameRecord {
+                played_at,
+                width: width as usize,
+                height: height as usize,
+                num_mines: num_mines as usize,
+                outcome,
+                duration: Duration::from_millis(millis),
+                seed,
+                revealed: revealed as usize,
+            })
+        }
+        _ => Err(bad()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let file = format!("mines-{}-{}.txt", name, ::std::process::id());
+        let path = ::std::env::temp_dir().join(file);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn record(played_at: u64, size: (usize, usize, usize), outcome: Outcome) -> GameRecord {
+        GameRecord {
+            played_at,
+            width: size.0,
+            height: size.1,
+            num_mines: size.2,
+            outcome,
+            duration: Duration::from_millis(1234),
+            seed: if played_at.is_multiple_of(2) { Some(played_at) } else { None },
+            revealed: 5,
+        }
+    }
+
+    #[test]
+    fn test_round_trip_and_queries() {
+        let path = temp_path("history-queries");
+        let records = [record(100, (9, 9, 10), Outcome::Won),
+                       record(101, (9, 9, 10), Outcome::Lost),
+                       record(200, (16, 16, 40), Outcome::Won),
+                       record(300, (30, 16, 99), Outcome::Abandoned),
+                       record(301, (10, 10, 10), Outcome::Won)];
+        {
+            let store = HistoryStore::open(&path).unwrap();
+            for r in &records {
+                store.append(r).unwrap();
+            }
+        }
+
+        // Reopening keeps what was there
+        let store = HistoryStore::open(&path).unwrap();
+        assert_eq!(store.records().unwrap(), records.to_vec());
+
+        struct Test {
+            query: Query,
+            expected: Vec<u64>,
+        }
+
+        let tests = [Test {
+                         query: Query::new().difficulty(Difficulty::Beginner),
+                         expected: vec![100, 101],
+                     },
+                     Test {
+                         query: Query::new().outcome(Outcome::Won),
+                         expected: vec![100, 200, 301],
+                     },
+                     Test {
+                         query: Query::new().between(101, 301),
+                         expected: vec![101, 200, 300],
+                     },
+                     Test {
+                         query: Query::new().difficulty(Difficulty::Custom).outcome(Outcome::Won),
+                         expected: vec![301],
+                     }];
+
+        for test in &tests {
+            let found: Vec<u64> =
+                store.query(&test.query).unwrap().iter().map(|r| r.played_at).collect();
+            assert_eq!(found, test.expected);
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_errors() {
+        let path = temp_path("history-errors");
+        fs::write(&path, "not a history\n").unwrap();
+        assert!(matches!(HistoryStore::open(&path), Err(HistoryError::NotAHistory)));
+
+        fs::write(&path, "mines-history\n1,1,9,9,10,won,5,,3\n2,1,9,9,10,won,5,,3,x\n").unwrap();
+        let store = HistoryStore::open(&path).unwrap();
+        assert!(matches!(store.records(),
+                         Err(HistoryError::UnsupportedVersion { line: 3, version: 2 })));
+
+        fs::write(&path, "mines-history\n1,1,9,9,10,won,5,,3\n1,1,9,9,10,maybe,5,,3\n").unwrap();
+        assert!(matches!(store.records(), Err(HistoryError::BadRecord { line: 3 })));
+        fs::remove_file(&path).unwrap();
+    }
+}
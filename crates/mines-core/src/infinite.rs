@@ -0,0 +1,448 @@
+#![warn(missing_docs)]
+//! [Re-exported] A `Board` without edges, for "infinite Minesweeper".
+//!
+//! An `InfiniteBoard` is made of square chunks of `CHUNK_SIZE` by
+//! `CHUNK_SIZE` `Tiles`, which are only generated when the player gets
+//! near them. Each chunk gets the same share of bombs, set by the
+//! density, and where they go depends only on the seed and the chunk's
+//! position, so the same seed always gives the same world, whichever
+//! way it is explored. The `Tiles` around the first one revealed are
+//! kept clear, as with `FirstClick::Opening`.
+//!
+//! `Tiles` are found by signed (x, y) coordinates, with y growing
+//! downwards. They are ordinary `Tiles`, and revealing, flagging, and
+//! chording work as on a `Board`. The game can be lost, but never won.
+//!
+//! Openings must come to an end somewhere, and below about one bomb in
+//! ten they may not, so the density can't be lower than `MIN_DENSITY`.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{GameState, InfiniteBoard};
+//!
+//! let mut b = InfiniteBoard::new(0.2).with_seed(7);
+//! b.reveal_tile((-1000, 250)).unwrap();
+//! assert_eq!(b.state(), GameState::Playing);
+//! assert_eq!(b.tile((-1000, 250)).unwrap().adjacent_bombs, 0);
+//!
+//! // Far away Tiles haven't been generated yet
+//! assert!(b.tile((0, 0)).is_none());
+//! println!("{}", b.visible_string((-1008, 242), 16, 16));
+//! ```
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use board::{FlagError, GameState};
+use layout;
+use render::GridFormat;
+use tile::{Tile, TileState};
+use util::{self, SeededRng};
+
+extern crate rand;
+
+use self::rand::Rng;
+
+/// The width and height of a chunk, in `Tiles`.
+pub const CHUNK_SIZE: usize = 16;
+
+/// The lowest density of bombs an `InfiniteBoard` can have.
+pub const MIN_DENSITY: f64 = 0.1;
+
+/// A `Board` that goes on forever in every direction. See the
+/// `infinite` module documentation.
+#[derive(Clone, Debug)]
+pub struct InfiniteBoard {
+    density: f64,
+    seed: u64,
+    // The first Tile revealed, once there is one
+    start: Option<(i64, i64)>,
+    // Where the bombs are in every chunk looked at so far
+    layouts: HashMap<(i64, i64), Vec<bool>>,
+    // The Tiles of every chunk that has been played on, which needs the
+    // layouts of the chunks all around it for the numbers at its edges
+    chunks: HashMap<(i64, i64), Vec<Tile>>,
+    lost: bool,
+}
+
+impl InfiniteBoard {
+    /// Creates an `InfiniteBoard` with `density` bombs per `Tile`, and
+    /// a random seed.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `density` is less than
+    /// `MIN_DENSITY`, or not less than 1.
+    pub fn new(density: f64) -> InfiniteBoard {
+        if !(MIN_DENSITY..1.0).contains(&density) {
+            panic!("Tried to make an InfiniteBoard with a density outside of {} to 1! Density \
+                    passed: {}",
+                   MIN_DENSITY,
+                   density);
+        }
+        InfiniteBoard {
+            density,
+            seed: rand::thread_rng().next_u64(),
+            start: None,
+            layouts: HashMap::new(),
+            chunks: HashMap::new(),
+            lost: false,
+        }
+    }
+
+    /// Sets the seed that places the bombs, and returns the
+    /// `InfiniteBoard`. Only has an effect before the first reveal.
+    pub fn with_seed(mut self, seed: u64) -> InfiniteBoard {
+        if self.start.is_none() {
+            self.seed = seed;
+        }
+        self
+    }
+
+    /// Returns the seed that places the bombs.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the share of `Tiles` that are bombs.
+    pub fn density(&self) -> f64 {
+        self.density
+    }
+
+    /// Returns whether the first `Tile` has been revealed yet.
+    pub fn is_generated(&self) -> bool {
+        self.start.is_some()
+    }
+
+    /// Returns how the game is going. It is never `GameState::Won`.
+    pub fn state(&self) -> GameState {
+        if self.lost {
+            GameState::Lost
+        } else if self.start.is_some() {
+            GameState::Playing
+        } else {
+            GameState::Ready
+        }
+    }
+
+    /// Returns the `Tile` at `p`, or `None` if its chunk hasn't been
+    /// generated yet, in which case it is hidden.
+    pub fn tile(&self, p: (i64, i64)) -> Option<&Tile> {
+        let (chunk, i) = split(p);
+        self.chunks.get(&chunk).map(|tiles| &tiles[i])
+    }
+
+    /// Returns the positions of the chunks generated so far, in order
+    /// from top to bottom and left to right. The chunk at (cx, cy)
+    /// holds the `Tiles` from (cx, cy) times `CHUNK_SIZE`.
+    pub fn chunks(&self) -> Vec<(i64, i64)> {
+        let mut chunks: Vec<(i64, i64)> = self.chunks.keys().cloned().collect();
+        chunks.sort_unstable_by_key(|&(x, y)| (y, x));
+        chunks
+    }
+
+    /// Returns the coordinates of the eight `Tiles` around `p`.
+    pub fn adjacent_coords(&self, p: (i64, i64)) -> Vec<(i64, i64)> {
+        adjacent_coords(p)
+    }
+
+    /// Reveals the `Tile` at `p`, and floods out from it as
+    /// `Board::reveal_tile` does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any `Tile` was not in a
+    /// revealable `TileState`, such as if it was flagged. It is safe to
+    /// discard this error; it is only for the programmer.
+    pub fn reveal_tile(&mut self, p: (i64, i64)) -> Result<(), &'static str> {
+        if self.start.is_none() {
+            self.start = Some(p);
+        }
+        if matches!(self.tile_mut(p).state, TileState::Revealed) {
+            return Ok(());
+        }
+        self.tile_mut(p).reveal()?;
+        if self.tile_mut(p).is_bomb {
+            self.resolve_loss();
+            return Ok(());
+        }
+
+        let mut queued = HashSet::new();
+        queued.insert(p);
+        let mut queue = VecDeque::new();
+        queue.push_back(p);
+        while let Some(q) = queue.pop_front() {
+            if q != p {
+                self.tile_mut(q).reveal()?;
+            }
+            if self.tile_mut(q).adjacent_bombs != 0 {
+                continue;
+            }
+            for n in adjacent_coords(q) {
+                if !queued.contains(&n) && matches!(self.tile_mut(n).state, TileState::Hidden) {
+                    queued.insert(n);
+                    queue.push_back(n);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Toggles a flag on the `Tile` at `p`. See `Board::flag_tile`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if nothing has been revealed
+    /// yet, or if the `Tile` was not in a flaggable `TileState`. It is
+    /// safe to discard this error; it is only for the programmer.
+    pub fn flag_tile(&mut self, p: (i64, i64)) -> Result<(), FlagError> {
+        if self.start.is_none() {
+            return Err(FlagError::NotGenerated);
+        }
+        self.tile_mut(p).flag().map_err(|_| FlagError::NotFlaggable)
+    }
+
+    /// Reveals every hidden, unflagged `Tile` around the revealed
+    /// number at `p`, as `Board::chord_tile` does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the `Tile` is not a
+    /// revealed number, or if the number of flags around it doesn't
+    /// match. It is safe to discard this error; it is only for the
+    /// programmer.
+    pub fn chord_tile(&mut self, p: (i64, i64)) -> Result<(), &'static str> {
+        let number = match self.tile(p) {
+            Some(t) if matches!(t.state, TileState::Revealed) && !t.is_bomb &&
+                       t.adjacent_bombs > 0 => t.adjacent_bombs,
+            _ => return Err("Tried to chord a Tile that isn't a revealed number!"),
+        };
+        let adjacent = adjacent_coords(p);
+        let flags = adjacent.iter()
+            .filter(|&&n| matches!(self.tile_mut(n).state, TileState::Flagged))
+            .count();
+        if flags as isize != number {
+            return Err("Tried to chord a Tile without the right number of flags!");
+        }
+        let mut result = Ok(());
+        for n in adjacent {
+            if matches!(self.tile_mut(n).state, TileState::Hidden) {
+                result = result.and(self.reveal_tile(n));
+            }
+        }
+        result
+    }
+
+    /// Returns what the user can see of the `height` rows of `width`
+    /// `Tiles` from `top_left`, in the visible format of
+    /// `Board::visible_string`.
+    pub fn visible_string(&self, top_left: (i64, i64), width: usize, height: usize) -> String {
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height as i64 {
+            for x in 0..width as i64 {
+                let p = (top_left.0 + x, top_left.1 + y);
+                cells.push(self.tile(p).map_or_else(|| String::from("?"), layout::visible_cell));
+            }
+        }
+        GridFormat::for_cells(&cells).render(&cells, width)
+    }
+
+    // Shows every unflagged bomb and every wrong flag in the chunks
+    // generated so far, as `Board::resolve_loss` does
+    fn resolve_loss(&mut self) {
+        self.lost = true;
+        for tile in self.chunks.values_mut().flat_map(|tiles| tiles.iter_mut()) {
+            tile.state = match (tile.is_bomb, &tile.state) {
+                (true, &TileState::Hidden) => TileState::Exposed,
+                (false, &TileState::Flagged) => TileState::WrongFlag,
+                _ => continue,
+            };
+        }
+    }
+
+    // The Tile at `p`, generating its chunk first if need be
+    fn tile_mut(&mut self, p: (i64, i64)) -> &mut Tile {
+        let (chunk, i) = split(p);
+        if !self.chunks.contains_key(&chunk) {
+            self.generate_chunk(chunk);
+        }
+        &mut self.chunks.get_mut(&chunk).expect("Tried to use a chunk that wasn't generated!")[i]
+    }
+
+    fn generate_chunk(&mut self, chunk: (i64, i64)) {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                self.place_bombs((chunk.0 + dx, chunk.1 + dy));
+            }
+        }
+        let size = CHUNK_SIZE as i64;
+        let mut tiles = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE);
+        for y in 0..size {
+            for x in 0..size {
+                let p = (chunk.0 * size + x, chunk.1 * size + y);
+                let is_bomb = self.is_bomb(p);
+                let adjacent_bombs = if is_bomb {
+                    0
+                } else {
+                    adjacent_coords(p).into_iter().filter(|&n| self.is_bomb(n)).count() as isize
+                };
+                tiles.push(Tile {
+                    adjacent_bombs,
+                    is_bomb,
+                    ..Tile::default()
+                });
+            }
+        }
+        self.chunks.insert(chunk, tiles);
+    }
+
+    // Lays out the bombs in `chunk`, if they haven't been already
+    fn place_bombs(&mut self, chunk: (i64, i64)) {
+        if self.layouts.contains_key(&chunk) {
+            return;
+        }
+        let start = self.start.expect("Tried to place bombs before the first reveal!");
+        let candidates: Vec<usize> = (0..CHUNK_SIZE * CHUNK_SIZE)
+            .filter(|&i| {
+                let (x, y) = join(chunk, i);
+                (x - start.0).abs() > 1 || (y - start.1).abs() > 1
+            })
+            .collect();
+        let count = ((self.density * (CHUNK_SIZE * CHUNK_SIZE) as f64).round() as usize)
+            .min(candidates.len());
+        let mut rng = SeededRng::new(self.seed ^ chunk_key(chunk));
+        let mut bombs = vec![false; CHUNK_SIZE * CHUNK_SIZE];
+        for pick in util::sample(candidates.len(), count, &mut rng) {
+            bombs[candidates[pick]] = true;
+        }
+        self.layouts.insert(chunk, bombs);
+    }
+
+    fn is_bomb(&self, p: (i64, i64)) -> bool {
+        let (chunk, i) = split(p);
+        self.layouts[&chunk][i]
+    }
+}
+
+// Splits `p` into the chunk it is in and its index within the chunk
+fn split(p: (i64, i64)) -> ((i64, i64), usize) {
+    let size = CHUNK_SIZE as i64;
+    let chunk = (p.0.div_euclid(size), p.1.div_euclid(size));
+    let i = p.1.rem_euclid(size) * size + p.0.rem_euclid(size);
+    (chunk, i as usize)
+}
+
+// The reverse of `split`
+fn join(chunk: (i64, i64), i: usize) -> (i64, i64) {
+    let size = CHUNK_SIZE as i64;
+    let i = i as i64;
+    (chunk.0 * size + i % size, chunk.1 * size + i / size)
+}
+
+// Mixes a chunk's position into 64 bits, so that every chunk gets its
+// own stream from the seed
+fn chunk_key(chunk: (i64, i64)) -> u64 {
+    let x = SeededRng::new(chunk.0 as u64).next_u64();
+    SeededRng::new(x ^ chunk.1 as u64).next_u64()
+}
+
+fn adjacent_coords(p: (i64, i64)) -> Vec<(i64, i64)> {
+    let mut adjacent = Vec::with_capacity(8);
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            if dx != 0 || dy != 0 {
+                adjacent.push((p.0 + dx, p.1 + dy));
+            }
+        }
+    }
+    adjacent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let size = CHUNK_SIZE as i64;
+        let tests = [((0, 0), ((0, 0), 0)),
+                     ((size - 1, 1), ((0, 0), CHUNK_SIZE * 2 - 1)),
+                     ((-1, -1), ((-1, -1), CHUNK_SIZE * CHUNK_SIZE - 1)),
+                     ((-size, size), ((-1, 1), 0))];
+        for &(p, expected) in &tests {
+            assert_eq!(split(p), expected);
+            assert_eq!(join(expected.0, expected.1), p);
+        }
+    }
+
+    #[test]
+    fn test_same_world() {
+        // The same seed gives the same bombs, whichever way it is
+        // explored
+        let mut a = InfiniteBoard::new(0.2).with_seed(5);
+        let mut b = InfiniteBoard::new(0.2).with_seed(5);
+        a.reveal_tile((3, 3)).unwrap();
+        a.reveal_tile((-40, 70)).ok();
+        b.reveal_tile((3, 3)).unwrap();
+        b.reveal_tile((100, -100)).ok();
+        b.reveal_tile((-40, 70)).ok();
+        for y in 64..80 {
+            for x in -48..-32 {
+                assert_eq!(a.tile((x, y)).unwrap().is_bomb, b.tile((x, y)).unwrap().is_bomb);
+            }
+        }
+        assert_eq!(a.visible_string((-8, -8), 24, 24), b.visible_string((-8, -8), 24, 24));
+
+        // And every chunk has its share
+        let bombs = (0..CHUNK_SIZE * CHUNK_SIZE).filter(|&i| a.is_bomb(join((0, 0), i))).count();
+        assert_eq!(bombs, 51);
+    }
+
+    #[test]
+    fn test_moves() {
+        let mut b = InfiniteBoard::new(0.3).with_seed(1);
+        assert!(b.flag_tile((0, 0)).is_err());
+        b.reveal_tile((0, 0)).unwrap();
+        for p in adjacent_coords((0, 0)) {
+            assert!(!b.tile(p).unwrap().is_bomb);
+        }
+
+        // Find a revealed number and flag all its bombs, then chord it
+        let number = (-8..8)
+            .flat_map(|y| (-8..8).map(move |x| (x, y)))
+            .find(|&p| {
+                let t = b.tile(p).unwrap();
+                matches!(t.state, TileState::Revealed) && t.adjacent_bombs > 0
+            })
+            .unwrap();
+        assert!(b.chord_tile(number).is_err());
+        for p in adjacent_coords(number) {
+            if b.tile(p).unwrap().is_bomb {
+                b.flag_tile(p).unwrap();
+            }
+        }
+        b.chord_tile(number).unwrap();
+        assert!(adjacent_coords(number).iter().all(|&p| {
+            !matches!(b.tile(p).unwrap().state, TileState::Hidden)
+        }));
+        assert_eq!(b.state(), GameState::Playing);
+
+        // Then lose
+        let bomb = (-8..8)
+            .flat_map(|y| (-8..8).map(move |x| (x, y)))
+            .find(|&p| {
+                let t = b.tile(p).unwrap();
+                t.is_bomb && matches!(t.state, TileState::Hidden)
+            })
+            .unwrap();
+        b.reveal_tile(bomb).unwrap();
+        assert_eq!(b.state(), GameState::Lost);
+        assert!(matches!(b.tile(bomb).unwrap().state, TileState::Detonated));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_too_sparse() {
+        InfiniteBoard::new(0.05);
+    }
+}
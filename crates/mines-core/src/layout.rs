@@ -0,0 +1,650 @@
+#![warn(missing_docs)]
+//! [Re-exported] Reading and writing `Boards` as text.
+//!
+//! Two formats are used, both of which are stable: unlike the output
+//! of `Debug` and `Display`, they will not change between versions of
+//! this crate.
+//!
+//! In both formats, each row of the `Board` is written on its own
+//! line, terminated by `\n`. If every cell fits in one character,
+//! the cells are written one after another; otherwise every cell is
+//! right-aligned to a common width and separated by a single space.
+//! Blank lines and surrounding whitespace are ignored when reading.
+//!
+//! # The layout format
+//!
+//! The layout format describes the hidden contents of every `Tile`:
+//!
+//! * `*` is a bomb.
+//! * `-` is an anti-mine (see `BoardBuilder::anti_mines`).
+//! * `.` is a `Tile` with no adjacent bombs.
+//! * A number is a `Tile` with that many adjacent bombs, less the
+//!   adjacent anti-mines, so it may be negative.
+//! * `_` is a `Tile` that isn't in play, on a `Board` with a mask (see
+//!   `BoardBuilder::mask`).
+//!
+//! # The visible format
+//!
+//! The visible format describes what the user can see:
+//!
+//! * `?` is a hidden `Tile`.
+//! * `!` is a flagged `Tile`.
+//! * `*` is the bomb that was revealed, losing the game, or `-` if it
+//!   was an anti-mine.
+//! * `#` is a bomb shown after the game was lost.
+//! * `X` is a wrongly flagged `Tile` shown after the game was lost.
+//! * `_` is a `Tile` that isn't in play, as in the layout format.
+//! * Anything else is a revealed `Tile`, written as in the layout
+//!   format.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::Board;
+//!
+//! let b: Board = "1*1\n111\n...".parse().unwrap();
+//! assert_eq!(b.num_mines, 1);
+//! assert_eq!(b.layout_string(), "1*1\n111\n...\n");
+//! assert_eq!(b.visible_string(), "???\n???\n???\n");
+//!
+//! let restored = Board::restore(&b.layout_string(), "?!?\n111\n...").unwrap();
+//! assert_eq!(restored.visible_string(), "?!?\n111\n...\n");
+//! ```
+//!
+//! # Mine layouts
+//!
+//! A `MineLayout` is the bare minimum needed to recreate a generated
+//! `Board`: its dimensions and where its mines are. It is cheaper to
+//! store and compare than a whole `Board`.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use board::Board;
+use render::GridFormat;
+use tile::{Tile, TileState};
+use topology::{Kernel, Topology};
+
+/// The reasons a textual layout can fail to become a `Board`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseBoardError {
+    /// The layout doesn't contain any rows.
+    Empty,
+    /// The layout is narrower or shorter than 3x3.
+    TooSmall {
+        /// The width of the layout.
+        width: usize,
+        /// The height of the layout.
+        height: usize,
+    },
+    /// A row is a different width from the first row.
+    RaggedRow {
+        /// The zero-indexed row.
+        row: usize,
+        /// The width of the first row.
+        expected: usize,
+        /// The width of this row.
+        found: usize,
+    },
+    /// A cell is not `*`, `.`, or a number.
+    InvalidCell {
+        /// The (x, y) coordinates of the cell.
+        at: (usize, usize),
+        /// The offending text.
+        found: String,
+    },
+    /// The visible state and the layout are different sizes.
+    SizeMismatch {
+        /// The width and height of the layout.
+        layout: (usize, usize),
+        /// The width and height of the visible state.
+        visible: (usize, usize),
+    },
+    /// A revealed cell in the visible state doesn't match the layout.
+    StateMismatch {
+        /// The (x, y) coordinates of the cell.
+        at: (usize, usize),
+    },
+    /// A number doesn't match the bombs surrounding it.
+    WrongCount {
+        /// The (x, y) coordinates of the cell.
+        at: (usize, usize),
+        /// The number of bombs that actually surround the cell, less
+        /// the anti-mines.
+        expected: isize,
+        /// The number written in the layout.
+        found: isize,
+    },
+}
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseBoardError::Empty => write!(f, "The layout is empty."),
+            ParseBoardError::TooSmall { width, height } => {
+                write!(f,
+                       "The layout is too small: {}x{}, but it must be at least 3x3.",
+                       width,
+                       height)
+            }
+            ParseBoardError::RaggedRow { row, expected, found } => {
+                write!(f,
+                       "Row {} is {} cells wide, but the first row is {} cells wide.",
+                       row,
+                       found,
+                       expected)
+            }
+            ParseBoardError::InvalidCell { at, ref found } => {
+                write!(f, "Invalid cell {:?} at ({}, {}).", found, at.0, at.1)
+            }
+            ParseBoardError::SizeMismatch { layout, visible } => {
+                write!(f,
+                       "The layout is {}x{}, but the visible state is {}x{}.",
+                       layout.0,
+                       layout.1,
+                       visible.0,
+                       visible.1)
+            }
+            ParseBoardError::StateMismatch { at } => {
+                write!(f,
+                       "The revealed cell at ({}, {}) doesn't match the layout.",
+                       at.0,
+                       at.1)
+            }
+            ParseBoardError::WrongCount { at, expected, found } => {
+                write!(f,
+                       "The cell at ({}, {}) says {}, but it is next to {} bombs.",
+                       at.0,
+                       at.1,
+                       found,
+                       expected)
+            }
+        }
+    }
+}
+
+impl Error for ParseBoardError {}
+
+/// The dimensions of a `Board` and the locations of its mines.
+///
+/// # Examples
+///
+/// ```
+/// use mines_core::{Board, MineLayout};
+///
+/// let layout = MineLayout::new(3, 3, &[1]);
+/// let b = Board::from_mine_layout(&layout);
+/// assert_eq!(format!("{:?}", b), "1*1\n111\n...\n");
+/// assert_eq!(MineLayout::from_board(&b), layout);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MineLayout {
+    width: usize,
+    height: usize,
+    mines: Vec<bool>,
+}
+
+impl MineLayout {
+    /// Creates a `width`x`height` layout with mines at the given
+    /// indices.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the layout is smaller than 3x3, or
+    /// if any index is not within the bounds of the grid.
+    pub fn new(width: usize, height: usize, mine_indices: &[usize]) -> MineLayout {
+        if width < 3 || height < 3 {
+            panic!("Tried to make a MineLayout smaller than 3x3! Size: {}x{}",
+                   width,
+                   height);
+        }
+        let mut mines = vec![false; width * height];
+        for &i in mine_indices {
+            if i >= mines.len() {
+                panic!("Tried to place a mine outside of the grid! Index passed: {}, Grid \
+                        length: {}",
+                       i,
+                       mines.len());
+            }
+            mines[i] = true;
+        }
+
+        MineLayout {
+            width,
+            height,
+            mines,
+        }
+    }
+
+    /// Records the mines of a `Board`. If the `Board` hasn't been
+    /// generated yet, the layout has no mines.
+    pub fn from_board(board: &Board) -> MineLayout {
+        MineLayout {
+            width: board.width,
+            height: board.height,
+            mines: board.tiles.iter().map(|t| t.is_bomb).collect(),
+        }
+    }
+
+    /// The horizontal width.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The vertical height.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The number of mines.
+    pub fn num_mines(&self) -> usize {
+        self.mines.iter().filter(|&&m| m).count()
+    }
+
+    /// Returns whether there is a mine at `index`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `index` is not within the bounds of
+    /// the grid.
+    pub fn is_mine(&self, index: usize) -> bool {
+        self.mines[index]
+    }
+
+    /// Returns the indices of every mine, in ascending order.
+    pub fn mine_indices(&self) -> Vec<usize> {
+        (0..self.mines.len()).filter(|&i| self.mines[i]).collect()
+    }
+}
+
+impl Board {
+    /// Creates a generated `Board` with its mines placed according to
+    /// `layout`. All of its `Tiles` start out hidden.
+    pub fn from_mine_layout(layout: &MineLayout) -> Board {
+        let mut board = Board::default();
+        board.reset_to_layout(layout);
+        board
+    }
+
+    /// Resets this `Board` in place so that it matches `layout`, with
+    /// every `Tile` hidden. The `Board` keeps its clock, and reuses its
+    /// existing allocation where possible, which makes this cheaper than
+    /// creating a new `Board` when playing through many layouts.
+    pub fn reset_to_layout(&mut self, layout: &MineLayout) {
+        self.reset_with(layout.width, layout.height, |i| layout.mines[i]);
+    }
+
+    /// Creates a fully generated `Board` from a textual layout. All of
+    /// its `Tiles` start out hidden.
+    ///
+    /// See the `layout` module documentation for the format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the layout is not a
+    /// rectangle of at least 3x3 valid cells, or if any number doesn't
+    /// match the bombs around it.
+    pub fn from_layout(s: &str) -> Result<Board, ParseBoardError> {
+        Board::from_layout_with(s, Topology::Square)
+    }
+
+    /// Creates a fully generated `Board` with the given `Topology` from
+    /// a textual layout, whose numbers count the bombs adjacent under
+    /// that `Topology`. All of its `Tiles` start out hidden.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error in the same cases as
+    /// `from_layout`.
+    pub fn from_layout_with(s: &str, topology: Topology) -> Result<Board, ParseBoardError> {
+        Board::from_layout_shaped(s, topology, None)
+    }
+
+    // As `from_layout_with`, counting the neighbours of `kernel` if
+    // there is one
+    pub(crate) fn from_layout_shaped(s: &str,
+                                     topology: Topology,
+                                     kernel: Option<Kernel>)
+                                     -> Result<Board, ParseBoardError> {
+        let rows = split_rows(s)?;
+        let width = rows[0].len();
+        let height = rows.len();
+        if width < 3 || height < 3 {
+            return Err(ParseBoardError::TooSmall { width, height });
+        }
+
+        // First pass: find the bombs, remembering the numbers so they
+        // can be checked once every bomb is known
+        let mut tiles = vec![Tile::default(); width * height];
+        let mut numbers: Vec<Option<isize>> = vec![None; width * height];
+        let mut mask = vec![true; width * height];
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let index = (width * y) + x;
+                match *cell {
+                    "*" => tiles[index].is_bomb = true,
+                    "-" => {
+                        tiles[index].is_bomb = true;
+                        tiles[index].is_anti_mine = true;
+                    }
+                    "." => numbers[index] = Some(0),
+                    HOLE => mask[index] = false,
+                    _ => {
+                        match cell.parse::<isize>() {
+                            Ok(n) => numbers[index] = Some(n),
+                            Err(_) => {
+                                return Err(ParseBoardError::InvalidCell {
+                                    at: (x, y),
+                                    found: String::from(*cell),
+                                })
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let mask = if mask.contains(&false) { Some(mask) } else { None };
+        let board = Board::from_tiles(width, height, topology, kernel, mask, tiles);
+
+        for (index, number) in numbers.iter().enumerate() {
+            if let Some(found) = *number {
+                let expected = board.tiles[index].adjacent_bombs;
+                if found != expected {
+                    return Err(ParseBoardError::WrongCount {
+                        at: board.cartesian_coords(index),
+                        expected,
+                        found,
+                    });
+                }
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Creates a `Board` from a layout (as with `from_layout`) and a
+    /// visible state describing which `Tiles` are revealed or flagged.
+    ///
+    /// See the `layout` module documentation for the formats.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if either string is
+    /// malformed, if they are different sizes, or if a revealed cell
+    /// doesn't match the layout.
+    pub fn restore(layout: &str, visible: &str) -> Result<Board, ParseBoardError> {
+        Board::restore_with(layout, visible, Topology::Square)
+    }
+
+    /// Creates a `Board` with the given `Topology` from a layout (as
+    /// with `from_layout_with`) and a visible state (as with `restore`).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error in the same cases as
+    /// `restore`.
+    pub fn restore_with(layout: &str,
+                        visible: &str,
+                        topology: Topology)
+                        -> Result<Board, ParseBoardError> {
+        Board::restore_shaped(layout, visible, topology, None)
+    }
+
+    // As `restore_with`, counting the neighbours of `kernel` if there
+    // is one
+    pub(crate) fn restore_shaped(layout: &str,
+                                 visible: &str,
+                                 topology: Topology,
+                                 kernel: Option<Kernel>)
+                                 -> Result<Board, ParseBoardError> {
+        let mut board = Board::from_layout_shaped(layout, topology, kernel)?;
+        let rows = split_rows(visible)?;
+        if rows.len() != board.height || rows[0].len() != board.width {
+            return Err(ParseBoardError::SizeMismatch {
+                layout: (board.width, board.height),
+                visible: (rows[0].len(), rows.len()),
+            });
+        }
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let index = (board.width * y) + x;
+                if (*cell == HOLE) == board.is_in_play(index) {
+                    return Err(ParseBoardError::StateMismatch { at: (x, y) });
+                }
+                let tile = &mut board.tiles[index];
+                tile.state = match *cell {
+                    HOLE => TileState::Hidden,
+                    "?" => TileState::Hidden,
+                    "!" => TileState::Flagged,
+                    "#" if tile.is_bomb => TileState::Exposed,
+                    "X" if !tile.is_bomb => TileState::WrongFlag,
+                    _ if tile.is_bomb && *cell == layout_cell(tile) => TileState::Detonated,
+                    _ if *cell == layout_cell(tile) => TileState::Revealed,
+                    _ => return Err(ParseBoardError::StateMismatch { at: (x, y) }),
+                };
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Returns the hidden contents of every `Tile`, in the stable
+    /// layout format described in the `layout` module documentation.
+    pub fn layout_string(&self) -> String {
+        let cells = self.cells(layout_cell, HOLE);
+        GridFormat::for_cells(&cells).render(&cells, self.width)
+    }
+
+    /// Returns what the user can see, in the stable visible format
+    /// described in the `layout` module documentation.
+    pub fn visible_string(&self) -> String {
+        let cells = self.cells(visible_cell, HOLE);
+        GridFormat::for_cells(&cells).render(&cells, self.width)
+    }
+}
+
+impl FromStr for Board {
+    type Err = ParseBoardError;
+
+    fn from_str(s: &str) -> Result<Board, ParseBoardError> {
+        Board::from_layout(s)
+    }
+}
+
+// How a Tile that isn't in play is written in both formats
+pub(crate) const HOLE: &str = "_";
+
+pub(crate) fn layout_cell(tile: &Tile) -> String {
+    if tile.is_anti_mine {
+        String::from("-")
+    } else if tile.is_bomb {
+        String::from("*")
+    } else if tile.adjacent_bombs == 0 {
+        String::from(".")
+    } else {
+        tile.adjacent_bombs.to_string()
+    }
+}
+
+pub(crate) fn visible_cell(tile: &Tile) -> String {
+    match tile.state {
+        TileState::Hidden => String::from("?"),
+        TileState::Flagged => String::from("!"),
+        TileState::Exposed => String::from("#"),
+        TileState::WrongFlag => String::from("X"),
+        TileState::Revealed | TileState::Detonated => layout_cell(tile),
+    }
+}
+
+// Splits text into rectangular rows of cells
+pub(crate) fn split_rows(s: &str) -> Result<Vec<Vec<&str>>, ParseBoardError> {
+    let rows: Vec<Vec<&str>> = s.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(split_cells)
+        .collect();
+
+    let width = match rows.first() {
+        Some(row) => row.len(),
+        None => return Err(ParseBoardError::Empty),
+    };
+    for (y, row) in rows.iter().enumerate() {
+        if row.len() != width {
+            return Err(ParseBoardError::RaggedRow {
+                row: y,
+                expected: width,
+                found: row.len(),
+            });
+        }
+    }
+
+    Ok(rows)
+}
+
+fn split_cells(line: &str) -> Vec<&str> {
+    if line.contains(char::is_whitespace) {
+        line.split_whitespace().collect()
+    } else {
+        line.char_indices().map(|(i, c)| &line[i..i + c.len_utf8()]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use board::GameState;
+
+    #[test]
+    fn test_round_trip() {
+        let layout = ".1*23*311\n.12*4*3*2\n.135*322*\n.1***2.11\n\
+                      .12322121\n111111*3*\n*12*3214*\n112**223*\n..123*2*2\n";
+        let b: Board = layout.parse().unwrap();
+        assert_eq!(b.width, 9);
+        assert_eq!(b.height, 9);
+        assert_eq!(b.num_mines, 20);
+        assert!(b.is_generated());
+        assert_eq!(format!("{:?}", b), layout);
+    }
+
+    #[test]
+    fn test_wide_cells() {
+        let b: Board = "1 * 1\n1 1 1\n. . .\n".parse().unwrap();
+        assert_eq!(format!("{:?}", b), "1*1\n111\n...\n");
+    }
+
+    #[test]
+    fn test_strings_round_trip() {
+        let mut b = Board::default();
+        b.reveal_tile(27).unwrap();
+        let hidden = (0..b.tiles.len()).find(|&i| matches!(b.tiles[i].state, TileState::Hidden));
+        if let Some(i) = hidden {
+            b.flag_tile(i).unwrap();
+        }
+
+        let restored = Board::restore(&b.layout_string(), &b.visible_string()).unwrap();
+        assert_eq!(restored.layout_string(), b.layout_string());
+        assert_eq!(restored.visible_string(), b.visible_string());
+    }
+
+    #[test]
+    fn test_mask() {
+        let layout = "*1.1*\n11.11\n.._..\n11.11\n*1.1*\n";
+        let mut b = Board::from_layout(layout).unwrap();
+        assert_eq!(b.layout_string(), layout);
+        assert!(!b.is_in_play(12));
+        assert!(b.adjacent_tile_indices(12).is_empty());
+        assert!(!b.adjacent_tile_indices(7).contains(&12));
+        assert!(b.reveal_tile(12).is_err());
+        assert!(b.flag_tile(12).is_err());
+
+        // The flood goes around the hole, and the game is won without
+        // revealing it
+        b.reveal_tile(2).unwrap();
+        assert_eq!(b.visible_string(), "?1.1?\n11.11\n.._..\n11.11\n?1.1?\n");
+        assert!(matches!(b.state(), GameState::Won));
+        assert_eq!(format!("{:?}", b).lines().nth(2), Some(".. .."));
+
+        let restored = Board::restore(layout, &b.visible_string()).unwrap();
+        assert_eq!(restored.visible_string(), b.visible_string());
+        assert_eq!(Board::restore(layout, "?????\n?????\n?????\n?????\n?????").unwrap_err(),
+                   ParseBoardError::StateMismatch { at: (2, 2) });
+    }
+
+    #[test]
+    fn test_mine_layout() {
+        let layout = MineLayout::new(4, 3, &[0, 11]);
+        assert_eq!(layout.num_mines(), 2);
+        assert_eq!(layout.mine_indices(), vec![0, 11]);
+        assert!(layout.is_mine(11));
+
+        let mut b = Board::new(9, 9, 10);
+        b.reveal_tile(0).unwrap();
+        b.reset_to_layout(&layout);
+        assert_eq!((b.width, b.height, b.num_mines), (4, 3, 2));
+        assert_eq!(b.layout_string(), "*1..\n1111\n..1*\n");
+        assert_eq!(b.visible_string(), "????\n????\n????\n");
+    }
+
+    #[test]
+    fn test_restore_errors() {
+        let layout = "1*1\n111\n...";
+        assert_eq!(Board::restore(layout, "???\n???").unwrap_err(),
+                   ParseBoardError::SizeMismatch {
+                       layout: (3, 3),
+                       visible: (3, 2),
+                   });
+        assert_eq!(Board::restore(layout, "???\n?2?\n???").unwrap_err(),
+                   ParseBoardError::StateMismatch { at: (1, 1) });
+    }
+
+    #[test]
+    fn test_errors() {
+        struct Test {
+            layout: &'static str,
+            expected: ParseBoardError,
+        }
+
+        let tests = [Test {
+                         layout: "\n\n",
+                         expected: ParseBoardError::Empty,
+                     },
+                     Test {
+                         layout: "...\n...",
+                         expected: ParseBoardError::TooSmall {
+                             width: 3,
+                             height: 2,
+                         },
+                     },
+                     Test {
+                         layout: "...\n....\n...",
+                         expected: ParseBoardError::RaggedRow {
+                             row: 1,
+                             expected: 3,
+                             found: 4,
+                         },
+                     },
+                     Test {
+                         layout: "...\n.x.\n...",
+                         expected: ParseBoardError::InvalidCell {
+                             at: (1, 1),
+                             found: String::from("x"),
+                         },
+                     },
+                     Test {
+                         layout: "1*1\n121\n...",
+                         expected: ParseBoardError::WrongCount {
+                             at: (1, 1),
+                             expected: 1,
+                             found: 2,
+                         },
+                     }];
+
+        for test in &tests {
+            assert_eq!(test.layout.parse::<Board>().unwrap_err(), test.expected);
+        }
+    }
+}
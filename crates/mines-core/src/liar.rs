@@ -0,0 +1,175 @@
+#![warn(missing_docs)]
+//! [Re-exported] The liar variant, where the numbers shown may be off
+//! by one.
+//!
+//! As with the hot/cold variant, the `Board` keeps the true numbers and
+//! only what the user is shown changes. A `Liar` decides, from its seed
+//! and the position of each `Tile`, whether that `Tile` shows one less
+//! than its true number, its true number, or one more; each is equally
+//! likely, except that a shown number is never below 0 or above the
+//! number of neighbours. The same seed always tells the same lies.
+//!
+//! A `Liar` is a `NumberHook`, so `Board::with_number_hook` makes the
+//! `Board` tell its lies through `Display`, `render_visible`, and
+//! `Board::shown_number`. The `solver`, `Board::hint`, and
+//! `Board::auto_flag` then take every shown number as a range, one
+//! either side of it, just as the user has to.
+//! `VisibleBoard::from_board_liar` does the same for a `Board` without
+//! the hook.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{Board, Liar};
+//!
+//! let b = Board::restore("1*1\n111\n...", "???\n111\n...").unwrap();
+//! let liar = Liar::new(7);
+//! let shown = liar.shown(&b, 4).unwrap();
+//! assert!(shown <= 2);
+//! assert_eq!(liar.shown(&b, 0), None);
+//!
+//! let b = b.with_number_hook(liar);
+//! assert_eq!(b.shown_number(4), Some(shown));
+//! ```
+
+use board::Board;
+use render::NumberHook;
+use solver::{VisibleBoard, VisibleCell};
+use tile::TileState;
+use util::SeededRng;
+
+/// A deterministic rule for which numbers lie, and how.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Liar {
+    /// The seed the lies are drawn from.
+    pub seed: u64,
+}
+
+impl Liar {
+    /// Creates a `Liar` that tells the lies given by `seed`.
+    pub fn new(seed: u64) -> Liar {
+        Liar { seed }
+    }
+
+    /// Returns the number shown on the `Tile` at `index`, or `None` if
+    /// it is not a revealed safe `Tile`.
+    pub fn shown(&self, board: &Board, index: usize) -> Option<isize> {
+        let tile = &board.tiles[index];
+        if tile.is_bomb || !matches!(tile.state, TileState::Revealed) {
+            return None;
+        }
+        let most = board.adjacent_tile_indices(index).len();
+        Some(self.lie(index, tile.adjacent_bombs, most))
+    }
+
+    // Picks what to show for a true number of `number`, out of at most
+    // `most` neighbours. Only a number that is already negative, from
+    // anti-mines, is allowed to go below 0
+    fn lie(&self, index: usize, number: isize, most: usize) -> isize {
+        let most = most as isize;
+        let least = if number < 0 { -most } else { 0 };
+        let choices: Vec<isize> = [number - 1, number, number + 1]
+            .iter()
+            .cloned()
+            .filter(|&n| least <= n && n <= most)
+            .collect();
+        // SplitMix64 only adds a constant to its state between outputs,
+        // so this is the `index`th output of the generator for `seed`
+        let state = self.seed.wrapping_add((index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        choices[SeededRng::new(state).below(choices.len())]
+    }
+}
+
+impl NumberHook for Liar {
+    fn report(&self, index: usize, number: isize, most: usize) -> isize {
+        self.lie(index, number, most)
+    }
+
+    fn bounds(&self, _index: usize, shown: isize, _most: usize) -> (isize, isize) {
+        (shown - 1, shown + 1)
+    }
+}
+
+impl VisibleBoard {
+    /// Captures what the user can currently see of `board` in the liar
+    /// variant, where every shown number may be one away from the truth.
+    pub fn from_board_liar(board: &Board, liar: &Liar) -> VisibleBoard {
+        let mut view = VisibleBoard::from_board(board);
+        if board.num_anti_mines() > 0 {
+            // The numbers already tell the solver nothing
+            return view;
+        }
+        for (i, cell) in view.cells.iter_mut().enumerate() {
+            if let Some(shown) = liar.shown(board, i) {
+                let most = board.adjacent_tile_indices(i).len();
+                let (least, greatest) = liar.bounds(i, shown, most);
+                let clamp = |n: isize| n.clamp(0, most as isize) as usize;
+                *cell = VisibleCell::Range(clamp(least), clamp(greatest));
+            }
+        }
+        view
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use render::GridFormat;
+    use solver::{self, Solver};
+
+    #[test]
+    fn test_lies_are_deterministic() {
+        let mut b = Board::new(16, 16, 40).with_seed(5);
+        b.reveal_tile(0).unwrap();
+        let a = Liar::new(1);
+
+        let mut told_a_lie = false;
+        for i in 0..b.tiles.len() {
+            assert_eq!(a.shown(&b, i), Liar::new(1).shown(&b, i));
+            if let Some(shown) = a.shown(&b, i) {
+                let truth = b.tiles[i].adjacent_bombs;
+                assert!(shown + 1 >= truth && shown <= truth + 1);
+                told_a_lie |= shown != truth;
+            }
+        }
+        assert!(told_a_lie);
+        assert_eq!(b.clone().with_number_hook(a).render_visible(&GridFormat::compact()),
+                   b.with_number_hook(Liar::new(1)).render_visible(&GridFormat::compact()));
+    }
+
+    #[test]
+    fn test_number_hook() {
+        let mut b = Board::new(16, 16, 40).with_seed(8);
+        b.reveal_tile(0).unwrap();
+        let liar = Liar::new(3);
+        let hooked = b.clone().with_number_hook(liar);
+        for i in 0..b.tiles.len() {
+            assert_eq!(hooked.shown_number(i), liar.shown(&b, i));
+        }
+        // The truth is still there underneath
+        assert_eq!(hooked.visible_string(), b.visible_string());
+    }
+
+    #[test]
+    fn test_solver_is_never_fooled() {
+        for seed in 0..20 {
+            let mut b = Board::new(9, 9, 10).with_seed(seed);
+            b.reveal_tile(40).unwrap();
+            let view = VisibleBoard::from_board_liar(&b, &Liar::new(seed));
+            let deductions = Solver::default().solve(&view);
+            assert!(deductions.safe.iter().all(|&i| !b.tiles[i].is_bomb));
+            assert!(deductions.mines.iter().all(|&i| b.tiles[i].is_bomb));
+
+            // Nor are hints and flags on a Board with the hook, which
+            // only see the lies
+            let mut hooked = b.clone().with_number_hook(Liar::new(seed));
+            assert_eq!(VisibleBoard::from_board(&hooked), view);
+            for i in hooked.auto_flag() {
+                assert!(b.tiles[i].is_bomb);
+            }
+            let safe = solver::solve(&hooked).safe;
+            assert_eq!(safe, deductions.safe);
+        }
+    }
+}
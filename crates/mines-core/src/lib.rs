@@ -0,0 +1,126 @@
+/*!
+# mines-core
+
+The core of **mines**: `Board` and `Tile` themselves, their topology,
+building, generating, saving, and rendering boards as text, the clock,
+undo, actions, listeners, statistics, replays, validation, and the
+`solver` that hints and no-guess generation are built on.
+
+Most users should depend on `mines` instead, which re-exports this
+crate along with the optional `mines-solver`, `mines-render`, and
+`mines-net` crates. The crate documentation of `mines` covers the
+library as a whole.
+
+## Features
+
+* `serde`: Implements `Serialize` and `Deserialize` for `Board`,
+  `Tile`, and `TileState`.
+
+* `rayon`: Counts the numbers of very large `Boards` on every core.
+
+* `debug-invariants`: Checks that the `Board` is still consistent after
+  every move, undo, and redo.
+
+* `history`: Adds `HistoryStore`.
+
+* `wasm`: Adds `WasmBoard`, bindings that let JavaScript play on a
+  `Board` through `wasm-bindgen`.
+
+* `experimental`: Builds the experimental modules of this crate.
+*/
+
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "wasm")]
+extern crate serde_json;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+
+pub mod action;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod adaptive;
+pub mod board;
+pub mod builder;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod cascade;
+pub mod clock;
+pub mod dump;
+pub mod game;
+pub mod generate;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod graph;
+#[cfg(feature = "history")]
+#[cfg_attr(docsrs, doc(cfg(feature = "history")))]
+pub mod history;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod infinite;
+pub mod layout;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod liar;
+pub mod mbf;
+pub mod observe;
+pub mod orientation;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod packed;
+pub mod pool;
+pub mod render;
+pub mod replay;
+pub mod save;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod shrink;
+pub mod solver;
+pub mod stats;
+pub mod tile;
+pub mod topology;
+pub mod undo;
+pub mod util;
+pub mod validate;
+pub mod view;
+#[cfg(feature = "wasm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wasm")))]
+pub mod wasm;
+
+pub use action::*;
+#[cfg(feature = "experimental")]
+pub use adaptive::*;
+pub use board::*;
+pub use builder::*;
+pub use clock::*;
+pub use game::*;
+#[cfg(feature = "experimental")]
+pub use graph::*;
+#[cfg(feature = "history")]
+pub use history::*;
+#[cfg(feature = "experimental")]
+pub use infinite::*;
+pub use layout::*;
+#[cfg(feature = "experimental")]
+pub use liar::*;
+pub use mbf::*;
+pub use observe::*;
+pub use orientation::*;
+#[cfg(feature = "experimental")]
+pub use packed::*;
+pub use pool::*;
+pub use render::*;
+pub use replay::*;
+pub use save::*;
+pub use stats::*;
+pub use tile::*;
+pub use topology::*;
+pub use validate::*;
+pub use view::*;
+#[cfg(feature = "wasm")]
+pub use wasm::*;
@@ -0,0 +1,242 @@
+#![warn(missing_docs)]
+//! [Re-exported] Reading and writing `Boards` as MBF files.
+//!
+//! MBF (Minesweeper Board File) is the small binary format that board
+//! sharing tools pass layouts around in. It only says where the mines
+//! are, so `Board::from_mbf` gives a generated `Board` with every
+//! `Tile` hidden, and `Board::to_mbf` leaves out everything but the
+//! layout.
+//!
+//! # Format
+//!
+//! ```text
+//! width       1 byte
+//! height      1 byte
+//! mine count  2 bytes, big-endian
+//! mines       2 bytes each: x, then y
+//! ```
+//!
+//! So no side can be longer than 255 `Tiles`, and no `Board` can have
+//! more than 65535 mines. `Board::to_mbf` writes the mines from the top
+//! left, row by row.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::Board;
+//!
+//! let b = Board::from_mbf(&[3, 3, 0, 1, 1, 0]).unwrap();
+//! assert_eq!(b.layout_string(), "1*1\n111\n...\n");
+//! assert_eq!(b.to_mbf().unwrap(), vec![3, 3, 0, 1, 1, 0]);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use board::Board;
+use layout::MineLayout;
+
+/// The reasons reading or writing an MBF file can fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MbfError {
+    /// The file is shorter than its header, or than its mine count
+    /// says it should be.
+    Truncated,
+    /// The file goes on after its last mine.
+    TrailingBytes,
+    /// The `Board` is narrower or shorter than 3x3.
+    TooSmall {
+        /// The width of the `Board`.
+        width: usize,
+        /// The height of the `Board`.
+        height: usize,
+    },
+    /// A mine is off the `Board`.
+    OutOfBounds {
+        /// The (x, y) coordinates of the mine.
+        at: (usize, usize),
+    },
+    /// The same mine is listed twice.
+    Duplicate {
+        /// The (x, y) coordinates of the mine.
+        at: (usize, usize),
+    },
+    /// The `Board` hasn't been generated yet, so has no mines to write.
+    NotGenerated,
+    /// The `Board` is too big for the format: a side is longer than 255
+    /// `Tiles`, or there are more than 65535 mines.
+    TooLarge,
+    /// The `Board` has anti-mines or `Tiles` out of play, which the
+    /// format has no way to write.
+    Unsupported,
+}
+
+impl fmt::Display for MbfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MbfError::Truncated => write!(f, "The MBF file is cut short."),
+            MbfError::TrailingBytes => write!(f, "The MBF file goes on after its last mine."),
+            MbfError::TooSmall { width, height } => {
+                write!(f,
+                       "The board is too small: {}x{}, but it must be at least 3x3.",
+                       width,
+                       height)
+            }
+            MbfError::OutOfBounds { at } => {
+                write!(f, "The mine at ({}, {}) is off the board.", at.0, at.1)
+            }
+            MbfError::Duplicate { at } => {
+                write!(f, "The mine at ({}, {}) is listed twice.", at.0, at.1)
+            }
+            MbfError::NotGenerated => write!(f, "The board hasn't been generated yet."),
+            MbfError::TooLarge => write!(f, "The board is too large for an MBF file."),
+            MbfError::Unsupported => {
+                write!(f, "MBF files can't hold anti-mines or tiles out of play.")
+            }
+        }
+    }
+}
+
+impl Error for MbfError {}
+
+impl Board {
+    /// Creates a fully generated `Board` from an MBF file. All of its
+    /// `Tiles` start out hidden. See the `mbf` module documentation for
+    /// the format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `bytes` is not a valid MBF
+    /// file of at least 3x3, with every mine on the `Board` and listed
+    /// once.
+    pub fn from_mbf(bytes: &[u8]) -> Result<Board, MbfError> {
+        if bytes.len() < 4 {
+            return Err(MbfError::Truncated);
+        }
+        let width = usize::from(bytes[0]);
+        let height = usize::from(bytes[1]);
+        if width < 3 || height < 3 {
+            return Err(MbfError::TooSmall { width, height });
+        }
+        let count = usize::from(u16::from_be_bytes([bytes[2], bytes[3]]));
+        let mines = &bytes[4..];
+        if mines.len() < count * 2 {
+            return Err(MbfError::Truncated);
+        }
+        if mines.len() > count * 2 {
+            return Err(MbfError::TrailingBytes);
+        }
+
+        let mut indices = Vec::with_capacity(count);
+        let mut seen = vec![false; width * height];
+        for mine in mines.chunks(2) {
+            let at = (usize::from(mine[0]), usize::from(mine[1]));
+            if at.0 >= width || at.1 >= height {
+                return Err(MbfError::OutOfBounds { at });
+            }
+            let index = at.1 * width + at.0;
+            if seen[index] {
+                return Err(MbfError::Duplicate { at });
+            }
+            seen[index] = true;
+            indices.push(index);
+        }
+        Ok(Board::from_mine_layout(&MineLayout::new(width, height, &indices)))
+    }
+
+    /// Writes where the mines are as an MBF file. See the `mbf` module
+    /// documentation for the format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the `Board` hasn't been
+    /// generated, is too large for the format, or has anti-mines or
+    /// `Tiles` out of play.
+    pub fn to_mbf(&self) -> Result<Vec<u8>, MbfError> {
+        if !self.is_generated() {
+            return Err(MbfError::NotGenerated);
+        }
+        if self.num_anti_mines() > 0 || (0..self.tiles.len()).any(|i| !self.is_in_play(i)) {
+            return Err(MbfError::Unsupported);
+        }
+        let mines = MineLayout::from_board(self).mine_indices();
+        if self.width > 255 || self.height > 255 || mines.len() > 65535 {
+            return Err(MbfError::TooLarge);
+        }
+
+        let mut bytes = Vec::with_capacity(4 + mines.len() * 2);
+        bytes.push(self.width as u8);
+        bytes.push(self.height as u8);
+        bytes.extend_from_slice(&(mines.len() as u16).to_be_bytes());
+        for index in mines {
+            let (x, y) = self.cartesian_coords(index);
+            bytes.push(x as u8);
+            bytes.push(y as u8);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut b = Board::new(30, 16, 99).with_seed(5);
+        b.reveal_tile(0).unwrap();
+        let bytes = b.to_mbf().unwrap();
+        assert_eq!(&bytes[..4], &[30, 16, 0, 99]);
+        assert_eq!(bytes.len(), 4 + 99 * 2);
+        let restored = Board::from_mbf(&bytes).unwrap();
+        assert_eq!(restored.layout_string(), b.layout_string());
+        assert_eq!(restored.to_mbf().unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_errors() {
+        struct Test {
+            bytes: &'static [u8],
+            expected: MbfError,
+        }
+
+        let tests = [Test {
+                         bytes: &[3, 3, 0],
+                         expected: MbfError::Truncated,
+                     },
+                     Test {
+                         bytes: &[3, 3, 0, 2, 1, 0],
+                         expected: MbfError::Truncated,
+                     },
+                     Test {
+                         bytes: &[3, 3, 0, 1, 1, 0, 2],
+                         expected: MbfError::TrailingBytes,
+                     },
+                     Test {
+                         bytes: &[2, 3, 0, 0],
+                         expected: MbfError::TooSmall {
+                             width: 2,
+                             height: 3,
+                         },
+                     },
+                     Test {
+                         bytes: &[3, 3, 0, 1, 3, 0],
+                         expected: MbfError::OutOfBounds { at: (3, 0) },
+                     },
+                     Test {
+                         bytes: &[3, 3, 0, 2, 1, 1, 1, 1],
+                         expected: MbfError::Duplicate { at: (1, 1) },
+                     }];
+
+        for test in &tests {
+            assert_eq!(Board::from_mbf(test.bytes).unwrap_err(), test.expected);
+        }
+
+        assert_eq!(Board::new(9, 9, 10).to_mbf().unwrap_err(), MbfError::NotGenerated);
+        let mut b = Board::new(256, 3, 1).with_seed(0);
+        b.reveal_tile(0).unwrap();
+        assert_eq!(b.to_mbf().unwrap_err(), MbfError::TooLarge);
+        let b = Board::from_layout("1*1\n111\n.._").unwrap();
+        assert_eq!(b.to_mbf().unwrap_err(), MbfError::Unsupported);
+    }
+}
@@ -0,0 +1,250 @@
+#![warn(missing_docs)]
+//! [Re-exported] Listening for changes to a `Board`.
+//!
+//! Front-ends that draw a `Board` would otherwise have to re-scan every
+//! `Tile` after each move to find out what a flood fill revealed. A
+//! `BoardListener` registered with `Board::add_listener` is told about
+//! each `Tile` that changes instead, and about the game being
+//! generated, won, or lost.
+//!
+//! For each move, the `Tiles` that changed come first, in ascending
+//! order, followed by `Won` or `Lost` if the move ended the game.
+//! `Generated` comes before everything else in the first reveal. Undo
+//! and redo only report the `Tiles` that changed. Failed moves report
+//! nothing. Every event carries the time on the `Board`'s clock when it
+//! happened, as `Board::now` reads it.
+//!
+//! Any closure taking a `&BoardEvent` is a `BoardListener`. Listeners
+//! are not cloned with the `Board`, nor serialized.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::{Arc, Mutex};
+//! use mines_core::{Board, BoardEvent};
+//!
+//! let revealed = Arc::new(Mutex::new(Vec::new()));
+//! let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+//! let log = revealed.clone();
+//! b.add_listener(move |event: &BoardEvent| {
+//!     if let BoardEvent::TileChanged { index, .. } = *event {
+//!         log.lock().unwrap().push(index);
+//!     }
+//! });
+//!
+//! b.reveal_tile(8).unwrap();
+//! assert_eq!(*revealed.lock().unwrap(), vec![3, 4, 5, 6, 7, 8]);
+//! ```
+
+use clock::Timestamp;
+use tile::TileState;
+
+/// Something that happened to a `Board`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BoardEvent {
+    /// A `Tile` changed state.
+    TileChanged {
+        /// When it changed.
+        at: Timestamp,
+        /// The index of the `Tile`.
+        index: usize,
+        /// The state it had before.
+        old_state: TileState,
+        /// The state it has now.
+        new_state: TileState,
+    },
+    /// The bombs were placed, on the first reveal.
+    Generated {
+        /// When they were placed.
+        at: Timestamp,
+    },
+    /// The game was won.
+    Won {
+        /// When it was won.
+        at: Timestamp,
+    },
+    /// The game was lost.
+    Lost {
+        /// When it was lost.
+        at: Timestamp,
+    },
+}
+
+/// Something that is told whenever a `Board` changes.
+pub trait BoardListener {
+    /// Called with each `BoardEvent`, in the order they happen.
+    fn on_event(&mut self, event: &BoardEvent);
+}
+
+impl<F: FnMut(&BoardEvent)> BoardListener for F {
+    fn on_event(&mut self, event: &BoardEvent) {
+        self(event)
+    }
+}
+
+/// Identifies a listener added with `Board::add_listener`, so that it
+/// can be removed again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ListenerId(usize);
+
+// The listeners on a Board. Cloning gives none, since listeners usually
+// belong to whatever is showing one particular Board
+#[derive(Default)]
+pub(crate) struct Listeners {
+    next_id: usize,
+    listeners: Vec<(ListenerId, Box<dyn BoardListener + Send + Sync>)>,
+}
+
+impl Listeners {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+
+    pub(crate) fn add(&mut self, listener: Box<dyn BoardListener + Send + Sync>) -> ListenerId {
+        let id = ListenerId(self.next_id);
+        self.next_id += 1;
+        self.listeners.push((id, listener));
+        id
+    }
+
+    pub(crate) fn remove(&mut self, id: ListenerId) -> bool {
+        let len = self.listeners.len();
+        self.listeners.retain(|&(i, _)| i != id);
+        self.listeners.len() != len
+    }
+
+    pub(crate) fn notify(&mut self, event: &BoardEvent) {
+        for &mut (_, ref mut listener) in &mut self.listeners {
+            listener.on_event(event);
+        }
+    }
+
+    // Reports every Tile whose state differs between `before` and
+    // `after`, as changed `at`
+    pub(crate) fn notify_changes(&mut self,
+                                 at: Timestamp,
+                                 before: &[TileState],
+                                 after: &[TileState]) {
+        if self.is_empty() {
+            return;
+        }
+        for (index, (old, new)) in before.iter().zip(after).enumerate() {
+            if ::std::mem::discriminant(old) != ::std::mem::discriminant(new) {
+                self.notify(&BoardEvent::TileChanged {
+                    at,
+                    index,
+                    old_state: old.clone(),
+                    new_state: new.clone(),
+                });
+            }
+        }
+    }
+}
+
+impl Clone for Listeners {
+    fn clone(&self) -> Listeners {
+        Listeners::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::*;
+    use board::Board;
+    use clock::{GameClock, MockClock};
+
+    // Records every event as a short string
+    fn record(board: &mut Board) -> (ListenerId, Arc<Mutex<Vec<String>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let log = events.clone();
+        let id = board.add_listener(move |event: &BoardEvent| {
+            let name = match *event {
+                BoardEvent::TileChanged { index, ref new_state, .. } => {
+                    match *new_state {
+                        TileState::Hidden => format!("{}?", index),
+                        TileState::Flagged => format!("{}!", index),
+                        TileState::Revealed => format!("{}.", index),
+                        TileState::Detonated => format!("{}*", index),
+                        _ => format!("{}~", index),
+                    }
+                }
+                BoardEvent::Generated { .. } => "generated".to_string(),
+                BoardEvent::Won { .. } => "won".to_string(),
+                BoardEvent::Lost { .. } => "lost".to_string(),
+            };
+            log.lock().unwrap().push(name);
+        });
+        (id, events)
+    }
+
+    #[test]
+    fn test_events() {
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap().with_undo(5);
+        let (id, events) = record(&mut b);
+
+        b.flag_tile(1).unwrap();
+        b.reveal_tile(0).unwrap();
+        // Moves that change nothing, or fail, report nothing
+        assert!(b.reveal_tile(0).is_ok());
+        assert!(b.reveal_tile(1).is_err());
+        b.undo().unwrap();
+        b.undo().unwrap();
+        b.undo().unwrap();
+        b.reveal_tile(1).unwrap();
+        assert_eq!(*events.lock().unwrap(),
+                   vec!["1!", "0.", "0?", "1?", "1*", "lost"]);
+
+        assert!(b.remove_listener(id));
+        assert!(!b.remove_listener(id));
+        b.undo().unwrap();
+        assert_eq!(events.lock().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_generated_and_won() {
+        let mut b = Board::new(9, 9, 10).with_seed(3);
+        let (_, events) = record(&mut b);
+        b.reveal_tile(40).unwrap();
+        assert_eq!(events.lock().unwrap()[0], "generated");
+
+        // Clones don't take the listeners with them
+        let mut copy = b.clone();
+        for i in 0..copy.tiles.len() {
+            if !copy.tiles[i].is_bomb {
+                let _ = copy.reveal_tile(i);
+            }
+        }
+        assert!(!events.lock().unwrap().contains(&"won".to_string()));
+
+        for i in 0..b.tiles.len() {
+            if !b.tiles[i].is_bomb {
+                let _ = b.reveal_tile(i);
+            }
+        }
+        assert_eq!(events.lock().unwrap().last().unwrap(), "won");
+    }
+
+    #[test]
+    fn test_timestamps() {
+        let clock = MockClock::new();
+        let mut b = Board::new(9, 9, 10).with_seed(3).with_clock(GameClock::new(clock.clone()));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let log = events.clone();
+        b.add_listener(move |event: &BoardEvent| log.lock().unwrap().push(event.clone()));
+
+        clock.advance(Duration::from_millis(1500));
+        b.reveal_tile(40).unwrap();
+        clock.advance(Duration::from_millis(500));
+        let bomb = b.tiles.iter().position(|t| t.is_bomb).unwrap();
+        b.reveal_tile(bomb).unwrap();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events[0], BoardEvent::Generated { at: Timestamp::from_millis(1500) });
+        assert!(matches!(events[1], BoardEvent::TileChanged { at, .. }
+                         if at == Timestamp::from_millis(1500)));
+        assert_eq!(*events.last().unwrap(), BoardEvent::Lost { at: Timestamp::from_millis(2000) });
+    }
+}
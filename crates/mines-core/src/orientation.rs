@@ -0,0 +1,130 @@
+#![warn(missing_docs)]
+//! [Re-exported] Rotating a `Board` between portrait and landscape.
+//!
+//! Mobile front-ends often want to turn an in-progress game on its side
+//! when the device rotates. `Board::reoriented` does this without
+//! losing any state: bombs, numbers, flags, and revealed `Tiles` all
+//! move with the grid.
+
+use board::Board;
+
+/// Whether a `Board` is being shown wider than it is tall, or taller
+/// than it is wide.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Orientation {
+    /// Wider than it is tall (or square).
+    #[default]
+    Landscape,
+    /// Taller than it is wide.
+    Portrait,
+}
+
+impl Orientation {
+    /// The natural orientation for a grid of the given dimensions.
+    pub fn for_size(width: usize, height: usize) -> Orientation {
+        if width >= height {
+            Orientation::Landscape
+        } else {
+            Orientation::Portrait
+        }
+    }
+}
+
+impl Board {
+    /// Returns the current orientation of the `Board`. A new `Board`
+    /// starts in the natural orientation for its size.
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Returns a copy of this `Board` turned to `orientation`.
+    ///
+    /// Turning from landscape to portrait rotates the grid a quarter
+    /// turn clockwise, and turning back rotates it anticlockwise, so
+    /// reorienting there and back again gives the original `Board`.
+    /// Asking for the current orientation just returns a copy.
+    ///
+    /// The copy keeps the seed of an ungenerated `Board`, but not of a
+    /// generated one, since the seed would no longer describe the
+    /// rotated layout.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::{Board, Orientation};
+    ///
+    /// let b: Board = "1*1.\n111.\n....".parse().unwrap();
+    /// assert_eq!(b.orientation(), Orientation::Landscape);
+    ///
+    /// let portrait = b.reoriented(Orientation::Portrait);
+    /// assert_eq!((portrait.width, portrait.height), (3, 4));
+    /// assert_eq!(portrait.layout_string(), ".11\n.1*\n.11\n...\n");
+    ///
+    /// let back = portrait.reoriented(Orientation::Landscape);
+    /// assert_eq!(back.layout_string(), b.layout_string());
+    /// ```
+    pub fn reoriented(&self, orientation: Orientation) -> Board {
+        let mut board = self.clone();
+        if orientation == self.orientation {
+            return board;
+        }
+
+        let (width, height) = (self.width, self.height);
+        for (index, tile) in self.tiles.iter().enumerate() {
+            let (x, y) = (index % width, index / width);
+            // The new grid is `height` wide
+            let (new_x, new_y) = match orientation {
+                // Clockwise
+                Orientation::Portrait => (height - 1 - y, x),
+                // Anticlockwise
+                Orientation::Landscape => (y, width - 1 - x),
+            };
+            board.tiles[(height * new_y) + new_x] = tile.clone();
+        }
+
+        board.width = height;
+        board.height = width;
+        board.orientation = orientation;
+        if board.is_generated() {
+            board.seed = None;
+        }
+        board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_keeps_state() {
+        let mut b = Board::new(12, 5, 8).with_seed(3);
+        b.reveal_tile(30).unwrap();
+        let hidden = (0..b.tiles.len())
+            .find(|&i| matches!(b.tiles[i].state, ::tile::TileState::Hidden))
+            .unwrap();
+        b.flag_tile(hidden).unwrap();
+
+        let portrait = b.reoriented(Orientation::Portrait);
+        assert_eq!((portrait.width, portrait.height), (5, 12));
+        assert_eq!(portrait.orientation(), Orientation::Portrait);
+        assert_eq!(portrait.seed(), None);
+
+        // The rotated numbers must still agree with the rotated bombs
+        let recounted = Board::from_layout(&portrait.layout_string()).unwrap();
+        assert_eq!(recounted.layout_string(), portrait.layout_string());
+
+        let back = portrait.reoriented(Orientation::Landscape);
+        assert_eq!(back.layout_string(), b.layout_string());
+        assert_eq!(back.visible_string(), b.visible_string());
+    }
+
+    #[test]
+    fn test_natural_orientation() {
+        assert_eq!(Board::new(9, 9, 10).orientation(), Orientation::Landscape);
+        assert_eq!(Board::new(9, 16, 10).orientation(), Orientation::Portrait);
+        let b = Board::new(9, 16, 10).with_seed(1);
+        assert_eq!(b.reoriented(Orientation::Landscape).seed(), Some(1));
+    }
+}
@@ -0,0 +1,688 @@
+#![warn(missing_docs)]
+//! [Re-exported] A compact `Board` for giant games.
+//!
+//! A `Board` keeps a whole `Tile` for every square, which is 16 bytes
+//! each, and a 4000x4000 game needs a quarter of a gigabyte just for
+//! those. A `PackedBoard` plays the same game with the same methods,
+//! but keeps three flat arrays instead: a bitmap of the bombs, one byte
+//! per `Tile` for its number, and two bits per `Tile` for whether it is
+//! hidden, flagged, revealed, or shown by a loss. That is under a byte
+//! and a half per `Tile`, so the same game fits in about 22 megabytes.
+//!
+//! The states that only appear once the game is over share the bits of
+//! others, told apart by the bomb bitmap: a revealed bomb is
+//! `TileState::Detonated`, and a `Tile` shown by a loss is
+//! `TileState::Exposed` if it is a bomb and `TileState::WrongFlag` if
+//! not. `PackedBoard::tile` returns a `Tile` with all of that filled in.
+//!
+//! A `Board` made with a mask keeps it as one more bitmap, of the
+//! `Tiles` that aren't in play, and the anti-mines are one more again.
+//!
+//! A `PackedBoard` is only the game itself: it has no clock, undo,
+//! action log, or listeners. Convert to and from a `Board` with
+//! `PackedBoard::to_board` and `TryFrom`, and a seeded `PackedBoard`
+//! places its bombs exactly where a `Board` with the same seed would.
+//! Every number has to fit in its byte, so a `Board` whose `Kernel` has
+//! more than 127 offsets can't be packed.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{Board, GameState, PackedBoard};
+//!
+//! let mut giant = PackedBoard::new(4000, 4000, 1_600_000).with_seed(1);
+//! giant.reveal_tile(8_002_000).unwrap();
+//! assert_eq!(giant.state(), GameState::Playing);
+//!
+//! let mut b = PackedBoard::new(9, 9, 10).with_seed(3);
+//! b.reveal_tile(40).unwrap();
+//! let mut same = Board::new(9, 9, 10).with_seed(3);
+//! same.reveal_tile(40).unwrap();
+//! assert_eq!(b.visible_string(), same.visible_string());
+//! ```
+
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use board::{Board, FlagError, GameState};
+use builder::FirstClick;
+use layout;
+use render::GridFormat;
+use tile::{Tile, TileState};
+use topology::{Kernel, Topology};
+use util::{self, SeededRng};
+
+extern crate rand;
+
+use self::rand::Rng;
+
+// The two-bit states kept for every Tile
+const HIDDEN: u8 = 0;
+const FLAGGED: u8 = 1;
+const REVEALED: u8 = 2;
+const SHOWN: u8 = 3;
+
+/// The error from packing a `Board` whose `Kernel` has more than 127
+/// offsets, so that its numbers can be too big for a `PackedBoard`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KernelTooLarge;
+
+impl fmt::Display for KernelTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The kernel has more than {} offsets, too many to pack.", i8::MAX)
+    }
+}
+
+impl Error for KernelTooLarge {}
+
+/// A `Board` that stores its `Tiles` compactly. See the `packed` module
+/// documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackedBoard {
+    /// The total number of bombs (revealed or not) on the
+    /// `PackedBoard`.
+    pub num_mines: usize,
+    /// The horizontal width.
+    pub width: usize,
+    /// The vertical height.
+    pub height: usize,
+    // One bit per Tile, set for bombs
+    bombs: Vec<u64>,
+    // The number on every safe Tile, and 0 for bombs
+    adjacent: Vec<i8>,
+    // Two bits per Tile: HIDDEN, FLAGGED, REVEALED, or SHOWN
+    states: Vec<u64>,
+    was_generated: bool,
+    seed: Option<u64>,
+    first_click: FirstClick,
+    topology: Topology,
+    kernel: Option<Kernel>,
+    // One bit per Tile, set for the ones that aren't in play
+    holes: Vec<u64>,
+    // One bit per Tile, set for the bombs that are anti-mines
+    anti_mines: Vec<u64>,
+    num_anti_mines: usize,
+    // Whether a bomb has been revealed, and how many safe Tiles have
+    lost: bool,
+    revealed: usize,
+}
+
+impl PackedBoard {
+    /// Creates a new `PackedBoard`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the same conditions as
+    /// `Board::new`.
+    pub fn new(width: usize, height: usize, num_mines: usize) -> PackedBoard {
+        if width * height <= 9 {
+            panic!("Tried to make too small of a board!");
+        }
+        if num_mines >= (width * height) - 9 {
+            panic!("Too many mines to make a functioning board! Mines passed: {}, Maximum mines: \
+                    {}",
+                   num_mines,
+                   (width * height) - 10);
+        }
+        PackedBoard::unchecked(width, height, num_mines)
+    }
+
+    fn unchecked(width: usize, height: usize, num_mines: usize) -> PackedBoard {
+        let len = width * height;
+        PackedBoard {
+            num_mines,
+            width,
+            height,
+            bombs: vec![0; len.div_ceil(64)],
+            adjacent: vec![0; len],
+            states: vec![0; len.div_ceil(32)],
+            was_generated: false,
+            seed: None,
+            first_click: FirstClick::default(),
+            topology: Topology::default(),
+            kernel: None,
+            holes: vec![0; len.div_ceil(64)],
+            anti_mines: vec![0; len.div_ceil(64)],
+            num_anti_mines: 0,
+            lost: false,
+            revealed: 0,
+        }
+    }
+
+    /// Sets the seed used to place the bombs, and returns the
+    /// `PackedBoard`. See `Board::with_seed`.
+    pub fn with_seed(mut self, seed: u64) -> PackedBoard {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Returns the seed used to place the bombs, if any.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Returns which `Tiles` are adjacent to each other. A
+    /// `PackedBoard` is always `Topology::Square` unless it was made
+    /// from a `Board` with another `Topology`.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Returns the `Kernel` that replaces the `Topology`'s neighbours,
+    /// if it was made from a `Board` with one.
+    pub fn kernel(&self) -> Option<&Kernel> {
+        self.kernel.as_ref()
+    }
+
+    /// Returns the number of `Tiles`.
+    pub fn len(&self) -> usize {
+        self.adjacent.len()
+    }
+
+    /// Returns whether there are no `Tiles`, which is never the case
+    /// for a `PackedBoard` made with `new`.
+    pub fn is_empty(&self) -> bool {
+        self.adjacent.is_empty()
+    }
+
+    /// Returns whether the bombs have been placed yet.
+    pub fn is_generated(&self) -> bool {
+        self.was_generated
+    }
+
+    /// Returns how the game is going. Unlike `Board::state`, this
+    /// doesn't need to look at every `Tile`.
+    pub fn state(&self) -> GameState {
+        if self.lost {
+            GameState::Lost
+        } else if self.revealed == 0 {
+            GameState::Ready
+        } else if self.revealed == self.len() - self.bomb_count() - count_bits(&self.holes) {
+            GameState::Won
+        } else {
+            GameState::Playing
+        }
+    }
+
+    /// Returns the `Tile` at `index`, as a `Board` would hold it.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `index` is not within the bounds of
+    /// the grid.
+    pub fn tile(&self, index: usize) -> Tile {
+        Tile {
+            adjacent_bombs: isize::from(self.adjacent[index]),
+            state: self.tile_state(index),
+            is_bomb: self.is_bomb(index),
+            is_anti_mine: bit(&self.anti_mines, index),
+        }
+    }
+
+    /// Returns the indices of the `Tiles` around `index`. See
+    /// `Board::adjacent_tile_indices`.
+    pub fn adjacent_tile_indices(&self, index: usize) -> Vec<usize> {
+        if bit(&self.holes, index) {
+            return Vec::new();
+        }
+        let mut indices = match self.kernel {
+            Some(ref kernel) => {
+                kernel.adjacent_indices(index, self.width, self.len(), self.topology)
+            }
+            None => self.topology.adjacent_indices(index, self.width, self.len()),
+        };
+        indices.retain(|&i| !bit(&self.holes, i));
+        indices
+    }
+
+    /// Reveals the `Tile` at `index`, placing the bombs first if this
+    /// is the first reveal, and floods out from it as
+    /// `Board::reveal_tile` does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any `Tile` was not in a
+    /// revealable `TileState`, such as if it was flagged. It is safe to
+    /// discard this error; it is only for the programmer.
+    pub fn reveal_tile(&mut self, index: usize) -> Result<(), &'static str> {
+        if bit(&self.holes, index) {
+            return Err("Tried to reveal a Tile that isn't in play!");
+        }
+        if !self.was_generated {
+            self.generate(index);
+        }
+        if matches!(self.tile_state(index), TileState::Revealed) {
+            return Ok(());
+        }
+        self.reveal_one(index)?;
+        if self.is_bomb(index) {
+            // Nothing floods out of a bomb; the game is over
+            return Ok(());
+        }
+
+        let mut queued = vec![0u64; self.bombs.len()];
+        set_bit(&mut queued, index);
+        let mut queue = VecDeque::new();
+        queue.push_back(index);
+        while let Some(i) = queue.pop_front() {
+            if i != index {
+                self.reveal_one(i)?;
+            }
+            if !self.is_blank(i) {
+                continue;
+            }
+            for n in self.adjacent_tile_indices(i) {
+                if !bit(&queued, n) && self.raw_state(n) == HIDDEN {
+                    set_bit(&mut queued, n);
+                    queue.push_back(n);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Toggles a flag on the `Tile` at `index`. See `Board::flag_tile`;
+    /// there is no flag limit.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the `PackedBoard` has not
+    /// been generated yet, or if the `Tile` was not in a flaggable
+    /// `TileState`. It is safe to discard this error; it is only for
+    /// the programmer.
+    pub fn flag_tile(&mut self, index: usize) -> Result<(), FlagError> {
+        if !self.was_generated {
+            return Err(FlagError::NotGenerated);
+        }
+        if bit(&self.holes, index) {
+            return Err(FlagError::NotFlaggable);
+        }
+        match self.tile_state(index) {
+            TileState::Hidden => self.set_raw_state(index, FLAGGED),
+            TileState::Flagged => self.set_raw_state(index, HIDDEN),
+            _ => return Err(FlagError::NotFlaggable),
+        }
+        Ok(())
+    }
+
+    /// Reveals every hidden, unflagged `Tile` around the revealed
+    /// number at `index`, as `Board::chord_tile` does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the `Tile` is not a
+    /// revealed number, or if the number of flags around it doesn't
+    /// match. It is safe to discard this error; it is only for the
+    /// programmer.
+    pub fn chord_tile(&mut self, index: usize) -> Result<(), &'static str> {
+        if self.raw_state(index) != REVEALED || self.is_bomb(index) || self.adjacent[index] == 0 {
+            return Err("Tried to chord a Tile that isn't a revealed number!");
+        }
+        let adjacent = self.adjacent_tile_indices(index);
+        let flags = adjacent.iter().filter(|&&i| self.raw_state(i) == FLAGGED).count();
+        if flags as isize != isize::from(self.adjacent[index]) {
+            return Err("Tried to chord a Tile without the right number of flags!");
+        }
+        let mut result = Ok(());
+        for i in adjacent {
+            if matches!(self.tile_state(i), TileState::Hidden) {
+                result = result.and(self.reveal_tile(i));
+            }
+        }
+        result
+    }
+
+    /// Returns the hidden contents of every `Tile`, in the same format
+    /// as `Board::layout_string`.
+    pub fn layout_string(&self) -> String {
+        let cells = self.cells(layout::layout_cell, layout::HOLE);
+        GridFormat::for_cells(&cells).render(&cells, self.width)
+    }
+
+    /// Returns what the user can see, in the same format as
+    /// `Board::visible_string`.
+    pub fn visible_string(&self) -> String {
+        let cells = self.cells(layout::visible_cell, layout::HOLE);
+        GridFormat::for_cells(&cells).render(&cells, self.width)
+    }
+
+    /// Returns a `Board` with the same `Tiles`, seed, first-click rule,
+    /// `Topology`, `Kernel`, and mask.
+    pub fn to_board(&self) -> Board {
+        let mut board = if self.was_generated {
+            let tiles = (0..self.len()).map(|i| self.tile(i)).collect();
+            Board::from_tiles(self.width,
+                              self.height,
+                              self.topology,
+                              self.kernel.clone(),
+                              self.mask(),
+                              tiles)
+        } else {
+            Board::unchecked(self.width, self.height, self.num_mines)
+        };
+        board.topology = self.topology;
+        board.kernel = self.kernel.clone();
+        board.mask = self.mask();
+        board.num_anti_mines = self.num_anti_mines;
+        board.num_mines = self.num_mines;
+        board.seed = self.seed;
+        board.first_click = self.first_click;
+        board
+    }
+
+    fn generate(&mut self, index: usize) {
+        self.was_generated = true;
+
+        // The candidates are every Tile but the protected ones, in
+        // order, as for a Board, but are never written out in full
+        let mut protected = self.first_click.protected(index, self.adjacent_tile_indices(index));
+        protected.extend((0..self.len()).filter(|&i| bit(&self.holes, i)));
+        protected.sort_unstable();
+        protected.dedup();
+        let candidates = self.len() - protected.len();
+        let picks = match self.seed {
+            Some(seed) => {
+                let mut rng = SeededRng::new(seed);
+                util::sparse_sample(candidates, self.num_mines, |n| rng.below(n))
+            }
+            None => {
+                let mut rng = rand::thread_rng();
+                util::sparse_sample(candidates, self.num_mines, |n| rng.gen_range(0, n))
+            }
+        };
+        for pick in picks {
+            let mut bomb = pick;
+            for &p in &protected {
+                if p <= bomb {
+                    bomb += 1;
+                }
+            }
+            set_bit(&mut self.bombs, bomb);
+        }
+        if self.num_anti_mines > 0 {
+            // Picked from the bombs as a Board picks them
+            let mut bombs: Vec<usize> = (0..self.len()).filter(|&i| self.is_bomb(i)).collect();
+            match self.seed {
+                Some(seed) => {
+                    for pick in util::seeded_sample(bombs.len(), self.num_anti_mines, !seed) {
+                        set_bit(&mut self.anti_mines, bombs[pick]);
+                    }
+                }
+                None => {
+                    let mut rng = rand::thread_rng();
+                    for k in 0..self.num_anti_mines {
+                        let j = rng.gen_range(k, bombs.len());
+                        bombs.swap(k, j);
+                        set_bit(&mut self.anti_mines, bombs[k]);
+                    }
+                }
+            }
+        }
+
+        for index in 0..self.len() {
+            if self.is_bomb(index) {
+                let sign = if bit(&self.anti_mines, index) { -1 } else { 1 };
+                for n in self.adjacent_tile_indices(index) {
+                    if !self.is_bomb(n) {
+                        self.adjacent[n] += sign;
+                    }
+                }
+            }
+        }
+    }
+
+    // As `Board::is_blank`
+    fn is_blank(&self, index: usize) -> bool {
+        self.adjacent[index] == 0 &&
+        (self.num_anti_mines == 0 ||
+         self.adjacent_tile_indices(index).into_iter().all(|i| !self.is_bomb(i)))
+    }
+
+    fn reveal_one(&mut self, index: usize) -> Result<(), &'static str> {
+        match self.tile_state(index) {
+            TileState::Hidden => {}
+            TileState::Revealed => return Ok(()),
+            _ => return Err("Tried to reveal a Tile that can't be revealed!"),
+        }
+        self.set_raw_state(index, REVEALED);
+        if self.is_bomb(index) {
+            self.lost = true;
+            self.resolve_loss();
+        } else {
+            self.revealed += 1;
+        }
+        Ok(())
+    }
+
+    // Shows every unflagged bomb and every wrong flag, as
+    // `Board::resolve_loss` does
+    fn resolve_loss(&mut self) {
+        for index in 0..self.len() {
+            match (self.raw_state(index), self.is_bomb(index)) {
+                (HIDDEN, true) | (FLAGGED, false) => self.set_raw_state(index, SHOWN),
+                _ => {}
+            }
+        }
+    }
+
+    fn bomb_count(&self) -> usize {
+        count_bits(&self.bombs)
+    }
+
+    // Which Tiles are in play, as a Board holds it
+    fn mask(&self) -> Option<Vec<bool>> {
+        if count_bits(&self.holes) == 0 {
+            return None;
+        }
+        Some((0..self.len()).map(|i| !bit(&self.holes, i)).collect())
+    }
+
+    // Each Tile as written by `cell`, with `hole` in place of the ones
+    // that aren't in play
+    fn cells<F: Fn(&Tile) -> String>(&self, cell: F, hole: &str) -> Vec<String> {
+        (0..self.len())
+            .map(|i| if bit(&self.holes, i) { hole.to_string() } else { cell(&self.tile(i)) })
+            .collect()
+    }
+
+    fn is_bomb(&self, index: usize) -> bool {
+        bit(&self.bombs, index)
+    }
+
+    fn tile_state(&self, index: usize) -> TileState {
+        match (self.raw_state(index), self.is_bomb(index)) {
+            (HIDDEN, _) => TileState::Hidden,
+            (FLAGGED, _) => TileState::Flagged,
+            (REVEALED, true) => TileState::Detonated,
+            (REVEALED, false) => TileState::Revealed,
+            (_, true) => TileState::Exposed,
+            (_, false) => TileState::WrongFlag,
+        }
+    }
+
+    fn raw_state(&self, index: usize) -> u8 {
+        ((self.states[index / 32] >> (index % 32 * 2)) & 0b11) as u8
+    }
+
+    fn set_raw_state(&mut self, index: usize, state: u8) {
+        let shift = index % 32 * 2;
+        let word = &mut self.states[index / 32];
+        *word = (*word & !(0b11 << shift)) | (u64::from(state) << shift);
+    }
+}
+
+impl<'a> TryFrom<&'a Board> for PackedBoard {
+    type Error = KernelTooLarge;
+
+    fn try_from(board: &'a Board) -> Result<PackedBoard, KernelTooLarge> {
+        // A number is at most the number of offsets, or at least minus
+        // that with anti-mines
+        if board.kernel.as_ref().is_some_and(|k| k.offsets().len() > i8::MAX as usize) {
+            return Err(KernelTooLarge);
+        }
+        let mut packed = PackedBoard::unchecked(board.width, board.height, board.num_mines);
+        packed.was_generated = board.is_generated();
+        packed.seed = board.seed;
+        packed.first_click = board.first_click;
+        packed.topology = board.topology;
+        packed.kernel = board.kernel.clone();
+        packed.num_anti_mines = board.num_anti_mines;
+        for index in (0..board.tiles.len()).filter(|&i| !board.is_in_play(i)) {
+            set_bit(&mut packed.holes, index);
+        }
+        for (index, tile) in board.tiles.iter().enumerate() {
+            if tile.is_bomb {
+                set_bit(&mut packed.bombs, index);
+            }
+            if tile.is_anti_mine {
+                set_bit(&mut packed.anti_mines, index);
+            }
+            packed.adjacent[index] = tile.adjacent_bombs as i8;
+            let state = match tile.state {
+                TileState::Hidden => HIDDEN,
+                TileState::Flagged => FLAGGED,
+                TileState::Revealed | TileState::Detonated => REVEALED,
+                TileState::Exposed | TileState::WrongFlag => SHOWN,
+            };
+            packed.set_raw_state(index, state);
+        }
+        packed.lost = board.state() == GameState::Lost;
+        packed.revealed = board.tiles
+            .iter()
+            .filter(|t| !t.is_bomb && matches!(t.state, TileState::Revealed))
+            .count();
+        Ok(packed)
+    }
+}
+
+/// Prints the `PackedBoard` as `Board` does.
+impl fmt::Display for PackedBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cells = self.cells(|t| format!("{}", t), " ");
+        write!(f, "{}", GridFormat::for_cells(&cells).render(&cells, self.width))
+    }
+}
+
+fn count_bits(bits: &[u64]) -> usize {
+    bits.iter().map(|word| word.count_ones() as usize).sum()
+}
+
+fn bit(bits: &[u64], index: usize) -> bool {
+    bits[index / 64] & (1 << (index % 64)) != 0
+}
+
+fn set_bit(bits: &mut [u64], index: usize) {
+    bits[index / 64] |= 1 << (index % 64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Plays the same moves on a Board and a PackedBoard, checking that
+    // they agree after each one
+    #[test]
+    fn test_same_as_board() {
+        struct Test {
+            width: usize,
+            height: usize,
+            num_mines: usize,
+            seed: u64,
+        }
+
+        let tests = [Test {
+                         width: 9,
+                         height: 9,
+                         num_mines: 10,
+                         seed: 3,
+                     },
+                     Test {
+                         width: 30,
+                         height: 16,
+                         num_mines: 99,
+                         seed: 8,
+                     },
+                     Test {
+                         width: 16,
+                         height: 16,
+                         num_mines: 200,
+                         seed: 1,
+                     }];
+
+        for test in &tests {
+            let mut board =
+                Board::new(test.width, test.height, test.num_mines).with_seed(test.seed);
+            let mut packed = PackedBoard::new(test.width, test.height, test.num_mines)
+                .with_seed(test.seed);
+            board.reveal_tile(test.width + 1).unwrap();
+            packed.reveal_tile(test.width + 1).unwrap();
+            assert_eq!(packed.layout_string(), board.layout_string());
+
+            for i in 0..board.tiles.len() {
+                assert_eq!(packed.flag_tile(i).is_ok(), board.flag_tile(i).is_ok());
+                assert_eq!(packed.chord_tile(i).is_ok(), board.chord_tile(i).is_ok());
+                if i % 3 == 0 {
+                    assert_eq!(packed.flag_tile(i).is_ok(), board.flag_tile(i).is_ok());
+                }
+                assert_eq!(packed.reveal_tile(i).is_ok(), board.reveal_tile(i).is_ok());
+                assert_eq!(packed.visible_string(), board.visible_string());
+                assert_eq!(packed.state(), board.state());
+            }
+            assert_eq!(packed.to_string(), board.to_string());
+        }
+    }
+
+    #[test]
+    fn test_conversions() {
+        let tests = [Board::new(9, 9, 10).with_seed(4),
+                     Board::from_layout("1*1\n111\n...").unwrap(),
+                     Board::restore("1*1\n111\n...", "1!?\n11?\n...").unwrap(),
+                     Board::restore("1*1.\n1221\n.1*1", "X#??\n12?1\n.1*1").unwrap()];
+        for board in &tests {
+            let packed = PackedBoard::try_from(board).unwrap();
+            assert_eq!(packed.state(), board.state());
+            let back = packed.to_board();
+            assert_eq!(back.config(), board.config());
+            assert_eq!(back.seed(), board.seed());
+            assert_eq!(back.is_generated(), board.is_generated());
+            assert_eq!(back.visible_string(), board.visible_string());
+            assert_eq!(back.layout_string(), board.layout_string());
+        }
+    }
+
+    #[test]
+    fn test_large_kernel() {
+        let packed = |radius: usize| {
+            let mut b = Board::builder()
+                .width(30)
+                .height(30)
+                .mines(700)
+                .kernel(Kernel::window(radius))
+                .first_click(FirstClick::Unprotected)
+                .seed(1)
+                .build()
+                .unwrap();
+            b.reveal_tile(0).unwrap_or(());
+            PackedBoard::try_from(&b).map(|p| (p, b))
+        };
+        // 224 offsets, with numbers up to 224
+        assert_eq!(packed(7).err(), Some(KernelTooLarge));
+        // 120 offsets
+        let (p, b) = packed(5).unwrap();
+        assert_eq!(p.to_board().layout_string(), b.layout_string());
+    }
+
+    #[test]
+    fn test_lost() {
+        let mut b = PackedBoard::try_from(&Board::from_layout("1*1\n221\n*1.").unwrap()).unwrap();
+        b.flag_tile(0).unwrap();
+        b.flag_tile(1).unwrap();
+        b.reveal_tile(6).unwrap();
+        assert_eq!(b.state(), GameState::Lost);
+        assert_eq!(b.to_string(), "X!?\n???\n*??\n");
+        assert!(b.reveal_tile(4).is_ok());
+        assert!(b.flag_tile(0).is_err());
+    }
+}
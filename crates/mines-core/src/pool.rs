@@ -0,0 +1,91 @@
+#![warn(missing_docs)]
+//! [Re-exported] Recycling `Boards` for simulations that play through
+//! many games.
+//!
+//! Creating a `Board` allocates its `Tiles`. When simulating thousands
+//! of games in a tight loop, a `BoardPool` avoids that cost by
+//! resetting finished `Boards` in place instead.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{BoardPool, MineLayout};
+//!
+//! let layouts = vec![MineLayout::new(4, 4, &[0, 5]), MineLayout::new(4, 4, &[15])];
+//! let mut pool = BoardPool::new();
+//!
+//! for layout in &layouts {
+//!     let mut board = pool.take(layout);
+//!     let _ = board.reveal_tile(10);
+//!     // ...inspect the result, then hand the Board back...
+//!     pool.give_back(board);
+//! }
+//! assert_eq!(pool.len(), 1);
+//! ```
+
+use board::Board;
+use layout::MineLayout;
+
+/// A collection of spare `Boards` that can be reset to new layouts.
+#[derive(Clone, Debug, Default)]
+pub struct BoardPool {
+    spare: Vec<Board>,
+}
+
+impl BoardPool {
+    /// Creates an empty pool.
+    pub fn new() -> BoardPool {
+        BoardPool::default()
+    }
+
+    /// Returns a `Board` matching `layout`, reusing a spare one if the
+    /// pool has any.
+    pub fn take(&mut self, layout: &MineLayout) -> Board {
+        match self.spare.pop() {
+            Some(mut board) => {
+                board.reset_to_layout(layout);
+                board
+            }
+            None => Board::from_mine_layout(layout),
+        }
+    }
+
+    /// Returns a `Board` to the pool so it can be reused by `take`.
+    pub fn give_back(&mut self, board: Board) {
+        self.spare.push(board);
+    }
+
+    /// The number of spare `Boards` in the pool.
+    pub fn len(&self) -> usize {
+        self.spare.len()
+    }
+
+    /// Returns whether the pool has no spare `Boards`.
+    pub fn is_empty(&self) -> bool {
+        self.spare.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reuses_allocation() {
+        let mut pool = BoardPool::new();
+        assert!(pool.is_empty());
+
+        let big = MineLayout::new(10, 10, &[0]);
+        let mut board = pool.take(&big);
+        board.reveal_tile(99).unwrap();
+        let capacity = board.tiles.capacity();
+        pool.give_back(board);
+
+        let small = MineLayout::new(3, 3, &[4]);
+        let board = pool.take(&small);
+        assert!(pool.is_empty());
+        assert_eq!(board.tiles.capacity(), capacity);
+        assert_eq!(board.layout_string(), "111\n1*1\n111\n");
+        assert_eq!(board.visible_string(), "???\n???\n???\n");
+    }
+}
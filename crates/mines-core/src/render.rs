@@ -0,0 +1,681 @@
+#![warn(missing_docs)]
+//! [Re-exported] Helpers for turning a `Board` into text.
+//!
+//! A standard `Board` only ever needs one character per `Tile`, but
+//! variant boards can produce wider cells (such as adjacency counts of
+//! 10 or more). `GridFormat` pads every cell to a common width so the
+//! columns stay aligned regardless.
+//!
+//! `BoardDiff` prints two states of a `Board` next to each other, with
+//! the cells that changed between them highlighted. It is mainly a
+//! debugging aid, e.g. for checking exactly which `Tiles` a cascade
+//! revealed.
+//!
+//! A `NumberHook` changes the numbers the user is shown on revealed
+//! `Tiles`, for variants such as the liar variant. The `Board` still
+//! keeps the true numbers, and the stable text formats in `layout`
+//! always write them; only `Display`, `render_visible`, and
+//! `Board::shown_number` go through the hook. The `solver`, and with it
+//! `Board::hint` and `Board::auto_flag`, work from the numbers shown,
+//! so that they know no more than the user does.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::Board;
+//!
+//! // Always show one more than the truth
+//! let mut b = Board::from_layout("1*1\n111\n...").unwrap().with_number_hook(|_, n, _| n + 1);
+//! b.reveal_tile(6).unwrap();
+//! assert_eq!(b.shown_number(3), Some(2));
+//! assert_eq!(b.to_string(), "???\n222\n111\n");
+//! assert_eq!(b.visible_string(), "???\n111\n...\n");
+//! ```
+//!
+//! `DisplayOptions` chooses the text drawn for each kind of `Tile`,
+//! which may be any string at all, such as an emoji. `Board::render`
+//! draws a `Board` with them; `Display` is the same as rendering with
+//! the defaults.
+//!
+//! ```
+//! use mines_core::{Board, DisplayOptions};
+//!
+//! let mut b = Board::restore("1*1\n111\n...", "1!?\n???\n???").unwrap();
+//! b.reveal_tile(8).unwrap();
+//! let options = DisplayOptions {
+//!     hidden: String::from("■"),
+//!     flagged: String::from("🚩"),
+//!     zero: String::from(" "),
+//!     ..Default::default()
+//! };
+//! assert_eq!(b.render(&options), "1🚩■\n111\n   \n");
+//! assert_eq!(b.render(&DisplayOptions::default()), b.to_string());
+//! ```
+//!
+//! With `DisplayOptions::bordered`, the grid is drawn in a box with the
+//! columns lettered and the rows numbered, so that a command-line game
+//! can take moves like "reveal c4" (see `Board::parse_cell_name`):
+//!
+//! ```text
+//!     a b c
+//!   ┌───────┐
+//! 1 │ 1 ! ? │
+//! 2 │ 1 1 1 │
+//! 3 │ . . . │
+//!   └───────┘
+//! ```
+
+use std::fmt;
+
+use board::Board;
+use tile::TileState;
+use view::TileView;
+
+/// How cells are aligned within their column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    /// Pad on the right.
+    Left,
+    /// Pad on the left.
+    Right,
+}
+
+/// Options for rendering a grid of cells as column-aligned text.
+///
+/// # Examples
+///
+/// ```
+/// use mines_core::GridFormat;
+///
+/// let cells = ["1", "12", ".", "3"];
+/// let format = GridFormat {
+///     separator: String::from("|"),
+///     ..Default::default()
+/// };
+/// assert_eq!(format.render(&cells, 2), " 1|12\n .| 3\n");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GridFormat {
+    /// Placed between adjacent cells in a row.
+    pub separator: String,
+    /// The minimum width of every cell. Cells are always padded to
+    /// the width of the widest cell in the grid, even if that is
+    /// larger.
+    pub min_width: usize,
+    /// How cells narrower than the column are padded.
+    pub alignment: Alignment,
+}
+
+impl GridFormat {
+    /// The classic format: no separators, and no padding beyond what
+    /// the widest cell requires.
+    pub fn compact() -> GridFormat {
+        GridFormat {
+            separator: String::new(),
+            min_width: 1,
+            alignment: Alignment::Right,
+        }
+    }
+
+    /// Renders `cells` as rows of `width` cells, each row ending with
+    /// a newline.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `width` is zero.
+    pub fn render<S: AsRef<str>>(&self, cells: &[S], width: usize) -> String {
+        if width == 0 {
+            panic!("Tried to render a grid with a width of zero!");
+        }
+
+        let cell_width = cells.iter()
+            .map(|c| c.as_ref().chars().count())
+            .fold(self.min_width, usize::max);
+
+        let mut s = String::new();
+        for (i, cell) in cells.iter().enumerate() {
+            let cell = cell.as_ref();
+            let padding = cell_width - cell.chars().count();
+
+            if i % width != 0 {
+                s.push_str(&self.separator);
+            }
+            if self.alignment == Alignment::Right {
+                s.extend(::std::iter::repeat_n(' ', padding));
+            }
+            s.push_str(cell);
+            if self.alignment == Alignment::Left {
+                s.extend(::std::iter::repeat_n(' ', padding));
+            }
+            if (i + 1).is_multiple_of(width) {
+                s.push('\n');
+            }
+        }
+
+        s
+    }
+
+    /// Picks a suitable format for `cells`: `compact` if every cell is
+    /// a single character, otherwise space-separated columns.
+    pub fn for_cells<S: AsRef<str>>(cells: &[S]) -> GridFormat {
+        if cells.iter().all(|c| c.as_ref().chars().count() <= 1) {
+            GridFormat::compact()
+        } else {
+            GridFormat::default()
+        }
+    }
+}
+
+impl Default for GridFormat {
+    fn default() -> GridFormat {
+        GridFormat {
+            separator: String::from(" "),
+            min_width: 1,
+            alignment: Alignment::Right,
+        }
+    }
+}
+
+/// How `BoardDiff` highlights cells that changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Highlight {
+    /// Surround changed cells with square brackets.
+    Brackets,
+    /// Show changed cells in reverse video using ANSI escape codes,
+    /// for printing to a terminal.
+    Ansi,
+}
+
+/// Two states of the same `Board`, printed side by side with `Display`
+/// and with every changed cell highlighted on both sides.
+///
+/// # Examples
+///
+/// ```
+/// use mines_core::{Board, Highlight};
+///
+/// let before: Board = "1*1\n111\n...".parse().unwrap();
+/// let mut after = before.clone();
+/// after.reveal_tile(7).unwrap();
+///
+/// let diff = before.diff(&after, Highlight::Brackets);
+/// assert_eq!(diff.changed(), vec![3, 4, 5, 6, 7, 8]);
+/// println!("{}", diff);
+/// ```
+///
+/// Example output:
+///
+/// ```text
+///  ?  ?  ?     ?  ?  ?
+/// [?][?][?]   [1][1][1]
+/// [?][?][?]   [.][.][.]
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct BoardDiff<'a> {
+    before: &'a Board,
+    after: &'a Board,
+    highlight: Highlight,
+}
+
+impl<'a> BoardDiff<'a> {
+    /// Compares two states of the same `Board`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `Boards` are different sizes.
+    pub fn new(before: &'a Board, after: &'a Board, highlight: Highlight) -> BoardDiff<'a> {
+        if before.width != after.width || before.height != after.height {
+            panic!("Tried to diff Boards of different sizes! Sizes: {}x{} and {}x{}",
+                   before.width,
+                   before.height,
+                   after.width,
+                   after.height);
+        }
+        BoardDiff {
+            before,
+            after,
+            highlight,
+        }
+    }
+
+    /// Returns the indices of the `Tiles` that look different between
+    /// the two states.
+    pub fn changed(&self) -> Vec<usize> {
+        (0..self.before.tiles.len())
+            .filter(|&i| self.is_changed(i))
+            .collect()
+    }
+
+    fn is_changed(&self, index: usize) -> bool {
+        format!("{}", self.before.tiles[index]) != format!("{}", self.after.tiles[index])
+    }
+
+    fn push_cell(&self, s: &mut String, cell: &str, cell_width: usize, changed: bool) {
+        let padding = cell_width - cell.chars().count();
+        match (self.highlight, changed) {
+            (Highlight::Brackets, true) => {
+                s.push('[');
+                s.extend(::std::iter::repeat_n(' ', padding));
+                s.push_str(cell);
+                s.push(']');
+            }
+            (Highlight::Ansi, true) => {
+                s.push(' ');
+                s.extend(::std::iter::repeat_n(' ', padding));
+                s.push_str("\x1b[7m");
+                s.push_str(cell);
+                s.push_str("\x1b[0m");
+                s.push(' ');
+            }
+            (_, false) => {
+                s.push(' ');
+                s.extend(::std::iter::repeat_n(' ', padding));
+                s.push_str(cell);
+                s.push(' ');
+            }
+        }
+    }
+}
+
+impl<'a> fmt::Display for BoardDiff<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let before: Vec<String> = self.before.tiles.iter().map(|t| format!("{}", t)).collect();
+        let after: Vec<String> = self.after.tiles.iter().map(|t| format!("{}", t)).collect();
+        let cell_width = before.iter()
+            .chain(after.iter())
+            .map(|c| c.chars().count())
+            .fold(1, usize::max);
+
+        let width = self.before.width;
+        let mut s = String::new();
+        for y in 0..self.before.height {
+            for cells in &[&before, &after] {
+                if !s.is_empty() && !s.ends_with('\n') {
+                    s.push_str("   ");
+                }
+                for x in 0..width {
+                    let index = (width * y) + x;
+                    self.push_cell(&mut s, &cells[index], cell_width, self.is_changed(index));
+                }
+            }
+            s.push('\n');
+        }
+
+        write!(f, "{}", s)
+    }
+}
+
+/// The text drawn for each kind of `Tile` by `Board::render`. See the
+/// module documentation.
+///
+/// The defaults are the characters `Display` uses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisplayOptions {
+    /// A `Tile` that hasn't been revealed. Defaults to `?`.
+    pub hidden: String,
+    /// A flagged `Tile`. Defaults to `!`.
+    pub flagged: String,
+    /// A revealed `Tile` with no bombs around it. Defaults to `.`.
+    pub zero: String,
+    /// The text for each number, indexed by the number: `numbers[3]` is
+    /// drawn for a 3. Numbers it has no entry for (including negative
+    /// ones, from anti-mines) are written out in digits, as they are
+    /// by default.
+    pub numbers: Vec<String>,
+    /// The bomb the user revealed. Defaults to `*`.
+    pub detonated: String,
+    /// The anti-mine the user revealed. Defaults to `-`.
+    pub anti_mine: String,
+    /// A bomb shown after the game was lost, or survived with a life to
+    /// spare. Defaults to `#`.
+    pub exposed: String,
+    /// A flag on a `Tile` that isn't a bomb, shown after the game was
+    /// lost. Defaults to `X`.
+    pub wrong_flag: String,
+    /// A `Tile` that isn't in play. Defaults to a space.
+    pub out_of_play: String,
+    /// How the cells are laid out, or `None` to pick with
+    /// `GridFormat::for_cells` (or the default `GridFormat`, with a
+    /// border).
+    pub format: Option<GridFormat>,
+    /// Whether to draw a box around the grid, with the name of each
+    /// column above it and each row beside it, as `Board::cell_name`
+    /// gives them. Defaults to `false`.
+    pub bordered: bool,
+}
+
+impl DisplayOptions {
+    /// Returns the text for the `Tile` at `index` of `board`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `index` is not within the bounds of
+    /// the grid.
+    pub fn cell(&self, board: &Board, index: usize) -> String {
+        match board.tile_view(index) {
+            TileView::Hidden => self.hidden.clone(),
+            TileView::Flagged => self.flagged.clone(),
+            TileView::Number(0) => self.zero.clone(),
+            TileView::Number(n) => {
+                if n < 0 {
+                    return n.to_string();
+                }
+                match self.numbers.get(n as usize) {
+                    Some(number) => number.clone(),
+                    None => n.to_string(),
+                }
+            }
+            TileView::Detonated if board.tiles[index].is_anti_mine => self.anti_mine.clone(),
+            TileView::Detonated => self.detonated.clone(),
+            TileView::Exposed => self.exposed.clone(),
+            TileView::WrongFlag => self.wrong_flag.clone(),
+            TileView::OutOfPlay => self.out_of_play.clone(),
+        }
+    }
+}
+
+impl Default for DisplayOptions {
+    fn default() -> DisplayOptions {
+        DisplayOptions {
+            hidden: String::from("?"),
+            flagged: String::from("!"),
+            zero: String::from("."),
+            numbers: Vec::new(),
+            detonated: String::from("*"),
+            anti_mine: String::from("-"),
+            exposed: String::from("#"),
+            wrong_flag: String::from("X"),
+            out_of_play: String::from(" "),
+            format: None,
+            bordered: false,
+        }
+    }
+}
+
+// The name of column `x`: a to z, then aa, ab, and so on
+fn column_name(x: usize) -> String {
+    let mut name = Vec::new();
+    let mut n = x + 1;
+    while n > 0 {
+        n -= 1;
+        name.push(b'a' + (n % 26) as u8);
+        n /= 26;
+    }
+    name.reverse();
+    String::from_utf8(name).expect("the letters are ASCII")
+}
+
+/// Decides the number shown on each revealed `Tile`, in place of the
+/// true number. See the module documentation.
+///
+/// Any `Fn(usize, isize, usize) -> isize` closure, taking the same
+/// arguments as `report`, can be used as a `NumberHook`.
+pub trait NumberHook: Send + Sync {
+    /// Returns the number to show on the `Tile` at `index`, whose true
+    /// number is `number`, out of `most` neighbours. It should give the
+    /// same answer every time it is asked about the same `Tile`.
+    fn report(&self, index: usize, number: isize, most: usize) -> isize;
+
+    /// Returns the least and most the true number on the `Tile` at
+    /// `index` could be, given that it shows `shown` out of `most`
+    /// neighbours. The `solver` deduces from these bounds. By default
+    /// the number shown is the truth.
+    fn bounds(&self, _index: usize, shown: isize, _most: usize) -> (isize, isize) {
+        (shown, shown)
+    }
+}
+
+impl<F> NumberHook for F
+    where F: Fn(usize, isize, usize) -> isize + Send + Sync
+{
+    fn report(&self, index: usize, number: isize, most: usize) -> isize {
+        self(index, number, most)
+    }
+}
+
+impl Board {
+    /// Makes `hook` decide the numbers shown on revealed `Tiles`,
+    /// returning the `Board`. See the module documentation.
+    pub fn with_number_hook<H: NumberHook + 'static>(mut self, hook: H) -> Board {
+        self.number_hook = Some(::std::sync::Arc::new(hook));
+        self
+    }
+
+    /// Goes back to showing the true numbers.
+    pub fn clear_number_hook(&mut self) {
+        self.number_hook = None;
+    }
+
+    /// Returns the number shown on the `Tile` at `index`, as decided by
+    /// the `NumberHook` if there is one, or `None` if it is not a
+    /// revealed safe `Tile`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `index` is not within the bounds of
+    /// the grid.
+    pub fn shown_number(&self, index: usize) -> Option<isize> {
+        let tile = &self.tiles[index];
+        if tile.is_bomb || !matches!(tile.state, TileState::Revealed) || !self.is_in_play(index) {
+            return None;
+        }
+        Some(match self.number_hook {
+            Some(ref hook) => {
+                hook.report(index, tile.adjacent_bombs, self.adjacent_tile_indices(index).len())
+            }
+            None => tile.adjacent_bombs,
+        })
+    }
+
+    /// Draws what the user can see with the text in `options`. See the
+    /// module documentation.
+    pub fn render(&self, options: &DisplayOptions) -> String {
+        let cells: Vec<String> = (0..self.tiles.len()).map(|i| options.cell(self, i)).collect();
+        match (&options.format, options.bordered) {
+            (Some(format), false) => format.render(&cells, self.width),
+            (None, false) => GridFormat::for_cells(&cells).render(&cells, self.width),
+            (Some(format), true) => self.render_bordered(&cells, format),
+            (None, true) => self.render_bordered(&cells, &GridFormat::default()),
+        }
+    }
+
+    // Draws `cells` in a box, with the column names above and the row
+    // names to the left
+    fn render_bordered(&self, cells: &[String], format: &GridFormat) -> String {
+        let columns: Vec<String> = (0..self.width).map(column_name).collect();
+        // The names are cells too, so that the columns line up
+        let format = GridFormat {
+            min_width: cells.iter()
+                .chain(columns.iter())
+                .map(|c| c.chars().count())
+                .fold(format.min_width, usize::max),
+            ..format.clone()
+        };
+        let grid = format.render(cells, self.width);
+        let header = format.render(&columns, self.width);
+        let inner = header.trim_end_matches('\n').chars().count();
+        let margin = self.height.to_string().len();
+        let rule = "─".repeat(inner + 2);
+
+        let mut s = format!("{:margin$}   {}", "", header, margin = margin);
+        s.push_str(&format!("{:margin$} ┌{}┐\n", "", rule, margin = margin));
+        for (y, row) in grid.lines().enumerate() {
+            s.push_str(&format!("{:>margin$} │ {} │\n", y + 1, row, margin = margin));
+        }
+        s.push_str(&format!("{:margin$} └{}┘\n", "", rule, margin = margin));
+        s
+    }
+
+    /// Returns the name of the `Tile` at `index`, as drawn on a
+    /// bordered `Board` (see `DisplayOptions::bordered`): the letters
+    /// of its column (`a` to `z`, then `aa`, `ab`, and so on), followed
+    /// by its row, counting from 1 at the top. The top left `Tile` is
+    /// `a1`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `index` is not within the bounds of
+    /// the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    ///
+    /// let b = Board::new(30, 16, 99);
+    /// assert_eq!(b.cell_name(b.linear_coords((2, 3))), "c4");
+    /// assert_eq!(b.cell_name(b.linear_coords((27, 15))), "ab16");
+    /// assert_eq!(b.parse_cell_name("C4"), Some(b.linear_coords((2, 3))));
+    /// assert_eq!(b.parse_cell_name("c17"), None);
+    /// ```
+    pub fn cell_name(&self, index: usize) -> String {
+        let (x, y) = self.cartesian_coords(index);
+        format!("{}{}", column_name(x), y + 1)
+    }
+
+    /// Returns the index of the `Tile` called `name`, as from
+    /// `cell_name`, ignoring case and surrounding whitespace, or `None`
+    /// if it isn't a name or the `Tile` is off the `Board`.
+    pub fn parse_cell_name(&self, name: &str) -> Option<usize> {
+        let name = name.trim();
+        let digits = name.find(|c: char| c.is_ascii_digit())?;
+        let (letters, row) = name.split_at(digits);
+        if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        // The letters count in base 26, but from a to z rather than 0
+        let mut column = 0usize;
+        for b in letters.to_ascii_lowercase().bytes() {
+            column = column.checked_mul(26)?.checked_add(usize::from(b - b'a') + 1)?;
+        }
+        let x = column - 1;
+        let y = row.parse::<usize>().ok()?.checked_sub(1)?;
+        if x < self.width && y < self.height {
+            Some(self.linear_coords((x, y)))
+        } else {
+            None
+        }
+    }
+
+    // Each cell as `Display` writes it, with the numbers the hook shows
+    pub(crate) fn shown_cells(&self) -> Vec<String> {
+        let options = DisplayOptions::default();
+        (0..self.tiles.len()).map(|i| options.cell(self, i)).collect()
+    }
+
+    /// Compares this `Board` with a later state of it. See
+    /// `BoardDiff`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `Boards` are different sizes.
+    pub fn diff<'a>(&'a self, later: &'a Board, highlight: Highlight) -> BoardDiff<'a> {
+        BoardDiff::new(self, later, highlight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        struct Test {
+            format: GridFormat,
+            expected: &'static str,
+        }
+
+        let cells = ["1", "10", "*", ".", "?", "!"];
+        let tests = [Test {
+                         format: GridFormat::compact(),
+                         expected: " 110 *\n . ? !\n",
+                     },
+                     Test {
+                         format: GridFormat::default(),
+                         expected: " 1 10  *\n .  ?  !\n",
+                     },
+                     Test {
+                         format: GridFormat {
+                             separator: String::from(","),
+                             min_width: 3,
+                             alignment: Alignment::Left,
+                         },
+                         expected: "1  ,10 ,*  \n.  ,?  ,!  \n",
+                     }];
+
+        for test in &tests {
+            assert_eq!(test.format.render(&cells, 3), test.expected);
+        }
+    }
+
+    #[test]
+    fn test_display_options() {
+        let options = DisplayOptions {
+            numbers: vec![String::new(), String::from("a"), String::from("b")],
+            detonated: String::from("💣"),
+            format: Some(GridFormat::compact()),
+            ..Default::default()
+        };
+        // Negative numbers are always written out
+        let b = Board::restore("- -1 .\n-1 -1 .\n. . .", "- ? ?\n-1 ? ?\n? ? ?").unwrap();
+        assert_eq!(b.render(&options), " - ? ?\n-1 ? ?\n ? ? ?\n");
+
+        let mut b = Board::restore("*2*\n121\n...", "!??\n???\n???").unwrap();
+        b.reveal_tile(8).unwrap();
+        b.reveal_tile(1).unwrap();
+        b.reveal_tile(2).unwrap();
+        assert_eq!(b.render(&options), "!b💣\naba\n...\n");
+        assert_eq!(b.render(&DisplayOptions::default()), b.to_string());
+    }
+
+    #[test]
+    fn test_bordered() {
+        let mut b = Board::restore("1*1\n111\n...", "1!?\n???\n???").unwrap();
+        b.reveal_tile(8).unwrap();
+        let options = DisplayOptions {
+            bordered: true,
+            ..Default::default()
+        };
+        assert_eq!(b.render(&options),
+                   concat!("    a b c\n",
+                           "  ┌───────┐\n",
+                           "1 │ 1 ! ? │\n",
+                           "2 │ 1 1 1 │\n",
+                           "3 │ . . . │\n",
+                           "  └───────┘\n"));
+
+        let b = Board::new(28, 10, 10);
+        let rendered = b.render(&options);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert!(lines[0].ends_with(" z aa ab"));
+        assert!(lines[2].starts_with(" 1 │  ?  ?"));
+        assert!(lines[11].starts_with("10 │"));
+        for i in 0..b.tiles.len() {
+            assert_eq!(b.parse_cell_name(&b.cell_name(i)), Some(i));
+        }
+        for name in &["", "a", "4", "a0", "4a", "a-1", "é1"] {
+            assert_eq!(b.parse_cell_name(name), None);
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let before: Board = "1*1\n111\n...".parse().unwrap();
+        let mut after = before.clone();
+        after.reveal_tile(7).unwrap();
+
+        let diff = before.diff(&after, Highlight::Brackets);
+        assert_eq!(format!("{}", diff),
+                   " ?  ?  ?     ?  ?  ? \n[?][?][?]   [1][1][1]\n[?][?][?]   [.][.][.]\n");
+
+        let diff = before.diff(&after, Highlight::Ansi);
+        assert!(format!("{}", diff).contains("\x1b[7m.\x1b[0m"));
+        assert!(before.diff(&before, Highlight::Ansi).changed().is_empty());
+    }
+
+    #[test]
+    fn test_for_cells() {
+        assert_eq!(GridFormat::for_cells(&["1", "*"]), GridFormat::compact());
+        assert_eq!(GridFormat::for_cells(&["1", "12"]), GridFormat::default());
+    }
+}
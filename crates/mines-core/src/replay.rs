@@ -0,0 +1,333 @@
+#![warn(missing_docs)]
+//! [Re-exported] Playing back a finished game move by move.
+//!
+//! A `Replay` starts from a `Board` before anything was revealed, and
+//! applies a logged list of `Actions` to it one at a time. The start
+//! can be a seeded `Board`, which places its bombs the same way again
+//! on the first reveal, an explicit layout, or simply the `Board` the
+//! game was played on, as long as its action log was on.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{Action, Board, Replay};
+//!
+//! let mut b = Board::new(9, 9, 10).with_seed(7).with_action_log();
+//! b.reveal_tile(40).unwrap();
+//! b.reveal_tile(0).unwrap_or(());
+//!
+//! let mut replay = Replay::of(&b).unwrap();
+//! assert_eq!(replay.len(), b.action_log().unwrap().len());
+//! assert_eq!(replay.step().map(|a| a.action), Some(Action::Reveal(40)));
+//!
+//! replay.seek(replay.len());
+//! assert_eq!(replay.current_board().visible_string(), b.visible_string());
+//!
+//! // The same game, from the seed alone
+//! let start = Board::new(9, 9, 10).with_seed(7);
+//! let mut again = Replay::new(start, b.action_log().unwrap().to_vec());
+//! again.seek(again.len());
+//! assert_eq!(again.current_board().visible_string(), b.visible_string());
+//! ```
+//!
+//! # Exporting
+//!
+//! `Replay::write_json` writes a game out for speedrun analysis tools
+//! and video makers, as one JSON object with the `Board`'s settings,
+//! its layout, and every action with the time it was made:
+//!
+//! ```text
+//! {"format":"mines-replay","version":1,"width":9,"height":9,"mines":10,
+//!  "outcome":"won","duration_ms":5120,"layout":["1*1......", ...],
+//!  "events":[{"time_ms":0,"action":"reveal","x":4,"y":4}, ...]}
+//! ```
+//!
+//! `outcome` is `won`, `lost`, or `abandoned`, as for a `GameRecord`,
+//! and `layout` has one row per string, in the layout format of the
+//! `layout` module. Times are in milliseconds from the first action,
+//! so the first event is always at 0, and `duration_ms` is the time of
+//! the last. `action` is `reveal`, `flag`, or `chord`, on the `Tile` at
+//! (`x`, `y`), counting from the top left.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use action::{Action, LoggedAction};
+use board::{Board, GameState};
+use layout::ParseBoardError;
+use stats::Outcome;
+use tile::{Tile, TileState};
+
+/// A game being played back from its action log.
+#[derive(Clone, Debug)]
+pub struct Replay {
+    start: Board,
+    actions: Vec<LoggedAction>,
+    board: Board,
+    position: usize,
+}
+
+impl Replay {
+    /// Creates a replay of `actions`, starting from `start`, which
+    /// should be the `Board` as it was before the first action: either
+    /// ungenerated with the same seed, or generated with the same bombs.
+    pub fn new(start: Board, actions: Vec<LoggedAction>) -> Replay {
+        Replay {
+            board: start.clone(),
+            start,
+            actions,
+            position: 0,
+        }
+    }
+
+    /// Creates a replay of the game on `board`, from its bombs and its
+    /// action log. Returns `None` if the action log is off.
+    pub fn of(board: &Board) -> Option<Replay> {
+        let actions = board.action_log()?.to_vec();
+        let start = if board.is_generated() {
+            let tiles = board.tiles
+                .iter()
+                .map(|t| {
+                    Tile {
+                        state: TileState::Hidden,
+                        ..t.clone()
+                    }
+                })
+                .collect();
+            board.with_tiles(tiles)
+        } else {
+            // Nothing has happened yet, so it starts out as it is now
+            let mut start = board.clone();
+            start.log = None;
+            start.undo = None;
+            start
+        };
+        Some(Replay::new(start, actions))
+    }
+
+    /// Creates a replay of `actions` on the bombs in `layout` (see
+    /// `Board::from_layout`).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `layout` can't be parsed.
+    pub fn from_layout(layout: &str,
+                       actions: Vec<LoggedAction>)
+                       -> Result<Replay, ParseBoardError> {
+        Ok(Replay::new(Board::from_layout(layout)?, actions))
+    }
+
+    /// Returns the number of actions in the replay.
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Returns whether the replay has no actions at all.
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Returns how many actions have been applied so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns every action in the replay.
+    pub fn actions(&self) -> &[LoggedAction] {
+        &self.actions
+    }
+
+    /// Returns the `Board` as it stands after the actions applied so
+    /// far.
+    pub fn current_board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns how far into the game the replay is: the time between
+    /// the first action and the last one applied.
+    pub fn elapsed(&self) -> Duration {
+        match (self.actions.first(), self.position) {
+            (Some(first), p) if p > 0 => self.actions[p - 1].at.since(first.at),
+            _ => Duration::from_secs(0),
+        }
+    }
+
+    /// Applies the next action, returning it, or `None` at the end.
+    /// An action that fails is passed over, as it would have been in
+    /// the game.
+    pub fn step(&mut self) -> Option<LoggedAction> {
+        let next = *self.actions.get(self.position)?;
+        let _ = self.board.apply(next.action);
+        self.position += 1;
+        Some(next)
+    }
+
+    /// Moves to just after the first `position` actions, going back to
+    /// the start if need be. A `position` past the end moves to the
+    /// end.
+    pub fn seek(&mut self, position: usize) {
+        let position = position.min(self.actions.len());
+        if position < self.position {
+            self.board = self.start.clone();
+            self.position = 0;
+        }
+        while self.position < position {
+            self.step();
+        }
+    }
+
+    /// Writes the whole game as JSON, wherever the replay is up to. See
+    /// the `replay` module documentation for the format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return any error returned by `out`.
+    pub fn write_json<W: Write>(&self, mut out: W) -> io::Result<()> {
+        let mut end = self.clone();
+        end.seek(end.len());
+        let board = end.current_board();
+        let outcome = match board.state() {
+            GameState::Won => Outcome::Won,
+            GameState::Lost => Outcome::Lost,
+            GameState::Ready | GameState::Playing => Outcome::Abandoned,
+        };
+        // The layout format only uses characters that need no escaping
+        let layout: Vec<String> =
+            board.layout_string().lines().map(|row| format!("\"{}\"", row)).collect();
+        let events: Vec<String> = self.actions
+            .iter()
+            .map(|logged| {
+                let name = match logged.action {
+                    Action::Reveal(_) => "reveal",
+                    Action::Flag(_) => "flag",
+                    Action::Chord(_) => "chord",
+                };
+                let (x, y) = board.cartesian_coords(logged.action.index());
+                let time = logged.at.since(self.actions[0].at).as_millis();
+                format!("{{\"time_ms\":{},\"action\":\"{}\",\"x\":{},\"y\":{}}}",
+                        time,
+                        name,
+                        x,
+                        y)
+            })
+            .collect();
+        writeln!(out,
+                 "{{\"format\":\"mines-replay\",\"version\":1,\"width\":{},\"height\":{},\
+                  \"mines\":{},\"outcome\":\"{}\",\"duration_ms\":{},\"layout\":[{}],\
+                  \"events\":[{}]}}",
+                 board.width,
+                 board.height,
+                 board.num_mines,
+                 outcome,
+                 end.elapsed().as_millis(),
+                 layout.join(","),
+                 events.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use action::Action;
+    use clock::Timestamp;
+
+    fn logged(actions: &[Action]) -> Vec<LoggedAction> {
+        actions.iter()
+            .enumerate()
+            .map(|(i, &action)| {
+                LoggedAction {
+                    at: Timestamp::from_millis(1000 * i as u64),
+                    action,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_step_and_seek() {
+        let actions =
+            logged(&[Action::Reveal(8), Action::Flag(1), Action::Chord(4), Action::Flag(1)]);
+        let mut replay = Replay::from_layout("1*1\n111\n...", actions).unwrap();
+        assert!(!replay.is_empty());
+
+        struct Test {
+            seek: usize,
+            visible: &'static str,
+            elapsed: u64,
+        }
+
+        let tests = [Test {
+                         seek: 3,
+                         visible: "1!1\n111\n...\n",
+                         elapsed: 2,
+                     },
+                     Test {
+                         seek: 1,
+                         visible: "???\n111\n...\n",
+                         elapsed: 0,
+                     },
+                     Test {
+                         seek: 10,
+                         visible: "1?1\n111\n...\n",
+                         elapsed: 3,
+                     },
+                     Test {
+                         seek: 0,
+                         visible: "???\n???\n???\n",
+                         elapsed: 0,
+                     }];
+
+        for test in &tests {
+            replay.seek(test.seek);
+            assert_eq!(replay.position(), test.seek.min(4));
+            assert_eq!(replay.current_board().visible_string(), test.visible);
+            assert_eq!(replay.elapsed(), Duration::from_secs(test.elapsed));
+        }
+
+        assert_eq!(replay.step().unwrap().action, Action::Reveal(8));
+        replay.seek(4);
+        assert!(replay.step().is_none());
+    }
+
+    #[test]
+    fn test_of_board() {
+        let mut b = Board::new(16, 16, 40).with_action_log();
+        assert!(!Replay::of(&b).unwrap().current_board().is_generated());
+        b.reveal_tile(100).unwrap();
+        b.hint();
+        b.hint();
+
+        let mut replay = Replay::of(&b).unwrap();
+        replay.seek(replay.len());
+        assert_eq!(replay.current_board().visible_string(), b.visible_string());
+        assert!(Replay::of(&Board::default()).is_none());
+    }
+
+    #[test]
+    fn test_write_json() {
+        extern crate serde_json;
+
+        let actions = logged(&[Action::Reveal(8), Action::Flag(1), Action::Reveal(0),
+                               Action::Reveal(2)]);
+        let mut replay = Replay::from_layout("1*1\n111\n...", actions).unwrap();
+        replay.seek(1);
+        let mut out = Vec::new();
+        replay.write_json(&mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["outcome"], "won");
+        assert_eq!(json["duration_ms"], 3000);
+        assert_eq!(json["layout"], serde_json::json!(["1*1", "111", "..."]));
+        assert_eq!(json["events"][0],
+                   serde_json::json!({"time_ms": 0, "action": "reveal", "x": 2, "y": 2}));
+        assert_eq!(json["events"][1]["action"], "flag");
+        assert_eq!(json["events"].as_array().unwrap().len(), 4);
+        // Writing doesn't move the replay
+        assert_eq!(replay.position(), 1);
+
+        let mut out = Vec::new();
+        Replay::from_layout("1*1\n111\n...", Vec::new()).unwrap().write_json(&mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["outcome"], "abandoned");
+        assert_eq!(json["events"], serde_json::json!([]));
+    }
+}
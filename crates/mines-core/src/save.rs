@@ -0,0 +1,510 @@
+#![warn(missing_docs)]
+//! [Re-exported] Saving a game part way through, and picking it up
+//! again later.
+//!
+//! `Board::save` writes everything needed to carry on playing: the
+//! layout, the state of every `Tile`, whether the bombs have been
+//! placed yet, the move count, the settings that decide how they will
+//! be placed, and the rules of play: the lives (see `Board::with_lives`)
+//! and the flag limit (see `Board::with_flag_limit`). `Board::load`
+//! reads it back. The clock is not saved, so the timer restarts on the
+//! next reveal, as with `serde`.
+//!
+//! # Format
+//!
+//! Saves are plain text. The first line is `mines-save` and the version
+//! of the format; then come the settings, one per line; then the
+//! layout and the visible state, in the formats of `Board::restore`:
+//!
+//! ```text
+//! mines-save 5
+//! size 3 3 1
+//! generated yes
+//! moves 2
+//! seed -
+//! first-click opening
+//! orientation landscape
+//! topology square
+//! kernel -
+//! anti-mines 0
+//! lives 1
+//! flag-limit no
+//! layout
+//! 1*1
+//! 111
+//! ...
+//! visible
+//! ?!?
+//! 111
+//! ...
+//! ```
+//!
+//! The mine count on the `size` line includes the anti-mines, and the
+//! `anti-mines` line says how many of them there are. The `kernel` line
+//! is `-`, or the offsets of the `Kernel` as `x,y` pairs separated by
+//! spaces. Older saves can still be loaded: version 4 has no `lives` or
+//! `flag-limit` lines, and always has one life and no flag limit,
+//! version 3 has no `anti-mines` line either, version 2 has no `kernel`
+//! line, and version 1 has no `topology` line, and is always square.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::Board;
+//!
+//! let mut b = Board::new(9, 9, 10).with_seed(3);
+//! b.reveal_tile(40).unwrap();
+//!
+//! let mut saved = Vec::new();
+//! b.save(&mut saved).unwrap();
+//! let loaded = Board::load(&mut &saved[..]).unwrap();
+//! assert_eq!(loaded.visible_string(), b.visible_string());
+//! assert_eq!(loaded.move_count(), 1);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use board::Board;
+use builder::{BuildError, FirstClick};
+use layout::ParseBoardError;
+use orientation::Orientation;
+use topology::{Kernel, Topology, MAX_OFFSET};
+
+const HEADER: &str = "mines-save";
+const VERSION: u32 = 5;
+
+/// The ways loading a saved game can fail.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The save could not be read.
+    Io(io::Error),
+    /// The save does not start with the save header.
+    NotASave,
+    /// The save was written by a newer version of this crate.
+    UnsupportedVersion(u32),
+    /// A line of the save could not be read.
+    BadLine {
+        /// The one-indexed line number.
+        line: usize,
+    },
+    /// The layout or visible state could not be read.
+    Board(ParseBoardError),
+    /// The `Board` hasn't been generated, and its settings are ones
+    /// that `BoardBuilder::build` refuses, such as too many mines.
+    Build(BuildError),
+    /// An offset of the `Kernel` reaches further than `MAX_OFFSET`.
+    KernelOffset {
+        /// The (x, y) offset.
+        offset: (isize, isize),
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LoadError::Io(ref err) => write!(f, "Couldn't read the save: {}", err),
+            LoadError::NotASave => write!(f, "The file is not a saved game."),
+            LoadError::UnsupportedVersion(version) => {
+                write!(f,
+                       "The save is version {}, but only up to version {} is supported.",
+                       version,
+                       VERSION)
+            }
+            LoadError::BadLine { line } => write!(f, "Line {} of the save is malformed.", line),
+            LoadError::Board(ref err) => write!(f, "The saved board is malformed: {}", err),
+            LoadError::Build(ref err) => write!(f, "The saved board can't be played: {}", err),
+            LoadError::KernelOffset { offset } => {
+                write!(f,
+                       "The kernel offset ({}, {}) reaches further than {}.",
+                       offset.0,
+                       offset.1,
+                       MAX_OFFSET)
+            }
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            LoadError::Io(ref err) => Some(err),
+            LoadError::Board(ref err) => Some(err),
+            LoadError::Build(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(err: io::Error) -> LoadError {
+        LoadError::Io(err)
+    }
+}
+
+impl From<ParseBoardError> for LoadError {
+    fn from(err: ParseBoardError) -> LoadError {
+        LoadError::Board(err)
+    }
+}
+
+impl Board {
+    /// Writes the full state of the game to `out`. See the `save`
+    /// module documentation for the format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return any error returned by `out`.
+    pub fn save<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "{} {}", HEADER, VERSION)?;
+        writeln!(out, "size {} {} {}", self.width, self.height, self.num_mines)?;
+        writeln!(out, "generated {}", if self.was_generated { "yes" } else { "no" })?;
+        writeln!(out, "moves {}", self.moves)?;
+        match self.seed {
+            Some(seed) => writeln!(out, "seed {}", seed)?,
+            None => writeln!(out, "seed -")?,
+        }
+        let first_click = match self.first_click {
+            FirstClick::Opening => "opening",
+            FirstClick::Safe => "safe",
+            FirstClick::Unprotected => "unprotected",
+        };
+        writeln!(out, "first-click {}", first_click)?;
+        let orientation = match self.orientation {
+            Orientation::Landscape => "landscape",
+            Orientation::Portrait => "portrait",
+        };
+        writeln!(out, "orientation {}", orientation)?;
+        writeln!(out, "topology {}", self.topology.name())?;
+        match self.kernel {
+            Some(ref kernel) => {
+                let offsets: Vec<String> =
+                    kernel.offsets().iter().map(|&(x, y)| format!("{},{}", x, y)).collect();
+                writeln!(out, "kernel {}", offsets.join(" "))?
+            }
+            None => writeln!(out, "kernel -")?,
+        }
+        writeln!(out, "anti-mines {}", self.num_anti_mines)?;
+        writeln!(out, "lives {}", self.lives())?;
+        writeln!(out, "flag-limit {}", if self.flag_limit { "yes" } else { "no" })?;
+        write!(out, "layout\n{}visible\n{}", self.layout_string(), self.visible_string())
+    }
+
+    /// Reads a game written by `save`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `input` can't be read, or
+    /// doesn't hold a valid save.
+    pub fn load<R: Read>(input: &mut R) -> Result<Board, LoadError> {
+        let mut text = String::new();
+        input.read_to_string(&mut text)?;
+        let lines: Vec<&str> = text.lines().collect();
+        Board::load_lines(&lines).map(|(board, _)| board)
+    }
+
+    // Reads a game written by `save` from the start of `lines`,
+    // returning it with the number of lines it took up
+    pub(crate) fn load_lines(lines: &[&str]) -> Result<(Board, usize), LoadError> {
+        let version = match lines.first().and_then(|l| l.strip_prefix(HEADER)) {
+            Some(rest) => rest.trim().parse::<u32>().map_err(|_| LoadError::NotASave)?,
+            None => return Err(LoadError::NotASave),
+        };
+        if version > VERSION {
+            return Err(LoadError::UnsupportedVersion(version));
+        }
+
+        // The value after `key` on one-indexed `line`
+        let field = |line: usize, key: &str| -> Result<&str, LoadError> {
+            lines.get(line - 1)
+                .and_then(|l| l.strip_prefix(key))
+                .and_then(|rest| rest.strip_prefix(' '))
+                .ok_or(LoadError::BadLine { line })
+        };
+        let number =
+            |line: usize, s: &str| s.parse::<usize>().map_err(|_| LoadError::BadLine { line });
+
+        let size: Vec<&str> = field(2, "size")?.split(' ').collect();
+        if size.len() != 3 {
+            return Err(LoadError::BadLine { line: 2 });
+        }
+        let (width, height, num_mines) =
+            (number(2, size[0])?, number(2, size[1])?, number(2, size[2])?);
+        // Both grids must fit in the save, which also keeps the line
+        // numbers below from overflowing
+        if height > lines.len() {
+            return Err(LoadError::BadLine { line: 2 });
+        }
+        let was_generated = match field(3, "generated")? {
+            "yes" => true,
+            "no" => false,
+            _ => return Err(LoadError::BadLine { line: 3 }),
+        };
+        let moves = number(4, field(4, "moves")?)?;
+        let seed = match field(5, "seed")? {
+            "-" => None,
+            s => Some(s.parse::<u64>().map_err(|_| LoadError::BadLine { line: 5 })?),
+        };
+        let first_click = match field(6, "first-click")? {
+            "opening" => FirstClick::Opening,
+            "safe" => FirstClick::Safe,
+            "unprotected" => FirstClick::Unprotected,
+            _ => return Err(LoadError::BadLine { line: 6 }),
+        };
+        let orientation = match field(7, "orientation")? {
+            "landscape" => Orientation::Landscape,
+            "portrait" => Orientation::Portrait,
+            _ => return Err(LoadError::BadLine { line: 7 }),
+        };
+        let (topology, settings) = if version >= 2 {
+            let topology = Topology::from_name(field(8, "topology")?)
+                .ok_or(LoadError::BadLine { line: 8 })?;
+            (topology, 8)
+        } else {
+            (Topology::Square, 7)
+        };
+        let (kernel, settings) = if version >= 3 {
+            let kernel = match field(9, "kernel")? {
+                "-" => None,
+                s => Some(parse_kernel(s)?),
+            };
+            (kernel, 9)
+        } else {
+            (None, settings)
+        };
+        let (num_anti_mines, settings) = if version >= 4 {
+            (number(10, field(10, "anti-mines")?)?, 10)
+        } else {
+            (0, settings)
+        };
+        if num_anti_mines > num_mines {
+            return Err(LoadError::BadLine { line: 10 });
+        }
+        let (lives, settings) = if version >= 5 {
+            (number(11, field(11, "lives")?)?, 11)
+        } else {
+            (1, settings)
+        };
+        if lives == 0 {
+            return Err(LoadError::BadLine { line: 11 });
+        }
+        let (flag_limit, settings) = if version >= 5 {
+            let flag_limit = match field(12, "flag-limit")? {
+                "yes" => true,
+                "no" => false,
+                _ => return Err(LoadError::BadLine { line: 12 }),
+            };
+            (flag_limit, 12)
+        } else {
+            (false, settings)
+        };
+
+        // Then the two grids, each after its own heading
+        let block = |heading_line: usize, heading: &str| -> Result<String, LoadError> {
+            if lines.get(heading_line - 1) != Some(&heading) {
+                return Err(LoadError::BadLine { line: heading_line });
+            }
+            let rows = lines.get(heading_line..heading_line + height)
+                .ok_or(LoadError::BadLine { line: lines.len() + 1 })?;
+            Ok(rows.join("\n"))
+        };
+        let layout = block(settings + 1, "layout")?;
+        let visible = block(settings + 2 + height, "visible")?;
+
+        let mut board = Board::restore_shaped(&layout, &visible, topology, kernel)?;
+        if board.width != width || board.height != height {
+            return Err(LoadError::BadLine { line: 2 });
+        }
+        if was_generated && board.num_mines != num_mines {
+            return Err(LoadError::BadLine { line: 2 });
+        }
+        if was_generated && board.num_anti_mines != num_anti_mines {
+            return Err(LoadError::BadLine { line: 10 });
+        }
+        if !was_generated {
+            // The bombs are still to be placed, so the settings must be
+            // ones the generator can place them with
+            let mut builder = Board::builder()
+                .width(width)
+                .height(height)
+                .mines(num_mines - num_anti_mines)
+                .anti_mines(num_anti_mines)
+                .first_click(first_click)
+                .topology(topology);
+            if let Some(kernel) = board.kernel() {
+                builder = builder.kernel(kernel.clone());
+            }
+            if let Some(mask) = board.mask() {
+                builder = builder.mask(mask.to_vec());
+            }
+            builder.build().map_err(LoadError::Build)?;
+        }
+        board.num_mines = num_mines;
+        board.num_anti_mines = num_anti_mines;
+        board.was_generated = was_generated;
+        board.moves = moves;
+        board.seed = seed;
+        board.first_click = first_click;
+        board.orientation = orientation;
+        board.extra_lives = lives - 1;
+        board.flag_limit = flag_limit;
+        Ok((board, settings + 2 + 2 * height))
+    }
+}
+
+// Reads offsets like `1,2 2,1`, from line 9
+fn parse_kernel(s: &str) -> Result<Kernel, LoadError> {
+    let mut offsets = Vec::new();
+    for pair in s.split(' ') {
+        let mut parts = pair.split(',').map(|part| part.parse().ok());
+        let offset = match (parts.next(), parts.next(), parts.next()) {
+            (Some(Some(x)), Some(Some(y)), None) => (x, y),
+            _ => return Err(LoadError::BadLine { line: 9 }),
+        };
+        if !Kernel::offset_in_range(offset) {
+            return Err(LoadError::KernelOffset { offset });
+        }
+        offsets.push(offset);
+    }
+    Ok(Kernel::new(&offsets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tile::TileState;
+
+    #[test]
+    fn test_round_trip() {
+        let mut b = Board::builder()
+            .width(5)
+            .height(4)
+            .mines(3)
+            .seed(11)
+            .first_click(FirstClick::Safe)
+            .build()
+            .unwrap();
+        let mut boards = vec![b.clone()];
+        b.reveal_tile(0).unwrap();
+        boards.push(b.clone());
+        let hidden = (0..20).find(|&i| matches!(b.tiles[i].state, TileState::Hidden)).unwrap();
+        b.flag_tile(hidden).unwrap();
+        boards.push(b.clone());
+
+        for board in &boards {
+            let mut saved = Vec::new();
+            board.save(&mut saved).unwrap();
+            let loaded = Board::load(&mut &saved[..]).unwrap();
+            assert_eq!(loaded.layout_string(), board.layout_string());
+            assert_eq!(loaded.visible_string(), board.visible_string());
+            assert_eq!(loaded.is_generated(), board.is_generated());
+            assert_eq!(loaded.move_count(), board.move_count());
+            assert_eq!(loaded.num_mines, board.num_mines);
+            assert_eq!(loaded.seed(), board.seed());
+            assert_eq!(loaded.first_click, board.first_click);
+
+            // And it saves back the same
+            let mut again = Vec::new();
+            loaded.save(&mut again).unwrap();
+            assert_eq!(again, saved);
+        }
+
+        // An ungenerated Board places its bombs the same way once loaded
+        let mut saved = Vec::new();
+        boards[0].save(&mut saved).unwrap();
+        let mut loaded = Board::load(&mut &saved[..]).unwrap();
+        loaded.reveal_tile(0).unwrap();
+        assert_eq!(loaded.layout_string(), boards[1].layout_string());
+
+        // A hex Board keeps its Topology
+        let mut hex = Board::from_layout_with("*1..\n1...\n...1\n..1*", Topology::Hex).unwrap();
+        hex.reveal_tile(5).unwrap();
+        let mut saved = Vec::new();
+        hex.save(&mut saved).unwrap();
+        let loaded = Board::load(&mut &saved[..]).unwrap();
+        assert_eq!(loaded.topology(), Topology::Hex);
+        assert_eq!(loaded.visible_string(), hex.visible_string());
+
+        // And a Board with a Kernel keeps it
+        let mut knight = Board::builder()
+            .width(5)
+            .height(5)
+            .mines(3)
+            .seed(2)
+            .kernel(Kernel::knight())
+            .build()
+            .unwrap();
+        knight.reveal_tile(12).unwrap();
+        let mut saved = Vec::new();
+        knight.save(&mut saved).unwrap();
+        let loaded = Board::load(&mut &saved[..]).unwrap();
+        assert_eq!(loaded.kernel(), Some(&Kernel::knight()));
+        assert_eq!(loaded.layout_string(), knight.layout_string());
+        assert_eq!(loaded.visible_string(), knight.visible_string());
+
+        // The rules of play carry over, with a life already lost
+        let mut b = Board::from_layout("1*1\n221\n*1.").unwrap().with_lives(3).with_flag_limit();
+        b.reveal_tile(1).unwrap();
+        let mut saved = Vec::new();
+        b.save(&mut saved).unwrap();
+        let mut loaded = Board::load(&mut &saved[..]).unwrap();
+        assert_eq!((loaded.lives(), loaded.lives_left()), (3, 2));
+        assert!(loaded.flag_tile(6).is_ok());
+        assert!(loaded.flag_tile(0).is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        let good = "mines-save 1\nsize 3 3 1\ngenerated yes\nmoves 0\nseed -\nfirst-click \
+                    opening\norientation landscape\nlayout\n1*1\n111\n...\nvisible\n???\n???\n\
+                    ???\n";
+        assert!(Board::load(&mut good.as_bytes()).is_ok());
+
+        assert!(matches!(Board::load(&mut "hello".as_bytes()), Err(LoadError::NotASave)));
+        let newer = good.replace("mines-save 1", "mines-save 6");
+        assert!(matches!(Board::load(&mut newer.as_bytes()),
+                         Err(LoadError::UnsupportedVersion(6))));
+        let version_2 = good.replace("mines-save 1", "mines-save 2");
+        assert!(matches!(Board::load(&mut version_2.as_bytes()),
+                         Err(LoadError::BadLine { line: 8 })));
+        let version_3 = good.replace("mines-save 1", "mines-save 3")
+            .replace("landscape\nlayout", "landscape\ntopology square\nkernel 1\nlayout");
+        assert!(matches!(Board::load(&mut version_3.as_bytes()),
+                         Err(LoadError::BadLine { line: 9 })));
+        let far = version_3.replace("kernel 1", "kernel 9223372036854775807,0");
+        assert!(matches!(Board::load(&mut far.as_bytes()),
+                         Err(LoadError::KernelOffset { offset: (isize::MAX, 0) })));
+        let version_4 = good.replace("mines-save 1", "mines-save 4")
+            .replace("landscape\nlayout",
+                     "landscape\ntopology square\nkernel -\nanti-mines 1\nlayout");
+        assert!(matches!(Board::load(&mut version_4.as_bytes()),
+                         Err(LoadError::BadLine { line: 10 })));
+        let version_5 = good.replace("mines-save 1", "mines-save 5")
+            .replace("landscape\nlayout",
+                     "landscape\ntopology square\nkernel -\nanti-mines 0\nlives 0\nlayout");
+        assert!(matches!(Board::load(&mut version_5.as_bytes()),
+                         Err(LoadError::BadLine { line: 11 })));
+        let no_limit = version_5.replace("lives 0", "lives 1");
+        assert!(matches!(Board::load(&mut no_limit.as_bytes()),
+                         Err(LoadError::BadLine { line: 12 })));
+        let bad_moves = good.replace("moves 0", "moves x");
+        assert!(matches!(Board::load(&mut bad_moves.as_bytes()),
+                         Err(LoadError::BadLine { line: 4 })));
+        let short = good.replace("\n...\nvisible", "\nvisible");
+        assert!(matches!(Board::load(&mut short.as_bytes()), Err(LoadError::BadLine { .. })));
+        let too_tall = good.replace("size 3 3 1", "size 9 18446744073709551615 10");
+        assert!(matches!(Board::load(&mut too_tall.as_bytes()),
+                         Err(LoadError::BadLine { line: 2 })));
+        let wrong_count = good.replace("size 3 3 1", "size 3 3 2");
+        assert!(matches!(Board::load(&mut wrong_count.as_bytes()),
+                         Err(LoadError::BadLine { line: 2 })));
+        let too_many = good.replace("generated yes", "generated no")
+            .replace("size 3 3 1", "size 3 3 10")
+            .replace("1*1\n111", "...\n...");
+        assert!(matches!(Board::load(&mut too_many.as_bytes()),
+                         Err(LoadError::Build(BuildError::TooManyMines { mines: 10, max: 0 }))));
+        let bad_visible = good.replace("visible\n???", "visible\n2??");
+        assert!(matches!(Board::load(&mut bad_visible.as_bytes()), Err(LoadError::Board(_))));
+    }
+}
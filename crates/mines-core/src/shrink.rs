@@ -0,0 +1,215 @@
+#![warn(missing_docs)]
+//! Shrinking an action log down to the moves that cause a failure.
+//!
+//! A bug report often comes with a long action log (from
+//! `Board::action_log`, or a dump from `Board::dump_debug`), of which
+//! only a few moves matter. `shrink` replays the log from the starting
+//! `Board`, notes the first `Failure`, and then searches for the
+//! smallest list of moves that still fails the same way: first by
+//! cutting off everything after the failing move, then by removing ever
+//! smaller runs of moves until removing any single one of them makes
+//! the failure go away.
+//!
+//! A `Failure` is either an error returned by a move or a panic, which
+//! is how the `debug-invariants` feature reports a broken `Board`.
+//! Panics are caught, but the panic hook still prints them as usual.
+//!
+//! The starting `Board` is cloned for every replay, so it must place
+//! its bombs the same way every time: either already generated, or
+//! seeded. A dump from a seeded game whose log goes back to the first
+//! move can be replayed from a fresh `Board` with the same settings and
+//! seed.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{Action, Board};
+//! use mines_core::shrink::{self, Failure};
+//!
+//! let start = Board::from_layout("1*1\n111\n...").unwrap();
+//! let log = [Action::Reveal(8), Action::Flag(0), Action::Flag(2), Action::Flag(0),
+//!            Action::Reveal(2)];
+//! assert_eq!(shrink::replay(&start, &log),
+//!            Some(Failure::Error("Tried to reveal a Tile that can't be revealed!")));
+//!
+//! // Only the flag on the Tile that was revealed matters
+//! let minimal = shrink::shrink(&start, &log).unwrap();
+//! assert_eq!(minimal, vec![Action::Flag(2), Action::Reveal(2)]);
+//! ```
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use action::Action;
+use board::Board;
+
+/// How replaying a list of moves failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// A move returned this error.
+    Error(&'static str),
+    /// A move panicked with this message.
+    Panic(String),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Failure::Error(e) => write!(f, "error: {}", e),
+            Failure::Panic(ref message) => write!(f, "panic: {}", message),
+        }
+    }
+}
+
+/// Applies `actions` to a copy of `start` in order, and returns the
+/// first `Failure`, or `None` if every move worked.
+pub fn replay(start: &Board, actions: &[Action]) -> Option<Failure> {
+    failing_move(start, actions).map(|(_, failure)| failure)
+}
+
+/// Returns the smallest list of moves found from `actions` that fails
+/// the same way as all of them do when applied to `start`, or `None`
+/// if they don't fail at all.
+///
+/// The moves keep their order, and the result is 1-minimal: leaving
+/// out any one of them makes the failure go away, or changes it.
+pub fn shrink(start: &Board, actions: &[Action]) -> Option<Vec<Action>> {
+    let (at, failure) = failing_move(start, actions)?;
+    let prefix = &actions[..at + 1];
+    Some(shrink_by(prefix, |candidate| replay(start, candidate).as_ref() == Some(&failure)))
+}
+
+/// Returns the smallest list of moves found from `actions` for which
+/// `reproduces` still returns `true`, for failures that `shrink` can't
+/// see, such as a front-end drawing the `Board` wrongly.
+///
+/// `reproduces` should return `true` for `actions` itself; if it
+/// doesn't, `actions` is returned as it is.
+pub fn shrink_by<F>(actions: &[Action], mut reproduces: F) -> Vec<Action>
+    where F: FnMut(&[Action]) -> bool
+{
+    let mut current = actions.to_vec();
+    if !reproduces(&current) {
+        return current;
+    }
+
+    // Try removing runs of moves, halving their length whenever no run
+    // of the current length can go, until single moves can't either
+    let mut run = current.len().div_ceil(2).max(1);
+    loop {
+        let mut removed = false;
+        let mut start = 0;
+        while start < current.len() {
+            let end = (start + run).min(current.len());
+            let mut candidate = current[..start].to_vec();
+            candidate.extend_from_slice(&current[end..]);
+            if reproduces(&candidate) {
+                current = candidate;
+                removed = true;
+            } else {
+                start += run;
+            }
+        }
+        if run == 1 && !removed {
+            return current;
+        }
+        if !removed {
+            run = run.div_ceil(2);
+        }
+        run = run.min(current.len().max(1));
+    }
+}
+
+// The index and Failure of the first move in `actions` to fail
+fn failing_move(start: &Board, actions: &[Action]) -> Option<(usize, Failure)> {
+    let mut board = start.clone();
+    for (i, &action) in actions.iter().enumerate() {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| board.apply(action)));
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => return Some((i, Failure::Error(e))),
+            Err(payload) => {
+                let message = payload.downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                return Some((i, Failure::Panic(message)));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shrink() {
+        struct Test {
+            start: Board,
+            actions: Vec<Action>,
+            expected: Option<Vec<Action>>,
+        }
+
+        let tests = [Test {
+                         // Nothing fails
+                         start: Board::from_layout("1*1\n111\n...").unwrap(),
+                         actions: vec![Action::Reveal(8), Action::Flag(1)],
+                         expected: None,
+                     },
+                     Test {
+                         // Everything after the failure goes
+                         start: Board::from_layout("1*1\n111\n...").unwrap(),
+                         actions: vec![Action::Chord(0), Action::Reveal(8), Action::Flag(1)],
+                         expected: Some(vec![Action::Chord(0)]),
+                     },
+                     Test {
+                         start: Board::new(9, 9, 10).with_seed(3),
+                         actions: vec![Action::Reveal(40),
+                                       Action::Flag(8),
+                                       Action::Flag(80),
+                                       Action::Flag(8),
+                                       Action::Flag(80),
+                                       Action::Flag(80),
+                                       Action::Reveal(80),
+                                       Action::Reveal(1)],
+                         expected: Some(vec![Action::Reveal(40),
+                                             Action::Flag(80),
+                                             Action::Reveal(80)]),
+                     }];
+
+        for test in &tests {
+            let shrunk = shrink(&test.start, &test.actions);
+            assert_eq!(shrunk, test.expected);
+            if let Some(ref shrunk) = shrunk {
+                assert_eq!(replay(&test.start, shrunk), replay(&test.start, &test.actions));
+            }
+        }
+    }
+
+    #[test]
+    fn test_shrink_by() {
+        // Fails whenever both 3 and 7 are flagged, in that order
+        let actions: Vec<Action> = (0..20).map(Action::Flag).collect();
+        let shrunk = shrink_by(&actions, |candidate| {
+            let three = candidate.iter().position(|&a| a == Action::Flag(3));
+            let seven = candidate.iter().position(|&a| a == Action::Flag(7));
+            matches!((three, seven), (Some(a), Some(b)) if a < b)
+        });
+        assert_eq!(shrunk, vec![Action::Flag(3), Action::Flag(7)]);
+
+        // Doesn't reproduce at all
+        assert_eq!(shrink_by(&actions, |_| false), actions);
+    }
+
+    #[test]
+    fn test_panic() {
+        // Flagging out of bounds panics, as a broken invariant would
+        let start = Board::from_layout("1*1\n111\n...").unwrap();
+        let actions = [Action::Reveal(8), Action::Flag(0), Action::Flag(50)];
+        let failure = replay(&start, &actions).unwrap();
+        assert!(matches!(failure, Failure::Panic(ref m) if m.contains("out of bounds")));
+        assert_eq!(shrink(&start, &actions).unwrap(), vec![Action::Flag(50)]);
+    }
+}
@@ -0,0 +1,819 @@
+#![warn(missing_docs)]
+//! Logical deduction from what the user can see.
+//!
+//! The solver never looks at where the bombs actually are. It works
+//! from a `VisibleBoard`, which only contains the information a player
+//! has: which `Tiles` are revealed, the numbers on them, and the total
+//! number of mines.
+//!
+//! Two strategies are available:
+//!
+//! * `Strategy::SinglePoint` looks at one number at a time. If a
+//!   number already touches as many known mines as its value, its other
+//!   unknown neighbours are safe; if it has exactly as many unknown
+//!   neighbours as it has mines left to find, they are all mines.
+//!
+//! * `Strategy::ConstraintPropagation` also compares overlapping
+//!   numbers. If every unknown neighbour of one number is also next to
+//!   another, the difference between their remaining mine counts
+//!   belongs to the `Tiles` only the second one touches. The total
+//!   number of mines is treated as one more such constraint.
+//!
+//! Both strategies repeat until they can deduce nothing new.
+//!
+//! Numbers that are only known to lie within a range, as in the
+//! hot/cold variant, are used in the same way: a range that already
+//! touches its most mines makes its other neighbours safe, and one that
+//! needs every neighbour to reach its fewest makes them all mines.
+//!
+//! By default, flags are treated as unknown `Tiles`: the solver does not
+//! trust the player's flags. With `FlagMode::Trust`, it takes every
+//! flag as a known mine instead, which lets it go further when the
+//! flags are right, and makes it wrong when they aren't. `Solver::audit`
+//! checks the flags first, finding every one that is provably wrong
+//! from the numbers alone, and `Solver::explain` picks out the numbers
+//! that prove it.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::Board;
+//! use mines_core::solver;
+//!
+//! let b = Board::restore("1*1..\n11111\n...1*", "?????\n11111\n...1?").unwrap();
+//! let deductions = solver::solve(&b);
+//! assert_eq!(deductions.safe, vec![0, 2, 3, 4]);
+//! assert_eq!(deductions.mines, vec![1, 14]);
+//! ```
+
+use std::collections::HashMap;
+
+use board::{self, Board};
+use layout::{self, ParseBoardError};
+use tile::TileState;
+
+/// What the user can see of a single `Tile`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VisibleCell {
+    /// The `Tile` has not been revealed.
+    Hidden,
+    /// The `Tile` has been flagged.
+    Flagged,
+    /// The `Tile` has been revealed and shows how many bombs surround
+    /// it.
+    Revealed(usize),
+    /// The `Tile` has been revealed, but only shows that the number of
+    /// bombs surrounding it is between these two bounds, inclusive (as
+    /// in the hot/cold variant; see `Proximity`).
+    Range(usize, usize),
+    /// The `Tile` has been revealed and is a bomb.
+    Mine,
+}
+
+impl VisibleCell {
+    /// Returns whether the user doesn't know what this `Tile` is.
+    pub fn is_unknown(&self) -> bool {
+        matches!(*self, VisibleCell::Hidden | VisibleCell::Flagged)
+    }
+
+    /// Returns the least and most bombs that could surround this
+    /// `Tile`, if it is a revealed number.
+    pub fn bounds(&self) -> Option<(usize, usize)> {
+        match *self {
+            VisibleCell::Revealed(number) => Some((number, number)),
+            VisibleCell::Range(min, max) => Some((min, max)),
+            _ => None,
+        }
+    }
+}
+
+/// Everything the user can see of a `Board`, and nothing more.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VisibleBoard {
+    /// The horizontal width.
+    pub width: usize,
+    /// The vertical height.
+    pub height: usize,
+    /// The total number of mines on the `Board`.
+    pub num_mines: usize,
+    /// What can be seen of each `Tile`, in the same order as
+    /// `Board::tiles`.
+    pub cells: Vec<VisibleCell>,
+    /// The indices adjacent to each `Tile`.
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl VisibleBoard {
+    /// Captures what the user can currently see of `board`.
+    pub fn from_board(board: &Board) -> VisibleBoard {
+        let cells = board.tiles
+            .iter()
+            .enumerate()
+            .map(|(i, tile)| {
+                match tile.state {
+                    // A Tile that isn't in play has no neighbors and
+                    // can't be a bomb, so it is as good as a revealed 0
+                    _ if !board.is_in_play(i) => VisibleCell::Revealed(0),
+                    TileState::Hidden => VisibleCell::Hidden,
+                    // A wrong flag is known to be safe, but shows no
+                    // number, so it is no more use than a flag
+                    TileState::Flagged | TileState::WrongFlag => VisibleCell::Flagged,
+                    TileState::Exposed | TileState::Detonated => VisibleCell::Mine,
+                    TileState::Revealed if tile.is_bomb => VisibleCell::Mine,
+                    TileState::Revealed => revealed_cell(board, i),
+                }
+            })
+            .collect();
+        let neighbors = (0..board.tiles.len()).map(|i| board.adjacent_tile_indices(i)).collect();
+
+        VisibleBoard {
+            width: board.width,
+            height: board.height,
+            num_mines: board.num_mines,
+            cells,
+            neighbors,
+        }
+    }
+
+    /// Creates a `VisibleBoard` for a standard rectangular grid.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the grid is smaller than 3x3, or if
+    /// the number of `cells` doesn't match its dimensions.
+    pub fn new(width: usize, height: usize, num_mines: usize, cells: Vec<VisibleCell>)
+               -> VisibleBoard {
+        if width < 3 || height < 3 {
+            panic!("Tried to make a VisibleBoard smaller than 3x3! Size: {}x{}",
+                   width,
+                   height);
+        }
+        if cells.len() != width * height {
+            panic!("Wrong number of cells for a VisibleBoard! Cells passed: {}, Expected: {}",
+                   cells.len(),
+                   width * height);
+        }
+        let neighbors = (0..cells.len())
+            .map(|i| ::board::adjacent_indices(i, width, cells.len()))
+            .collect();
+
+        VisibleBoard {
+            width,
+            height,
+            num_mines,
+            cells,
+            neighbors,
+        }
+    }
+
+    /// Reads what the user can see from the visible format described in
+    /// the `layout` module documentation, which is also how `Board`'s
+    /// `Display` writes it. The format doesn't say how many mines there
+    /// are, so that is passed in. Bombs shown after a loss count as
+    /// mines, and wrong flags as flags.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the text is malformed,
+    /// smaller than 3x3, or has a cell that isn't in the format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::solver::{Solver, VisibleBoard};
+    ///
+    /// let view = VisibleBoard::parse("???\n111\n...", 1).unwrap();
+    /// assert_eq!(Solver::default().solve(&view).mines, vec![1]);
+    /// ```
+    pub fn parse(s: &str, num_mines: usize) -> Result<VisibleBoard, ParseBoardError> {
+        let rows = layout::split_rows(s)?;
+        let (width, height) = (rows[0].len(), rows.len());
+        if width < 3 || height < 3 {
+            return Err(ParseBoardError::TooSmall { width, height });
+        }
+
+        let mut cells = Vec::with_capacity(width * height);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                cells.push(match cell {
+                    // As in `from_board`, a Tile that isn't in play is
+                    // as good as a revealed 0, once it has no neighbors
+                    layout::HOLE | "." => VisibleCell::Revealed(0),
+                    "?" => VisibleCell::Hidden,
+                    "!" | "X" => VisibleCell::Flagged,
+                    "*" | "-" | "#" => VisibleCell::Mine,
+                    _ => {
+                        match cell.parse() {
+                            Ok(number) => VisibleCell::Revealed(number),
+                            Err(_) => {
+                                return Err(ParseBoardError::InvalidCell {
+                                    at: (x, y),
+                                    found: cell.to_string(),
+                                })
+                            }
+                        }
+                    }
+                });
+            }
+        }
+        let in_play: Vec<bool> =
+            rows.iter().flat_map(|row| row.iter().map(|&cell| cell != layout::HOLE)).collect();
+        let neighbors = (0..cells.len())
+            .map(|i| {
+                if !in_play[i] {
+                    return Vec::new();
+                }
+                board::adjacent_indices(i, width, cells.len())
+                    .into_iter()
+                    .filter(|&n| in_play[n])
+                    .collect()
+            })
+            .collect();
+
+        Ok(VisibleBoard {
+            width,
+            height,
+            num_mines,
+            cells,
+            neighbors,
+        })
+    }
+
+    /// Returns the indices of the `Tiles` adjacent to `index`.
+    pub fn neighbors(&self, index: usize) -> &[usize] {
+        &self.neighbors[index]
+    }
+}
+
+/// How hard the solver works.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    /// Only consider one number at a time.
+    SinglePoint,
+    /// Also compare overlapping numbers, and the total mine count.
+    ConstraintPropagation,
+}
+
+/// The `Tiles` the solver was able to prove something about. Both
+/// lists are sorted, and only contain `Tiles` that are unknown to the
+/// user.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Deductions {
+    /// The indices of `Tiles` that are provably not bombs.
+    pub safe: Vec<usize>,
+    /// The indices of `Tiles` that are provably bombs.
+    pub mines: Vec<usize>,
+}
+
+impl Deductions {
+    /// Returns whether nothing could be deduced.
+    pub fn is_empty(&self) -> bool {
+        self.safe.is_empty() && self.mines.is_empty()
+    }
+}
+
+/// Why a `Tile` can't be a bomb. See `Solver::explain`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Explanation {
+    /// The indices of the revealed numbers that make up the proof, in
+    /// ascending order.
+    pub numbers: Vec<usize>,
+    /// Whether the proof also needs the total number of mines.
+    pub uses_mine_count: bool,
+}
+
+/// What the solver makes of the player's flags.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlagMode {
+    /// Flags are treated as unknown `Tiles`.
+    #[default]
+    Ignore,
+    /// Flags are taken to be mines. They are left out of the
+    /// `Deductions`, since they were never in doubt.
+    Trust,
+}
+
+/// Deduces safe `Tiles` and mines from a `VisibleBoard`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Solver {
+    /// The strategy to use.
+    pub strategy: Strategy,
+    /// What to make of the player's flags.
+    pub flags: FlagMode,
+}
+
+impl Default for Solver {
+    fn default() -> Solver {
+        Solver::new(Strategy::ConstraintPropagation)
+    }
+}
+
+// "These unknown tiles contain between `min` and `max` mines"
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Constraint {
+    cells: Vec<usize>,
+    min: usize,
+    max: usize,
+}
+
+impl Solver {
+    /// Creates a solver using `strategy`.
+    pub fn new(strategy: Strategy) -> Solver {
+        Solver {
+            strategy,
+            flags: FlagMode::Ignore,
+        }
+    }
+
+    /// Sets what the solver makes of the player's flags, returning the
+    /// `Solver`.
+    pub fn with_flags(mut self, flags: FlagMode) -> Solver {
+        self.flags = flags;
+        self
+    }
+
+    /// Returns the flagged `Tiles` of `view` that are provably not
+    /// bombs, judging only by the numbers, in ascending order. These
+    /// are the flags that would lead the solver astray under
+    /// `FlagMode::Trust`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    /// use mines_core::solver::{Solver, VisibleBoard};
+    ///
+    /// let b = Board::restore("1*1\n111\n...", "!??\n111\n...").unwrap();
+    /// // The 1s against the wall prove the corner is safe
+    /// assert_eq!(Solver::default().audit(&VisibleBoard::from_board(&b)), vec![0]);
+    /// ```
+    pub fn audit(&self, view: &VisibleBoard) -> Vec<usize> {
+        self.with_flags(FlagMode::Ignore)
+            .solve(view)
+            .safe
+            .into_iter()
+            .filter(|&i| view.cells[i] == VisibleCell::Flagged)
+            .collect()
+    }
+
+    /// Returns everything that can be proven about the unknown `Tiles`
+    /// of `view`.
+    pub fn solve(&self, view: &VisibleBoard) -> Deductions {
+        self.deduce(view, true)
+    }
+
+    /// Explains why the `Tile` at `index` can't be a bomb, as a
+    /// smallest set of revealed numbers that proves it on their own:
+    /// taking any one of them away leaves the `Tile` unproven. This is
+    /// mainly for showing the user why a flag found by `audit` is
+    /// wrong. Flags play no part in the proof.
+    ///
+    /// A proof that only works with the total number of mines says so
+    /// in the `Explanation`; one that doesn't need it is always
+    /// preferred. Returns `None` if the `Tile` can't be proven safe.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mines_core::Board;
+    /// use mines_core::solver::{Solver, VisibleBoard};
+    ///
+    /// let b = Board::restore("1*1\n111\n...", "!??\n111\n...").unwrap();
+    /// let explanation = Solver::default().explain(&VisibleBoard::from_board(&b), 0).unwrap();
+    /// // The middle 1 has one bomb among three Tiles, and the right 1
+    /// // accounts for it in the two it shares
+    /// assert_eq!(explanation.numbers, vec![4, 5]);
+    /// assert!(!explanation.uses_mine_count);
+    /// ```
+    pub fn explain(&self, view: &VisibleBoard, index: usize) -> Option<Explanation> {
+        let solver = self.with_flags(FlagMode::Ignore);
+        let proves = |view: &VisibleBoard, count_mines: bool| {
+            solver.deduce(view, count_mines).safe.binary_search(&index).is_ok()
+        };
+        let uses_mine_count = if proves(view, false) {
+            false
+        } else if proves(view, true) {
+            true
+        } else {
+            return None;
+        };
+
+        // Try dropping the numbers furthest away first, so that what is
+        // left is as close to the Tile as it can be
+        let (x, y) = (index % view.width, index / view.width);
+        let distance = |i: usize| {
+            let (ix, iy) = (i % view.width, i / view.width);
+            ix.abs_diff(x).max(iy.abs_diff(y))
+        };
+        let mut numbers: Vec<usize> =
+            (0..view.cells.len()).filter(|&i| view.cells[i].bounds().is_some()).collect();
+        numbers.sort_by_key(|&i| (::std::cmp::Reverse(distance(i)), ::std::cmp::Reverse(i)));
+
+        let mut reduced = view.clone();
+        let mut needed = Vec::new();
+        for i in numbers {
+            // A revealed Tile that says nothing about its neighbours
+            let cell = reduced.cells[i];
+            reduced.cells[i] = VisibleCell::Range(0, reduced.neighbors(i).len());
+            if !proves(&reduced, uses_mine_count) {
+                reduced.cells[i] = cell;
+                needed.push(i);
+            }
+        }
+        needed.sort();
+        Some(Explanation {
+            numbers: needed,
+            uses_mine_count,
+        })
+    }
+
+    // Solves `view`, using the total number of mines only if
+    // `count_mines` is set
+    fn deduce(&self, view: &VisibleBoard, count_mines: bool) -> Deductions {
+        // None: unknown, Some(true): mine, Some(false): safe
+        let mut known: Vec<Option<bool>> = view.cells
+            .iter()
+            .map(|cell| {
+                match *cell {
+                    VisibleCell::Flagged if self.flags == FlagMode::Trust => Some(true),
+                    VisibleCell::Hidden | VisibleCell::Flagged => None,
+                    VisibleCell::Revealed(_) | VisibleCell::Range(..) => Some(false),
+                    VisibleCell::Mine => Some(true),
+                }
+            })
+            .collect();
+
+        loop {
+            let constraints = self.constraints(view, &known, count_mines);
+            let mut progress = false;
+
+            for c in &constraints {
+                progress |= apply(&mut known, &c.cells, c.min, c.max);
+            }
+
+            if !progress && self.strategy == Strategy::ConstraintPropagation {
+                progress = propagate(&mut known, &constraints);
+            }
+
+            if !progress {
+                break;
+            }
+        }
+
+        let mut deductions = Deductions::default();
+        for (i, cell) in view.cells.iter().enumerate() {
+            let trusted = self.flags == FlagMode::Trust && *cell == VisibleCell::Flagged;
+            if !cell.is_unknown() || trusted {
+                continue;
+            }
+            match known[i] {
+                Some(true) => deductions.mines.push(i),
+                Some(false) => deductions.safe.push(i),
+                None => {}
+            }
+        }
+        deductions
+    }
+
+    fn constraints(&self,
+                   view: &VisibleBoard,
+                   known: &[Option<bool>],
+                   count_mines: bool)
+                   -> Vec<Constraint> {
+        let mut constraints = Vec::new();
+
+        for (i, cell) in view.cells.iter().enumerate() {
+            if let Some((min, max)) = cell.bounds() {
+                let mut cells = Vec::new();
+                let mut found = 0;
+                for &n in view.neighbors(i) {
+                    match known[n] {
+                        Some(true) => found += 1,
+                        Some(false) => {}
+                        None => cells.push(n),
+                    }
+                }
+                // A number touching more mines than it shows means the
+                // view is inconsistent; there is nothing to learn there
+                if !cells.is_empty() && found <= max {
+                    cells.sort();
+                    constraints.push(Constraint {
+                        cells,
+                        min: min.saturating_sub(found),
+                        max: max - found,
+                    });
+                }
+            }
+        }
+
+        if count_mines && self.strategy == Strategy::ConstraintPropagation {
+            let found = known.iter().filter(|&&k| k == Some(true)).count();
+            let cells: Vec<usize> = (0..known.len()).filter(|&i| known[i].is_none()).collect();
+            if !cells.is_empty() && found <= view.num_mines {
+                constraints.push(Constraint {
+                    cells,
+                    min: view.num_mines - found,
+                    max: view.num_mines - found,
+                });
+            }
+        }
+
+        constraints
+    }
+}
+
+/// Solves `board` from what the user can currently see, using
+/// constraint propagation.
+pub fn solve(board: &Board) -> Deductions {
+    Solver::default().solve(&VisibleBoard::from_board(board))
+}
+
+/// Returns the flags on `board` that are provably wrong from what the
+/// user can currently see, using constraint propagation. See
+/// `Solver::audit`.
+pub fn audit_flags(board: &Board) -> Vec<usize> {
+    Solver::default().audit(&VisibleBoard::from_board(board))
+}
+
+/// Returns what the number on the revealed safe `Tile` at `index` of
+/// `board` tells the solver. The solver only counts bombs, so on a
+/// `Board` with anti-mines, where the numbers are bombs less
+/// anti-mines, it is told nothing by them.
+pub fn revealed_cell(board: &Board, index: usize) -> VisibleCell {
+    if board.num_anti_mines() > 0 {
+        return VisibleCell::Range(0, board.adjacent_tile_indices(index).len());
+    }
+    match (&board.number_hook, board.shown_number(index)) {
+        // The user only sees the number the hook shows, so that is all
+        // there is to go on
+        (Some(hook), Some(shown)) => {
+            let most = board.adjacent_tile_indices(index).len();
+            let (least, greatest) = hook.bounds(index, shown, most);
+            let clamp = |n: isize| n.clamp(0, most as isize) as usize;
+            match (clamp(least), clamp(greatest)) {
+                (least, greatest) if least == greatest => VisibleCell::Revealed(least),
+                (least, greatest) => VisibleCell::Range(least, greatest),
+            }
+        }
+        _ => VisibleCell::Revealed(board.tiles[index].adjacent_bombs as usize),
+    }
+}
+
+// Given that `cells` contain between `min` and `max` mines, marks the
+// unknown ones as safe if the most mines there could be have all been
+// found, or as mines if every one of them must be a mine to reach the
+// fewest. Returns whether anything new was learned.
+fn apply(known: &mut [Option<bool>], cells: &[usize], min: usize, max: usize) -> bool {
+    let found = cells.iter().filter(|&&c| known[c] == Some(true)).count();
+    let unknown: Vec<usize> = cells.iter().cloned().filter(|&c| known[c].is_none()).collect();
+    if unknown.is_empty() || found > max {
+        return false;
+    }
+    let is_mine = if found == max {
+        false
+    } else if min.saturating_sub(found) == unknown.len() {
+        true
+    } else {
+        return false;
+    };
+    for c in unknown {
+        known[c] = Some(is_mine);
+    }
+    true
+}
+
+// Applies the subset rule to pairs of overlapping constraints, stopping
+// as soon as something is learned (which makes the constraints stale).
+fn propagate(known: &mut [Option<bool>], constraints: &[Constraint]) -> bool {
+    // Only constraints sharing a cell can be subsets of one another
+    let mut by_cell: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, c) in constraints.iter().enumerate() {
+        for &cell in &c.cells {
+            by_cell.entry(cell).or_default().push(i);
+        }
+    }
+
+    for (a, small) in constraints.iter().enumerate() {
+        let candidates = &by_cell[&small.cells[0]];
+        for &b in candidates {
+            let large = &constraints[b];
+            if a == b || large.cells.len() <= small.cells.len() || large.max < small.min {
+                continue;
+            }
+            if !small.cells.iter().all(|c| large.cells.binary_search(c).is_ok()) {
+                continue;
+            }
+            let rest: Vec<usize> = large.cells
+                .iter()
+                .cloned()
+                .filter(|c| small.cells.binary_search(c).is_err())
+                .collect();
+            // The rest hold whatever the large one has that the small
+            // one can't account for
+            let min = large.min.saturating_sub(small.max);
+            if apply(known, &rest, min, large.max - small.min) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let b = Board::restore("1*1.\n111.\n._..", "1!?.\n11??\n._..").unwrap();
+        let view = VisibleBoard::parse(&b.visible_string(), 1).unwrap();
+        assert_eq!(view, VisibleBoard::from_board(&b));
+
+        assert_eq!(VisibleBoard::parse("???\n?a?\n???", 1),
+                   Err(ParseBoardError::InvalidCell {
+                       at: (1, 1),
+                       found: String::from("a"),
+                   }));
+        assert_eq!(VisibleBoard::parse("??\n??", 1),
+                   Err(ParseBoardError::TooSmall {
+                       width: 2,
+                       height: 2,
+                   }));
+    }
+
+    #[test]
+    fn test_strategies() {
+        struct Test {
+            layout: &'static str,
+            visible: &'static str,
+            strategy: Strategy,
+            expected: Deductions,
+        }
+
+        let tests = [Test {
+                         // A lone number says nothing
+                         layout: "1*1\n111\n...",
+                         visible: "???\n?1?\n???",
+                         strategy: Strategy::ConstraintPropagation,
+                         expected: Deductions::default(),
+                     },
+                     Test {
+                         // 1-1 against a wall needs the subset rule
+                         layout: "1*1\n111\n...",
+                         visible: "???\n111\n...",
+                         strategy: Strategy::SinglePoint,
+                         expected: Deductions::default(),
+                     },
+                     Test {
+                         layout: "1*1\n111\n...",
+                         visible: "???\n111\n...",
+                         strategy: Strategy::ConstraintPropagation,
+                         expected: Deductions {
+                             safe: vec![0, 2],
+                             mines: vec![1],
+                         },
+                     },
+                     Test {
+                         // A 1-2-1 pattern
+                         layout: "*2*\n121\n...",
+                         visible: "???\n121\n...",
+                         strategy: Strategy::ConstraintPropagation,
+                         expected: Deductions {
+                             safe: vec![1],
+                             mines: vec![0, 2],
+                         },
+                     },
+                     Test {
+                         // Flags are not trusted
+                         layout: "1*1..\n11111\n...1*",
+                         visible: "!!!??\n11111\n...1?",
+                         strategy: Strategy::SinglePoint,
+                         expected: Deductions {
+                             safe: vec![0, 2, 3, 4],
+                             mines: vec![1, 14],
+                         },
+                     }];
+
+        for test in &tests {
+            let board = Board::restore(test.layout, test.visible).unwrap();
+            let view = VisibleBoard::from_board(&board);
+            assert_eq!(Solver::new(test.strategy).solve(&view), test.expected);
+        }
+    }
+
+    #[test]
+    fn test_global_mine_count() {
+        // The only mine has already gone off, so everything else is
+        // safe, but no number says so
+        let mut cells = vec![VisibleCell::Hidden; 9];
+        cells[4] = VisibleCell::Mine;
+        let view = VisibleBoard::new(3, 3, 1, cells);
+        assert_eq!(view.neighbors(0), &[1, 3, 4]);
+
+        assert!(Solver::new(Strategy::SinglePoint).solve(&view).is_empty());
+        assert_eq!(Solver::default().solve(&view),
+                   Deductions {
+                       safe: vec![0, 1, 2, 3, 5, 6, 7, 8],
+                       mines: vec![],
+                   });
+    }
+
+    #[test]
+    fn test_flag_modes() {
+        struct Test {
+            visible: &'static str,
+            expected: Deductions,
+            wrong: Vec<usize>,
+        }
+
+        // Only the bottom-right bomb is known when flags are ignored
+        let layout = "1*1..\n11111\n...1*";
+        let tests = [Test {
+                         // Trusting the flag solves the top row outright
+                         visible: "?!???\n11111\n...1?",
+                         expected: Deductions {
+                             safe: vec![0, 2, 3, 4],
+                             mines: vec![14],
+                         },
+                         wrong: vec![],
+                     },
+                     Test {
+                         // A wrong flag leads the solver astray, which is
+                         // what the audit is for
+                         visible: "!????\n11111\n...1?",
+                         expected: Deductions {
+                             safe: vec![1, 2, 4, 14],
+                             mines: vec![3],
+                         },
+                         wrong: vec![0],
+                     }];
+
+        for test in &tests {
+            let board = Board::restore(layout, test.visible).unwrap();
+            let view = VisibleBoard::from_board(&board);
+            let solver = Solver::new(Strategy::SinglePoint).with_flags(FlagMode::Trust);
+            assert_eq!(solver.solve(&view), test.expected);
+            assert_eq!(audit_flags(&board), test.wrong);
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        struct Test {
+            layout: &'static str,
+            visible: &'static str,
+            index: usize,
+            expected: Option<Explanation>,
+        }
+
+        let tests = [Test {
+                         layout: "1*1..\n11111\n...1*",
+                         visible: "!????\n11111\n...1?",
+                         // The bomb in the corner pins down the one in
+                         // the top row, one number at a time
+                         index: 0,
+                         expected: Some(Explanation {
+                             numbers: vec![5, 7, 8, 13],
+                             uses_mine_count: false,
+                         }),
+                     },
+                     Test {
+                         // Nothing says where the bomb isn't
+                         layout: "1*1\n111\n...",
+                         visible: "!??\n?1?\n???",
+                         index: 0,
+                         expected: None,
+                     },
+                     Test {
+                         // Only the count says the last bomb has gone off
+                         layout: "*1.\n11.\n...",
+                         visible: "*!?\n???\n???",
+                         index: 1,
+                         expected: Some(Explanation {
+                             numbers: vec![],
+                             uses_mine_count: true,
+                         }),
+                     }];
+
+        for test in &tests {
+            let board = Board::restore(test.layout, test.visible).unwrap();
+            let view = VisibleBoard::from_board(&board);
+            assert_eq!(Solver::default().explain(&view, test.index), test.expected);
+        }
+    }
+
+    #[test]
+    fn test_deductions_are_sound() {
+        for seed in 0..20 {
+            let mut b = Board::new(16, 16, 40).with_seed(seed);
+            b.reveal_tile(100).unwrap();
+            let deductions = solve(&b);
+            for &i in &deductions.safe {
+                assert!(!b.tiles[i].is_bomb);
+            }
+            for &i in &deductions.mines {
+                assert!(b.tiles[i].is_bomb);
+            }
+        }
+    }
+}
@@ -0,0 +1,874 @@
+#![warn(missing_docs)]
+//! [Re-exported] Records of finished games, and ways to export them.
+//!
+//! A `GameRecord` summarises one game in a few plain numbers, so that
+//! front-ends can keep a history without holding on to whole `Boards`.
+//! Records can be written out as CSV, for spreadsheets, or as JSON
+//! lines, for anything else, through any `io::Write`.
+//!
+//! For speedruns, `PersonalBests` keeps the fastest win at each board
+//! size, along with split times at 25%, 50%, and 75% of the 3BV (the
+//! smallest number of clicks that clears the `Board`), so that a game
+//! in progress can be compared against it. The 3BV is counted from the
+//! `openings` of the `Board`: the regions of empty `Tiles` that reveal
+//! each other, along with the numbers around their edges.
+//!
+//! Every `Board` counts the clicks made on it, including the ones that
+//! did nothing, and `efficiency` reports them along with the IOE (3BV
+//! cleared per click).
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{Board, GameRecord, Outcome};
+//! use mines_core::stats;
+//!
+//! let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+//! b.reveal_tile(0).unwrap();
+//! b.reveal_tile(8).unwrap();
+//! b.reveal_tile(2).unwrap();
+//!
+//! let record = GameRecord::from_board(&b, 1_500_000_000);
+//! assert_eq!(record.outcome, Outcome::Won);
+//!
+//! let mut csv = Vec::new();
+//! stats::write_csv(&[record], &mut csv).unwrap();
+//! let csv = String::from_utf8(csv).unwrap();
+//! assert!(csv.starts_with("played_at,width,height,mines,outcome,duration_ms,seed,revealed\n"));
+//! assert!(csv.contains("1500000000,3,3,1,won,"));
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use action::Action;
+use board::{Board, GameState};
+use game::Game;
+use tile::TileState;
+
+/// The fractions of the 3BV at which split times are taken.
+pub const SPLITS: [f64; 3] = [0.25, 0.5, 0.75];
+
+/// How a game ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Outcome {
+    /// Every safe `Tile` was revealed.
+    Won,
+    /// A bomb was revealed.
+    Lost,
+    /// The game was left unfinished.
+    Abandoned,
+}
+
+/// Writes `won`, `lost`, or `abandoned`.
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            Outcome::Won => "won",
+            Outcome::Lost => "lost",
+            Outcome::Abandoned => "abandoned",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// The standard difficulty levels, recognised by size and mine count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Difficulty {
+    /// 9x9 with 10 mines.
+    Beginner,
+    /// 16x16 with 40 mines.
+    Intermediate,
+    /// 30x16 (or 16x30) with 99 mines.
+    Expert,
+    /// Anything else.
+    Custom,
+}
+
+impl Difficulty {
+    /// Returns the difficulty of a `Board` of the given size.
+    pub fn of(width: usize, height: usize, num_mines: usize) -> Difficulty {
+        match (width, height, num_mines) {
+            (9, 9, 10) => Difficulty::Beginner,
+            (16, 16, 40) => Difficulty::Intermediate,
+            (30, 16, 99) | (16, 30, 99) => Difficulty::Expert,
+            _ => Difficulty::Custom,
+        }
+    }
+
+    /// Returns the width, height, and number of mines of a standard
+    /// difficulty, or `None` for `Difficulty::Custom`. Expert is 30
+    /// wide and 16 tall.
+    pub fn dimensions(&self) -> Option<(usize, usize, usize)> {
+        match *self {
+            Difficulty::Beginner => Some((9, 9, 10)),
+            Difficulty::Intermediate => Some((16, 16, 40)),
+            Difficulty::Expert => Some((30, 16, 99)),
+            Difficulty::Custom => None,
+        }
+    }
+}
+
+/// A summary of one game.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameRecord {
+    /// When the game was played, in seconds since the Unix epoch. The
+    /// crate never reads the wall clock itself, so this is up to the
+    /// front-end.
+    pub played_at: u64,
+    /// The width of the `Board`.
+    pub width: usize,
+    /// The height of the `Board`.
+    pub height: usize,
+    /// The number of bombs on the `Board`.
+    pub num_mines: usize,
+    /// How the game ended.
+    pub outcome: Outcome,
+    /// How long the game lasted, from the first reveal.
+    pub duration: Duration,
+    /// The seed the `Board` was generated from, if any.
+    pub seed: Option<u64>,
+    /// How many safe `Tiles` had been revealed.
+    pub revealed: usize,
+}
+
+impl GameRecord {
+    /// Summarises the game on `board` as it stands, stamped with
+    /// `played_at`. An unfinished game counts as abandoned.
+    pub fn from_board(board: &Board, played_at: u64) -> GameRecord {
+        let outcome = match board.state() {
+            GameState::Won => Outcome::Won,
+            GameState::Lost => Outcome::Lost,
+            GameState::Ready | GameState::Playing => Outcome::Abandoned,
+        };
+        GameRecord {
+            played_at,
+            width: board.width,
+            height: board.height,
+            num_mines: board.num_mines,
+            outcome,
+            duration: board.elapsed(),
+            seed: board.seed(),
+            revealed: board.tiles
+                .iter()
+                .filter(|t| !t.is_bomb && matches!(t.state, TileState::Revealed))
+                .count(),
+        }
+    }
+
+    /// Returns the difficulty the game was played at.
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty::of(self.width, self.height, self.num_mines)
+    }
+
+    /// Returns how long the game lasted, in whole milliseconds.
+    pub fn duration_millis(&self) -> u64 {
+        self.duration.as_secs() * 1000 + u64::from(self.duration.subsec_millis())
+    }
+}
+
+/// Writes `records` as CSV, with a header row. An unseeded game has an
+/// empty `seed` column.
+///
+/// # Errors
+///
+/// This function will return any error returned by `out`.
+pub fn write_csv<W: Write>(records: &[GameRecord], mut out: W) -> io::Result<()> {
+    writeln!(out,
+             "played_at,width,height,mines,outcome,duration_ms,seed,revealed")?;
+    for r in records {
+        writeln!(out,
+                 "{},{},{},{},{},{},{},{}",
+                 r.played_at,
+                 r.width,
+                 r.height,
+                 r.num_mines,
+                 r.outcome,
+                 r.duration_millis(),
+                 r.seed.map(|s| s.to_string()).unwrap_or_default(),
+                 r.revealed)?;
+    }
+    Ok(())
+}
+
+/// Writes `records` as JSON lines: one JSON object per line, with the
+/// same fields as the CSV columns. An unseeded game has a `null` seed.
+///
+/// # Errors
+///
+/// This function will return any error returned by `out`.
+pub fn write_jsonl<W: Write>(records: &[GameRecord], mut out: W) -> io::Result<()> {
+    for r in records {
+        writeln!(out,
+                 "{{\"played_at\":{},\"width\":{},\"height\":{},\"mines\":{},\"outcome\":\"{}\",\
+                  \"duration_ms\":{},\"seed\":{},\"revealed\":{}}}",
+                 r.played_at,
+                 r.width,
+                 r.height,
+                 r.num_mines,
+                 r.outcome,
+                 r.duration_millis(),
+                 r.seed.map(|s| s.to_string()).unwrap_or_else(|| String::from("null")),
+                 r.revealed)?;
+    }
+    Ok(())
+}
+
+/// An opening of a `Board`: a connected region of empty `Tiles`, and
+/// the numbers around its edge that revealing it also reveals.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Opening {
+    /// The indices of the empty `Tiles`, in ascending order.
+    pub zeros: Vec<usize>,
+    /// The indices of the numbered `Tiles` around the edge, in
+    /// ascending order.
+    pub border: Vec<usize>,
+}
+
+impl Opening {
+    /// Returns how many `Tiles` revealing the opening reveals.
+    pub fn size(&self) -> usize {
+        self.zeros.len() + self.border.len()
+    }
+
+    /// Returns whether any `Tile` of the opening has been revealed on
+    /// `board`, which means all of it has been.
+    pub fn is_opened(&self, board: &Board) -> bool {
+        self.zeros.iter().chain(&self.border).any(|&i| {
+            matches!(board.tiles[i].state, TileState::Revealed)
+        })
+    }
+}
+
+/// Returns every opening of `board`, in order of their lowest index.
+/// An ungenerated `Board` has no openings.
+///
+/// # Examples
+///
+/// ```
+/// use mines_core::Board;
+/// use mines_core::stats;
+///
+/// let b = Board::from_layout("..1*\n..11\n....").unwrap();
+/// let openings = stats::openings(&b);
+/// assert_eq!(openings.len(), 1);
+/// assert_eq!(openings[0].border, vec![2, 6, 7]);
+/// assert_eq!(openings[0].size(), 11);
+/// ```
+pub fn openings(board: &Board) -> Vec<Opening> {
+    if !board.is_generated() {
+        return Vec::new();
+    }
+    let empty = |i: usize| {
+        board.is_in_play(i) && !board.tiles[i].is_bomb && board.is_blank(i)
+    };
+
+    let mut openings = Vec::new();
+    let mut seen = vec![false; board.tiles.len()];
+    for start in 0..board.tiles.len() {
+        if !empty(start) || seen[start] {
+            continue;
+        }
+        let mut zeros = vec![start];
+        let mut todo = vec![start];
+        seen[start] = true;
+        let mut border = HashSet::new();
+        while let Some(index) = todo.pop() {
+            for i in board.adjacent_tile_indices(index) {
+                if empty(i) {
+                    if !seen[i] {
+                        seen[i] = true;
+                        zeros.push(i);
+                        todo.push(i);
+                    }
+                } else {
+                    border.insert(i);
+                }
+            }
+        }
+        zeros.sort_unstable();
+        let mut border: Vec<usize> = border.into_iter().collect();
+        border.sort_unstable();
+        openings.push(Opening { zeros, border });
+    }
+    openings
+}
+
+// Returns (cleared, total) 3BV for `board`: each opening counts once,
+// as does each safe Tile that isn't on the edge of an opening
+pub(crate) fn bbbv_progress(board: &Board) -> (usize, usize) {
+    if !board.is_generated() {
+        return (0, 0);
+    }
+    let openings = openings(board);
+    let mut cleared = openings.iter().filter(|o| o.is_opened(board)).count();
+    let mut total = openings.len();
+    let mut covered = vec![false; board.tiles.len()];
+    for opening in &openings {
+        for &i in opening.zeros.iter().chain(&opening.border) {
+            covered[i] = true;
+        }
+    }
+    for (i, tile) in board.tiles.iter().enumerate() {
+        if !tile.is_bomb && !covered[i] && board.is_in_play(i) {
+            total += 1;
+            if matches!(tile.state, TileState::Revealed) {
+                cleared += 1;
+            }
+        }
+    }
+    (cleared, total)
+}
+
+/// Returns the rate the game on `board` is being cleared at, in 3BV
+/// per second, or `None` before any time has passed.
+pub fn bbbv_per_second(board: &Board) -> Option<f64> {
+    let elapsed = board.elapsed();
+    let seconds = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) / 1e9;
+    if seconds == 0.0 {
+        return None;
+    }
+    Some(bbbv_progress(board).0 as f64 / seconds)
+}
+
+/// Returns how long the game on `board` will take in total if the rest
+/// of its 3BV is cleared at the current 3BV per second, so that
+/// front-ends can show an estimated finish time.
+///
+/// Returns `None` until some 3BV has been cleared with time on the
+/// clock, and once the game has been lost.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use mines_core::{Board, GameClock, MockClock};
+/// use mines_core::stats;
+///
+/// let clock = MockClock::new();
+/// let mut b = Board::from_layout("*2*\n242\n*2*")
+///     .unwrap()
+///     .with_clock(GameClock::new(clock.clone()));
+/// b.reveal_tile(1).unwrap();
+/// clock.advance(Duration::from_secs(4));
+/// b.reveal_tile(3).unwrap();
+/// // 2 of 5 3BV in 4 seconds
+/// assert_eq!(stats::projected_time(&b), Some(Duration::from_secs(10)));
+/// ```
+pub fn projected_time(board: &Board) -> Option<Duration> {
+    if board.state() == GameState::Lost {
+        return None;
+    }
+    let (cleared, total) = bbbv_progress(board);
+    let rate = bbbv_per_second(board)?;
+    if cleared == 0 {
+        return None;
+    }
+    let remaining = (total - cleared) as f64 / rate;
+    Some(board.elapsed() + Duration::from_millis((remaining * 1000.0).round() as u64))
+}
+
+/// The clicks made on a `Board` so far.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Clicks {
+    /// Every reveal, whether or not it worked.
+    pub left: usize,
+    /// Every flag placed or removed, whether or not it worked.
+    pub right: usize,
+    /// Every chord, whether or not it worked.
+    pub chords: usize,
+    /// The flags placed.
+    pub flags: usize,
+    /// The clicks that revealed nothing and placed no flag: reveals and
+    /// chords that failed or had nothing to reveal, and flags that were
+    /// removed or couldn't be placed.
+    pub wasted: usize,
+}
+
+impl Clicks {
+    /// Returns the total number of clicks.
+    pub fn total(&self) -> usize {
+        self.left + self.right + self.chords
+    }
+
+    // Counts one click
+    pub(crate) fn count(&mut self, action: Action, wasted: bool) {
+        match action {
+            Action::Reveal(_) => self.left += 1,
+            Action::Flag(_) => {
+                self.right += 1;
+                if !wasted {
+                    self.flags += 1;
+                }
+            }
+            Action::Chord(_) => self.chords += 1,
+        }
+        if wasted {
+            self.wasted += 1;
+        }
+    }
+}
+
+/// How efficiently a game has been played.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Efficiency {
+    /// The clicks made so far.
+    pub clicks: Clicks,
+    /// The 3BV cleared so far.
+    pub solved_bbbv: usize,
+    /// The 3BV of the whole `Board`.
+    pub bbbv: usize,
+}
+
+impl Efficiency {
+    /// Returns the IOE: the 3BV cleared per click. Returns `None` before
+    /// any clicks.
+    pub fn ioe(&self) -> Option<f64> {
+        match self.clicks.total() {
+            0 => None,
+            clicks => Some(self.solved_bbbv as f64 / clicks as f64),
+        }
+    }
+}
+
+/// Returns the clicks made on `board` so far, and how efficient they
+/// were.
+///
+/// # Examples
+///
+/// ```
+/// use mines_core::Board;
+/// use mines_core::stats;
+///
+/// let mut b = Board::from_layout("*2*\n242\n*2*").unwrap();
+/// b.reveal_tile(1).unwrap();
+/// b.flag_tile(0).unwrap();
+/// let _ = b.reveal_tile(1);
+/// let efficiency = stats::efficiency(&b);
+/// assert_eq!(efficiency.clicks.total(), 3);
+/// assert_eq!(efficiency.clicks.wasted, 1);
+/// assert_eq!((efficiency.solved_bbbv, efficiency.bbbv), (1, 5));
+/// assert_eq!(efficiency.ioe(), Some(1.0 / 3.0));
+/// ```
+pub fn efficiency(board: &Board) -> Efficiency {
+    let (solved_bbbv, bbbv) = bbbv_progress(board);
+    Efficiency {
+        clicks: board.clicks,
+        solved_bbbv,
+        bbbv,
+    }
+}
+
+/// The times at which a game reached each of the `SPLITS`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Splits {
+    times: [Option<Duration>; 3],
+}
+
+impl Splits {
+    /// Creates an empty set of splits.
+    pub fn new() -> Splits {
+        Splits::default()
+    }
+
+    /// Records the elapsed time on `board` against every split it has
+    /// newly reached. `Game` calls this after every move.
+    pub fn observe(&mut self, board: &Board) {
+        let (cleared, total) = bbbv_progress(board);
+        for (i, time) in self.times.iter_mut().enumerate() {
+            if time.is_none() && total > 0 && cleared as f64 >= SPLITS[i] * total as f64 {
+                *time = Some(board.elapsed());
+            }
+        }
+    }
+
+    /// Returns the time each split was reached, or `None` for the ones
+    /// that haven't been yet.
+    pub fn times(&self) -> [Option<Duration>; 3] {
+        self.times
+    }
+}
+
+/// The fastest win at one board size.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PersonalBest {
+    /// The width of the `Board`.
+    pub width: usize,
+    /// The height of the `Board`.
+    pub height: usize,
+    /// The number of bombs on the `Board`.
+    pub num_mines: usize,
+    /// How long the win took.
+    pub time: Duration,
+    /// When the win reached each of the `SPLITS`.
+    pub splits: [Duration; 3],
+}
+
+/// How a game in progress compares with the personal best, at the
+/// latest split it has reached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pace {
+    /// Which of the `SPLITS` this is.
+    pub split: usize,
+    /// When the game reached the split.
+    pub time: Duration,
+    /// When the personal best reached it.
+    pub best: Duration,
+}
+
+impl Pace {
+    /// Returns whether the game reached the split no later than the
+    /// personal best did.
+    pub fn is_ahead(&self) -> bool {
+        self.time <= self.best
+    }
+
+    /// Returns the game's time minus the personal best's, in
+    /// milliseconds. Negative means ahead.
+    pub fn delta_millis(&self) -> i64 {
+        let millis = |d: Duration| d.as_secs() as i64 * 1000 + i64::from(d.subsec_millis());
+        millis(self.time) - millis(self.best)
+    }
+}
+
+/// The personal best at every board size that has been won.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use mines_core::{Board, Game, GameClock, MockClock, PersonalBests};
+///
+/// let clock = MockClock::new();
+/// let b = Board::from_layout("1*1\n111\n...").unwrap().with_clock(GameClock::new(clock.clone()));
+/// let mut game = Game::new(b);
+/// game.reveal(8).unwrap();
+/// clock.advance(Duration::from_secs(3));
+/// game.reveal(0).unwrap();
+/// game.reveal(2).unwrap();
+///
+/// let mut bests = PersonalBests::new();
+/// assert!(bests.record(&game));
+/// assert_eq!(bests.get(3, 3, 1).unwrap().time, Duration::from_secs(3));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PersonalBests {
+    bests: Vec<PersonalBest>,
+}
+
+impl PersonalBests {
+    /// Creates an empty set of personal bests.
+    pub fn new() -> PersonalBests {
+        PersonalBests::default()
+    }
+
+    /// Returns the personal best at the given size, if it has been won.
+    pub fn get(&self, width: usize, height: usize, num_mines: usize) -> Option<&PersonalBest> {
+        self.bests
+            .iter()
+            .find(|b| b.width == width && b.height == height && b.num_mines == num_mines)
+    }
+
+    /// Returns every personal best, in the order they were first set.
+    pub fn all(&self) -> &[PersonalBest] {
+        &self.bests
+    }
+
+    /// Takes `game` as the new personal best for its size if it has
+    /// been won faster than the current one. Returns whether it was.
+    pub fn record(&mut self, game: &Game) -> bool {
+        let board = game.board();
+        if board.state() != GameState::Won {
+            return false;
+        }
+        let time = board.elapsed();
+        // A win reaches every split, but fill in any gaps just in case
+        let times = game.splits().times();
+        let best = PersonalBest {
+            width: board.width,
+            height: board.height,
+            num_mines: board.num_mines,
+            time,
+            splits: [times[0].unwrap_or(time), times[1].unwrap_or(time), times[2].unwrap_or(time)],
+        };
+        match self.bests.iter_mut().find(|b| {
+            b.width == best.width && b.height == best.height && b.num_mines == best.num_mines
+        }) {
+            Some(old) if old.time <= time => false,
+            Some(old) => {
+                *old = best;
+                true
+            }
+            None => {
+                self.bests.push(best);
+                true
+            }
+        }
+    }
+
+    /// Compares `game` with the personal best for its size, at the
+    /// latest split it has reached. Returns `None` if there is no
+    /// personal best yet, or no split has been reached.
+    pub fn pace(&self, game: &Game) -> Option<Pace> {
+        let board = game.board();
+        let best = self.get(board.width, board.height, board.num_mines)?;
+        let times = game.splits().times();
+        let split = times.iter().rposition(|t| t.is_some())?;
+        Some(Pace {
+            split,
+            time: times[split]?,
+            best: best.splits[split],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clock::{GameClock, MockClock};
+
+    fn records() -> Vec<GameRecord> {
+        vec![GameRecord {
+                 played_at: 100,
+                 width: 9,
+                 height: 9,
+                 num_mines: 10,
+                 outcome: Outcome::Won,
+                 duration: Duration::from_millis(61_250),
+                 seed: Some(7),
+                 revealed: 71,
+             },
+             GameRecord {
+                 played_at: 200,
+                 width: 30,
+                 height: 16,
+                 num_mines: 99,
+                 outcome: Outcome::Lost,
+                 duration: Duration::from_millis(5),
+                 seed: None,
+                 revealed: 12,
+             }]
+    }
+
+    #[test]
+    fn test_openings() {
+        struct Test {
+            layout: &'static str,
+            zeros: Vec<Vec<usize>>,
+            border: Vec<Vec<usize>>,
+        }
+
+        let tests = [Test {
+                         layout: ".2*2.\n.3*3.\n.2*2.",
+                         zeros: vec![vec![0, 5, 10], vec![4, 9, 14]],
+                         border: vec![vec![1, 6, 11], vec![3, 8, 13]],
+                     },
+                     Test {
+                         layout: "*2*\n242\n*2*",
+                         zeros: vec![],
+                         border: vec![],
+                     },
+                     Test {
+                         layout: "....\n....\n....",
+                         zeros: vec![(0..12).collect()],
+                         border: vec![vec![]],
+                     }];
+
+        for test in &tests {
+            let b = Board::from_layout(test.layout).unwrap();
+            let openings = openings(&b);
+            let zeros: Vec<Vec<usize>> = openings.iter().map(|o| o.zeros.clone()).collect();
+            let border: Vec<Vec<usize>> = openings.iter().map(|o| o.border.clone()).collect();
+            assert_eq!(zeros, test.zeros);
+            assert_eq!(border, test.border);
+        }
+
+        let mut b = Board::from_layout(".2*2.\n.3*3.\n.2*2.").unwrap();
+        b.reveal_tile(14).unwrap();
+        let openings = openings(&b);
+        assert!(!openings[0].is_opened(&b));
+        assert!(openings[1].is_opened(&b));
+        assert!(self::openings(&Board::new(9, 9, 10)).is_empty());
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut out = Vec::new();
+        write_csv(&records(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(),
+                   "played_at,width,height,mines,outcome,duration_ms,seed,revealed\n\
+                    100,9,9,10,won,61250,7,71\n\
+                    200,30,16,99,lost,5,,12\n");
+    }
+
+    #[test]
+    fn test_write_jsonl() {
+        let mut out = Vec::new();
+        write_jsonl(&records(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(),
+                   "{\"played_at\":100,\"width\":9,\"height\":9,\"mines\":10,\"outcome\":\"won\",\
+                    \"duration_ms\":61250,\"seed\":7,\"revealed\":71}\n\
+                    {\"played_at\":200,\"width\":30,\"height\":16,\"mines\":99,\"outcome\":\
+                    \"lost\",\"duration_ms\":5,\"seed\":null,\"revealed\":12}\n");
+    }
+
+    #[test]
+    fn test_from_board() {
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+        assert_eq!(GameRecord::from_board(&b, 0).outcome, Outcome::Abandoned);
+        b.reveal_tile(8).unwrap();
+        let record = GameRecord::from_board(&b, 0);
+        assert_eq!(record.outcome, Outcome::Abandoned);
+        assert_eq!(record.revealed, 6);
+        b.reveal_tile(1).unwrap();
+        assert_eq!(GameRecord::from_board(&b, 0).outcome, Outcome::Lost);
+    }
+
+    #[test]
+    fn test_bbbv_progress() {
+        struct Test {
+            layout: &'static str,
+            reveal: Vec<usize>,
+            expected: (usize, usize),
+        }
+
+        let tests = [Test {
+                         layout: "1*1\n111\n...",
+                         reveal: vec![],
+                         expected: (0, 3),
+                     },
+                     Test {
+                         layout: "1*1\n111\n...",
+                         reveal: vec![8],
+                         expected: (1, 3),
+                     },
+                     Test {
+                         layout: "*2*\n242\n*2*",
+                         reveal: vec![1, 4],
+                         expected: (2, 5),
+                     },
+                     Test {
+                         layout: "...\n111\n1*1\n111\n...",
+                         reveal: vec![0],
+                         expected: (1, 4),
+                     }];
+
+        for test in &tests {
+            let mut b = Board::from_layout(test.layout).unwrap();
+            for &i in &test.reveal {
+                b.reveal_tile(i).unwrap();
+            }
+            assert_eq!(bbbv_progress(&b), test.expected);
+        }
+        assert_eq!(bbbv_progress(&Board::new(9, 9, 10)), (0, 0));
+    }
+
+    #[test]
+    fn test_clicks() {
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+        b.reveal_tile(3).unwrap();
+        let _ = b.reveal_tile(3);
+        b.flag_tile(1).unwrap();
+        b.flag_tile(0).unwrap();
+        b.flag_tile(0).unwrap();
+        b.chord_tile(3).unwrap();
+        // Nothing left around it to reveal
+        b.chord_tile(3).unwrap();
+        b.chord_tile(5).unwrap();
+        let _ = b.reveal_tile(2);
+
+        assert_eq!(b.clicks,
+                   Clicks {
+                       left: 3,
+                       right: 3,
+                       chords: 3,
+                       flags: 2,
+                       wasted: 4,
+                   });
+        assert_eq!(efficiency(&b).ioe(), Some(1.0 / 3.0));
+    }
+
+    #[test]
+    fn test_projected_time() {
+        let clock = MockClock::new();
+        let mut b = Board::from_layout("...\n111\n1*1\n111\n...")
+            .unwrap()
+            .with_clock(GameClock::new(clock.clone()));
+        assert_eq!(projected_time(&b), None);
+        b.reveal_tile(0).unwrap();
+        assert_eq!(projected_time(&b), None);
+
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(bbbv_per_second(&b), Some(1.0 / 1.5));
+        assert_eq!(projected_time(&b), Some(Duration::from_secs(6)));
+        b.reveal_tile(6).unwrap();
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(projected_time(&b), Some(Duration::from_secs(6)));
+
+        b.reveal_tile(7).unwrap();
+        assert_eq!(projected_time(&b), None);
+    }
+
+    #[test]
+    fn test_personal_bests() {
+        // A 3BV of 5, so the splits fall on clicks 2, 3, and 4
+        let layout = "*2*\n242\n*2*";
+        let play = |step: u64| {
+            let clock = MockClock::new();
+            let b = Board::from_layout(layout).unwrap().with_clock(GameClock::new(clock.clone()));
+            let mut game = Game::new(b);
+            for (click, &i) in [1, 3, 4, 5, 7].iter().enumerate() {
+                if click > 0 {
+                    clock.advance(Duration::from_secs(step));
+                }
+                game.reveal(i).unwrap();
+            }
+            game
+        };
+
+        let mut bests = PersonalBests::new();
+        let slow = play(2);
+        assert_eq!(slow.splits().times(),
+                   [Some(Duration::from_secs(2)),
+                    Some(Duration::from_secs(4)),
+                    Some(Duration::from_secs(6))]);
+        assert!(bests.record(&slow));
+        assert_eq!(bests.get(3, 3, 4).unwrap().time, Duration::from_secs(8));
+
+        let fast = play(1);
+        let pace = bests.pace(&fast).unwrap();
+        assert_eq!(pace.split, 2);
+        assert!(pace.is_ahead());
+        assert_eq!(pace.delta_millis(), -3000);
+        assert!(bests.record(&fast));
+        assert!(!bests.record(&slow));
+        assert_eq!(bests.get(3, 3, 4).unwrap().splits,
+                   [Duration::from_secs(1), Duration::from_secs(2), Duration::from_secs(3)]);
+        assert_eq!(bests.all().len(), 1);
+
+        // Losses and unfinished games never count
+        let mut lost = Game::new(Board::from_layout(layout).unwrap());
+        lost.reveal(0).unwrap();
+        assert!(!bests.record(&lost));
+        assert!(bests.pace(&Game::new(Board::from_layout(layout).unwrap())).is_none());
+    }
+
+    #[test]
+    fn test_jsonl_is_valid_json() {
+        extern crate serde_json;
+        let mut out = Vec::new();
+        write_jsonl(&records(), &mut out).unwrap();
+        for line in String::from_utf8(out).unwrap().lines() {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert!(value["outcome"].is_string());
+        }
+    }
+}
@@ -7,23 +7,30 @@ use std::result::Result;
 use std::default::Default;
 
 /// Representation of one square on a standard Minesweeper board.
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Tile {
     /// Corresponds to what one would see if this `Tile` were
     /// revealed. A value of 2 would indicate the `Tile` is adjacent
     /// to 2 bombs, a value of 0 would mean it isn't surrounded by any
-    /// bombs, etc.
-    pub adjacent_bombs: usize,
+    /// bombs, etc. Each adjacent anti-mine counts as -1, so on a
+    /// `Board` with anti-mines the value can be negative.
+    pub adjacent_bombs: isize,
     /// Refers to the current condition of this `Tile`.
     pub state: TileState,
     /// Indicates whether this `Tile` is a bomb.
     pub is_bomb: bool,
+    /// Indicates whether this `Tile`, which must also be a bomb, is an
+    /// anti-mine. Anti-mines are as deadly as any other bomb, but take
+    /// one away from the numbers around them instead of adding one.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_anti_mine: bool,
 }
 
 impl Tile {
     /// Marks this `Tile` as revealed, allowing the user to see its
-    /// value. Returns a `Result` indicating whether the reveal was
-    /// successful.
+    /// value. A bomb becomes `TileState::Detonated` instead. Returns a
+    /// `Result` indicating whether the reveal was successful.
     ///
     /// # Errors
     ///
@@ -32,6 +39,10 @@ impl Tile {
     /// is safe to discard this error; it is only for the programmer.
     pub fn reveal(&mut self) -> Result<(), &'static str> {
         match self.state {
+            TileState::Hidden | TileState::Revealed if self.is_bomb => {
+                self.state = TileState::Detonated;
+                Ok(())
+            }
             TileState::Hidden | TileState::Revealed => {
                 self.state = TileState::Revealed;
                 Ok(())
@@ -70,6 +81,7 @@ impl Default for Tile {
             adjacent_bombs: 0,
             state: TileState::Hidden,
             is_bomb: false,
+            is_anti_mine: false,
         }
     }
 }
@@ -78,12 +90,14 @@ impl fmt::Debug for Tile {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // "What gets printed to the screen?"
         // In order of priority:
-        // 1. Whether it's a bomb
+        // 1. Whether it's a bomb, and which kind
         // 2. Whether it's adjacent to bombs
         // 3. Whether it's not adjacent to bombs
         let adjacent_bombs = self.adjacent_bombs.to_string();
 
-        let s = if self.is_bomb {
+        let s = if self.is_anti_mine {
+            "-"
+        } else if self.is_bomb {
             "*"
         } else if adjacent_bombs != "0" {
             adjacent_bombs.as_str()
@@ -109,7 +123,9 @@ impl fmt::Display for Tile {
         let s = match self.state {
             TileState::Flagged => "!",
             TileState::Hidden => "?",
-            TileState::Revealed => debug_string.as_str(),
+            TileState::Revealed | TileState::Detonated => debug_string.as_str(),
+            TileState::Exposed => "#",
+            TileState::WrongFlag => "X",
         };
 
         write!(f, "{}", s)
@@ -117,7 +133,8 @@ impl fmt::Display for Tile {
 }
 
 /// Corresponds to the current condition of a `Tile`.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TileState {
     /// The `Tile` has not been clicked on, and has an unknown value
     /// to the user.
@@ -127,6 +144,15 @@ pub enum TileState {
     Revealed,
     /// The user has marked this `Tile` as containing a bomb.
     Flagged,
+    /// The `Tile` is the bomb the user revealed, losing the game.
+    Detonated,
+    /// The `Tile` is a bomb the user never found, shown after the
+    /// game was lost, or a bomb the user revealed but survived, using
+    /// up one of their lives (see `Board::with_lives`).
+    Exposed,
+    /// The user flagged this `Tile`, but it isn't a bomb; shown after
+    /// the game was lost.
+    WrongFlag,
 }
 
 #[cfg(test)]
@@ -149,6 +175,29 @@ mod tests {
 
         t.state = TileState::Flagged;
         assert_eq!(format!("{}", t), "!");
+
+        t.state = TileState::Exposed;
+        assert_eq!(format!("{}", t), "#");
+
+        t.is_bomb = false;
+        t.state = TileState::WrongFlag;
+        assert_eq!(format!("{}", t), "X");
+    }
+
+    #[test]
+    fn test_reveal() {
+        let mut t: Tile = Default::default();
+        t.reveal().unwrap();
+        assert_eq!(t.state, TileState::Revealed);
+
+        let mut t = Tile {
+            is_bomb: true,
+            ..Default::default()
+        };
+        t.reveal().unwrap();
+        assert_eq!(t.state, TileState::Detonated);
+        assert_eq!(format!("{}", t), "*");
+        assert!(t.flag().is_err());
     }
 
     #[test]
@@ -159,7 +208,13 @@ mod tests {
         t.adjacent_bombs = 2;
         assert_eq!(format!("{:?}", t), "2");
 
+        t.adjacent_bombs = -1;
+        assert_eq!(format!("{:?}", t), "-1");
+
         t.is_bomb = true;
         assert_eq!(format!("{:?}", t), "*");
+
+        t.is_anti_mine = true;
+        assert_eq!(format!("{:?}", t), "-");
     }
 }
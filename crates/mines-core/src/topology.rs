@@ -0,0 +1,513 @@
+#![warn(missing_docs)]
+//! [Re-exported] The shape of the grid a `Board` is played on.
+//!
+//! Every `Board` stores its `Tiles` in a `width`x`height` array, indexed
+//! the same way whatever its `Topology`; only which `Tiles` count as
+//! adjacent changes. Revealing, flagging, chording, and the solver all
+//! go through `Board::adjacent_tile_indices`, so they work on any
+//! `Topology` unchanged.
+//!
+//! # Hexagonal boards
+//!
+//! A `Topology::Hex` `Board` uses axial coordinates: the column is `q`
+//! and the row is `r`. The six neighbours of (q, r) are (q ± 1, r),
+//! (q, r ± 1), (q + 1, r - 1), and (q - 1, r + 1), so the `Board` is a
+//! rhombus of pointy-topped hexagons. To draw it, shift each row right
+//! by half a hexagon more than the row above it.
+//!
+//! The text formats of the `layout` module write a hexagonal `Board`
+//! row by row, just like a square one, without the shift.
+//!
+//! # Toroidal boards
+//!
+//! A `Topology::Torus` `Board` is drawn like a square one, but its
+//! edges wrap around: the left edge is adjacent to the right, and the
+//! top to the bottom, so every `Tile` has all eight neighbours. It is
+//! at least 3x3, so no `Tile` is ever its own neighbour, or counted
+//! twice.
+//!
+//! # Kernels
+//!
+//! A `Kernel` replaces the neighbours of a `Topology` with any list of
+//! offsets, for variants such as knight's-move Minesweeper, where the
+//! number on a `Tile` counts the bombs a knight's move away, or window
+//! Minesweeper, where it counts every bomb within two steps. Offsets
+//! are (x, y), or (q, r) on a hexagonal `Board`, and wrap around the
+//! edges of a toroidal one. Numbers, the first-click opening, flood
+//! reveals, chording, and the solver all use the `Kernel`. No offset
+//! can reach further than `MAX_OFFSET` in either direction.
+//!
+//! ```
+//! use mines_core::{Board, Kernel};
+//!
+//! let b = Board::builder().width(8).height(8).kernel(Kernel::knight()).build().unwrap();
+//! assert_eq!(b.adjacent_tile_indices(0), vec![10, 17]);
+//!
+//! let b = Board::builder().width(8).height(8).kernel(Kernel::window(2)).build().unwrap();
+//! assert_eq!(b.adjacent_tile_indices(27).len(), 24);
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{Board, Topology};
+//!
+//! let b = Board::builder().width(5).height(5).mines(3).topology(Topology::Hex).build().unwrap();
+//! assert_eq!(b.topology(), Topology::Hex);
+//! // (2, 2) in the middle
+//! assert_eq!(b.adjacent_tile_indices(12), vec![7, 8, 11, 13, 16, 17]);
+//!
+//! let b = Board::from_layout_with("*1.\n1..\n...", Topology::Hex).unwrap();
+//! assert_eq!(b.tiles[4].adjacent_bombs, 0);
+//! ```
+
+use board;
+use builder::MAX_TILES;
+
+/// The furthest a `Kernel` offset can reach in either direction. No
+/// `Board` is wider or taller than `MAX_TILES`, so no further offset
+/// could change which `Tiles` are adjacent.
+pub const MAX_OFFSET: isize = MAX_TILES as isize;
+
+/// Which `Tiles` of a `Board` are adjacent to each other.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Topology {
+    /// A rectangle of squares, each with up to eight neighbours. This
+    /// is the default.
+    #[default]
+    Square,
+    /// A rhombus of hexagons in axial coordinates, each with up to six
+    /// neighbours. See the `topology` module documentation.
+    Hex,
+    /// A rectangle of squares whose edges wrap around, so that every
+    /// `Tile` has eight neighbours. See the `topology` module
+    /// documentation.
+    Torus,
+}
+
+impl Topology {
+    /// Returns the most neighbours a `Tile` can have, and so the
+    /// highest number it can show.
+    pub fn max_neighbors(&self) -> usize {
+        match *self {
+            Topology::Square | Topology::Torus => 8,
+            Topology::Hex => 6,
+        }
+    }
+
+    /// Returns the name used for this `Topology` in saves and links.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Topology::Square => "square",
+            Topology::Hex => "hex",
+            Topology::Torus => "torus",
+        }
+    }
+
+    /// Returns the `Topology` called `name` by `Topology::name`, if
+    /// there is one.
+    pub fn from_name(name: &str) -> Option<Topology> {
+        match name {
+            "square" => Some(Topology::Square),
+            "hex" => Some(Topology::Hex),
+            "torus" => Some(Topology::Torus),
+            _ => None,
+        }
+    }
+
+    // The indices adjacent to `index` in a grid `width` wide, in
+    // ascending order
+    #[cfg_attr(not(feature = "experimental"), allow(dead_code))]
+    pub(crate) fn adjacent_indices(&self, index: usize, width: usize, length: usize) -> Vec<usize> {
+        if *self == Topology::Square {
+            return board::adjacent_indices(index, width, length);
+        }
+        let mut indices = Vec::with_capacity(8);
+        self.for_each_adjacent(index, width, length, |i| indices.push(i));
+        indices.sort_unstable();
+        indices
+    }
+
+    // Calls `f` with each index adjacent to `index` in a grid `width`
+    // wide, in no particular order
+    pub(crate) fn for_each_adjacent<F>(&self, index: usize, width: usize, length: usize, mut f: F)
+        where F: FnMut(usize)
+    {
+        if index >= length {
+            panic!("Tried to find adjacent indices using an index greater than the length of the \
+                    grid! Passed index: {}, Grid length: {}",
+                   index,
+                   length);
+        }
+
+        let (x, y) = ((index % width) as isize, (index / width) as isize);
+        let (w, h) = (width as isize, (length / width) as isize);
+        match *self {
+            Topology::Hex => {
+                for &(dx, dy) in &[(0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)] {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if nx >= 0 && ny >= 0 && nx < w && ny < h {
+                        f((ny * w + nx) as usize);
+                    }
+                }
+            }
+            Topology::Square | Topology::Torus => {
+                // On a Torus, every one of the eight wraps around to a
+                // distinct Tile, since the grid is at least 3x3
+                for dy in -1..2 {
+                    for dx in -1..2 {
+                        if dx == 0 && dy == 0 {
+                            continue;
+                        }
+                        let (nx, ny) = if *self == Topology::Torus {
+                            ((x + dx).rem_euclid(w), (y + dy).rem_euclid(h))
+                        } else {
+                            (x + dx, y + dy)
+                        };
+                        if nx >= 0 && ny >= 0 && nx < w && ny < h {
+                            f((ny * w + nx) as usize);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A list of offsets that decides which `Tiles` are adjacent, in place
+/// of those of the `Topology`. See the `topology` module documentation.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Kernel {
+    offsets: Vec<(isize, isize)>,
+}
+
+impl Kernel {
+    /// Creates a `Kernel` from (x, y) offsets. Duplicates, and (0, 0),
+    /// are left out.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an offset reaches further than `MAX_OFFSET`.
+    pub fn new(offsets: &[(isize, isize)]) -> Kernel {
+        if let Some(&(x, y)) = offsets.iter().find(|&&o| !Kernel::offset_in_range(o)) {
+            panic!("Tried to make a Kernel with an offset further than MAX_OFFSET! Passed \
+                    offset: ({}, {})",
+                   x,
+                   y);
+        }
+        let mut offsets: Vec<(isize, isize)> =
+            offsets.iter().cloned().filter(|&o| o != (0, 0)).collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+        Kernel { offsets }
+    }
+
+    /// Returns the `Kernel` of the eight knight's moves.
+    pub fn knight() -> Kernel {
+        Kernel::new(&[(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)])
+    }
+
+    /// Returns the `Kernel` of every offset at most `radius` steps away
+    /// in each direction. `Kernel::window(1)` is the usual eight
+    /// neighbours.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is greater than `MAX_OFFSET`.
+    pub fn window(radius: usize) -> Kernel {
+        if radius > MAX_OFFSET as usize {
+            panic!("Tried to make a Kernel with an offset further than MAX_OFFSET! Passed \
+                    radius: {}",
+                   radius);
+        }
+        let r = radius as isize;
+        let offsets: Vec<(isize, isize)> =
+            (-r..r + 1).flat_map(|y| (-r..r + 1).map(move |x| (x, y))).collect();
+        Kernel::new(&offsets)
+    }
+
+    /// Returns the offsets, sorted.
+    pub fn offsets(&self) -> &[(isize, isize)] {
+        &self.offsets
+    }
+
+    /// Returns whether `Kernel::new` accepts the offset: whether both
+    /// of its coordinates are within `MAX_OFFSET` of 0.
+    pub fn offset_in_range((x, y): (isize, isize)) -> bool {
+        (-MAX_OFFSET..=MAX_OFFSET).contains(&x) && (-MAX_OFFSET..=MAX_OFFSET).contains(&y)
+    }
+
+    // The indices adjacent to `index` in a grid `width` wide, in
+    // ascending order, wrapping around on a Topology::Torus
+    pub(crate) fn adjacent_indices(&self,
+                                   index: usize,
+                                   width: usize,
+                                   length: usize,
+                                   topology: Topology)
+                                   -> Vec<usize> {
+        let mut indices = Vec::with_capacity(self.offsets.len());
+        self.for_each_adjacent(index, width, length, topology, |i| indices.push(i));
+        // Large offsets can wrap around onto the same Tile more than once
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+
+    // Calls `f` with each index adjacent to `index` in a grid `width`
+    // wide, in no particular order, and more than once if large offsets
+    // wrap around onto the same Tile
+    pub(crate) fn for_each_adjacent<F>(&self,
+                                       index: usize,
+                                       width: usize,
+                                       length: usize,
+                                       topology: Topology,
+                                       mut f: F)
+        where F: FnMut(usize)
+    {
+        if index >= length {
+            panic!("Tried to find adjacent indices using an index greater than the length of the \
+                    grid! Passed index: {}, Grid length: {}",
+                   index,
+                   length);
+        }
+
+        let (x, y) = ((index % width) as isize, (index / width) as isize);
+        let (w, h) = (width as isize, (length / width) as isize);
+        for &(dx, dy) in &self.offsets {
+            // A deserialized Kernel hasn't been through Kernel::new, so
+            // its offsets may be anything
+            let (nx, ny) = if topology == Topology::Torus {
+                ((x + dx.rem_euclid(w)) % w, (y + dy.rem_euclid(h)) % h)
+            } else {
+                match (x.checked_add(dx), y.checked_add(dy)) {
+                    (Some(nx), Some(ny)) if nx >= 0 && ny >= 0 && nx < w && ny < h => (nx, ny),
+                    _ => continue,
+                }
+            };
+            let i = (ny * w + nx) as usize;
+            if i != index {
+                f(i);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use board::Board;
+    use builder::BuildError;
+    use layout::MineLayout;
+    use solver;
+    use tile::TileState;
+
+    #[test]
+    fn test_hex_adjacent_indices() {
+        struct Test {
+            index: usize,
+            expected: Vec<usize>,
+        }
+
+        // A 4x3 grid:
+        //  0  1  2  3
+        //  4  5  6  7
+        //  8  9 10 11
+        let tests = [Test {
+                         index: 0,
+                         expected: vec![1, 4],
+                     },
+                     Test {
+                         index: 3,
+                         expected: vec![2, 6, 7],
+                     },
+                     Test {
+                         index: 5,
+                         expected: vec![1, 2, 4, 6, 8, 9],
+                     },
+                     Test {
+                         index: 8,
+                         expected: vec![4, 5, 9],
+                     },
+                     Test {
+                         index: 11,
+                         expected: vec![7, 10],
+                     }];
+
+        for test in &tests {
+            assert_eq!(Topology::Hex.adjacent_indices(test.index, 4, 12), test.expected);
+        }
+        assert_eq!(Topology::Square.adjacent_indices(0, 4, 12), vec![1, 4, 5]);
+    }
+
+    #[test]
+    fn test_torus_adjacent_indices() {
+        struct Test {
+            index: usize,
+            expected: Vec<usize>,
+        }
+
+        // The same 4x3 grid, wrapping around
+        let tests = [Test {
+                         index: 0,
+                         expected: vec![1, 3, 4, 5, 7, 8, 9, 11],
+                     },
+                     Test {
+                         index: 7,
+                         expected: vec![0, 2, 3, 4, 6, 8, 10, 11],
+                     },
+                     Test {
+                         index: 5,
+                         expected: vec![0, 1, 2, 4, 6, 8, 9, 10],
+                     }];
+
+        for test in &tests {
+            assert_eq!(Topology::Torus.adjacent_indices(test.index, 4, 12), test.expected);
+        }
+    }
+
+    #[test]
+    fn test_kernel_adjacent_indices() {
+        struct Test {
+            kernel: Kernel,
+            topology: Topology,
+            index: usize,
+            expected: Vec<usize>,
+        }
+
+        // A 5x5 grid
+        let tests = [Test {
+                         kernel: Kernel::knight(),
+                         topology: Topology::Square,
+                         index: 12,
+                         expected: vec![1, 3, 5, 9, 15, 19, 21, 23],
+                     },
+                     Test {
+                         kernel: Kernel::knight(),
+                         topology: Topology::Square,
+                         index: 0,
+                         expected: vec![7, 11],
+                     },
+                     Test {
+                         kernel: Kernel::knight(),
+                         topology: Topology::Torus,
+                         index: 0,
+                         expected: vec![7, 8, 11, 14, 16, 19, 22, 23],
+                     },
+                     Test {
+                         kernel: Kernel::window(1),
+                         topology: Topology::Square,
+                         index: 6,
+                         expected: vec![0, 1, 2, 5, 7, 10, 11, 12],
+                     },
+                     Test {
+                         // Wraps onto every other Tile exactly once
+                         kernel: Kernel::window(3),
+                         topology: Topology::Torus,
+                         index: 0,
+                         expected: (1..25).collect(),
+                     }];
+
+        for test in &tests {
+            assert_eq!(test.kernel.adjacent_indices(test.index, 5, 25, test.topology),
+                       test.expected);
+        }
+        assert_eq!(Kernel::new(&[(0, 0), (1, 0), (1, 0)]).offsets(), &[(1, 0)]);
+        assert_eq!(Kernel::window(2).offsets().len(), 24);
+
+        // Offsets that only a deserialized Kernel can have
+        let huge = Kernel { offsets: vec![(isize::MAX, 0), (isize::MIN, isize::MIN), (1, 0)] };
+        assert_eq!(huge.adjacent_indices(12, 5, 25, Topology::Square), vec![13]);
+        assert_eq!(huge.adjacent_indices(12, 5, 25, Topology::Torus).len(), 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_kernel_offset_too_large() {
+        Kernel::new(&[(MAX_OFFSET + 1, 0)]);
+    }
+
+    #[test]
+    fn test_kernel_board() {
+        let knight_board = || {
+            let mut b = Board::builder()
+                .width(5)
+                .height(5)
+                .mines(1)
+                .kernel(Kernel::knight())
+                .build()
+                .unwrap();
+            b.reset_to_layout(&MineLayout::new(5, 5, &[0]));
+            b
+        };
+
+        // Knight's moves from the bomb in the corner
+        let mut b = knight_board();
+        assert_eq!(b.layout_string(), "*....\n..1..\n.1...\n.....\n.....\n");
+
+        // Every number is 0 or 1, so one reveal opens everything else
+        b.reveal_tile(24).unwrap();
+        assert_eq!(b.visible_string(), "?....\n..1..\n.1...\n.....\n.....\n");
+        assert_eq!(solver::solve(&b).mines, vec![0]);
+
+        // Chording a number reveals the knight's moves from it
+        let mut b = knight_board();
+        b.reveal_tile(7).unwrap();
+        b.flag_tile(0).unwrap();
+        b.chord_tile(7).unwrap();
+        for &i in &[4, 10, 14, 16, 18] {
+            assert!(matches!(b.tiles[i].state, TileState::Revealed));
+        }
+
+        // The opening covers the whole Kernel, which can fill the Board
+        let window = Board::builder().width(5).height(5).mines(1).kernel(Kernel::window(2)).build();
+        assert_eq!(window.err(), Some(BuildError::TooManyMines { mines: 1, max: 0 }));
+    }
+
+    #[test]
+    fn test_torus_board() {
+        // The bomb in the corner touches every corner
+        let mut b = Board::from_layout_with("*1.1\n11.1\n....\n11.1", Topology::Torus).unwrap();
+        assert!(Board::from_layout("*1.1\n11.1\n....\n11.1").is_err());
+        b.reveal_tile(10).unwrap();
+        assert_eq!(b.visible_string(), "?1.1\n11.1\n....\n11.1\n");
+        assert_eq!(solver::solve(&b).mines, vec![0]);
+
+        // The opening around the first click wraps too
+        for seed in 0..10 {
+            let mut b = Board::builder()
+                .width(9)
+                .height(9)
+                .mines(60)
+                .seed(seed)
+                .topology(Topology::Torus)
+                .build()
+                .unwrap();
+            b.reveal_tile(0).unwrap();
+            for &i in &[0, 1, 8, 9, 10, 17, 72, 73, 80] {
+                assert!(!b.tiles[i].is_bomb);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hex_board() {
+        let mut b = Board::from_layout_with("*1..\n1...\n...1\n..1*", Topology::Hex).unwrap();
+        assert_eq!(b.topology(), Topology::Hex);
+        // Square counting would put a 1 diagonally next to each bomb
+        assert!(Board::from_layout("*1..\n1...\n...1\n..1*").is_err());
+
+        // The opening spreads across the hex neighbours only
+        b.reveal_tile(5).unwrap();
+        assert_eq!(b.visible_string(), "?1..\n1...\n...1\n..1?\n");
+        assert!(solver::solve(&b).mines.contains(&0));
+
+        // Chording works off the same neighbours
+        let mut b = Board::from_layout_with("*1..\n1...\n...1\n..1*", Topology::Hex).unwrap();
+        b.reveal_tile(1).unwrap();
+        b.flag_tile(0).unwrap();
+        b.chord_tile(1).unwrap();
+        assert!(matches!(b.tiles[4].state, TileState::Revealed));
+    }
+}
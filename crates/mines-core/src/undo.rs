@@ -0,0 +1,206 @@
+#![warn(missing_docs)]
+//! Undoing and redoing moves on a `Board`.
+//!
+//! Undo is off by default. Once turned on with `Board::with_undo`,
+//! every successful reveal, flag, or chord is remembered, up to a
+//! configurable depth, and can be rolled back with `Board::undo` and
+//! replayed with `Board::redo`. Only what the user can see is rolled
+//! back: the bombs stay where they are, so undoing the losing move
+//! lets the user try somewhere else on the same `Board`.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::Board;
+//!
+//! let mut b = Board::from_layout("1*1\n111\n...").unwrap().with_undo(10);
+//! b.reveal_tile(8).unwrap();
+//! b.reveal_tile(1).unwrap();
+//! assert_eq!(b.visible_string(), "?*?\n111\n...\n");
+//!
+//! // Take back the losing move
+//! b.undo().unwrap();
+//! assert_eq!(b.visible_string(), "???\n111\n...\n");
+//! b.redo().unwrap();
+//! assert_eq!(b.visible_string(), "?*?\n111\n...\n");
+//! ```
+
+use std::collections::VecDeque;
+
+use board::Board;
+use tile::TileState;
+
+// The states of every Tile before each remembered move
+#[derive(Clone)]
+pub(crate) struct UndoHistory {
+    depth: usize,
+    past: VecDeque<Vec<TileState>>,
+    future: Vec<Vec<TileState>>,
+}
+
+impl UndoHistory {
+    fn new(depth: usize) -> UndoHistory {
+        UndoHistory {
+            depth,
+            past: VecDeque::new(),
+            future: Vec::new(),
+        }
+    }
+
+    // Remembers `before` as the state to go back to, forgetting the
+    // oldest move if there are too many and anything that was undone
+    pub(crate) fn push(&mut self, before: Vec<TileState>) {
+        self.future.clear();
+        if self.depth == 0 {
+            return;
+        }
+        if self.past.len() == self.depth {
+            self.past.pop_front();
+        }
+        self.past.push_back(before);
+    }
+}
+
+impl Board {
+    /// Turns on undo, remembering up to `depth` moves, and returns the
+    /// `Board`. Any moves remembered before are forgotten.
+    pub fn with_undo(mut self, depth: usize) -> Board {
+        self.undo = Some(UndoHistory::new(depth));
+        self
+    }
+
+    /// Returns whether there is a move to undo.
+    pub fn can_undo(&self) -> bool {
+        self.undo.as_ref().is_some_and(|h| !h.past.is_empty())
+    }
+
+    /// Returns whether there is an undone move to redo.
+    pub fn can_redo(&self) -> bool {
+        self.undo.as_ref().is_some_and(|h| !h.future.is_empty())
+    }
+
+    /// Rolls back the most recent move, so that every `Tile` looks as
+    /// it did before it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if undo is off, or if there
+    /// is nothing left to undo. It is safe to discard this error; it
+    /// is only for the programmer.
+    pub fn undo(&mut self) -> Result<(), &'static str> {
+        let before = match self.undo.as_mut().and_then(|h| h.past.pop_back()) {
+            Some(before) => before,
+            None => return Err("Tried to undo with nothing to undo!"),
+        };
+        let after = self.swap_states(before);
+        self.notify_swap(&after);
+        self.check_invariants();
+        if let Some(ref mut history) = self.undo {
+            history.future.push(after);
+        }
+        Ok(())
+    }
+
+    /// Replays the most recently undone move.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if undo is off, or if there
+    /// is nothing to redo. Any new move clears what there was to redo.
+    /// It is safe to discard this error; it is only for the programmer.
+    pub fn redo(&mut self) -> Result<(), &'static str> {
+        let after = match self.undo.as_mut().and_then(|h| h.future.pop()) {
+            Some(after) => after,
+            None => return Err("Tried to redo with nothing to redo!"),
+        };
+        let before = self.swap_states(after);
+        self.notify_swap(&before);
+        self.check_invariants();
+        if let Some(ref mut history) = self.undo {
+            history.past.push_back(before);
+        }
+        Ok(())
+    }
+
+    // Returns the state of every Tile, if undo is on or anything is
+    // listening for changes
+    pub(crate) fn undo_snapshot(&self) -> Option<Vec<TileState>> {
+        if self.undo.is_none() && self.listeners.is_empty() {
+            return None;
+        }
+        Some(self.tiles.iter().map(|t| t.state.clone()).collect())
+    }
+
+    // Tells the listeners which Tiles changed from `old` in an undo or
+    // redo
+    fn notify_swap(&mut self, old: &[TileState]) {
+        if self.listeners.is_empty() {
+            return;
+        }
+        let new: Vec<TileState> = self.tiles.iter().map(|t| t.state.clone()).collect();
+        let at = self.now();
+        self.listeners.notify_changes(at, old, &new);
+    }
+
+    // Sets the state of every Tile to `states`, returning the old ones
+    fn swap_states(&mut self, states: Vec<TileState>) -> Vec<TileState> {
+        self.tiles
+            .iter_mut()
+            .zip(states)
+            .map(|(tile, state)| ::std::mem::replace(&mut tile.state, state))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_redo() {
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap().with_undo(2);
+        assert!(!b.can_undo());
+        assert!(b.undo().is_err());
+
+        b.reveal_tile(0).unwrap();
+        b.flag_tile(1).unwrap();
+        b.reveal_tile(8).unwrap();
+        // Failed moves aren't remembered
+        assert!(b.reveal_tile(1).is_err());
+
+        // Only two moves fit
+        b.undo().unwrap();
+        assert_eq!(b.visible_string(), "1!?\n???\n???\n");
+        b.undo().unwrap();
+        assert_eq!(b.visible_string(), "1??\n???\n???\n");
+        assert!(b.undo().is_err());
+        assert!(b.can_redo());
+
+        b.redo().unwrap();
+        assert_eq!(b.visible_string(), "1!?\n???\n???\n");
+        // A new move forgets what was undone
+        b.flag_tile(1).unwrap();
+        assert!(!b.can_redo());
+        assert!(b.redo().is_err());
+        assert_eq!(b.visible_string(), "1??\n???\n???\n");
+    }
+
+    #[test]
+    fn test_undo_chord() {
+        let mut b = Board::restore("1*1\n111\n...", "1??\n???\n???").unwrap().with_undo(5);
+        b.flag_tile(1).unwrap();
+        b.chord_tile(0).unwrap();
+        assert_eq!(b.visible_string(), "1!?\n11?\n???\n");
+        b.undo().unwrap();
+        assert_eq!(b.visible_string(), "1!?\n???\n???\n");
+    }
+
+    #[test]
+    fn test_undo_off() {
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+        b.reveal_tile(8).unwrap();
+        assert!(!b.can_undo());
+        assert!(b.undo().is_err());
+        assert!(b.redo().is_err());
+    }
+}
@@ -0,0 +1,222 @@
+#![warn(missing_docs)]
+//! Deterministic randomness shared by the whole crate.
+//!
+//! Everything that the crate does with a seed goes through the
+//! functions in this module, and their algorithms are fixed: the same
+//! seed produces the same results on every platform and in every
+//! version of this crate. Tools built alongside the crate (custom
+//! generators, map pickers, and the like) can use them to stay
+//! compatible with seeded `Boards` and replays.
+//!
+//! The generator is SplitMix64. Numbers below a bound `n` are drawn by
+//! rejecting any 64-bit output at or above the largest multiple of `n`,
+//! and then taking the remainder, so there is no modulo bias.
+
+use self::rand::Rng;
+
+extern crate rand;
+
+/// A small, fast, seedable random number generator (SplitMix64).
+///
+/// It also implements `rand::Rng`, so it can be used anywhere the
+/// crate accepts a random number generator.
+///
+/// # Examples
+///
+/// ```
+/// use mines_core::util::SeededRng;
+///
+/// let mut a = SeededRng::new(42);
+/// let mut b = SeededRng::new(42);
+/// assert_eq!(a.next_u64(), b.next_u64());
+/// assert!(a.below(6) < 6);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> SeededRng {
+        SeededRng { state: seed }
+    }
+
+    /// Returns the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniformly distributed number in `[0, n)`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `n` is zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        if n == 0 {
+            panic!("Tried to pick a random number below zero!");
+        }
+        let n = n as u64;
+        let limit = u64::MAX - (u64::MAX % n);
+        loop {
+            let x = self.next_u64();
+            if x < limit {
+                return (x % n) as usize;
+            }
+        }
+    }
+}
+
+impl Rng for SeededRng {
+    fn next_u32(&mut self) -> u32 {
+        (SeededRng::next_u64(self) >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        SeededRng::next_u64(self)
+    }
+}
+
+/// Chooses `k` distinct numbers from `[0, n)`, deterministically for a
+/// given `seed`.
+///
+/// This is a partial Fisher–Yates shuffle of `[0, n)`: for each `i` in
+/// `[0, k)`, the element at `i` is swapped with a random element in
+/// `[i, n)`. The first `k` elements are returned in the order they were
+/// chosen.
+///
+/// # Examples
+///
+/// ```
+/// use mines_core::util::seeded_sample;
+///
+/// let picks = seeded_sample(64, 10, 7);
+/// assert_eq!(picks.len(), 10);
+/// assert_eq!(picks, seeded_sample(64, 10, 7));
+/// ```
+///
+/// # Panics
+///
+/// This function will panic if `k` is greater than `n`.
+pub fn seeded_sample(n: usize, k: usize, seed: u64) -> Vec<usize> {
+    sample(n, k, &mut SeededRng::new(seed))
+}
+
+/// Shuffles `items` in place, deterministically for a given `seed`,
+/// using a Fisher–Yates shuffle that works from the front of the
+/// slice in the same way as `seeded_sample`.
+pub fn seeded_shuffle<T>(items: &mut [T], seed: u64) {
+    shuffle(items, &mut SeededRng::new(seed));
+}
+
+pub(crate) fn shuffle<T>(items: &mut [T], rng: &mut SeededRng) {
+    let n = items.len();
+    for i in 0..n {
+        let j = i + rng.below(n - i);
+        items.swap(i, j);
+    }
+}
+
+pub(crate) fn sample(n: usize, k: usize, rng: &mut SeededRng) -> Vec<usize> {
+    if k > n {
+        panic!("Tried to choose more numbers than are available! Asked for: {}, Available: {}",
+               k,
+               n);
+    }
+    let mut pool: Vec<usize> = (0..n).collect();
+    for i in 0..k {
+        let j = i + rng.below(n - i);
+        pool.swap(i, j);
+    }
+    pool.truncate(k);
+    pool
+}
+
+// The same picks as `sample`, for a pool of `n` drawn from by `below`,
+// but remembering only the slots that have been swapped rather than the
+// whole pool, so that sampling from a huge Board takes memory for the
+// picks alone
+#[cfg(feature = "experimental")]
+pub(crate) fn sparse_sample<F>(n: usize, k: usize, mut below: F) -> Vec<usize>
+    where F: FnMut(usize) -> usize
+{
+    if k > n {
+        panic!("Tried to choose more numbers than are available! Asked for: {}, Available: {}",
+               k,
+               n);
+    }
+    let mut swapped = ::std::collections::HashMap::new();
+    let mut picks = Vec::with_capacity(k);
+    for i in 0..k {
+        let j = i + below(n - i);
+        let at_i = *swapped.get(&i).unwrap_or(&i);
+        let at_j = *swapped.get(&j).unwrap_or(&j);
+        // Slot `i` is never looked at again, so only `j` is remembered
+        swapped.insert(j, at_i);
+        picks.push(at_j);
+    }
+    picks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_values() {
+        // Reference outputs of SplitMix64 seeded with 0; these must
+        // never change, or seeded Boards would change with them
+        let mut rng = SeededRng::new(0);
+        assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+        assert_eq!(rng.next_u64(), 0x06C4_5D18_8009_454F);
+    }
+
+    #[test]
+    fn test_seeded_sample() {
+        struct Test {
+            n: usize,
+            k: usize,
+        }
+
+        let tests = [Test { n: 10, k: 0 }, Test { n: 10, k: 10 }, Test { n: 1000, k: 37 }];
+
+        for test in &tests {
+            let picks = seeded_sample(test.n, test.k, 1234);
+            assert_eq!(picks.len(), test.k);
+            let mut sorted = picks.clone();
+            sorted.sort();
+            sorted.dedup();
+            assert_eq!(sorted.len(), test.k);
+            assert!(picks.iter().all(|&p| p < test.n));
+            assert_eq!(picks, seeded_sample(test.n, test.k, 1234));
+        }
+
+        assert_ne!(seeded_sample(1000, 37, 1), seeded_sample(1000, 37, 2));
+    }
+
+    #[test]
+    #[cfg(feature = "experimental")]
+    fn test_sparse_sample() {
+        for &(n, k) in &[(10, 0), (10, 10), (1000, 37), (1000, 990)] {
+            let mut rng = SeededRng::new(5);
+            let sparse = sparse_sample(n, k, |m| rng.below(m));
+            assert_eq!(sparse, seeded_sample(n, k, 5));
+        }
+    }
+
+    #[test]
+    fn test_seeded_shuffle() {
+        let mut a: Vec<usize> = (0..20).collect();
+        let mut b = a.clone();
+        seeded_shuffle(&mut a, 99);
+        seeded_shuffle(&mut b, 99);
+        assert_eq!(a, b);
+        a.sort();
+        assert_eq!(a, (0..20).collect::<Vec<usize>>());
+    }
+}
@@ -0,0 +1,329 @@
+#![warn(missing_docs)]
+//! [Re-exported] Checking that a `Board` is consistent.
+//!
+//! A `Board` made by the crate always is, but one put together by hand,
+//! deserialized, or handed over through FFI can say it has ten mines
+//! and hold nine, or show numbers that don't match its bombs.
+//! `Board::validate` checks everything the rest of the crate relies on
+//! and lists whatever is wrong. It is the same check the
+//! `debug-invariants` feature makes after every move.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{Board, Inconsistency};
+//!
+//! let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+//! assert!(b.validate().is_ok());
+//!
+//! b.tiles[4].adjacent_bombs = 3;
+//! b.num_mines = 2;
+//! let report = b.validate().unwrap_err();
+//! assert_eq!(report.problems,
+//!            vec![Inconsistency::MineCount { expected: 2, found: 1 },
+//!                 Inconsistency::WrongNumber { at: (1, 1), shown: 3, actual: 1 }]);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use board::Board;
+use tile::TileState;
+
+/// One thing wrong with a `Board`. Positions are (x, y) coordinates,
+/// as from `Board::cartesian_coords`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// There isn't one `Tile` for every position on the grid. Nothing
+    /// else is checked when this is wrong.
+    TileCount {
+        /// The width times the height.
+        expected: usize,
+        /// The number of `Tiles`.
+        found: usize,
+    },
+    /// The mask doesn't have one entry per `Tile`. Nothing else is
+    /// checked when this is wrong.
+    MaskSize {
+        /// The number of `Tiles`.
+        expected: usize,
+        /// The number of entries in the mask.
+        found: usize,
+    },
+    /// There are bombs on a `Board` that hasn't been generated yet.
+    BombsBeforeGeneration {
+        /// The number of bombs.
+        found: usize,
+    },
+    /// A `Tile` isn't hidden on a `Board` that hasn't been generated
+    /// yet.
+    ShownBeforeGeneration {
+        /// Where the `Tile` is.
+        at: (usize, usize),
+    },
+    /// `num_mines` doesn't match the number of bombs.
+    MineCount {
+        /// What `num_mines` says.
+        expected: usize,
+        /// The number of bombs.
+        found: usize,
+    },
+    /// The number of anti-mines doesn't match the number the `Board`
+    /// was made with.
+    AntiMineCount {
+        /// What `Board::num_anti_mines` says.
+        expected: usize,
+        /// The number of anti-mines.
+        found: usize,
+    },
+    /// A `Tile` is an anti-mine, but not a bomb.
+    AntiMineNotBomb {
+        /// Where the `Tile` is.
+        at: (usize, usize),
+    },
+    /// A `Tile` that isn't in play is a bomb.
+    BombOutOfPlay {
+        /// Where the `Tile` is.
+        at: (usize, usize),
+    },
+    /// A safe `Tile`'s `adjacent_bombs` doesn't match the bombs around
+    /// it.
+    WrongNumber {
+        /// Where the `Tile` is.
+        at: (usize, usize),
+        /// Its `adjacent_bombs`.
+        shown: isize,
+        /// What its neighbours add up to.
+        actual: isize,
+    },
+    /// A `Tile` is shown as a bomb, but isn't one.
+    ShownAsBomb {
+        /// Where the `Tile` is.
+        at: (usize, usize),
+    },
+    /// A `Tile` is shown as a wrong flag, but is a bomb.
+    WrongFlagOnBomb {
+        /// Where the `Tile` is.
+        at: (usize, usize),
+    },
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Inconsistency::TileCount { expected, found } => {
+                write!(f, "{} tiles, but there should be {}", found, expected)
+            }
+            Inconsistency::MaskSize { expected, found } => {
+                write!(f, "the mask has {} entries, but there are {} tiles", found, expected)
+            }
+            Inconsistency::BombsBeforeGeneration { found } => {
+                write!(f, "{} bombs before generation", found)
+            }
+            Inconsistency::ShownBeforeGeneration { at } => {
+                write!(f, "{:?} isn't hidden before generation", at)
+            }
+            Inconsistency::MineCount { expected, found } => {
+                write!(f, "{} bombs, but num_mines is {}", found, expected)
+            }
+            Inconsistency::AntiMineCount { expected, found } => {
+                write!(f, "{} anti-mines, but num_anti_mines is {}", found, expected)
+            }
+            Inconsistency::AntiMineNotBomb { at } => {
+                write!(f, "{:?} is an anti-mine, but isn't a bomb", at)
+            }
+            Inconsistency::BombOutOfPlay { at } => {
+                write!(f, "{:?} is a bomb, but isn't in play", at)
+            }
+            Inconsistency::WrongNumber { at, shown, actual } => {
+                write!(f, "{:?} counts {} bombs around it, but there are {}", at, shown, actual)
+            }
+            Inconsistency::ShownAsBomb { at } => {
+                write!(f, "{:?} is shown as a bomb, but isn't one", at)
+            }
+            Inconsistency::WrongFlagOnBomb { at } => {
+                write!(f, "{:?} is shown as a wrong flag, but is a bomb", at)
+            }
+        }
+    }
+}
+
+/// Everything `Board::validate` found wrong with a `Board`, in the
+/// order it was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// What is wrong. Never empty.
+    pub problems: Vec<Inconsistency>,
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines: Vec<String> = self.problems.iter().map(|p| p.to_string()).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Error for ValidationReport {}
+
+impl Board {
+    /// Checks that the `Board` is consistent: that it has one `Tile`
+    /// for every position, that `num_mines` matches its bombs, that
+    /// every safe `Tile`'s number matches its neighbours, and so on.
+    /// See the `validate` module.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a report of everything wrong with the
+    /// `Board`, if anything is.
+    pub fn validate(&self) -> Result<(), ValidationReport> {
+        let problems = self.inconsistencies();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationReport { problems })
+        }
+    }
+
+    fn inconsistencies(&self) -> Vec<Inconsistency> {
+        let mut problems = Vec::new();
+        let expected = self.width * self.height;
+        if self.tiles.len() != expected {
+            problems.push(Inconsistency::TileCount {
+                expected,
+                found: self.tiles.len(),
+            });
+            return problems;
+        }
+        if let Some(mask) = self.mask() {
+            if mask.len() != expected {
+                problems.push(Inconsistency::MaskSize {
+                    expected,
+                    found: mask.len(),
+                });
+                return problems;
+            }
+        }
+        let bombs = self.tiles.iter().filter(|t| t.is_bomb).count();
+        if !self.is_generated() {
+            if bombs != 0 {
+                problems.push(Inconsistency::BombsBeforeGeneration { found: bombs });
+            }
+            for (i, tile) in self.tiles.iter().enumerate() {
+                if !matches!(tile.state, TileState::Hidden) {
+                    problems.push(Inconsistency::ShownBeforeGeneration {
+                        at: self.cartesian_coords(i),
+                    });
+                }
+            }
+            return problems;
+        }
+
+        if bombs != self.num_mines {
+            problems.push(Inconsistency::MineCount {
+                expected: self.num_mines,
+                found: bombs,
+            });
+        }
+        let anti_mines = self.tiles.iter().filter(|t| t.is_anti_mine).count();
+        if anti_mines != self.num_anti_mines() {
+            problems.push(Inconsistency::AntiMineCount {
+                expected: self.num_anti_mines(),
+                found: anti_mines,
+            });
+        }
+        for (i, tile) in self.tiles.iter().enumerate() {
+            let at = self.cartesian_coords(i);
+            if tile.is_anti_mine && !tile.is_bomb {
+                problems.push(Inconsistency::AntiMineNotBomb { at });
+            }
+            if tile.is_bomb && !self.is_in_play(i) {
+                problems.push(Inconsistency::BombOutOfPlay { at });
+            }
+            let actual = self.adjacent_count(i);
+            if !tile.is_bomb && tile.adjacent_bombs != actual {
+                problems.push(Inconsistency::WrongNumber {
+                    at,
+                    shown: tile.adjacent_bombs,
+                    actual,
+                });
+            }
+            match (tile.is_bomb, &tile.state) {
+                (false, &TileState::Detonated) | (false, &TileState::Exposed) => {
+                    problems.push(Inconsistency::ShownAsBomb { at })
+                }
+                (true, &TileState::WrongFlag) => {
+                    problems.push(Inconsistency::WrongFlagOnBomb { at })
+                }
+                _ => {}
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        struct Test {
+            board: Board,
+            problems: Vec<Inconsistency>,
+        }
+
+        let generated = || Board::from_layout("1*1\n111\n...").unwrap();
+        let mut short = generated();
+        short.tiles.pop();
+        let mut ungenerated = Board::new(4, 4, 1);
+        ungenerated.tiles[0].is_bomb = true;
+        ungenerated.tiles[1].state = TileState::Revealed;
+        let mut extra = generated();
+        extra.tiles[8].is_bomb = true;
+        extra.tiles[8].is_anti_mine = true;
+        extra.count_adjacent_bombs();
+        let mut shown = generated();
+        shown.tiles[0].state = TileState::Exposed;
+        shown.tiles[1].state = TileState::WrongFlag;
+
+        let tests = vec![Test {
+                             board: generated(),
+                             problems: vec![],
+                         },
+                         Test {
+                             board: short,
+                             problems: vec![Inconsistency::TileCount {
+                                                expected: 9,
+                                                found: 8,
+                                            }],
+                         },
+                         Test {
+                             board: ungenerated,
+                             problems: vec![Inconsistency::BombsBeforeGeneration { found: 1 },
+                                            Inconsistency::ShownBeforeGeneration { at: (1, 0) }],
+                         },
+                         Test {
+                             board: extra,
+                             problems: vec![Inconsistency::MineCount {
+                                                expected: 1,
+                                                found: 2,
+                                            },
+                                            Inconsistency::AntiMineCount {
+                                                expected: 0,
+                                                found: 1,
+                                            }],
+                         },
+                         Test {
+                             board: shown,
+                             problems: vec![Inconsistency::ShownAsBomb { at: (0, 0) },
+                                            Inconsistency::WrongFlagOnBomb { at: (1, 0) }],
+                         }];
+
+        for test in &tests {
+            match test.board.validate() {
+                Ok(()) => assert!(test.problems.is_empty()),
+                Err(report) => assert_eq!(report.problems, test.problems),
+            }
+        }
+    }
+}
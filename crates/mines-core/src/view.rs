@@ -0,0 +1,498 @@
+#![warn(missing_docs)]
+//! [Re-exported] Looking at part of a `Board`.
+//!
+//! Front-ends that scroll around a large `Board`, or draw a minimap of
+//! it, only need the `Tiles` in one rectangle at a time. `Board::view`
+//! returns a `BoardView` of that rectangle, which borrows the `Board`
+//! rather than copying anything, and has its own coordinates: (0, 0) is
+//! the top left of the rectangle.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::{Board, Rect};
+//!
+//! let b = Board::from_layout("1*1..\n111..\n.....\n..111\n..1*1").unwrap();
+//! let view = b.view(Rect::new(2, 2, 3, 3));
+//! assert_eq!(view.layout_string(), "...\n111\n1*1\n");
+//! assert!(view.tile((1, 2)).is_bomb);
+//! assert_eq!(view.board_coords((1, 2)), (3, 4));
+//! assert_eq!(view.local_coords((0, 0)), None);
+//! ```
+//!
+//! Front-ends that would rather not know about `Tiles` at all, such as
+//! GUI toolkits and scripting bridges, can take a plain snapshot of what
+//! the user sees instead: `Board::to_grid` gives a row of `TileViews`
+//! for each row of the `Board`.
+//!
+//! ```
+//! use mines_core::{Board, TileView};
+//!
+//! let mut b = Board::restore("1*1\n111\n...", "1!?\n???\n???").unwrap();
+//! b.reveal_tile(8).unwrap();
+//! let grid = b.to_grid();
+//! assert_eq!(grid[0], vec![TileView::Number(1), TileView::Flagged, TileView::Hidden]);
+//! assert_eq!(grid[2][2], TileView::Number(0));
+//! ```
+//!
+//! Web front-ends that leave the game to a server can send the same
+//! snapshot as JSON, from `Board::to_json_state`. Like the grid, it
+//! only says what the user can see, so the mines stay on the server
+//! until the game shows them:
+//!
+//! ```text
+//! {"width":3,"height":3,"state":"playing","mines":1,"mines_remaining":0,
+//!  "cells":[[{"revealed":true,"flagged":false,"number":1},
+//!            {"revealed":false,"flagged":true,"number":null},...],...]}
+//! ```
+//!
+//! `state` is `ready`, `playing`, `won`, or `lost`, and `cells` has one
+//! array per row, from the top. `number` is the number a revealed
+//! `Tile` shows, and `null` otherwise. Bombs the game has shown, such
+//! as once it is lost, are revealed with a `mine` field of `true`, and
+//! a flag shown to be on a safe `Tile` gets a `wrong` field of `true`.
+//! A `Tile` that isn't in play is `null`.
+//!
+//! ```
+//! use mines_core::Board;
+//!
+//! let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+//! b.reveal_tile(0).unwrap();
+//! let json = b.to_json_state();
+//! assert!(json.starts_with("{\"width\":3,\"height\":3,\"state\":\"playing\""));
+//! assert!(json.contains("{\"revealed\":true,\"flagged\":false,\"number\":1}"));
+//! assert!(!json.contains("mine\""));
+//! ```
+//!
+//! Two `Boards` compare equal when the user would see the same thing on
+//! each: the same size, the same number of mines, and the same
+//! `TileView` everywhere. Where the hidden bombs are doesn't matter, so
+//! a game can be checked against the one it was restored from, or kept
+//! in a `HashSet` of positions already seen.
+//!
+//! ```
+//! use mines_core::Board;
+//!
+//! let a = Board::restore("1*1\n111\n...", "1??\n???\n???").unwrap();
+//! let b = Board::restore("111\n1*1\n111", "1??\n???\n???").unwrap();
+//! assert_eq!(a, b);
+//! ```
+
+use std::hash::{Hash, Hasher};
+
+use board::{Board, GameState};
+use layout;
+use render::GridFormat;
+use tile::{Tile, TileState};
+
+/// A rectangle of `Tiles`, from its top left corner.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    /// The column of the left edge.
+    pub x: usize,
+    /// The row of the top edge.
+    pub y: usize,
+    /// How many columns it covers.
+    pub width: usize,
+    /// How many rows it covers.
+    pub height: usize,
+}
+
+impl Rect {
+    /// Creates a `Rect`.
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns whether (x, y) is inside the `Rect`.
+    pub fn contains(&self, p: (usize, usize)) -> bool {
+        p.0 >= self.x && p.0 - self.x < self.width && p.1 >= self.y && p.1 - self.y < self.height
+    }
+}
+
+/// The `Tiles` of a `Board` inside a `Rect`. See the `view` module
+/// documentation.
+#[derive(Clone, Copy, Debug)]
+pub struct BoardView<'a> {
+    board: &'a Board,
+    rect: Rect,
+}
+
+/// What the user can see of one `Tile`. See `Board::to_grid`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TileView {
+    /// The `Tile` hasn't been revealed.
+    Hidden,
+    /// The user has flagged the `Tile`.
+    Flagged,
+    /// The `Tile` has been revealed, and shows this number (as decided
+    /// by the `Board`'s `NumberHook`, if it has one).
+    Number(isize),
+    /// The `Tile` is the bomb the user revealed, losing the game.
+    Detonated,
+    /// The `Tile` is a bomb shown after the game was lost, or revealed
+    /// without losing a game with lives to spare.
+    Exposed,
+    /// The user flagged the `Tile`, but it isn't a bomb.
+    WrongFlag,
+    /// The `Tile` isn't part of the playing field.
+    OutOfPlay,
+}
+
+impl Board {
+    /// Returns what the user can see of the `Tile` at `index`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `index` is not within the bounds of
+    /// the grid.
+    pub fn tile_view(&self, index: usize) -> TileView {
+        if !self.is_in_play(index) {
+            return TileView::OutOfPlay;
+        }
+        let tile = &self.tiles[index];
+        match tile.state {
+            TileState::Hidden => TileView::Hidden,
+            TileState::Flagged => TileView::Flagged,
+            TileState::Revealed if tile.is_bomb => TileView::Detonated,
+            TileState::Revealed => {
+                TileView::Number(self.shown_number(index).unwrap_or(tile.adjacent_bombs))
+            }
+            TileState::Detonated => TileView::Detonated,
+            TileState::Exposed => TileView::Exposed,
+            TileState::WrongFlag => TileView::WrongFlag,
+        }
+    }
+
+    /// Returns what the user can see of the whole `Board`, as one `Vec`
+    /// per row, from the top. See the `view` module documentation.
+    pub fn to_grid(&self) -> Vec<Vec<TileView>> {
+        (0..self.height)
+            .map(|y| (0..self.width).map(|x| self.tile_view(y * self.width + x)).collect())
+            .collect()
+    }
+
+    /// Returns what the user can see of the `Board` as JSON, leaving out
+    /// the hidden mines. See the `view` module documentation for the
+    /// schema.
+    pub fn to_json_state(&self) -> String {
+        let state = match self.state() {
+            GameState::Ready => "ready",
+            GameState::Playing => "playing",
+            GameState::Won => "won",
+            GameState::Lost => "lost",
+        };
+        let rows: Vec<String> = self.to_grid()
+            .iter()
+            .map(|row| {
+                let cells: Vec<String> = row.iter().map(|&view| json_cell(view)).collect();
+                format!("[{}]", cells.join(","))
+            })
+            .collect();
+        format!("{{\"width\":{},\"height\":{},\"state\":\"{}\",\"mines\":{},\
+                 \"mines_remaining\":{},\"cells\":[{}]}}",
+                self.width,
+                self.height,
+                state,
+                self.num_mines,
+                self.mines_remaining(),
+                rows.join(","))
+    }
+
+    /// Returns a view of the `Tiles` inside `rect`. Whatever part of
+    /// `rect` lies off the `Board` is cut off, so the view may be
+    /// smaller than asked for, or even empty.
+    pub fn view(&self, rect: Rect) -> BoardView<'_> {
+        let x = rect.x.min(self.width);
+        let y = rect.y.min(self.height);
+        BoardView {
+            board: self,
+            rect: Rect {
+                x,
+                y,
+                width: rect.width.min(self.width - x),
+                height: rect.height.min(self.height - y),
+            },
+        }
+    }
+}
+
+impl PartialEq for Board {
+    fn eq(&self, other: &Board) -> bool {
+        self.width == other.width && self.height == other.height &&
+        self.num_mines == other.num_mines &&
+        (0..self.tiles.len()).all(|i| self.tile_view(i) == other.tile_view(i))
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width.hash(state);
+        self.height.hash(state);
+        self.num_mines.hash(state);
+        for i in 0..self.tiles.len() {
+            self.tile_view(i).hash(state);
+        }
+    }
+}
+
+impl<'a> BoardView<'a> {
+    /// Returns the part of the `Board` in view.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Returns the width of the view.
+    pub fn width(&self) -> usize {
+        self.rect.width
+    }
+
+    /// Returns the height of the view.
+    pub fn height(&self) -> usize {
+        self.rect.height
+    }
+
+    /// Returns whether nothing is in view.
+    pub fn is_empty(&self) -> bool {
+        self.rect.width == 0 || self.rect.height == 0
+    }
+
+    /// Returns the `Tile` at (x, y) in the view's coordinates.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if (x, y) is outside the view.
+    pub fn tile(&self, p: (usize, usize)) -> &'a Tile {
+        &self.board.tiles[self.index(p)]
+    }
+
+    /// Returns the `Tile` at (x, y) in the view's coordinates, or
+    /// `None` if it is outside the view.
+    pub fn get(&self, p: (usize, usize)) -> Option<&'a Tile> {
+        if p.0 < self.rect.width && p.1 < self.rect.height {
+            Some(self.tile(p))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the index in the `Board` of the `Tile` at (x, y) in the
+    /// view's coordinates.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if (x, y) is outside the view.
+    pub fn index(&self, p: (usize, usize)) -> usize {
+        if p.0 >= self.rect.width || p.1 >= self.rect.height {
+            panic!("Tried to get a Tile that wasn't within the bounds of the view! Coordinates \
+                    passed: ({}, {}), View bounds: {}x{}",
+                   p.0,
+                   p.1,
+                   self.rect.width,
+                   self.rect.height);
+        }
+        self.board.linear_coords(self.board_coords(p))
+    }
+
+    /// Converts (x, y) in the view's coordinates to the `Board`'s.
+    pub fn board_coords(&self, p: (usize, usize)) -> (usize, usize) {
+        (self.rect.x + p.0, self.rect.y + p.1)
+    }
+
+    /// Converts (x, y) in the `Board`'s coordinates to the view's, or
+    /// returns `None` if it is outside the view.
+    pub fn local_coords(&self, p: (usize, usize)) -> Option<(usize, usize)> {
+        if self.rect.contains(p) {
+            Some((p.0 - self.rect.x, p.1 - self.rect.y))
+        } else {
+            None
+        }
+    }
+
+    /// Returns each row of the view, top to bottom, as a slice of the
+    /// `Board`'s `Tiles`.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [Tile]> + 'a {
+        let rect = self.rect;
+        let board = self.board;
+        (rect.y..rect.y + rect.height).map(move |y| {
+            let start = y * board.width + rect.x;
+            &board.tiles[start..start + rect.width]
+        })
+    }
+
+    /// Returns the hidden contents of the view, in the layout format of
+    /// `Board::layout_string`.
+    pub fn layout_string(&self) -> String {
+        self.render(layout::layout_cell)
+    }
+
+    /// Returns what the user can see of the view, in the visible format
+    /// of `Board::visible_string`.
+    pub fn visible_string(&self) -> String {
+        self.render(layout::visible_cell)
+    }
+
+    fn render<F: Fn(&Tile) -> String>(&self, cell: F) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let cells: Vec<String> = (0..self.rect.height)
+            .flat_map(|y| (0..self.rect.width).map(move |x| self.index((x, y))))
+            .map(|i| {
+                if self.board.is_in_play(i) {
+                    cell(&self.board.tiles[i])
+                } else {
+                    layout::HOLE.to_string()
+                }
+            })
+            .collect();
+        GridFormat::for_cells(&cells).render(&cells, self.rect.width)
+    }
+}
+
+// Writes one cell of `Board::to_json_state`
+fn json_cell(view: TileView) -> String {
+    let (revealed, flagged) = match view {
+        TileView::Hidden => (false, false),
+        TileView::Flagged => (false, true),
+        TileView::Number(n) => {
+            return format!("{{\"revealed\":true,\"flagged\":false,\"number\":{}}}", n)
+        }
+        TileView::Detonated | TileView::Exposed => {
+            return String::from("{\"revealed\":true,\"flagged\":false,\"number\":null,\
+                                 \"mine\":true}")
+        }
+        TileView::WrongFlag => {
+            return String::from("{\"revealed\":false,\"flagged\":true,\"number\":null,\
+                                 \"wrong\":true}")
+        }
+        TileView::OutOfPlay => return String::from("null"),
+    };
+    format!("{{\"revealed\":{},\"flagged\":{},\"number\":null}}", revealed, flagged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view() {
+        struct Test {
+            rect: Rect,
+            expected: Rect,
+            visible: &'static str,
+        }
+
+        let mut b = Board::from_layout("1*1..\n111..\n.....\n..111\n..1*1").unwrap();
+        b.reveal_tile(4).unwrap();
+        let tests = [Test {
+                         rect: Rect::new(0, 0, 5, 5),
+                         expected: Rect::new(0, 0, 5, 5),
+                         visible: "??1..\n111..\n.....\n..111\n..1??\n",
+                     },
+                     Test {
+                         rect: Rect::new(1, 3, 2, 1),
+                         expected: Rect::new(1, 3, 2, 1),
+                         visible: ".1\n",
+                     },
+                     Test {
+                         // Cut off at the edges
+                         rect: Rect::new(3, 3, 10, 10),
+                         expected: Rect::new(3, 3, 2, 2),
+                         visible: "11\n??\n",
+                     },
+                     Test {
+                         rect: Rect::new(7, 1, 2, 2),
+                         expected: Rect::new(5, 1, 0, 2),
+                         visible: "",
+                     }];
+
+        for test in &tests {
+            let view = b.view(test.rect);
+            assert_eq!(view.rect(), test.expected);
+            assert_eq!(view.visible_string(), test.visible);
+            assert_eq!(view.rows().count(), view.height());
+            for y in 0..view.height() {
+                for x in 0..view.width() {
+                    let (bx, by) = view.board_coords((x, y));
+                    assert_eq!(view.local_coords((bx, by)), Some((x, y)));
+                    assert_eq!(view.index((x, y)), b.linear_coords((bx, by)));
+                }
+            }
+            assert!(view.get((view.width(), 0)).is_none());
+        }
+    }
+
+    #[test]
+    fn test_to_grid() {
+        let mut b = Board::restore("1*1\n221\n*1.", "?!!\n???\n???").unwrap().with_lives(2);
+        b.reveal_tile(8).unwrap();
+        b.reveal_tile(6).unwrap();
+        assert_eq!(b.to_grid(),
+                   vec![vec![TileView::Hidden, TileView::Flagged, TileView::Flagged],
+                        vec![TileView::Hidden, TileView::Number(2), TileView::Number(1)],
+                        vec![TileView::Exposed, TileView::Number(1), TileView::Number(0)]]);
+        // The second bomb loses, showing the wrong flag
+        b.flag_tile(1).unwrap();
+        b.reveal_tile(1).unwrap();
+        assert_eq!(b.to_grid()[0],
+                   vec![TileView::Hidden, TileView::Detonated, TileView::WrongFlag]);
+    }
+
+    #[test]
+    fn test_board_eq() {
+        use std::collections::HashSet;
+
+        let a = Board::restore("1*1\n111\n...", "???\n???\n???").unwrap();
+        let mut b = Board::restore("*1.\n11.\n...", "???\n???\n???").unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, Board::restore("1*1\n111\n...", "!??\n???\n???").unwrap());
+        assert_ne!(a, Board::restore("2*1\n*21\n11.", "???\n???\n???").unwrap());
+
+        let mut seen = HashSet::new();
+        seen.insert(b.clone());
+        b.reveal_tile(8).unwrap();
+        assert!(seen.insert(b.clone()));
+        assert!(!seen.insert(b));
+    }
+
+    #[test]
+    fn test_json_state() {
+        extern crate serde_json;
+
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+        b.flag_tile(1).unwrap();
+        b.reveal_tile(0).unwrap();
+        let state: serde_json::Value = serde_json::from_str(&b.to_json_state()).unwrap();
+        assert_eq!(state["state"], "playing");
+        assert_eq!(state["mines_remaining"], 0);
+        assert_eq!(state["cells"][0][0]["number"], 1);
+        assert_eq!(state["cells"][0][1]["flagged"], true);
+        assert!(state["cells"][0][1].get("mine").is_none());
+        assert_eq!(state["cells"][1][2]["revealed"], false);
+
+        // The bombs only show up once the game is lost
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+        b.flag_tile(2).unwrap();
+        b.reveal_tile(1).unwrap();
+        let state: serde_json::Value = serde_json::from_str(&b.to_json_state()).unwrap();
+        assert_eq!(state["state"], "lost");
+        assert_eq!(state["cells"][0][1]["mine"], true);
+        assert_eq!(state["cells"][0][2]["wrong"], true);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_out_of_view() {
+        let b = Board::from_layout("1*1\n111\n...").unwrap();
+        b.view(Rect::new(1, 1, 2, 2)).tile((2, 0));
+    }
+}
@@ -0,0 +1,186 @@
+#![warn(missing_docs)]
+//! [Re-exported] Bindings for JavaScript front-ends, through
+//! `wasm-bindgen`.
+//!
+//! A `WasmBoard` wraps a `Board` in the handful of calls a browser game
+//! needs: making a `Board`, revealing, flagging, and chording, and a
+//! snapshot of what the user sees to draw from. The snapshot is JSON,
+//! so it crosses into JavaScript as a string for `JSON.parse`:
+//!
+//! ```json
+//! {"width":3,"height":3,"state":"Playing","mines_remaining":1,
+//!  "lives_left":0,"grid":[["Hidden",{"Number":1},"Flagged"],...]}
+//! ```
+//!
+//! The browser has no system randomness that the crate can reach, so
+//! every `WasmBoard` is seeded; pass in something like
+//! `Math.random() * 2 ** 32 >>> 0`. Time is read from
+//! `performance.now()`.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_core::WasmBoard;
+//!
+//! let mut b = WasmBoard::new(9, 9, 10, 42).unwrap();
+//! b.reveal_tile(40).unwrap();
+//! assert!(b.snapshot().contains("\"state\":\"Playing\""));
+//! ```
+
+use wasm_bindgen;
+use wasm_bindgen::prelude::*;
+use serde_json;
+
+use board::{Board, GameState};
+use builder::BoardBuilder;
+use view::TileView;
+
+/// A `Board` for JavaScript. See the `wasm` module documentation.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct WasmBoard {
+    board: Board,
+}
+
+// What `WasmBoard::snapshot` hands to JavaScript
+#[derive(Serialize)]
+struct Snapshot {
+    width: usize,
+    height: usize,
+    state: GameState,
+    mines_remaining: i64,
+    lives_left: usize,
+    grid: Vec<Vec<TileView>>,
+}
+
+#[wasm_bindgen]
+impl WasmBoard {
+    /// Makes a `width` x `height` `Board` with `mines` mines, placed
+    /// from `seed` on the first reveal.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the reason the `Board` can't be made,
+    /// as for `BoardBuilder::build`, to show to the user.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize, mines: usize, seed: u32)
+               -> Result<WasmBoard, String> {
+        BoardBuilder::new()
+            .width(width)
+            .height(height)
+            .mines(mines)
+            .seed(u64::from(seed))
+            .build()
+            .map(|board| WasmBoard { board })
+            .map_err(|err| err.to_string())
+    }
+
+    /// Returns the width of the `Board`.
+    pub fn width(&self) -> usize {
+        self.board.width
+    }
+
+    /// Returns the height of the `Board`.
+    pub fn height(&self) -> usize {
+        self.board.height
+    }
+
+    /// Reveals the `Tile` at `index`, as `Board::reveal_tile` does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `Board::reveal_tile`. It is safe to discard this error; it is
+    /// only for the programmer.
+    #[wasm_bindgen(js_name = revealTile)]
+    pub fn reveal_tile(&mut self, index: usize) -> Result<(), String> {
+        self.board.reveal_tile(index).map_err(String::from)
+    }
+
+    /// Toggles a flag on the `Tile` at `index`, as `Board::flag_tile`
+    /// does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `Board::flag_tile`.
+    #[wasm_bindgen(js_name = flagTile)]
+    pub fn flag_tile(&mut self, index: usize) -> Result<(), String> {
+        self.board.flag_tile(index).map_err(|err| err.to_string())
+    }
+
+    /// Chords the revealed number at `index`, as `Board::chord_tile`
+    /// does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `Board::chord_tile`. It is safe to discard this error; it is
+    /// only for the programmer.
+    #[wasm_bindgen(js_name = chordTile)]
+    pub fn chord_tile(&mut self, index: usize) -> Result<(), String> {
+        self.board.chord_tile(index).map_err(String::from)
+    }
+
+    /// Returns what the user can see, as JSON. See the `wasm` module
+    /// documentation.
+    pub fn snapshot(&self) -> String {
+        let snapshot = Snapshot {
+            width: self.board.width,
+            height: self.board.height,
+            state: self.board.state(),
+            mines_remaining: self.board.mines_remaining(),
+            lives_left: self.board.lives_left(),
+            grid: self.board.to_grid(),
+        };
+        serde_json::to_string(&snapshot).expect("a snapshot is always valid JSON")
+    }
+}
+
+impl WasmBoard {
+    /// Returns the `Board`.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns the `Board`, for everything the bindings don't cover.
+    pub fn into_board(self) -> Board {
+        self.board
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = performance, js_name = now)]
+    fn performance_now() -> f64;
+}
+
+// The time since the page loaded, for `SystemClock`, which can't use
+// `Instant` in a browser
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now() -> ::std::time::Duration {
+    ::std::time::Duration::from_secs_f64(performance_now() / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wasm_board() {
+        assert!(WasmBoard::new(2, 2, 1, 0).is_err());
+
+        let mut a = WasmBoard::new(9, 9, 10, 7).unwrap();
+        let mut b = WasmBoard::new(9, 9, 10, 7).unwrap();
+        a.reveal_tile(0).unwrap();
+        b.reveal_tile(0).unwrap();
+        assert_eq!(a.snapshot(), b.snapshot());
+        assert!(a.flag_tile(0).is_err());
+
+        let snapshot: serde_json::Value = serde_json::from_str(&a.snapshot()).unwrap();
+        assert_eq!(snapshot["width"], 9);
+        assert_eq!(snapshot["mines_remaining"], 10);
+        assert!(snapshot["grid"][0][0]["Number"].is_i64());
+    }
+}
@@ -0,0 +1,808 @@
+#![warn(missing_docs)]
+//! [Re-exported] Short codes for sharing a `Board`.
+//!
+//! `Board::to_code` packs a `Board` into a short string of letters,
+//! digits, `-`, and `_` (URL-safe base64, without padding) that can be
+//! pasted into a chat or a link, and `Board::from_code` turns it back
+//! into a `Board` with nothing revealed.
+//!
+//! What the code holds depends on the `Board`:
+//!
+//! * Once the bombs are placed, the code holds the layout itself, so
+//!   the friend gets exactly the same bombs.
+//! * A seeded `Board` that hasn't been generated yet holds the size,
+//!   mine count, seed, and first-click rule. The friend's `Board`
+//!   places its bombs exactly the same way if they start from the same
+//!   `Tile`.
+//! * Any other `Board` only holds the size, mine count, and first-click
+//!   rule, since there is nothing more to share yet.
+//!
+//! # Format
+//!
+//! Before encoding, the first byte is the version of the format (1),
+//! and the second says which of the three kinds of code it is (0 for
+//! a layout, 1 for a seed, 2 for neither) in its low three bits,
+//! whether the `Board` has a mask in the next, the `Topology` (0 square,
+//! 1 hex, 2 torus) in the next three, and whether there is a `Kernel` in
+//! the top bit. Then come the width, height, and mine count as LEB128
+//! numbers, and if there is a `Kernel`, the number of offsets followed
+//! by each offset's x and y, zigzag encoded (0, -1, 1, -2, ... as 0, 1,
+//! 2, 3, ...) as LEB128 numbers. If there is a mask, one bit per `Tile`
+//! follows, set for `Tiles` that are in play, least significant bit
+//! first. A layout code ends with one bit per `Tile`, set for bombs, in
+//! the same order, and if there are anti-mines, one more bit per `Tile`,
+//! set for them. The others end with the first-click rule as a byte (0
+//! opening, 1 safe, 2 unprotected), then a seed code has the seed as a
+//! LEB128 number, and if there are anti-mines, their number follows as
+//! another. The mine count includes the anti-mines.
+//!
+//! # Short Codes
+//!
+//! A seeded `Board` can also be shared as a `ShortCode`, which is meant
+//! to be read out loud or typed in by hand rather than pasted. It only
+//! holds the `BoardConfig` and the seed, written in Crockford's base32
+//! in groups of four, with a check symbol at the end to catch typos,
+//! such as `2081-0A5S-C0P`. Decoding ignores case and hyphens, and reads
+//! `O` as `0` and `I` and `L` as `1`.
+//!
+//! Before encoding, the first byte holds the version of the format (1)
+//! in its high four bits, the `Topology` (as above) in the next two,
+//! and the first-click rule (as above) in the low two. Then come the
+//! width, height, mine count, and seed as LEB128 numbers. The check
+//! symbol is those bytes, read as one big-endian number, modulo 37.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate mines_core;
+//! # extern crate mines_net;
+//! use mines_core::Board;
+//! use mines_net::{BoardCode, ShortCode};
+//!
+//! let mut b = Board::new(16, 16, 40);
+//! b.reveal_tile(0).unwrap();
+//! let code = b.to_code();
+//! println!("{}", code);
+//!
+//! let shared = Board::from_code(&code).unwrap();
+//! assert_eq!(shared.layout_string(), b.layout_string());
+//!
+//! let b = Board::new(16, 16, 40).with_seed(12345);
+//! let code = ShortCode::for_board(&b).unwrap();
+//! assert_eq!(code.to_string(), "2081-0A5S-C0P");
+//! let (config, seed) = ShortCode::decode(code.as_str()).unwrap();
+//! assert_eq!(config, b.config());
+//! assert_eq!(seed, 12345);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use mines_core::board::Board;
+use mines_core::builder::{BoardConfig, FirstClick};
+use mines_core::tile::Tile;
+use mines_core::topology::{Kernel, Topology, MAX_OFFSET};
+
+const VERSION: u8 = 1;
+const LAYOUT: u8 = 0;
+const SEED: u8 = 1;
+const SIZE_ONLY: u8 = 2;
+pub(crate) const ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const SHORT_VERSION: u8 = 1;
+// Crockford's base32 symbols, followed by the extra check symbols
+const CROCKFORD: &[u8; 37] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ*~$=U";
+
+/// The ways a board code can be invalid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseCodeError {
+    /// The code contains a character that is never used in codes.
+    InvalidCharacter(char),
+    /// The code was made by a newer version of this crate.
+    UnsupportedVersion(u8),
+    /// The code is cut short, or describes a `Board` that can't exist.
+    Malformed,
+    /// A `ShortCode` doesn't match its check symbol, so it was probably
+    /// mistyped.
+    WrongChecksum,
+    /// An offset of the `Kernel` reaches further than `MAX_OFFSET`.
+    KernelOffset {
+        /// The (x, y) offset.
+        offset: (isize, isize),
+    },
+}
+
+impl fmt::Display for ParseCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseCodeError::InvalidCharacter(c) => {
+                write!(f, "Board codes can't contain '{}'.", c)
+            }
+            ParseCodeError::UnsupportedVersion(version) => {
+                write!(f,
+                       "The code is version {}, but only up to version {} is supported.",
+                       version,
+                       VERSION)
+            }
+            ParseCodeError::Malformed => write!(f, "The code doesn't describe a valid board."),
+            ParseCodeError::WrongChecksum => write!(f, "The code has a typo in it."),
+            ParseCodeError::KernelOffset { offset } => {
+                write!(f,
+                       "The kernel offset ({}, {}) reaches further than {}.",
+                       offset.0,
+                       offset.1,
+                       MAX_OFFSET)
+            }
+        }
+    }
+}
+
+impl Error for ParseCodeError {}
+
+/// Sharing a `Board` as a code. See the `code` module documentation.
+pub trait BoardCode {
+    /// Returns a short code that `Board::from_code` turns back into
+    /// this `Board`, with nothing revealed. See the `code` module
+    /// documentation for what it holds.
+    fn to_code(&self) -> String;
+
+    /// Creates a `Board` from a code made by `Board::to_code`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `code` isn't a valid
+    /// board code.
+    fn from_code(code: &str) -> Result<Board, ParseCodeError>;
+}
+
+impl BoardCode for Board {
+    fn to_code(&self) -> String {
+        let mut bytes = vec![VERSION];
+        let kind = if self.is_generated() {
+            LAYOUT
+        } else if self.seed().is_some() {
+            SEED
+        } else {
+            SIZE_ONLY
+        };
+        let has_kernel = if self.kernel().is_some() { 0x80 } else { 0 };
+        let has_mask = if self.mask().is_some() { 0x08 } else { 0 };
+        bytes.push(has_kernel | topology_byte(self.topology()) << 4 | has_mask | kind);
+        for &n in &[self.width, self.height, self.num_mines] {
+            push_number(&mut bytes, n as u64);
+        }
+        if let Some(kernel) = self.kernel() {
+            push_number(&mut bytes, kernel.offsets().len() as u64);
+            for &(x, y) in kernel.offsets() {
+                push_number(&mut bytes, zigzag(x));
+                push_number(&mut bytes, zigzag(y));
+            }
+        }
+        if let Some(mask) = self.mask() {
+            bytes.extend(pack_bits(mask.iter().cloned()));
+        }
+
+        if kind == LAYOUT {
+            bytes.extend(pack_bits(self.tiles.iter().map(|tile| tile.is_bomb)));
+            if self.num_anti_mines() > 0 {
+                bytes.extend(pack_bits(self.tiles.iter().map(|tile| tile.is_anti_mine)));
+            }
+        } else {
+            bytes.push(first_click_byte(self.config().first_click));
+            if let Some(seed) = self.seed() {
+                push_number(&mut bytes, seed);
+            }
+            if self.num_anti_mines() > 0 {
+                push_number(&mut bytes, self.num_anti_mines() as u64);
+            }
+        }
+        encode(&bytes)
+    }
+
+    fn from_code(code: &str) -> Result<Board, ParseCodeError> {
+        let bytes = decode(code.trim())?;
+        let mut rest = &bytes[..];
+        let version = take_byte(&mut rest)?;
+        if version > VERSION {
+            return Err(ParseCodeError::UnsupportedVersion(version));
+        }
+        let byte = take_byte(&mut rest)?;
+        let (kind, topology) = (byte & 0x07, topology_from(byte >> 4 & 0x07)?);
+        let width = take_number(&mut rest)? as usize;
+        let height = take_number(&mut rest)? as usize;
+        let num_mines = take_number(&mut rest)? as usize;
+        let kernel = if byte & 0x80 != 0 {
+            let count = take_number(&mut rest)? as usize;
+            let mut offsets = Vec::new();
+            for _ in 0..count {
+                let x = unzigzag(take_number(&mut rest)?);
+                let y = unzigzag(take_number(&mut rest)?);
+                if !Kernel::offset_in_range((x, y)) {
+                    return Err(ParseCodeError::KernelOffset { offset: (x, y) });
+                }
+                offsets.push((x, y));
+            }
+            Some(Kernel::new(&offsets))
+        } else {
+            None
+        };
+        let length = width.checked_mul(height).ok_or(ParseCodeError::Malformed)?;
+
+        let mask = if byte & 0x08 != 0 {
+            Some(take_bits(&mut rest, length)?)
+        } else {
+            None
+        };
+
+        if kind == LAYOUT {
+            let bits = length.div_ceil(8);
+            if width < 3 || height < 3 || (rest.len() != bits && rest.len() != 2 * bits) {
+                return Err(ParseCodeError::Malformed);
+            }
+            let mut tiles: Vec<Tile> = take_bits(&mut rest, length)?
+                .into_iter()
+                .map(|is_bomb| Tile { is_bomb, ..Tile::default() })
+                .collect();
+            if !rest.is_empty() {
+                for (tile, is_anti_mine) in tiles.iter_mut().zip(take_bits(&mut rest, length)?) {
+                    if is_anti_mine && !tile.is_bomb {
+                        return Err(ParseCodeError::Malformed);
+                    }
+                    tile.is_anti_mine = is_anti_mine;
+                }
+            }
+            let in_play = |i: usize| mask.as_ref().is_none_or(|mask| mask[i]);
+            if tiles.iter().enumerate().any(|(i, tile)| tile.is_bomb && !in_play(i)) {
+                return Err(ParseCodeError::Malformed);
+            }
+            let board = Board::from_tiles(width, height, topology, kernel, mask, tiles);
+            if board.num_mines != num_mines {
+                return Err(ParseCodeError::Malformed);
+            }
+            return Ok(board);
+        }
+
+        let first_click = first_click_from(take_byte(&mut rest)?)?;
+        let mut builder = Board::builder()
+            .width(width)
+            .height(height)
+            .mines(num_mines)
+            .first_click(first_click)
+            .topology(topology);
+        if let Some(kernel) = kernel {
+            builder = builder.kernel(kernel);
+        }
+        if let Some(mask) = mask {
+            builder = builder.mask(mask);
+        }
+        match kind {
+            SEED => builder = builder.seed(take_number(&mut rest)?),
+            SIZE_ONLY => {}
+            _ => return Err(ParseCodeError::Malformed),
+        }
+        if !rest.is_empty() {
+            let anti_mines = take_number(&mut rest)? as usize;
+            let mines = num_mines.checked_sub(anti_mines).ok_or(ParseCodeError::Malformed)?;
+            builder = builder.mines(mines).anti_mines(anti_mines);
+        }
+        if !rest.is_empty() {
+            return Err(ParseCodeError::Malformed);
+        }
+        builder.build().map_err(|_| ParseCodeError::Malformed)
+    }
+}
+
+/// A short, human-friendly code for a seeded `Board`. See the module
+/// documentation.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ShortCode(String);
+
+impl ShortCode {
+    /// Returns the code for a `Board` with the settings in `config`
+    /// and its bombs placed from `seed`.
+    pub fn encode(config: &BoardConfig, seed: u64) -> ShortCode {
+        let mut bytes = vec![SHORT_VERSION << 4 | topology_byte(config.topology) << 2 |
+                             first_click_byte(config.first_click)];
+        for &n in &[config.width, config.height, config.num_mines] {
+            push_number(&mut bytes, n as u64);
+        }
+        push_number(&mut bytes, seed);
+
+        let mut code = String::new();
+        for (i, c) in encode_base32(&bytes).chars().enumerate() {
+            if i > 0 && i % 4 == 0 {
+                code.push('-');
+            }
+            code.push(c);
+        }
+        code.push(CROCKFORD[checksum(&bytes)] as char);
+        ShortCode(code)
+    }
+
+    /// Returns the code for `board`, or `None` if it isn't seeded, or
+    /// has a `Kernel`, a mask, or anti-mines, which a short code can't
+    /// hold.
+    pub fn for_board(board: &Board) -> Option<ShortCode> {
+        if board.kernel().is_some() || board.mask().is_some() || board.num_anti_mines() > 0 {
+            return None;
+        }
+        board.seed().map(|seed| ShortCode::encode(&board.config(), seed))
+    }
+
+    /// Reads the settings and seed back out of a code made by
+    /// `ShortCode::encode`, as typed in by a person.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `code` doesn't match its
+    /// check symbol, or isn't a valid short code.
+    pub fn decode(code: &str) -> Result<(BoardConfig, u64), ParseCodeError> {
+        let symbols: Vec<char> = code.chars().filter(|&c| c != '-' && !c.is_whitespace()).collect();
+        let (&check, body) = symbols.split_last().ok_or(ParseCodeError::Malformed)?;
+        let bytes = decode_base32(body)?;
+        if crockford_value(check, CROCKFORD.len())? != checksum(&bytes) {
+            return Err(ParseCodeError::WrongChecksum);
+        }
+
+        let mut rest = &bytes[..];
+        let first = take_byte(&mut rest)?;
+        let version = first >> 4;
+        if version > SHORT_VERSION {
+            return Err(ParseCodeError::UnsupportedVersion(version));
+        }
+        let config = BoardConfig {
+            first_click: first_click_from(first & 0x03)?,
+            topology: topology_from(first >> 2 & 0x03)?,
+            width: take_number(&mut rest)? as usize,
+            height: take_number(&mut rest)? as usize,
+            num_mines: take_number(&mut rest)? as usize,
+        };
+        let seed = take_number(&mut rest)?;
+        if version == 0 || !rest.is_empty() || config.builder().build().is_err() {
+            return Err(ParseCodeError::Malformed);
+        }
+        Ok((config, seed))
+    }
+
+    /// Returns the code as text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ShortCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn first_click_byte(first_click: FirstClick) -> u8 {
+    match first_click {
+        FirstClick::Opening => 0,
+        FirstClick::Safe => 1,
+        FirstClick::Unprotected => 2,
+    }
+}
+
+fn first_click_from(byte: u8) -> Result<FirstClick, ParseCodeError> {
+    match byte {
+        0 => Ok(FirstClick::Opening),
+        1 => Ok(FirstClick::Safe),
+        2 => Ok(FirstClick::Unprotected),
+        _ => Err(ParseCodeError::Malformed),
+    }
+}
+
+fn topology_byte(topology: Topology) -> u8 {
+    match topology {
+        Topology::Square => 0,
+        Topology::Hex => 1,
+        Topology::Torus => 2,
+    }
+}
+
+fn topology_from(byte: u8) -> Result<Topology, ParseCodeError> {
+    match byte {
+        0 => Ok(Topology::Square),
+        1 => Ok(Topology::Hex),
+        2 => Ok(Topology::Torus),
+        _ => Err(ParseCodeError::Malformed),
+    }
+}
+
+fn push_number(bytes: &mut Vec<u8>, mut n: u64) {
+    loop {
+        let low = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            bytes.push(low);
+            return;
+        }
+        bytes.push(low | 0x80);
+    }
+}
+
+fn take_byte(bytes: &mut &[u8]) -> Result<u8, ParseCodeError> {
+    let (&first, rest) = bytes.split_first().ok_or(ParseCodeError::Malformed)?;
+    *bytes = rest;
+    Ok(first)
+}
+
+// Maps 0, -1, 1, -2, ... to 0, 1, 2, 3, ...
+fn zigzag(n: isize) -> u64 {
+    ((n << 1) ^ (n >> (isize::BITS - 1))) as u64
+}
+
+fn unzigzag(n: u64) -> isize {
+    (n >> 1) as isize ^ -((n & 1) as isize)
+}
+
+fn take_number(bytes: &mut &[u8]) -> Result<u64, ParseCodeError> {
+    let mut n = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = take_byte(bytes)?;
+        n |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+    Err(ParseCodeError::Malformed)
+}
+
+// Packs bits into bytes, least significant bit first
+fn pack_bits<I: Iterator<Item = bool>>(bits: I) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for (i, bit) in bits.enumerate() {
+        if i % 8 == 0 {
+            bytes.push(0);
+        }
+        if bit {
+            *bytes.last_mut().unwrap() |= 1 << (i % 8);
+        }
+    }
+    bytes
+}
+
+fn take_bits(bytes: &mut &[u8], count: usize) -> Result<Vec<bool>, ParseCodeError> {
+    let len = count.div_ceil(8);
+    if bytes.len() < len {
+        return Err(ParseCodeError::Malformed);
+    }
+    let bits = (0..count).map(|i| bytes[i / 8] & (1 << (i % 8)) != 0).collect();
+    *bytes = &bytes[len..];
+    Ok(bits)
+}
+
+fn encode(bytes: &[u8]) -> String {
+    let mut s = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..chunk.len() + 1 {
+            s.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    s
+}
+
+fn decode(s: &str) -> Result<Vec<u8>, ParseCodeError> {
+    let mut values = Vec::new();
+    for c in s.chars() {
+        match ALPHABET.iter().position(|&a| a as char == c) {
+            Some(v) => values.push(v as u32),
+            None => return Err(ParseCodeError::InvalidCharacter(c)),
+        }
+    }
+    let mut bytes = Vec::new();
+    for chunk in values.chunks(4) {
+        if chunk.len() == 1 {
+            return Err(ParseCodeError::Malformed);
+        }
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &v)| n | v << (18 - 6 * i));
+        for i in 0..chunk.len() - 1 {
+            bytes.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+fn encode_base32(bytes: &[u8]) -> String {
+    let mut s = String::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &b in bytes {
+        buffer = (buffer << 8 | u32::from(b)) & 0xfff;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            s.push(CROCKFORD[(buffer >> bits & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        s.push(CROCKFORD[(buffer << (5 - bits) & 0x1f) as usize] as char);
+    }
+    s
+}
+
+fn decode_base32(symbols: &[char]) -> Result<Vec<u8>, ParseCodeError> {
+    let mut bytes = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &c in symbols {
+        buffer = (buffer << 5 | crockford_value(c, 32)? as u32) & 0xfff;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    // Whatever is left over must be padding
+    if bits >= 5 || buffer & ((1 << bits) - 1) != 0 {
+        return Err(ParseCodeError::Malformed);
+    }
+    Ok(bytes)
+}
+
+// Returns the value of a Crockford symbol, among the first `count`
+fn crockford_value(c: char, count: usize) -> Result<usize, ParseCodeError> {
+    let value = match c.to_ascii_uppercase() {
+        'O' => Some(0),
+        'I' | 'L' => Some(1),
+        upper => CROCKFORD[..count].iter().position(|&s| s as char == upper),
+    };
+    value.ok_or(ParseCodeError::InvalidCharacter(c))
+}
+
+fn checksum(bytes: &[u8]) -> usize {
+    bytes.iter().fold(0, |r, &b| (r * 256 + b as usize) % CROCKFORD.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64() {
+        let tests: [(&[u8], &str); 5] = [(b"", ""),
+                                         (b"f", "Zg"),
+                                         (b"fo", "Zm8"),
+                                         (b"foo", "Zm9v"),
+                                         (&[0xfb, 0xff], "-_8")];
+        for &(bytes, text) in &tests {
+            assert_eq!(encode(bytes), text);
+            assert_eq!(decode(text).unwrap(), bytes);
+        }
+        assert_eq!(decode("Zm9=").unwrap_err(), ParseCodeError::InvalidCharacter('='));
+        assert_eq!(decode("Zm9vZ").unwrap_err(), ParseCodeError::Malformed);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // A layout
+        let b = Board::from_layout("1*1\n111\n...").unwrap();
+        let shared = Board::from_code(&b.to_code()).unwrap();
+        assert_eq!(shared.layout_string(), b.layout_string());
+        let hex = Board::from_layout_with("*1.\n1..\n...", Topology::Hex).unwrap();
+        let shared = Board::from_code(&hex.to_code()).unwrap();
+        assert_eq!(shared.topology(), Topology::Hex);
+        assert_eq!(shared.layout_string(), hex.layout_string());
+
+        // A Kernel, before and after the bombs are placed
+        let mut knight = Board::builder()
+            .width(6)
+            .height(6)
+            .mines(4)
+            .seed(9)
+            .kernel(Kernel::knight())
+            .build()
+            .unwrap();
+        let mut shared = Board::from_code(&knight.to_code()).unwrap();
+        assert_eq!(shared.kernel(), Some(&Kernel::knight()));
+        knight.reveal_tile(0).unwrap();
+        shared.reveal_tile(0).unwrap();
+        assert_eq!(shared.layout_string(), knight.layout_string());
+        let shared = Board::from_code(&knight.to_code()).unwrap();
+        assert_eq!(shared.kernel(), Some(&Kernel::knight()));
+        assert_eq!(shared.layout_string(), knight.layout_string());
+        assert!(ShortCode::for_board(&knight).is_none());
+
+        // A mask, before and after the bombs are placed
+        let mask: Vec<bool> = (0..25).map(|i| i != 12).collect();
+        let mut donut = Board::builder()
+            .width(5)
+            .height(5)
+            .mines(5)
+            .seed(3)
+            .mask(mask.clone())
+            .build()
+            .unwrap();
+        let shared = Board::from_code(&donut.to_code()).unwrap();
+        assert_eq!(shared.mask(), Some(&mask[..]));
+        donut.reveal_tile(0).unwrap();
+        let shared = Board::from_code(&donut.to_code()).unwrap();
+        assert_eq!(shared.mask(), Some(&mask[..]));
+        assert_eq!(shared.layout_string(), donut.layout_string());
+        assert!(ShortCode::for_board(&donut).is_none());
+
+        // Anti-mines, before and after they are placed
+        let mut anti =
+            Board::builder().width(6).height(6).mines(4).anti_mines(2).seed(5).build().unwrap();
+        let mut shared = Board::from_code(&anti.to_code()).unwrap();
+        assert_eq!((shared.num_mines, shared.num_anti_mines()), (6, 2));
+        anti.reveal_tile(0).unwrap();
+        shared.reveal_tile(0).unwrap();
+        assert_eq!(shared.layout_string(), anti.layout_string());
+        let shared = Board::from_code(&anti.to_code()).unwrap();
+        assert_eq!(shared.layout_string(), anti.layout_string());
+        assert!(ShortCode::for_board(&anti).is_none());
+
+        // A seed, which places the same bombs from the same first click
+        let seeded = Board::builder()
+            .width(30)
+            .height(16)
+            .mines(99)
+            .seed(u64::MAX)
+            .build()
+            .unwrap();
+        let code = seeded.to_code();
+        let (mut mine, mut theirs) = (seeded.clone(), Board::from_code(&code).unwrap());
+        assert_eq!(theirs.seed(), Some(u64::MAX));
+        mine.reveal_tile(200).unwrap();
+        theirs.reveal_tile(200).unwrap();
+        assert_eq!(theirs.layout_string(), mine.layout_string());
+
+        // Just the size
+        let plain = Board::builder()
+            .width(5)
+            .height(4)
+            .mines(3)
+            .first_click(FirstClick::Safe)
+            .build()
+            .unwrap();
+        let shared = Board::from_code(&plain.to_code()).unwrap();
+        assert!(!shared.is_generated());
+        assert_eq!((shared.width, shared.height, shared.num_mines), (5, 4, 3));
+        assert_eq!(shared.config().first_click, FirstClick::Safe);
+        assert!(plain.to_code().len() < 12);
+    }
+
+    #[test]
+    fn test_errors() {
+        struct Test {
+            bytes: Vec<u8>,
+            expected: ParseCodeError,
+        }
+
+        let tests = [Test {
+                         bytes: vec![],
+                         expected: ParseCodeError::Malformed,
+                     },
+                     Test {
+                         bytes: vec![2, 0],
+                         expected: ParseCodeError::UnsupportedVersion(2),
+                     },
+                     Test {
+                         // Too few bits for the layout
+                         bytes: vec![1, 0, 3, 3, 1, 0xff],
+                         expected: ParseCodeError::Malformed,
+                     },
+                     Test {
+                         // The wrong mine count for the layout
+                         bytes: vec![1, 0, 3, 3, 2, 0x02, 0x00],
+                         expected: ParseCodeError::Malformed,
+                     },
+                     Test {
+                         // Too many mines
+                         bytes: vec![1, 2, 3, 3, 9, 0],
+                         expected: ParseCodeError::Malformed,
+                     },
+                     Test {
+                         bytes: vec![1, 7, 3, 3, 1, 0],
+                         expected: ParseCodeError::Malformed,
+                     },
+                     Test {
+                         // Too many Tiles for a seed: 2^20 on a side
+                         bytes: vec![1, 1, 0x80, 0x80, 0x40, 0x80, 0x80, 0x40, 1, 0, 0],
+                         expected: ParseCodeError::Malformed,
+                     },
+                     Test {
+                         // Or for the size alone: 2^40 on a side
+                         bytes: vec![1, 2, 0x80, 0x80, 0x80, 0x80, 0x80, 0x20, 0x80, 0x80, 0x80,
+                                     0x80, 0x80, 0x20, 1, 0],
+                         expected: ParseCodeError::Malformed,
+                     },
+                     Test {
+                         // A Kernel offset of 2^25
+                         bytes: vec![1, 0x82, 5, 5, 1, 1, 0x80, 0x80, 0x80, 0x20, 0, 0],
+                         expected: ParseCodeError::KernelOffset { offset: (1 << 25, 0) },
+                     }];
+
+        for test in &tests {
+            assert_eq!(Board::from_code(&encode(&test.bytes)).unwrap_err(), test.expected);
+        }
+        let b = Board::from_code(&encode(&[1, 0, 3, 3, 1, 0x02, 0x00])).unwrap();
+        assert_eq!(b.layout_string(), "1*1\n111\n...\n");
+    }
+
+    #[test]
+    fn test_base32() {
+        let tests: [(&[u8], &str); 4] =
+            [(b"", ""), (b"f", "CR"), (b"fo", "CSQG"), (&[0xff; 5], "ZZZZZZZZ")];
+        for &(bytes, text) in &tests {
+            assert_eq!(encode_base32(bytes), text);
+            let symbols: Vec<char> = text.chars().collect();
+            assert_eq!(decode_base32(&symbols).unwrap(), bytes);
+        }
+        // Padding bits must be zero
+        assert_eq!(decode_base32(&['C', 'S']).unwrap_err(), ParseCodeError::Malformed);
+        assert_eq!(decode_base32(&['C', 'U']).unwrap_err(), ParseCodeError::InvalidCharacter('U'));
+    }
+
+    #[test]
+    fn test_short_code() {
+        let config = BoardConfig {
+            width: 30,
+            height: 16,
+            num_mines: 99,
+            first_click: FirstClick::Safe,
+            topology: Topology::Square,
+        };
+        let code = ShortCode::encode(&config, 987654321);
+        assert_eq!(ShortCode::decode(code.as_str()), Ok((config, 987654321)));
+        assert_eq!(code.as_str().len(), 19);
+
+        // Typed in sloppily
+        let sloppy = code.as_str()
+            .replace('-', " ")
+            .replace('0', "o")
+            .replace('1', "l")
+            .to_lowercase();
+        assert_eq!(ShortCode::decode(&sloppy), Ok((config, 987654321)));
+
+        // Every single typo is caught
+        let symbols: Vec<char> = code.as_str().chars().collect();
+        for i in (0..symbols.len()).filter(|&i| symbols[i] != '-') {
+            for &typo in CROCKFORD.iter().take(32) {
+                let mut typed = symbols.clone();
+                if typed[i] == typo as char {
+                    continue;
+                }
+                typed[i] = typo as char;
+                let typed: String = typed.into_iter().collect();
+                assert!(ShortCode::decode(&typed).is_err(), "{}", typed);
+            }
+        }
+
+        let b = Board::builder().width(9).height(9).mines(10).seed(42).build().unwrap();
+        let (config, seed) = ShortCode::decode(ShortCode::for_board(&b).unwrap().as_str()).unwrap();
+        assert_eq!(config.builder().seed(seed).build().unwrap().config(), b.config());
+        assert!(ShortCode::for_board(&Board::new(9, 9, 10)).is_none());
+
+        for &topology in &[Topology::Hex, Topology::Torus] {
+            let config = BoardConfig { topology, ..config };
+            let code = ShortCode::encode(&config, 5);
+            assert_eq!(ShortCode::decode(code.as_str()), Ok((config, 5)));
+        }
+    }
+
+    #[test]
+    fn test_short_code_errors() {
+        let code = |bytes: &[u8]| {
+            format!("{}{}", encode_base32(bytes), CROCKFORD[checksum(bytes)] as char)
+        };
+        let tests: [(String, ParseCodeError); 8] =
+            [(String::new(), ParseCodeError::Malformed),
+             ("0".to_string(), ParseCodeError::Malformed),
+             (code(&[0x20, 9, 9, 10, 0]), ParseCodeError::UnsupportedVersion(2)),
+             (code(&[0x13, 9, 9, 10, 0]), ParseCodeError::Malformed),
+             // Too many mines
+             (code(&[0x10, 9, 9, 80, 0]), ParseCodeError::Malformed),
+             // Too many Tiles: 2^20 and 2^40 on a side
+             ("2208-0G40-G100-M18S".to_string(), ParseCodeError::Malformed),
+             (code(&[0x10, 0x80, 0x80, 0x80, 0x80, 0x80, 0x20, 0x80, 0x80, 0x80, 0x80, 0x80, 0x20,
+                     1, 0]),
+              ParseCodeError::Malformed),
+             (format!("{}0", encode_base32(&[0x10, 9, 9, 10, 0])), ParseCodeError::WrongChecksum)];
+        for &(ref text, expected) in &tests {
+            assert_eq!(ShortCode::decode(text), Err(expected), "{}", text);
+        }
+        assert!(ShortCode::decode(&code(&[0x10, 9, 9, 10, 0])).is_ok());
+    }
+}
@@ -0,0 +1,325 @@
+#![warn(missing_docs)]
+//! [Re-exported] Hashing a `Board`'s layout, and committing to it
+//! without giving it away.
+//!
+//! Everything here hashes the same canonical encoding of a `Board`
+//! (see `Board::canonical_bytes`) with SHA-256, so hashes made by
+//! different front-ends, or by code in other languages, agree.
+//!
+//! A `Redaction` is what a streamer can show: the board as the player
+//! sees it, plus a `Commitment` to the hidden layout. Publishing the
+//! layout and the salt afterwards lets anyone check that the board
+//! shown was never altered.
+//!
+//! # Commit-reveal
+//!
+//! For online play, a server can prove it didn't pick the bombs after
+//! seeing the players' moves. It creates the `Board` with
+//! `Board::committed`, which places the bombs from a secret seed right
+//! away, and sends the players the `Commitment` before play starts.
+//! Once the game is over it reveals the seed, and each player checks it
+//! with `Board::verify_commitment`.
+//!
+//! Since the bombs are placed before anyone clicks, a committed `Board`
+//! has no first-click protection: the first reveal can hit a bomb.
+//!
+//! ```
+//! # extern crate mines_core;
+//! # extern crate mines_net;
+//! use mines_core::Board;
+//! use mines_net::BoardCommit;
+//!
+//! // On the server
+//! let seed = 0x5eed;
+//! let (mut board, commitment) = Board::committed(16, 16, 40, seed).unwrap();
+//! let _ = board.reveal_tile(0);
+//!
+//! // On each client, once the seed is revealed
+//! assert!(board.verify_commitment(seed, &commitment));
+//! assert!(!board.verify_commitment(seed + 1, &commitment));
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate mines_core;
+//! # extern crate mines_net;
+//! use mines_core::Board;
+//! use mines_net::BoardCommit;
+//!
+//! let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+//! b.reveal_tile(8).unwrap();
+//!
+//! let redaction = b.redact(1234);
+//! assert_eq!(redaction.visible, "???\n111\n...\n");
+//! println!("{}", redaction.commitment);
+//!
+//! // Later, the layout and salt are published
+//! assert!(redaction.verify("1*1\n111\n...", 1234));
+//! assert!(!redaction.verify("*1.\n11.\n...", 1234));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use sha2::{Digest, Sha256};
+
+use mines_core::board::Board;
+use mines_core::builder::{BuildError, FirstClick};
+
+/// A SHA-256 hash of something about a `Board`. Written and parsed as
+/// 64 lowercase hexadecimal digits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Commitment([u8; 32]);
+
+impl Commitment {
+    /// Hashes `bytes`.
+    pub fn of(bytes: &[u8]) -> Commitment {
+        Commitment(Sha256::digest(bytes).into())
+    }
+
+    /// Returns the raw bytes of the hash.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// An error returned when a `Commitment` can't be parsed from text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseCommitmentError;
+
+impl fmt::Display for ParseCommitmentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "A commitment must be 64 hexadecimal digits.")
+    }
+}
+
+impl Error for ParseCommitmentError {}
+
+impl FromStr for Commitment {
+    type Err = ParseCommitmentError;
+
+    fn from_str(s: &str) -> Result<Commitment, ParseCommitmentError> {
+        if s.len() != 64 || !s.is_ascii() {
+            return Err(ParseCommitmentError);
+        }
+        let mut bytes = [0; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).map_err(|_| ParseCommitmentError)?;
+        }
+        Ok(Commitment(bytes))
+    }
+}
+
+/// What can be shown of a `Board` without giving away its layout.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Redaction {
+    /// The `Board` as the player sees it, in the visible format of
+    /// `Board::restore`.
+    pub visible: String,
+    /// A salted commitment to the layout.
+    pub commitment: Commitment,
+}
+
+impl Redaction {
+    /// Returns whether `layout` (in the format of `Board::from_layout`)
+    /// and `salt` are what was committed to, and whether the visible
+    /// board could have been shown on that layout.
+    pub fn verify(&self, layout: &str, salt: u64) -> bool {
+        match Board::restore(layout, &self.visible) {
+            Ok(board) => salted(&board, salt) == self.commitment,
+            Err(_) => false,
+        }
+    }
+}
+
+/// Committing to the layout of a `Board`. See the `commit` module documentation.
+pub trait BoardCommit {
+    /// Returns the canonical encoding of this `Board`'s layout, which
+    /// every hash in the crate is taken over: the dimensions as
+    /// `{width}x{height}` and a newline, followed by the layout in the
+    /// format of `Board::from_layout`, as UTF-8.
+    fn canonical_bytes(&self) -> Vec<u8>;
+
+    /// Returns a hash of this `Board`'s layout. Two `Boards` hash the
+    /// same exactly when they have the same bombs, whatever has been
+    /// revealed on them.
+    fn layout_hash(&self) -> Commitment;
+
+    /// Creates a `Board` with its bombs placed from `seed` straight
+    /// away, along with a commitment to its layout to hand out before
+    /// play. See the `commit` module documentation.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `BoardBuilder::build` with `FirstClick::Unprotected`.
+    fn committed(width: usize,
+                 height: usize,
+                 num_mines: usize,
+                 seed: u64)
+                 -> Result<(Board, Commitment), BuildError>;
+
+    /// Returns whether `seed` really does produce the layout committed
+    /// to by `commitment` (from `Board::committed`) at this `Board`'s
+    /// size, and, if this `Board` has been generated, whether that is
+    /// the layout it has.
+    fn verify_commitment(&self, seed: u64, commitment: &Commitment) -> bool;
+
+    /// Returns what the player can see of this `Board`, along with a
+    /// commitment to its layout, salted with `salt` so that the hidden
+    /// part can't be found by trying every possibility. Keep the salt
+    /// secret until the layout is published.
+    fn redact(&self, salt: u64) -> Redaction;
+}
+
+impl BoardCommit for Board {
+    fn canonical_bytes(&self) -> Vec<u8> {
+        format!("{}x{}\n{}", self.width, self.height, self.layout_string()).into_bytes()
+    }
+
+    fn layout_hash(&self) -> Commitment {
+        Commitment::of(&self.canonical_bytes())
+    }
+
+    fn committed(width: usize,
+                 height: usize,
+                 num_mines: usize,
+                 seed: u64)
+                 -> Result<(Board, Commitment), BuildError> {
+        let mut board = Board::builder()
+            .width(width)
+            .height(height)
+            .mines(num_mines)
+            .seed(seed)
+            .first_click(FirstClick::Unprotected)
+            .build()?;
+        // Nothing is protected, so where the "first click" is makes no
+        // difference to where the bombs go
+        board.generate(0);
+        let commitment = board.layout_hash();
+        Ok((board, commitment))
+    }
+
+    fn verify_commitment(&self, seed: u64, commitment: &Commitment) -> bool {
+        let expected = match Board::committed(self.width, self.height, self.num_mines, seed) {
+            Ok((board, _)) => board,
+            Err(_) => return false,
+        };
+        expected.layout_hash() == *commitment &&
+        (!self.is_generated() || self.layout_hash() == *commitment)
+    }
+
+    fn redact(&self, salt: u64) -> Redaction {
+        Redaction {
+            visible: self.visible_string(),
+            commitment: salted(self, salt),
+        }
+    }
+}
+
+// The commitment to `board`'s layout with `salt`
+fn salted(board: &Board, salt: u64) -> Commitment {
+    let mut bytes = format!("{}\n", salt).into_bytes();
+    bytes.extend(board.canonical_bytes());
+    Commitment::of(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_hash() {
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+        let hash = b.layout_hash();
+        // Known value, so that other implementations can check theirs
+        assert_eq!(b.canonical_bytes(), b"3x3\n1*1\n111\n...\n".to_vec());
+        assert_eq!(hash.to_string(),
+                   "b6e0f9fc87d82af18515a58e09e2b341a7e27c2f80209cc66a00b1e1f97e57cf");
+        b.reveal_tile(8).unwrap();
+        assert_eq!(b.layout_hash(), hash);
+        assert_ne!(Board::from_layout("*1.\n11.\n...").unwrap().layout_hash(), hash);
+
+        let text = hash.to_string();
+        assert_eq!(text.len(), 64);
+        assert_eq!(text.parse::<Commitment>(), Ok(hash));
+        assert_eq!("abc".parse::<Commitment>(), Err(ParseCommitmentError));
+        assert_eq!(format!("g{}", &text[1..]).parse::<Commitment>(),
+                   Err(ParseCommitmentError));
+    }
+
+    #[test]
+    fn test_commit_reveal() {
+        let (board, commitment) = Board::committed(9, 9, 10, 77).unwrap();
+        assert!(board.is_generated());
+        assert_eq!(board.tiles.iter().filter(|t| t.is_bomb).count(), 10);
+        assert_eq!(Board::committed(9, 9, 10, 77).unwrap().1, commitment);
+
+        struct Test {
+            board: Board,
+            seed: u64,
+            expected: bool,
+        }
+
+        let tests = [Test {
+                         board: board.clone(),
+                         seed: 77,
+                         expected: true,
+                     },
+                     Test {
+                         // The client only knows the size
+                         board: Board::new(9, 9, 10),
+                         seed: 77,
+                         expected: true,
+                     },
+                     Test {
+                         board: board.clone(),
+                         seed: 78,
+                         expected: false,
+                     },
+                     Test {
+                         // The right seed, but not the board that was
+                         // played
+                         board: Board::committed(9, 9, 10, 78).unwrap().0,
+                         seed: 77,
+                         expected: false,
+                     },
+                     Test {
+                         board: Board::new(9, 9, 11),
+                         seed: 77,
+                         expected: false,
+                     }];
+
+        for test in &tests {
+            assert_eq!(test.board.verify_commitment(test.seed, &commitment), test.expected);
+        }
+        assert!(Board::committed(2, 2, 1, 0).is_err());
+    }
+
+    #[test]
+    fn test_redaction() {
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+        b.reveal_tile(8).unwrap();
+        let redaction = b.redact(7);
+        assert_ne!(redaction.commitment, b.redact(8).commitment);
+        assert!(redaction.verify("1*1\n111\n...", 7));
+        assert!(!redaction.verify("1*1\n111\n...", 8));
+
+        // A board the visible one couldn't have come from
+        let mut altered = redaction.clone();
+        altered.visible = String::from("???\n???\n11.\n");
+        assert!(!altered.verify("1*1\n111\n...", 7));
+    }
+}
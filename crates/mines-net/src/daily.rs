@@ -0,0 +1,142 @@
+#![warn(missing_docs)]
+//! [Re-exported] The puzzle of the day.
+//!
+//! `Board::daily` gives every user of the crate exactly the same
+//! `Board` for a given date and difficulty, on every platform, so that
+//! players can compare their times on it.
+//!
+//! # Algorithm
+//!
+//! The seed for a date and difficulty is fixed as follows, and will
+//! never change:
+//!
+//! 1. The date is packed into one number as `year << 16 | month << 8 |
+//!    day`, with the year as a two's complement 32-bit number, then
+//!    shifted left by two and combined with the difficulty: 0 for
+//!    beginner, 1 for intermediate, 2 for expert.
+//! 2. The seed is the first output of `util::SeededRng` (SplitMix64)
+//!    seeded with that number.
+//!
+//! The bombs are placed from the seed as for any seeded `Board`, with
+//! the middle `Tile` as the first click (for an even size, the one just
+//! above and to the left of the middle). That opening is already
+//! revealed on the daily `Board`, so everyone starts from the same
+//! place, but the clock only starts on the player's first reveal.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate mines_core;
+//! # extern crate mines_net;
+//! use mines_core::{Board, Difficulty};
+//! use mines_net::BoardDaily;
+//!
+//! let a = Board::daily((2024, 2, 29), Difficulty::Expert);
+//! let b = Board::daily((2024, 2, 29), Difficulty::Expert);
+//! assert_eq!(a.layout_string(), b.layout_string());
+//! assert_eq!(a.visible_string(), b.visible_string());
+//! assert!(a.started_at().is_none());
+//! ```
+
+use mines_core::board::Board;
+use mines_core::stats::Difficulty;
+use mines_core::util::SeededRng;
+
+/// Returns the seed of the puzzle of the day for `date` (year, month,
+/// day) at `difficulty`. See the `daily` module documentation for how
+/// it is derived.
+///
+/// # Panics
+///
+/// This function will panic if `date` is not a real date, or if
+/// `difficulty` is `Difficulty::Custom`.
+pub fn daily_seed(date: (i32, u32, u32), difficulty: Difficulty) -> u64 {
+    let (year, month, day) = date;
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        panic!("Tried to make the puzzle of a day that doesn't exist! Date passed: {}-{}-{}",
+               year,
+               month,
+               day);
+    }
+    let level = match difficulty {
+        Difficulty::Beginner => 0,
+        Difficulty::Intermediate => 1,
+        Difficulty::Expert => 2,
+        Difficulty::Custom => panic!("Tried to make a puzzle of the day at a custom difficulty!"),
+    };
+    let packed = u64::from(year as u32) << 16 | u64::from(month) << 8 | u64::from(day);
+    SeededRng::new(packed << 2 | level).next_u64()
+}
+
+/// The board of the day. See the `daily` module documentation.
+pub trait BoardDaily {
+    /// Returns the puzzle of the day for `date` (year, month, day) at
+    /// `difficulty`, with its opening revealed. See the `daily` module
+    /// documentation.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the same conditions as
+    /// `daily_seed`.
+    fn daily(date: (i32, u32, u32), difficulty: Difficulty) -> Board;
+}
+
+impl BoardDaily for Board {
+    fn daily(date: (i32, u32, u32), difficulty: Difficulty) -> Board {
+        let seed = daily_seed(date, difficulty);
+        // Custom was ruled out by daily_seed
+        let (width, height, num_mines) = difficulty.dimensions().unwrap();
+        let mut board = Board::new(width, height, num_mines).with_seed(seed);
+        let start = board.linear_coords(((width - 1) / 2, (height - 1) / 2));
+        // The first reveal is always safe, so this can't fail
+        let _ = board.reveal_tile(start);
+        board.forget_moves();
+        board
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mines_core::tile::TileState;
+
+    #[test]
+    fn test_daily_seed() {
+        // Known values, so that other implementations can check theirs
+        assert_eq!(daily_seed((2024, 1, 1), Difficulty::Beginner),
+                   11925818955400212062);
+        assert_eq!(daily_seed((2024, 1, 1), Difficulty::Beginner),
+                   SeededRng::new(0x07e8_0101 << 2).next_u64());
+        assert_eq!(daily_seed((-1, 12, 31), Difficulty::Expert),
+                   6430105974377596979);
+
+        let seeds = [daily_seed((2024, 2, 29), Difficulty::Beginner),
+                     daily_seed((2024, 2, 29), Difficulty::Intermediate),
+                     daily_seed((2024, 3, 1), Difficulty::Beginner)];
+        assert!(seeds[0] != seeds[1] && seeds[0] != seeds[2] && seeds[1] != seeds[2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_no_such_day() {
+        daily_seed((2023, 2, 29), Difficulty::Beginner);
+    }
+
+    #[test]
+    fn test_daily() {
+        let b = Board::daily((2000, 2, 29), Difficulty::Intermediate);
+        assert_eq!((b.width, b.height, b.num_mines), (16, 16, 40));
+        assert_eq!(b.seed(), Some(daily_seed((2000, 2, 29), Difficulty::Intermediate)));
+        assert!(matches!(b.tiles[b.linear_coords((7, 7))].state, TileState::Revealed));
+        assert_eq!(b.move_count(), 0);
+    }
+}
@@ -0,0 +1,50 @@
+/*!
+# mines-net
+
+Sharing and playing **mines** `Boards` with other people: share codes
+and links, commitments to layouts, daily boards, and multiplayer and
+versus games.
+
+Most users should depend on `mines` with the `net` feature instead,
+which re-exports this crate.
+
+## Features
+
+* `serde`: Implements `Serialize` and `Deserialize` for `Commitment`.
+
+* `experimental`: Builds the experimental modules of this crate.
+*/
+
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+extern crate mines_core;
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "experimental", macro_use)]
+extern crate serde;
+extern crate sha2;
+
+pub mod code;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod commit;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod daily;
+pub mod link;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod multiplayer;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod versus;
+
+pub use code::*;
+#[cfg(feature = "experimental")]
+pub use commit::*;
+#[cfg(feature = "experimental")]
+pub use daily::*;
+pub use link::*;
+#[cfg(feature = "experimental")]
+pub use multiplayer::*;
+#[cfg(feature = "experimental")]
+pub use versus::*;
@@ -0,0 +1,351 @@
+#![warn(missing_docs)]
+//! Query strings for shareable links.
+//!
+//! `Board::to_query` writes a `Board` as the query (or fragment) part of
+//! a URL, and `Board::from_query` reads it back, so that web front-ends
+//! all make and accept the same links. Everything in the query is
+//! already URL-safe, so nothing needs escaping.
+//!
+//! What the query holds depends on the `Board`:
+//!
+//! * A `Board` that hasn't been generated yet holds its settings, in
+//!   readable keys: `w`, `h`, and `m` for the size and mine count,
+//!   `first` for the first-click rule (`opening`, `safe`, or
+//!   `unprotected`), and `seed` for the seed, if it has one. For
+//!   example, `w=30&h=16&m=99&first=opening&seed=42`. A `Board` that
+//!   isn't a plain square one also holds `topology` (`hex` or `torus`),
+//!   `kernel` for the offsets of its `Kernel`, as x,y pairs all
+//!   separated by commas, `anti` for how many of its `m` bombs are
+//!   anti-mines, and `mask` for which `Tiles` are in play. The mask has
+//!   one character for every six `Tiles`, from the alphabet of
+//!   `Board::to_code`, whose bits from the highest down are set for
+//!   each `Tile` in play.
+//! * A generated `Board` holds the whole position: `board` is the code
+//!   from `Board::to_code`, and `state`, if anything has happened yet,
+//!   has one character for every two `Tiles`, from the same alphabet.
+//!   Each character is the state of the first `Tile` times six plus the
+//!   state of the second (0 hidden, 1 flagged, 2 revealed, 3 detonated,
+//!   4 exposed, 5 wrongly flagged).
+//!
+//! A leading `?` or `#` is skipped, and keys that aren't listed here
+//! are ignored, so that front-ends can add their own.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate mines_core;
+//! # extern crate mines_net;
+//! use mines_core::Board;
+//! use mines_net::BoardLink;
+//!
+//! let b = Board::new(30, 16, 99).with_seed(42);
+//! assert_eq!(b.to_query(), "w=30&h=16&m=99&first=opening&seed=42");
+//!
+//! let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+//! b.reveal_tile(8).unwrap();
+//! let link = format!("https://example.com/play#{}", b.to_query());
+//! let shared = Board::from_query(&link[link.find('#').unwrap()..]).unwrap();
+//! assert_eq!(shared.visible_string(), "???\n111\n...\n");
+//! ```
+
+use mines_core::board::Board;
+use mines_core::builder::FirstClick;
+use mines_core::tile::TileState;
+use mines_core::topology::{Kernel, Topology};
+
+use code::{BoardCode, ParseCodeError, ALPHABET};
+
+/// Sharing a `Board` as a link. See the `link` module documentation.
+pub trait BoardLink {
+    /// Returns the query part of a link to this `Board`. See the `link`
+    /// module documentation for what it holds.
+    fn to_query(&self) -> String;
+
+    /// Creates a `Board` from a query made by `Board::to_query`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `query` is missing a key,
+    /// has a value that can't be read, or describes a `Board` that
+    /// can't exist.
+    fn from_query(query: &str) -> Result<Board, ParseCodeError>;
+}
+
+impl BoardLink for Board {
+    fn to_query(&self) -> String {
+        if self.is_generated() {
+            let mut query = format!("board={}", self.to_code());
+            if self.tiles.iter().any(|t| !matches!(t.state, TileState::Hidden)) {
+                query.push_str("&state=");
+                for pair in self.tiles.chunks(2) {
+                    let second = pair.get(1).map_or(0, |t| state_value(&t.state));
+                    query.push(ALPHABET[state_value(&pair[0].state) * 6 + second] as char);
+                }
+            }
+            return query;
+        }
+
+        let first_click = match self.config().first_click {
+            FirstClick::Opening => "opening",
+            FirstClick::Safe => "safe",
+            FirstClick::Unprotected => "unprotected",
+        };
+        let mut query = format!("w={}&h={}&m={}&first={}",
+                                self.width,
+                                self.height,
+                                self.num_mines,
+                                first_click);
+        if let Some(seed) = self.seed() {
+            query.push_str(&format!("&seed={}", seed));
+        }
+        if self.topology() != Topology::Square {
+            query.push_str(&format!("&topology={}", self.topology().name()));
+        }
+        if let Some(kernel) = self.kernel() {
+            let offsets: Vec<String> =
+                kernel.offsets().iter().map(|&(x, y)| format!("{},{}", x, y)).collect();
+            query.push_str(&format!("&kernel={}", offsets.join(",")));
+        }
+        if self.num_anti_mines() > 0 {
+            query.push_str(&format!("&anti={}", self.num_anti_mines()));
+        }
+        if let Some(mask) = self.mask() {
+            query.push_str("&mask=");
+            for six in mask.chunks(6) {
+                let value = six.iter()
+                    .enumerate()
+                    .fold(0, |value, (i, &in_play)| value | (in_play as usize) << (5 - i));
+                query.push(ALPHABET[value] as char);
+            }
+        }
+        query
+    }
+
+    fn from_query(query: &str) -> Result<Board, ParseCodeError> {
+        let query = query.trim_start_matches(['?', '#']);
+        let get = |key: &str| {
+            query.split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|&(k, _)| k == key)
+                .map(|(_, v)| v)
+        };
+
+        if let Some(code) = get("board") {
+            let mut board = Board::from_code(code)?;
+            if let Some(state) = get("state") {
+                set_states(&mut board, state)?;
+            }
+            return Ok(board);
+        }
+
+        let number = |key: &str| -> Result<u64, ParseCodeError> {
+            get(key).and_then(|v| v.parse().ok()).ok_or(ParseCodeError::Malformed)
+        };
+        let first_click = match get("first") {
+            Some("opening") | None => FirstClick::Opening,
+            Some("safe") => FirstClick::Safe,
+            Some("unprotected") => FirstClick::Unprotected,
+            Some(_) => return Err(ParseCodeError::Malformed),
+        };
+        let mut builder = Board::builder()
+            .width(number("w")? as usize)
+            .height(number("h")? as usize)
+            .mines(number("m")? as usize)
+            .first_click(first_click);
+        if get("seed").is_some() {
+            builder = builder.seed(number("seed")?);
+        }
+        if let Some(name) = get("topology") {
+            builder = builder.topology(Topology::from_name(name).ok_or(ParseCodeError::Malformed)?);
+        }
+        if let Some(offsets) = get("kernel") {
+            builder = builder.kernel(parse_kernel(offsets)?);
+        }
+        if get("anti").is_some() {
+            let anti_mines = number("anti")? as usize;
+            let mines = (number("m")? as usize)
+                .checked_sub(anti_mines)
+                .ok_or(ParseCodeError::Malformed)?;
+            builder = builder.mines(mines).anti_mines(anti_mines);
+        }
+        if let Some(mask) = get("mask") {
+            let length = (number("w")? as usize)
+                .checked_mul(number("h")? as usize)
+                .ok_or(ParseCodeError::Malformed)?;
+            builder = builder.mask(parse_mask(mask, length)?);
+        }
+        builder.build().map_err(|_| ParseCodeError::Malformed)
+    }
+}
+
+// Reads the `kernel` value of a query, like `1,2,2,1`
+fn parse_kernel(offsets: &str) -> Result<Kernel, ParseCodeError> {
+    let numbers = offsets.split(',')
+        .map(|n| n.parse::<isize>().map_err(|_| ParseCodeError::Malformed))
+        .collect::<Result<Vec<isize>, ParseCodeError>>()?;
+    if numbers.len() % 2 != 0 {
+        return Err(ParseCodeError::Malformed);
+    }
+    let mut pairs = Vec::with_capacity(numbers.len() / 2);
+    for pair in numbers.chunks(2) {
+        let offset = (pair[0], pair[1]);
+        if !Kernel::offset_in_range(offset) {
+            return Err(ParseCodeError::KernelOffset { offset });
+        }
+        pairs.push(offset);
+    }
+    Ok(Kernel::new(&pairs))
+}
+
+// Reads the `mask` value of a query for a Board of `length` Tiles,
+// checking that the bits past the last Tile are clear
+fn parse_mask(mask: &str, length: usize) -> Result<Vec<bool>, ParseCodeError> {
+    if mask.len() != length.div_ceil(6) {
+        return Err(ParseCodeError::Malformed);
+    }
+    let mut bits = Vec::with_capacity(mask.len() * 6);
+    for c in mask.chars() {
+        let value = ALPHABET.iter()
+            .position(|&a| a as char == c)
+            .ok_or(ParseCodeError::InvalidCharacter(c))?;
+        bits.extend((0..6).map(|i| value >> (5 - i) & 1 == 1));
+    }
+    if bits.drain(length..).any(|b| b) {
+        return Err(ParseCodeError::Malformed);
+    }
+    Ok(bits)
+}
+
+fn state_value(state: &TileState) -> usize {
+    match *state {
+        TileState::Hidden => 0,
+        TileState::Flagged => 1,
+        TileState::Revealed => 2,
+        TileState::Detonated => 3,
+        TileState::Exposed => 4,
+        TileState::WrongFlag => 5,
+    }
+}
+
+// Sets the state of every Tile from the `state` value of a query,
+// checking that it makes sense for the bombs
+fn set_states(board: &mut Board, state: &str) -> Result<(), ParseCodeError> {
+    if state.len() != board.tiles.len().div_ceil(2) {
+        return Err(ParseCodeError::Malformed);
+    }
+    for (pair, c) in board.tiles.chunks_mut(2).zip(state.chars()) {
+        let value = ALPHABET.iter()
+            .position(|&a| a as char == c)
+            .ok_or(ParseCodeError::InvalidCharacter(c))?;
+        if value >= 36 || (pair.len() == 1 && value % 6 != 0) {
+            return Err(ParseCodeError::Malformed);
+        }
+        for (tile, value) in pair.iter_mut().zip(&[value / 6, value % 6]) {
+            tile.state = match (*value, tile.is_bomb) {
+                (0, _) => TileState::Hidden,
+                (1, _) => TileState::Flagged,
+                (2, _) => TileState::Revealed,
+                (3, true) => TileState::Detonated,
+                (4, true) => TileState::Exposed,
+                (5, false) => TileState::WrongFlag,
+                _ => return Err(ParseCodeError::Malformed),
+            };
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let tests = [Board::new(9, 9, 10),
+                     Board::new(30, 16, 99).with_seed(u64::MAX),
+                     Board::builder()
+                         .width(5)
+                         .height(4)
+                         .mines(3)
+                         .first_click(FirstClick::Safe)
+                         .build()
+                         .unwrap(),
+                     Board::from_layout("1*1\n111\n...").unwrap(),
+                     Board::restore("1*1\n111\n...", "1!?\n11?\n...").unwrap(),
+                     Board::restore("1*1.\n1221\n.1*1", "X#??\n12?1\n.1*1").unwrap()];
+        for b in &tests {
+            let shared = Board::from_query(&format!("?{}&theme=dark", b.to_query())).unwrap();
+            assert_eq!(shared.to_query(), b.to_query());
+            assert_eq!(shared.config(), b.config());
+            assert_eq!(shared.seed(), b.seed());
+            if b.is_generated() {
+                assert_eq!(shared.layout_string(), b.layout_string());
+                assert_eq!(shared.visible_string(), b.visible_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip_shapes() {
+        let builder = Board::builder().width(7).height(5).mines(4).seed(3);
+        let mut mask = vec![true; 35];
+        mask[0] = false;
+        mask[34] = false;
+        let torus = builder.clone().topology(Topology::Torus);
+        let tests = [builder.clone().topology(Topology::Hex),
+                     builder.clone().topology(Topology::Torus),
+                     builder.clone().kernel(Kernel::knight()),
+                     torus.kernel(Kernel::new(&[(-3, 0), (0, 2)])),
+                     builder.clone().mask(mask),
+                     builder.clone().mines(3).anti_mines(1)];
+        for builder in &tests {
+            let mut b = builder.build().unwrap();
+            // Before and after the bombs are placed
+            for _ in 0..2 {
+                let shared = Board::from_query(&b.to_query()).unwrap();
+                assert_eq!(shared.to_query(), b.to_query());
+                assert_eq!(shared.config(), b.config());
+                assert_eq!(shared.kernel(), b.kernel());
+                assert_eq!(shared.mask(), b.mask());
+                assert_eq!(shared.num_anti_mines(), b.num_anti_mines());
+                b.reveal_tile(17).unwrap();
+            }
+        }
+
+        let mut mask = vec![true; 9];
+        mask[0] = false;
+        let b = Board::builder().width(3).height(3).mines(1).first_click(FirstClick::Unprotected);
+        assert_eq!(b.mask(mask).build().unwrap().to_query(),
+                   "w=3&h=3&m=1&first=unprotected&mask=f4");
+    }
+
+    #[test]
+    fn test_errors() {
+        let tests = ["",
+                     "w=9&h=9",
+                     "w=9&h=9&m=100",
+                     "w=9&h=9&m=10&first=lucky",
+                     "w=9&h=9&m=10&seed=x",
+                     // Too many Tiles
+                     "w=100000000&h=100000000&m=1",
+                     "w=18446744073709551615&h=3&m=1",
+                     "w=9&h=9&m=10&topology=round",
+                     "w=9&h=9&m=10&kernel=1,2,2",
+                     "w=9&h=9&m=10&kernel=1,x",
+                     "w=9&h=9&m=10&anti=11",
+                     // One character short, and a bit set past the end
+                     "w=9&h=9&m=10&mask=______________",
+                     "w=9&h=9&m=10&mask=_____________-",
+                     // Too short for the 9 Tiles
+                     "board=AQADAwECAA&state=AAA",
+                     // Detonating a Tile that isn't a bomb
+                     "board=AQADAwECAA&state=SAAAA",
+                     "board=AQADAwECAA&state=AAAA!"];
+        for query in &tests {
+            assert!(Board::from_query(query).is_err(), "{}", query);
+        }
+        assert!(Board::from_query("board=AQADAwECAA&state=AIAAA").is_ok());
+        assert_eq!(Board::from_query("w=9&h=9&m=10&kernel=0,16777217").err(),
+                   Some(ParseCodeError::KernelOffset { offset: (0, 16777217) }));
+    }
+}
@@ -0,0 +1,285 @@
+#![warn(missing_docs)]
+//! [Re-exported] Several players sharing one `Board`, each with their
+//! own view of it.
+//!
+//! A `SharedBoard` owns the authoritative `Board`. Every player only
+//! sees the `Tiles` they revealed themselves (or that were shared with
+//! them), and keeps their own flags, so that "split the board" co-op
+//! variants can be built where each player works in fog. The game is
+//! won once the players have revealed every safe `Tile` between them.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate mines_core;
+//! # extern crate mines_net;
+//! use mines_core::Board;
+//! use mines_net::SharedBoard;
+//!
+//! let b = Board::from_layout("1*1...\n111...\n......").unwrap();
+//! let mut game = SharedBoard::new(b, 2);
+//!
+//! let seen = game.reveal(0, 5).unwrap();
+//! assert!(seen.contains(&17));
+//! assert!(game.sees(0, 17));
+//! assert!(!game.sees(1, 17));
+//!
+//! game.reveal(1, 0).unwrap();
+//! game.reveal(1, 2).unwrap();
+//! assert!(game.is_won());
+//! ```
+
+use mines_core::board::{Board, GameState};
+use mines_core::solver::{self, VisibleBoard, VisibleCell};
+use mines_core::tile::{Tile, TileState};
+
+/// A `Board` shared between players who each see a different part of
+/// it. Players are numbered from 0.
+#[derive(Clone, Debug)]
+pub struct SharedBoard {
+    board: Board,
+    // visible[player][index]
+    visible: Vec<Vec<bool>>,
+    // flags[player][index]
+    flags: Vec<Vec<bool>>,
+    // The player who first revealed each Tile
+    revealed_by: Vec<Option<usize>>,
+}
+
+impl SharedBoard {
+    /// Creates a game for `num_players` players on `board`. Any `Tiles`
+    /// already revealed on `board` are visible to every player.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `num_players` is zero.
+    pub fn new(board: Board, num_players: usize) -> SharedBoard {
+        if num_players == 0 {
+            panic!("Tried to make a shared Board without any players!");
+        }
+        let revealed: Vec<bool> = board.tiles.iter().map(is_open).collect();
+        let length = board.tiles.len();
+        SharedBoard {
+            board,
+            visible: vec![revealed; num_players],
+            flags: vec![vec![false; length]; num_players],
+            revealed_by: vec![None; length],
+        }
+    }
+
+    /// Returns the authoritative `Board`, as no single player sees it.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns the number of players.
+    pub fn num_players(&self) -> usize {
+        self.visible.len()
+    }
+
+    /// Reveals the `Tile` at `index` for `player`, flooding out as
+    /// usual. Every `Tile` revealed by this becomes visible to `player`
+    /// (along with the `Tile` itself, even if another player had
+    /// already revealed it), and is returned in ascending order.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `player` has flagged the
+    /// `Tile`, or under the same conditions as `Board::reveal_tile`.
+    /// It is safe to discard this error; it is only for the programmer.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `player` is not in the game.
+    pub fn reveal(&mut self, player: usize, index: usize) -> Result<Vec<usize>, &'static str> {
+        self.check_player(player);
+        if self.flags[player][index] {
+            return Err("Tried to reveal a Tile that the player has flagged!");
+        }
+
+        let before: Vec<bool> = self.board.tiles.iter().map(is_open).collect();
+        self.board.reveal_tile(index)?;
+
+        let mut seen = Vec::new();
+        for (i, tile) in self.board.tiles.iter().enumerate() {
+            let newly = !before[i] && is_open(tile);
+            if newly {
+                self.revealed_by[i] = Some(player);
+            }
+            if newly || i == index {
+                if !self.visible[player][i] {
+                    seen.push(i);
+                }
+                self.visible[player][i] = true;
+                self.flags[player][i] = false;
+            }
+        }
+        Ok(seen)
+    }
+
+    /// Toggles `player`'s flag on the `Tile` at `index`. Flags are
+    /// private to each player.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `player` can already see
+    /// the `Tile`. It is safe to discard this error; it is only for
+    /// the programmer.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `player` is not in the game.
+    pub fn flag(&mut self, player: usize, index: usize) -> Result<(), &'static str> {
+        self.check_player(player);
+        if self.visible[player][index] {
+            return Err("Tried to flag a Tile that the player can already see!");
+        }
+        self.flags[player][index] = !self.flags[player][index];
+        Ok(())
+    }
+
+    /// Makes everything `from` can see visible to `to` as well.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if either player is not in the game.
+    pub fn share(&mut self, from: usize, to: usize) {
+        self.check_player(from);
+        self.check_player(to);
+        for i in 0..self.board.tiles.len() {
+            if self.visible[from][i] {
+                self.visible[to][i] = true;
+                self.flags[to][i] = false;
+            }
+        }
+    }
+
+    /// Returns whether `player` can see the `Tile` at `index`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `player` is not in the game.
+    pub fn sees(&self, player: usize, index: usize) -> bool {
+        self.check_player(player);
+        self.visible[player][index]
+    }
+
+    /// Returns whether `player` has flagged the `Tile` at `index`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `player` is not in the game.
+    pub fn is_flagged(&self, player: usize, index: usize) -> bool {
+        self.check_player(player);
+        self.flags[player][index]
+    }
+
+    /// Returns the player who revealed the `Tile` at `index`, or `None`
+    /// if no player has (including if it was revealed before the game
+    /// was shared).
+    pub fn revealed_by(&self, index: usize) -> Option<usize> {
+        self.revealed_by[index]
+    }
+
+    /// Returns the `Board` as `player` sees it, for rendering or for the
+    /// `solver`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `player` is not in the game.
+    pub fn view(&self, player: usize) -> VisibleBoard {
+        self.check_player(player);
+        let cells = self.board
+            .tiles
+            .iter()
+            .enumerate()
+            .map(|(i, tile)| {
+                if !self.visible[player][i] {
+                    if self.flags[player][i] {
+                        VisibleCell::Flagged
+                    } else {
+                        VisibleCell::Hidden
+                    }
+                } else if tile.is_bomb {
+                    VisibleCell::Mine
+                } else {
+                    solver::revealed_cell(&self.board, i)
+                }
+            })
+            .collect();
+        VisibleBoard::new(self.board.width, self.board.height, self.board.num_mines, cells)
+    }
+
+    /// Returns whether the players have revealed every safe `Tile`
+    /// between them, without any of them revealing a bomb.
+    pub fn is_won(&self) -> bool {
+        self.board.state() == GameState::Won
+    }
+
+    /// Returns whether any player has revealed a bomb.
+    pub fn is_lost(&self) -> bool {
+        self.board.state() == GameState::Lost
+    }
+
+    fn check_player(&self, player: usize) {
+        if player >= self.visible.len() {
+            panic!("Tried to act as a player that isn't in the game! Player passed: {}, Number \
+                    of players: {}",
+                   player,
+                   self.visible.len());
+        }
+    }
+}
+
+// Whether a Tile has been opened, safely or not
+fn is_open(tile: &Tile) -> bool {
+    matches!(tile.state, TileState::Revealed | TileState::Detonated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fog_per_player() {
+        let b = Board::from_layout("1*1...\n111...\n......").unwrap();
+        let mut game = SharedBoard::new(b, 2);
+
+        let seen = game.reveal(0, 5).unwrap();
+        assert_eq!(seen, (2..18).collect::<Vec<usize>>());
+        assert_eq!(game.revealed_by(9), Some(0));
+        assert!(!game.sees(1, 9));
+        assert!(!game.is_won());
+
+        // Revealing a Tile someone else already revealed only shows it
+        assert_eq!(game.reveal(1, 9).unwrap(), vec![9]);
+        assert_eq!(game.revealed_by(9), Some(0));
+
+        game.share(0, 1);
+        assert!(game.sees(1, 17));
+
+        // Flags are private and block that player's reveals
+        game.flag(1, 0).unwrap();
+        assert!(game.is_flagged(1, 0));
+        assert!(!game.is_flagged(0, 0));
+        assert!(game.reveal(1, 0).is_err());
+        assert!(game.flag(1, 9).is_err());
+
+        game.reveal(0, 0).unwrap();
+        game.reveal(0, 2).unwrap();
+        assert!(game.is_won());
+        assert!(matches!(game.view(0).cells[0], VisibleCell::Revealed(1)));
+        assert!(matches!(game.view(1).cells[0], VisibleCell::Flagged));
+    }
+
+    #[test]
+    fn test_loss() {
+        let b = Board::from_layout("1*1...\n111...\n......").unwrap();
+        let mut game = SharedBoard::new(b, 3);
+        game.reveal(2, 1).unwrap();
+        assert!(game.is_lost());
+        assert!(!game.is_won());
+        assert!(matches!(game.view(2).cells[1], VisibleCell::Mine));
+        assert!(matches!(game.view(0).cells[1], VisibleCell::Hidden));
+    }
+}
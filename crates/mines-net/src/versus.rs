@@ -0,0 +1,190 @@
+#![warn(missing_docs)]
+//! [Re-exported] Races between players on linked boards, with
+//! handicaps to even them out.
+//!
+//! In a `Versus` match every player gets their own `Game`, but all of
+//! the `Boards` come from the same seed, so everyone faces the same
+//! bombs. The bombs are placed as soon as the match is set up (as with
+//! `Board::committed`), so that they can't depend on where anyone
+//! clicks first.
+//!
+//! Each player can be given a `Handicap`:
+//!
+//! * `time_bonus` is added to their blitz countdown, if there is one,
+//!   and taken off their finishing time in `Game::adjusted_time`.
+//! * `opening` reveals the biggest opening on their `Board` before the
+//!   match starts. Their clock still starts on their first reveal.
+//! * `fewer_mines` takes that many bombs off their `Board`. Which ones
+//!   go is decided by the seed, so the rest of their `Board` still
+//!   matches everyone else's.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate mines_core;
+//! # extern crate mines_net;
+//! use std::time::Duration;
+//! use mines_core::Handicap;
+//! use mines_net::Versus;
+//!
+//! let versus = Versus::new(16, 16, 40, 2024);
+//! let games = versus.games(&[Handicap::default(),
+//!                            Handicap {
+//!                                opening: true,
+//!                                fewer_mines: 5,
+//!                                ..Default::default()
+//!                            }]);
+//! assert_eq!(games[0].board().num_mines, 40);
+//! assert_eq!(games[1].board().num_mines, 35);
+//! ```
+
+use mines_core::board::Board;
+use mines_core::builder::BuildError;
+use mines_core::game::{Game, Handicap};
+use mines_core::stats;
+use mines_core::tile::Tile;
+use mines_core::util;
+
+use commit::BoardCommit;
+
+/// A race between players on linked `Boards`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Versus {
+    /// The width of every `Board`.
+    pub width: usize,
+    /// The height of every `Board`.
+    pub height: usize,
+    /// The number of bombs on a `Board` without a handicap.
+    pub num_mines: usize,
+    /// The seed the bombs are placed from.
+    pub seed: u64,
+}
+
+impl Versus {
+    /// Sets up a match.
+    pub fn new(width: usize, height: usize, num_mines: usize, seed: u64) -> Versus {
+        Versus {
+            width,
+            height,
+            num_mines,
+            seed,
+        }
+    }
+
+    /// Returns a `Game` for each player, with their handicap applied.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the match's settings can't make a
+    /// `Board`. Use `try_games` to handle that instead.
+    pub fn games(&self, handicaps: &[Handicap]) -> Vec<Game> {
+        match self.try_games(handicaps) {
+            Ok(games) => games,
+            Err(err) => panic!("Tried to set up a match that can't be played! {}", err),
+        }
+    }
+
+    /// Returns a `Game` for each player, with their handicap applied.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `Board::committed`.
+    pub fn try_games(&self, handicaps: &[Handicap]) -> Result<Vec<Game>, BuildError> {
+        let (linked, _) = Board::committed(self.width, self.height, self.num_mines, self.seed)?;
+        Ok(handicaps.iter()
+            .map(|h| Game::new(self.board_for(&linked, h)).with_handicap(*h))
+            .collect())
+    }
+
+    // The linked Board with the parts of `handicap` that change the
+    // Board itself
+    fn board_for(&self, linked: &Board, handicap: &Handicap) -> Board {
+        let mut board = linked.clone();
+        if handicap.fewer_mines > 0 {
+            let bombs: Vec<usize> =
+                (0..board.tiles.len()).filter(|&i| board.tiles[i].is_bomb).collect();
+            let mut tiles: Vec<Tile> = board.tiles.clone();
+            let count = handicap.fewer_mines.min(bombs.len());
+            for pick in util::seeded_sample(bombs.len(), count, self.seed) {
+                tiles[bombs[pick]].is_bomb = false;
+            }
+            board = linked.with_tiles(tiles);
+        }
+        if handicap.opening {
+            if let Some(start) = biggest_opening(&board) {
+                // Revealing an empty Tile can't fail
+                let _ = board.reveal_tile(start);
+                board.forget_moves();
+            }
+        }
+        board
+    }
+}
+
+// An empty Tile that reveals the most when clicked, if there are any
+fn biggest_opening(board: &Board) -> Option<usize> {
+    stats::openings(board)
+        .iter()
+        .rev()
+        .max_by_key(|o| o.size())
+        .map(|o| o.zeros[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use mines_core::clock::{GameClock, MockClock};
+    use mines_core::game::Blitz;
+    use mines_core::tile::TileState;
+
+    #[test]
+    fn test_handicaps() {
+        let versus = Versus::new(16, 16, 40, 99);
+        let handicaps = [Handicap::default(),
+                         Handicap {
+                             fewer_mines: 10,
+                             ..Default::default()
+                         },
+                         Handicap {
+                             opening: true,
+                             ..Default::default()
+                         }];
+        let games = versus.games(&handicaps);
+        let boards: Vec<&Board> = games.iter().map(|g| g.board()).collect();
+
+        // Everyone has the same bombs, bar the ones taken away
+        assert_eq!(boards[0].layout_string(), boards[2].layout_string());
+        assert_eq!(boards[1].num_mines, 30);
+        for i in 0..boards[0].tiles.len() {
+            assert!(boards[0].tiles[i].is_bomb || !boards[1].tiles[i].is_bomb);
+        }
+
+        // The opening is revealed, but the clock hasn't started
+        assert!(boards[0].tiles.iter().all(|t| matches!(t.state, TileState::Hidden)));
+        assert!(boards[2].tiles.iter().any(|t| matches!(t.state, TileState::Revealed)));
+        assert!(boards[2].started_at().is_none());
+        assert_eq!(versus.games(&handicaps)[1].board().layout_string(), boards[1].layout_string());
+        assert!(Versus::new(2, 2, 1, 0).try_games(&handicaps).is_err());
+    }
+
+    #[test]
+    fn test_time_bonus() {
+        let clock = MockClock::new();
+        let b = Board::from_layout("1*1\n111\n...")
+            .unwrap()
+            .with_clock(GameClock::new(clock.clone()));
+        let handicap = Handicap {
+            time_bonus: Duration::from_secs(3),
+            ..Default::default()
+        };
+        let mut game = Game::new(b)
+            .with_blitz(Blitz::new(Duration::from_secs(10)))
+            .with_handicap(handicap);
+        game.reveal(8).unwrap();
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(game.time_left(), Some(Duration::from_secs(8)));
+        assert_eq!(game.adjusted_time(), Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,17 @@
+/*!
+# mines-render
+
+Drawing **mines** `Boards` as pictures. Rendering as text is part of
+`mines-core`; this crate holds the renderers that need heavier
+dependencies.
+
+Most users should depend on `mines` with the `render` feature instead,
+which re-exports this crate.
+*/
+
+extern crate image;
+extern crate mines_core;
+
+pub mod raster;
+
+pub use raster::*;
@@ -0,0 +1,338 @@
+#![warn(missing_docs)]
+//! [Re-exported] Drawing `Boards` as images.
+//!
+//! `Board::to_image` draws what the user can see in the style of the
+//! classic game, and `Board::render_png` saves it, for thumbnails,
+//! images to share, and datasets. With `RasterTheme::show_layout`, the
+//! hidden layout is drawn instead, as if every `Tile` were revealed.
+//!
+//! Numbers are drawn with a small built-in pixel font, so no font
+//! files are needed, and a `RasterTheme` chooses the size and colours.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate mines_core;
+//! # extern crate mines_render;
+//! use mines_core::Board;
+//! use mines_render::{BoardRaster, RasterTheme};
+//!
+//! let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+//! b.reveal_tile(8).unwrap();
+//! let image = b.to_image(&RasterTheme::default());
+//! assert_eq!(image.dimensions(), (3 * 17 + 1, 3 * 17 + 1));
+//!
+//! let theme = RasterTheme {
+//!     cell_size: 32,
+//!     show_layout: true,
+//!     ..Default::default()
+//! };
+//! let path = std::env::temp_dir().join("mines-raster-doc.png");
+//! b.render_png(&path, &theme).unwrap();
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use std::path::Path;
+
+use image::{ImageFormat, ImageResult, Rgb, RgbImage};
+
+use mines_core::board::Board;
+use mines_core::view::TileView;
+
+/// The size and colours `Board::to_image` draws with. See the `raster`
+/// module documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RasterTheme {
+    /// The width and height of each `Tile`, in pixels, not counting the
+    /// line around it. Defaults to 16.
+    pub cell_size: u32,
+    /// Whether to draw the hidden layout instead of what the user can
+    /// see. Defaults to `false`.
+    pub show_layout: bool,
+    /// The lines between `Tiles`.
+    pub grid: [u8; 3],
+    /// A `Tile` that hasn't been revealed.
+    pub hidden: [u8; 3],
+    /// A revealed `Tile`.
+    pub revealed: [u8; 3],
+    /// Behind the bomb the user revealed.
+    pub detonated: [u8; 3],
+    /// A `Tile` that isn't in play.
+    pub out_of_play: [u8; 3],
+    /// The numbers from 1 to 8, in order. Any other number is drawn in
+    /// the colour of 8.
+    pub numbers: [[u8; 3]; 8],
+    /// Bombs, flagpoles, and the cross on a wrong flag.
+    pub mine: [u8; 3],
+    /// Flags.
+    pub flag: [u8; 3],
+}
+
+impl Default for RasterTheme {
+    fn default() -> RasterTheme {
+        RasterTheme {
+            cell_size: 16,
+            show_layout: false,
+            grid: [128, 128, 128],
+            hidden: [189, 189, 189],
+            revealed: [224, 224, 224],
+            detonated: [255, 0, 0],
+            out_of_play: [255, 255, 255],
+            numbers: [[0, 0, 255],
+                      [0, 128, 0],
+                      [255, 0, 0],
+                      [0, 0, 128],
+                      [128, 0, 0],
+                      [0, 128, 128],
+                      [0, 0, 0],
+                      [128, 128, 128]],
+            mine: [0, 0, 0],
+            flag: [255, 0, 0],
+        }
+    }
+}
+
+// What is drawn in a Tile, over its background
+enum Mark {
+    Nothing,
+    Number(isize),
+    Mine,
+    Flag,
+    WrongFlag,
+}
+
+/// Drawing a `Board` as a picture. See the `raster` module documentation.
+pub trait BoardRaster {
+    /// Draws the `Board` with `theme`. See the `raster` module
+    /// documentation.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `theme.cell_size` is zero.
+    fn to_image(&self, theme: &RasterTheme) -> RgbImage;
+
+    /// Draws the `Board` with `theme`, as with `to_image`, and saves it
+    /// as a PNG at `path`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file can't be
+    /// written.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `theme.cell_size` is zero.
+    fn render_png<P: AsRef<Path>>(&self, path: P, theme: &RasterTheme) -> ImageResult<()>;
+}
+
+impl BoardRaster for Board {
+    fn to_image(&self, theme: &RasterTheme) -> RgbImage {
+        if theme.cell_size == 0 {
+            panic!("Tried to draw a Board with Tiles of no size!");
+        }
+        let step = theme.cell_size + 1;
+        let mut image = RgbImage::from_pixel(self.width as u32 * step + 1,
+                                             self.height as u32 * step + 1,
+                                             Rgb(theme.grid));
+        for index in 0..self.tiles.len() {
+            let (x, y) = self.cartesian_coords(index);
+            let (background, mark) = look(self, index, theme);
+            let cell = Cell {
+                left: x as u32 * step + 1,
+                top: y as u32 * step + 1,
+                size: theme.cell_size,
+            };
+            cell.fill(&mut image, background);
+            match mark {
+                Mark::Nothing => {}
+                Mark::Number(n) => {
+                    let color = theme.numbers[n.unsigned_abs().clamp(1, 8) - 1];
+                    cell.text(&mut image, &n.to_string(), color);
+                }
+                Mark::Mine => cell.mine(&mut image, theme.mine),
+                Mark::Flag => cell.flag(&mut image, theme.flag, theme.mine),
+                Mark::WrongFlag => {
+                    cell.flag(&mut image, theme.flag, theme.mine);
+                    cell.cross(&mut image, theme.mine);
+                }
+            }
+        }
+        image
+    }
+
+    fn render_png<P: AsRef<Path>>(&self, path: P, theme: &RasterTheme) -> ImageResult<()> {
+        self.to_image(theme).save_with_format(path, ImageFormat::Png)
+    }
+}
+
+// The background colour of the Tile at `index`, and what goes on it
+fn look(board: &Board, index: usize, theme: &RasterTheme) -> ([u8; 3], Mark) {
+    if theme.show_layout {
+        let tile = &board.tiles[index];
+        return if !board.is_in_play(index) {
+            (theme.out_of_play, Mark::Nothing)
+        } else if tile.is_bomb {
+            (theme.revealed, Mark::Mine)
+        } else if tile.adjacent_bombs == 0 {
+            (theme.revealed, Mark::Nothing)
+        } else {
+            (theme.revealed, Mark::Number(tile.adjacent_bombs))
+        };
+    }
+    match board.tile_view(index) {
+        TileView::Hidden => (theme.hidden, Mark::Nothing),
+        TileView::Flagged => (theme.hidden, Mark::Flag),
+        TileView::Number(0) => (theme.revealed, Mark::Nothing),
+        TileView::Number(n) => (theme.revealed, Mark::Number(n)),
+        TileView::Detonated => (theme.detonated, Mark::Mine),
+        TileView::Exposed => (theme.revealed, Mark::Mine),
+        TileView::WrongFlag => (theme.hidden, Mark::WrongFlag),
+        TileView::OutOfPlay => (theme.out_of_play, Mark::Nothing),
+    }
+}
+
+// The pixels of one Tile
+struct Cell {
+    left: u32,
+    top: u32,
+    size: u32,
+}
+
+impl Cell {
+    fn fill(&self, image: &mut RgbImage, color: [u8; 3]) {
+        self.paint(image, |_, _| true, color);
+    }
+
+    // Colours the pixels for which `inside` is true, given coordinates
+    // from 0 to 1 across the Tile
+    fn paint<F: Fn(f32, f32) -> bool>(&self, image: &mut RgbImage, inside: F, color: [u8; 3]) {
+        let size = self.size as f32;
+        for dy in 0..self.size {
+            for dx in 0..self.size {
+                if inside((dx as f32 + 0.5) / size, (dy as f32 + 0.5) / size) {
+                    image.put_pixel(self.left + dx, self.top + dy, Rgb(color));
+                }
+            }
+        }
+    }
+
+    fn mine(&self, image: &mut RgbImage, color: [u8; 3]) {
+        self.paint(image, |x, y| (x - 0.5).powi(2) + (y - 0.5).powi(2) <= 0.09, color);
+    }
+
+    // A pennant in `color` on a pole in `pole`
+    fn flag(&self, image: &mut RgbImage, color: [u8; 3], pole: [u8; 3]) {
+        let pennant = |x: f32, y: f32| (0.2..=0.5).contains(&y) && (0.3..=0.8 - y).contains(&x);
+        self.paint(image, pennant, color);
+        let stick = |x: f32, y: f32| (0.27..=0.35).contains(&x) && (0.2..=0.8).contains(&y);
+        self.paint(image, stick, pole);
+        let base = |x: f32, y: f32| (0.2..=0.6).contains(&x) && (0.75..=0.85).contains(&y);
+        self.paint(image, base, pole);
+    }
+
+    fn cross(&self, image: &mut RgbImage, color: [u8; 3]) {
+        let near = |a: f32, b: f32| (a - b).abs() <= 0.07;
+        self.paint(image,
+                   |x, y| x > 0.15 && x < 0.85 && (near(x, y) || near(x, 1.0 - y)),
+                   color);
+    }
+
+    // Writes `text` in the middle of the Tile, in the pixel font
+    fn text(&self, image: &mut RgbImage, text: &str, color: [u8; 3]) {
+        let glyphs: Vec<[u8; 5]> = text.chars().filter_map(glyph).collect();
+        if glyphs.is_empty() {
+            return;
+        }
+        // Each glyph is 3 dots wide, with a dot between glyphs
+        let dots_wide = glyphs.len() as u32 * 4 - 1;
+        let scale = (self.size * 3 / 5 / dots_wide.max(5)).max(1);
+        let left = self.left + self.size.saturating_sub(dots_wide * scale) / 2;
+        let top = self.top + self.size.saturating_sub(5 * scale) / 2;
+        for (i, rows) in glyphs.iter().enumerate() {
+            for (row, bits) in rows.iter().enumerate() {
+                for column in 0..3 {
+                    if bits & (0b100 >> column) == 0 {
+                        continue;
+                    }
+                    let x = left + (i as u32 * 4 + column) * scale;
+                    let y = top + row as u32 * scale;
+                    for dy in 0..scale {
+                        for dx in 0..scale {
+                            if x + dx < self.left + self.size && y + dy < self.top + self.size {
+                                image.put_pixel(x + dx, y + dy, Rgb(color));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// The 3x5 dots of `c`, one row per byte with the leftmost dot highest
+fn glyph(c: char) -> Option<[u8; 5]> {
+    Some(match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_image() {
+        let theme = RasterTheme::default();
+        let mut b = Board::restore("1*1\n111\n...", "???\n???\n???").unwrap();
+        b.reveal_tile(8).unwrap();
+        let step = theme.cell_size + 1;
+        // The middle of a Tile, and its top left corner
+        let middle = |index: u32| (index % 3 * step + step / 2, index / 3 * step + step / 2);
+        let corner = |index: u32| (index % 3 * step + 1, index / 3 * step + 1);
+
+        let image = b.to_image(&theme);
+        assert_eq!(image.dimensions(), (3 * step + 1, 3 * step + 1));
+        assert_eq!(*image.get_pixel(0, 0), Rgb(theme.grid));
+        let (x, y) = corner(1);
+        assert_eq!(*image.get_pixel(x, y), Rgb(theme.hidden));
+        let (x, y) = corner(8);
+        assert_eq!(*image.get_pixel(x, y), Rgb(theme.revealed));
+        let (x, y) = middle(4);
+        assert_eq!(*image.get_pixel(x, y), Rgb(theme.numbers[0]));
+
+        let layout = b.to_image(&RasterTheme {
+            show_layout: true,
+            ..theme.clone()
+        });
+        let (x, y) = middle(1);
+        assert_eq!(*layout.get_pixel(x, y), Rgb(theme.mine));
+        let (x, y) = corner(1);
+        assert_eq!(*layout.get_pixel(x, y), Rgb(theme.revealed));
+
+        b.reveal_tile(1).unwrap();
+        let lost = b.to_image(&theme);
+        let (x, y) = corner(1);
+        assert_eq!(*lost.get_pixel(x, y), Rgb(theme.detonated));
+    }
+
+    #[test]
+    fn test_render_png() {
+        let b = Board::new(9, 9, 10);
+        let path = ::std::env::temp_dir().join("mines-test-render-png.png");
+        b.render_png(&path, &RasterTheme::default()).unwrap();
+        let bytes = ::std::fs::read(&path).unwrap();
+        ::std::fs::remove_file(&path).unwrap();
+        assert!(bytes.starts_with(b"\x89PNG"));
+    }
+}
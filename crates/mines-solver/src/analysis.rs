@@ -0,0 +1,642 @@
+#![warn(missing_docs)]
+//! Analysis of `Boards` beyond what the `solver` can prove.
+//!
+//! # Mine probabilities
+//!
+//! `mine_probabilities` gives, for every `Tile`, the chance that it is
+//! a bomb given only what the user can see, assuming every layout
+//! consistent with that information is equally likely.
+//!
+//! The unknown `Tiles` next to revealed numbers (the frontier) are
+//! split into independent groups of `Tiles` that share numbers, and
+//! every consistent assignment of mines within each group is
+//! enumerated. The remaining unknown `Tiles` are only constrained by
+//! the total mine count, so the groups are weighted by how many ways
+//! the leftover mines can be placed among them.
+//!
+//! Enumeration is exponential in the size of the largest group. This is
+//! fine for anything a person would actually play, but very large
+//! frontiers with few deductions can be slow.
+//!
+//! # Openings
+//!
+//! `openings` lists the openings of a generated `Board`: the regions of
+//! empty `Tiles` that reveal each other, along with the numbers around
+//! their edges. Clicking any `Tile` of an opening reveals all of it.
+//! They are what 3BV is counted from, so they live in the `stats`
+//! module of `mines-core`, and are re-exported here.
+//!
+//! # Layout statistics
+//!
+//! `layout_stats` describes where the bombs of a generated `Board` ended
+//! up: how many of each number there are, how clustered the bombs are,
+//! and how many sit on the edge. This is useful for tuning generators,
+//! and for showing what kind of `Board` the user is about to play.
+//!
+//! # Revealed regions
+//!
+//! `revealed_regions` splits the revealed `Tiles` of a `Board` into
+//! connected regions, for effects and statistics by region. It is
+//! computed on demand, in time linear in the size of the `Board`.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::collections::hash_map::Entry;
+
+use mines_core::board::Board;
+use mines_core::tile::TileState;
+use mines_core::solver::{Solver, VisibleBoard, VisibleCell};
+
+pub use mines_core::stats::{openings, Opening};
+
+/// Returns the probability of each `Tile` of `board` being a bomb,
+/// given only what the user can see. Revealed `Tiles` have a
+/// probability of 0 (or 1, for a revealed bomb), and flags are not
+/// trusted.
+///
+/// If what the user can see is contradictory (which can only happen if
+/// the `Board` was edited by hand), every unknown `Tile` is given the
+/// same probability instead.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate mines_core;
+/// # extern crate mines_solver;
+/// use mines_core::Board;
+/// use mines_solver::analysis;
+///
+/// let b = Board::restore("1*1\n111\n...", "???\n111\n...").unwrap();
+/// let probabilities = analysis::mine_probabilities(&b);
+/// assert_eq!(&probabilities[0..3], &[0.0, 1.0, 0.0]);
+/// ```
+pub fn mine_probabilities(board: &Board) -> Vec<f64> {
+    probabilities(&VisibleBoard::from_board(board))
+}
+
+/// Returns the probability of each `Tile` being a bomb. See
+/// `mine_probabilities`.
+pub fn probabilities(view: &VisibleBoard) -> Vec<f64> {
+    // None: unknown, Some(true): mine, Some(false): safe
+    let mut known: Vec<Option<bool>> = view.cells
+        .iter()
+        .map(|cell| {
+            match *cell {
+                VisibleCell::Hidden | VisibleCell::Flagged => None,
+                VisibleCell::Revealed(_) | VisibleCell::Range(..) => Some(false),
+                VisibleCell::Mine => Some(true),
+            }
+        })
+        .collect();
+    let deductions = Solver::default().solve(view);
+    for &i in &deductions.safe {
+        known[i] = Some(false);
+    }
+    for &i in &deductions.mines {
+        known[i] = Some(true);
+    }
+
+    let mut probabilities: Vec<f64> = known.iter()
+        .map(|k| if *k == Some(true) { 1.0 } else { 0.0 })
+        .collect();
+    let unknown: Vec<usize> = (0..known.len()).filter(|&i| known[i].is_none()).collect();
+    if unknown.is_empty() {
+        return probabilities;
+    }
+
+    let found = known.iter().filter(|&&k| k == Some(true)).count();
+    let result = if found <= view.num_mines {
+        enumerate(view, &known, view.num_mines - found)
+    } else {
+        None
+    };
+
+    match result {
+        Some(frontier) => {
+            for (i, p) in frontier {
+                probabilities[i] = p;
+            }
+        }
+        None => {
+            let p = (view.num_mines.saturating_sub(found) as f64 / unknown.len() as f64).min(1.0);
+            for &i in &unknown {
+                probabilities[i] = p;
+            }
+        }
+    }
+    probabilities
+}
+
+/// Returns the unknown `Tile` of `board` least likely to be a bomb, or
+/// `None` if there are no unknown `Tiles`. Ties go to the lowest index.
+pub fn safest_tile(board: &Board) -> Option<usize> {
+    safest(&VisibleBoard::from_board(board))
+}
+
+// The unknown Tile of `view` least likely to be a bomb
+pub(crate) fn safest(view: &VisibleBoard) -> Option<usize> {
+    let probabilities = probabilities(view);
+    (0..view.cells.len())
+        .filter(|&i| view.cells[i].is_unknown())
+        .fold(None, |best: Option<usize>, i| {
+            match best {
+                Some(b) if probabilities[b] <= probabilities[i] => Some(b),
+                _ => Some(i),
+            }
+        })
+}
+
+/// Statistics about where the bombs of a `Board` are. See
+/// `layout_stats`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutStats {
+    /// How many safe `Tiles` have each number of adjacent bombs, from 0
+    /// to 8. Numbers outside that range, such as the negative numbers
+    /// anti-mines make, aren't counted.
+    pub numbers: [usize; 9],
+    /// The average fraction of each bomb's neighbours that are also
+    /// bombs. Bombs placed uniformly at random give roughly the density
+    /// of bombs on the `Board`; higher means more clustered.
+    pub clustering: f64,
+    /// The fraction of bombs on the edge of the `Board`.
+    pub edge_fraction: f64,
+}
+
+/// Returns statistics about where the bombs of `board` are, or `None`
+/// if it hasn't been generated yet. Both fractions are 0 on a `Board`
+/// without bombs.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate mines_core;
+/// # extern crate mines_solver;
+/// use mines_core::Board;
+/// use mines_solver::analysis;
+///
+/// let b = Board::from_layout("1*1\n111\n...").unwrap();
+/// let stats = analysis::layout_stats(&b).unwrap();
+/// assert_eq!(stats.numbers, [3, 5, 0, 0, 0, 0, 0, 0, 0]);
+/// assert_eq!(stats.clustering, 0.0);
+/// assert_eq!(stats.edge_fraction, 1.0);
+/// ```
+pub fn layout_stats(board: &Board) -> Option<LayoutStats> {
+    if !board.is_generated() {
+        return None;
+    }
+    let mut numbers = [0; 9];
+    let mut clustering = 0.0;
+    let mut on_edge = 0;
+    let mut bombs = 0;
+    for (i, tile) in board.tiles.iter().enumerate() {
+        if !tile.is_bomb {
+            let count = usize::try_from(tile.adjacent_bombs).ok().and_then(|n| numbers.get_mut(n));
+            if let Some(count) = count {
+                *count += 1;
+            }
+            continue;
+        }
+        bombs += 1;
+        let adjacent = board.adjacent_tile_indices(i);
+        let clustered = adjacent.iter().filter(|&&j| board.tiles[j].is_bomb).count();
+        clustering += clustered as f64 / adjacent.len() as f64;
+        // Edge Tiles are the only ones with fewer than 8 neighbours
+        if adjacent.len() < 8 {
+            on_edge += 1;
+        }
+    }
+    let per_bomb = |total: f64| if bombs == 0 { 0.0 } else { total / f64::from(bombs) };
+    Some(LayoutStats {
+        numbers,
+        clustering: per_bomb(clustering),
+        edge_fraction: per_bomb(f64::from(on_edge)),
+    })
+}
+
+/// Returns the connected regions of revealed `Tiles` on `board`, each
+/// in ascending order of index, and in order of their lowest index.
+/// Diagonal neighbours are connected, just as they are for numbers.
+/// Detonated bombs aren't part of any region.
+///
+/// This visits every `Tile` once, so it takes time linear in the size
+/// of the `Board`.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate mines_core;
+/// # extern crate mines_solver;
+/// use mines_core::Board;
+/// use mines_solver::analysis;
+///
+/// let b = Board::restore(".2*2.\n.3*3.\n.2*2.", ".2?2?\n.3???\n.2???").unwrap();
+/// assert_eq!(analysis::revealed_regions(&b), vec![vec![0, 1, 5, 6, 10, 11], vec![3]]);
+/// ```
+pub fn revealed_regions(board: &Board) -> Vec<Vec<usize>> {
+    let revealed = |i: usize| {
+        !board.tiles[i].is_bomb && matches!(board.tiles[i].state, TileState::Revealed)
+    };
+
+    let mut regions = Vec::new();
+    let mut seen = vec![false; board.tiles.len()];
+    for start in 0..board.tiles.len() {
+        if seen[start] || !revealed(start) {
+            continue;
+        }
+        seen[start] = true;
+        let mut region = vec![start];
+        let mut todo = vec![start];
+        while let Some(index) = todo.pop() {
+            for i in board.adjacent_tile_indices(index) {
+                if !seen[i] && revealed(i) {
+                    seen[i] = true;
+                    region.push(i);
+                    todo.push(i);
+                }
+            }
+        }
+        region.sort_unstable();
+        regions.push(region);
+    }
+    regions
+}
+
+// The consistent mine assignments of one group of frontier tiles
+struct Group {
+    cells: Vec<usize>,
+    // solutions[k]: how many assignments place k mines in the group
+    solutions: Vec<f64>,
+    // cell_mines[k][j]: how many of those have a mine on cells[j]
+    cell_mines: Vec<Vec<f64>>,
+}
+
+// Computes the probability of every unknown tile, given that `remaining`
+// mines are left among them. Returns None if no assignment works.
+fn enumerate(view: &VisibleBoard,
+             known: &[Option<bool>],
+             remaining: usize)
+             -> Option<Vec<(usize, f64)>> {
+    // Every revealed number gives a constraint on its unknown
+    // neighbours: they hold between `min` and `max` mines
+    let mut constraints: Vec<(Vec<usize>, usize, usize)> = Vec::new();
+    for (i, cell) in view.cells.iter().enumerate() {
+        if let Some((min, max)) = cell.bounds() {
+            let mut cells = Vec::new();
+            let mut mines = 0;
+            for &n in view.neighbors(i) {
+                match known[n] {
+                    Some(true) => mines += 1,
+                    Some(false) => {}
+                    None => cells.push(n),
+                }
+            }
+            if mines > max {
+                return None;
+            }
+            if !cells.is_empty() {
+                constraints.push((cells, min.saturating_sub(mines), max - mines));
+            }
+        }
+    }
+
+    let groups = solve_groups(group_constraints(&constraints), &constraints);
+    if groups.iter().any(|g| g.solutions.iter().all(|&s| s == 0.0)) {
+        return None;
+    }
+
+    let in_frontier: Vec<bool> = {
+        let mut v = vec![false; known.len()];
+        for g in &groups {
+            for &c in &g.cells {
+                v[c] = true;
+            }
+        }
+        v
+    };
+    let interior: Vec<usize> = (0..known.len())
+        .filter(|&i| known[i].is_none() && !in_frontier[i])
+        .collect();
+
+    // weight[k]: the (scaled) number of ways to place the leftover mines
+    // among the interior, when the frontier holds k of them
+    let max_frontier: usize = groups.iter().map(|g| g.cells.len()).sum();
+    let ln_weights: Vec<Option<f64>> = (0..max_frontier + 1)
+        .map(|k| {
+            if k <= remaining && remaining - k <= interior.len() {
+                Some(ln_choose(interior.len(), remaining - k))
+            } else {
+                None
+            }
+        })
+        .collect();
+    let scale = ln_weights.iter().filter_map(|&w| w).fold(f64::MIN, f64::max);
+    if scale == f64::MIN {
+        return None;
+    }
+    let weight: Vec<f64> = ln_weights.iter()
+        .map(|w| w.map_or(0.0, |w| (w - scale).exp()))
+        .collect();
+
+    // prefix[c]: how many ways groups [0, c) can hold each number of
+    // mines; suffix[c] likewise for groups [c, n)
+    let mut prefix = vec![vec![1.0]];
+    for g in &groups {
+        let next = convolve(prefix.last().unwrap(), &g.solutions);
+        prefix.push(next);
+    }
+    let mut suffix = vec![vec![1.0]];
+    for g in groups.iter().rev() {
+        let next = convolve(suffix.last().unwrap(), &g.solutions);
+        suffix.push(next);
+    }
+    suffix.reverse();
+
+    let all = &prefix[groups.len()];
+    let total: f64 = all.iter().enumerate().map(|(k, &s)| s * weight[k]).sum();
+    if total <= 0.0 || !total.is_finite() {
+        return None;
+    }
+
+    let mut result = Vec::new();
+    for (c, g) in groups.iter().enumerate() {
+        let others = convolve(&prefix[c], &suffix[c + 1]);
+        // For each number of mines in this group, the weight of every
+        // way the rest of the board could go
+        let rest: Vec<f64> = (0..g.solutions.len())
+            .map(|k| others.iter().enumerate().map(|(o, &s)| s * weight[k + o]).sum())
+            .collect();
+        for (j, &cell) in g.cells.iter().enumerate() {
+            let p: f64 = (0..g.solutions.len()).map(|k| g.cell_mines[k][j] * rest[k]).sum();
+            result.push((cell, p / total));
+        }
+    }
+
+    if !interior.is_empty() {
+        let expected: f64 = all.iter()
+            .enumerate()
+            .filter(|&(k, _)| k <= remaining)
+            .map(|(k, &s)| s * weight[k] * (remaining - k) as f64)
+            .sum();
+        let p = expected / total / interior.len() as f64;
+        for &i in &interior {
+            result.push((i, p));
+        }
+    }
+
+    Some(result)
+}
+
+// Solves each group on its own, since they share no cells
+#[cfg(not(feature = "rayon"))]
+fn solve_groups(groups: Vec<Vec<usize>>,
+                constraints: &[(Vec<usize>, usize, usize)])
+                -> Vec<Group> {
+    groups.iter().map(|group| solve_group(group, constraints)).collect()
+}
+
+// Solves each group on its own, since they share no cells, on every
+// core
+#[cfg(feature = "rayon")]
+fn solve_groups(groups: Vec<Vec<usize>>,
+                constraints: &[(Vec<usize>, usize, usize)])
+                -> Vec<Group> {
+    use rayon::prelude::*;
+
+    groups.par_iter().map(|group| solve_group(group, constraints)).collect()
+}
+
+// Splits the constraints into groups that share cells, returning the
+// indices of the constraints in each group
+fn group_constraints(constraints: &[(Vec<usize>, usize, usize)]) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..constraints.len()).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+
+    let mut owner: HashMap<usize, usize> = HashMap::new();
+    for (i, (cells, _, _)) in constraints.iter().enumerate() {
+        for &cell in cells {
+            match owner.get(&cell).cloned() {
+                Some(other) => {
+                    let (a, b) = (find(&mut parent, i), find(&mut parent, other));
+                    parent[a] = b;
+                }
+                None => {
+                    owner.insert(cell, i);
+                }
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..constraints.len() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+    let mut groups: Vec<Vec<usize>> = groups.into_values().collect();
+    groups.sort();
+    groups
+}
+
+fn solve_group(group: &[usize], constraints: &[(Vec<usize>, usize, usize)]) -> Group {
+    // Order the cells constraint by constraint, so that constraints
+    // fill up (and can be checked) as early as possible
+    let mut cells: Vec<usize> = Vec::new();
+    let mut local: HashMap<usize, usize> = HashMap::new();
+    for &c in group {
+        for &cell in &constraints[c].0 {
+            if let Entry::Vacant(e) = local.entry(cell) {
+                e.insert(cells.len());
+                cells.push(cell);
+            }
+        }
+    }
+
+    // For each local cell, the constraints (by position in `group`)
+    // that include it
+    let mut touching: Vec<Vec<usize>> = vec![Vec::new(); cells.len()];
+    for (g, &c) in group.iter().enumerate() {
+        for cell in &constraints[c].0 {
+            touching[local[cell]].push(g);
+        }
+    }
+
+    let mut search = Search {
+        bounds: group.iter().map(|&c| (constraints[c].1, constraints[c].2)).collect(),
+        mines: vec![0; group.len()],
+        unassigned: group.iter().map(|&c| constraints[c].0.len()).collect(),
+        touching,
+        assignment: vec![false; cells.len()],
+        solutions: vec![0.0; cells.len() + 1],
+        cell_mines: vec![vec![0.0; cells.len()]; cells.len() + 1],
+    };
+    search.run(0);
+
+    Group {
+        cells,
+        solutions: search.solutions,
+        cell_mines: search.cell_mines,
+    }
+}
+
+// Backtracking search over the mine assignments of one group
+struct Search {
+    bounds: Vec<(usize, usize)>,
+    mines: Vec<usize>,
+    unassigned: Vec<usize>,
+    touching: Vec<Vec<usize>>,
+    assignment: Vec<bool>,
+    solutions: Vec<f64>,
+    cell_mines: Vec<Vec<f64>>,
+}
+
+impl Search {
+    fn run(&mut self, cell: usize) {
+        if cell == self.assignment.len() {
+            let k = self.assignment.iter().filter(|&&m| m).count();
+            self.solutions[k] += 1.0;
+            for (j, &m) in self.assignment.iter().enumerate() {
+                if m {
+                    self.cell_mines[k][j] += 1.0;
+                }
+            }
+            return;
+        }
+
+        for &mine in &[false, true] {
+            self.assignment[cell] = mine;
+            let mut feasible = true;
+            for &c in &self.touching[cell] {
+                self.unassigned[c] -= 1;
+                if mine {
+                    self.mines[c] += 1;
+                }
+                let (min, max) = self.bounds[c];
+                if self.mines[c] > max || self.mines[c] + self.unassigned[c] < min {
+                    feasible = false;
+                }
+            }
+            if feasible {
+                self.run(cell + 1);
+            }
+            for &c in &self.touching[cell] {
+                self.unassigned[c] += 1;
+                if mine {
+                    self.mines[c] -= 1;
+                }
+            }
+        }
+        self.assignment[cell] = false;
+    }
+}
+
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut out = vec![0.0; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        if x == 0.0 {
+            continue;
+        }
+        for (j, &y) in b.iter().enumerate() {
+            out[i + j] += x * y;
+        }
+    }
+    out
+}
+
+fn ln_choose(n: usize, r: usize) -> f64 {
+    let r = r.min(n - r);
+    (1..r + 1).map(|i| ((n - r + i) as f64).ln() - (i as f64).ln()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_uniform_without_information() {
+        let view = VisibleBoard::new(3, 3, 3, vec![VisibleCell::Hidden; 9]);
+        for p in probabilities(&view) {
+            assert_close(p, 1.0 / 3.0);
+        }
+
+        // One number, one mine somewhere around it
+        let mut cells = vec![VisibleCell::Hidden; 9];
+        cells[4] = VisibleCell::Revealed(1);
+        let view = VisibleBoard::new(3, 3, 1, cells);
+        let probabilities = probabilities(&view);
+        assert_close(probabilities[4], 0.0);
+        assert_close(probabilities[0], 1.0 / 8.0);
+    }
+
+    #[test]
+    fn test_frontier_and_interior() {
+        let mut cells = vec![VisibleCell::Revealed(0); 12];
+        for &i in &[2, 3, 6, 7, 10, 11] {
+            cells[i] = VisibleCell::Hidden;
+        }
+        cells[1] = VisibleCell::Revealed(1);
+        cells[5] = VisibleCell::Revealed(1);
+        cells[9] = VisibleCell::Revealed(0);
+        // Columns 2 and 3 of a 4x3 grid are hidden; (1, 0) and (1, 1)
+        // are 1s, and (1, 2) is a 0, so (2, 1) and (2, 2) are safe...
+        let view = VisibleBoard::new(4, 3, 2, cells);
+        let probabilities = probabilities(&view);
+        assert_close(probabilities[6], 0.0);
+        assert_close(probabilities[10], 0.0);
+        // ...which leaves (2, 0) as the mine for both 1s
+        assert_close(probabilities[2], 1.0);
+        // ...and the other mine equally likely in the last column
+        assert_close(probabilities[3], 1.0 / 3.0);
+        assert_close(probabilities[7], 1.0 / 3.0);
+        assert_close(probabilities[11], 1.0 / 3.0);
+    }
+
+    #[test]
+    fn test_probabilities_add_up() {
+        for seed in 0..10 {
+            let mut b = Board::new(9, 9, 10).with_seed(seed);
+            b.reveal_tile(40).unwrap();
+            let probabilities = mine_probabilities(&b);
+            let total: f64 = probabilities.iter().sum();
+            assert_close(total, 10.0);
+
+            let safest = safest_tile(&b).unwrap();
+            assert!(matches!(b.tiles[safest].state, TileState::Hidden));
+        }
+    }
+
+    #[test]
+    fn test_layout_stats() {
+        let b = Board::from_layout("**1.\n2221\n..1*").unwrap();
+        let stats = layout_stats(&b).unwrap();
+        assert_eq!(stats.numbers, [3, 3, 3, 0, 0, 0, 0, 0, 0]);
+        // The top two neighbour each other, from 3 and 5 neighbours
+        assert_close(stats.clustering, (1.0 / 3.0 + 1.0 / 5.0) / 3.0);
+        assert_close(stats.edge_fraction, 1.0);
+
+        let b = Board::from_layout("111..\n1*1..\n111..").unwrap();
+        assert_close(layout_stats(&b).unwrap().edge_fraction, 0.0);
+        assert!(layout_stats(&Board::new(9, 9, 10)).is_none());
+    }
+
+    #[test]
+    fn test_revealed_regions() {
+        let mut b = Board::from_layout(".2*2.\n.3*3.\n.2*2.").unwrap();
+        assert!(revealed_regions(&b).is_empty());
+        b.reveal_tile(0).unwrap();
+        b.reveal_tile(4).unwrap();
+        assert_eq!(revealed_regions(&b),
+                   vec![vec![0, 1, 5, 6, 10, 11], vec![3, 4, 8, 9, 13, 14]]);
+        b.reveal_tile(2).unwrap();
+        assert_eq!(revealed_regions(&b).len(), 2);
+    }
+}
@@ -0,0 +1,199 @@
+#![warn(missing_docs)]
+//! Standard boards for timing the crate, and a report of the results.
+//!
+//! `boards` lists a few seeded `Boards` that stress the slow paths:
+//! a huge opening, a dense expert-sized board, and a board packed with
+//! as many bombs as the first click allows. `run` times generating,
+//! revealing, and solving one of them, and `report` lays the results
+//! out as a table that can be compared between machines and versions.
+//! `mines bench` runs them all from the command line.
+//!
+//! Generation is timed without the seed, since that is how most games
+//! are made, so it doesn't place the same bombs every round. The reveal
+//! and the solve are timed on the seeded layout, so they do the same
+//! work everywhere.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_solver::bench::{self, BenchBoard};
+//!
+//! let small = BenchBoard {
+//!     name: "beginner",
+//!     width: 9,
+//!     height: 9,
+//!     num_mines: 10,
+//!     seed: 1,
+//! };
+//! let result = bench::run(&small, 3);
+//! println!("{}", bench::report(&[result]));
+//! ```
+
+use std::time::{Duration, Instant};
+
+use mines_core::board::Board;
+use mines_core::solver;
+
+/// A seeded `Board` to time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BenchBoard {
+    /// What to call it in the report.
+    pub name: &'static str,
+    /// The width of the `Board`.
+    pub width: usize,
+    /// The height of the `Board`.
+    pub height: usize,
+    /// The number of bombs on the `Board`.
+    pub num_mines: usize,
+    /// The seed that places the bombs.
+    pub seed: u64,
+}
+
+impl BenchBoard {
+    // The Tile clicked first: the middle of the Board
+    fn start(&self) -> usize {
+        (self.height / 2) * self.width + self.width / 2
+    }
+}
+
+/// Returns the standard benchmark boards.
+pub fn boards() -> Vec<BenchBoard> {
+    vec![BenchBoard {
+             // Opens up almost everything in one reveal
+             name: "huge-cascade",
+             width: 1000,
+             height: 1000,
+             num_mines: 10_000,
+             seed: 1,
+         },
+         BenchBoard {
+             name: "dense-expert",
+             width: 30,
+             height: 16,
+             num_mines: 170,
+             seed: 2,
+         },
+         BenchBoard {
+             // As many bombs as fit around an opening
+             name: "packed-generator",
+             width: 30,
+             height: 16,
+             num_mines: 470,
+             seed: 3,
+         }]
+}
+
+/// How long each step took on a `BenchBoard`, on average.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BenchResult {
+    /// The `BenchBoard` that was timed.
+    pub board: BenchBoard,
+    /// How many times each step was timed.
+    pub rounds: usize,
+    /// Placing the bombs.
+    pub generate: Duration,
+    /// The first reveal, including the flood fill.
+    pub reveal: Duration,
+    /// Solving what the first reveal showed.
+    pub solve: Duration,
+}
+
+/// Times each step on `bench`, `rounds` times (at least once), and
+/// returns the averages.
+pub fn run(bench: &BenchBoard, rounds: usize) -> BenchResult {
+    let rounds = rounds.max(1);
+    let start = bench.start();
+    let (mut generate, mut reveal, mut solve) = (Duration::ZERO, Duration::ZERO, Duration::ZERO);
+    for _ in 0..rounds {
+        let mut board = Board::new(bench.width, bench.height, bench.num_mines);
+        generate += time(|| board.generate(start));
+
+        let mut board =
+            Board::new(bench.width, bench.height, bench.num_mines).with_seed(bench.seed);
+        board.generate(start);
+        reveal += time(|| {
+            // The first reveal is always safe
+            let _ = board.reveal_tile(start);
+        });
+        solve += time(|| {
+            solver::solve(&board);
+        });
+    }
+    BenchResult {
+        board: *bench,
+        rounds,
+        generate: generate / rounds as u32,
+        reveal: reveal / rounds as u32,
+        solve: solve / rounds as u32,
+    }
+}
+
+fn time<F: FnOnce()>(f: F) -> Duration {
+    let started = Instant::now();
+    f();
+    started.elapsed()
+}
+
+/// Lays out `results` as a table, one row per `BenchBoard`, with every
+/// time in milliseconds.
+pub fn report(results: &[BenchResult]) -> String {
+    let mut s = format!("{:<18} {:>11} {:>7} {:>12} {:>12} {:>12}\n",
+                        "board",
+                        "size",
+                        "mines",
+                        "generate ms",
+                        "reveal ms",
+                        "solve ms");
+    for result in results {
+        let b = &result.board;
+        s.push_str(&format!("{:<18} {:>11} {:>7} {:>12.3} {:>12.3} {:>12.3}\n",
+                            b.name,
+                            format!("{}x{}", b.width, b.height),
+                            b.num_mines,
+                            millis(result.generate),
+                            millis(result.reveal),
+                            millis(result.solve)));
+    }
+    s
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boards_are_valid() {
+        for b in boards() {
+            let built = Board::builder().width(b.width).height(b.height).mines(b.num_mines).build();
+            assert!(built.is_ok(), "{}", b.name);
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let result = BenchResult {
+            board: BenchBoard {
+                name: "tiny",
+                width: 9,
+                height: 9,
+                num_mines: 10,
+                seed: 0,
+            },
+            rounds: 1,
+            generate: Duration::from_micros(1500),
+            reveal: Duration::from_millis(2),
+            solve: Duration::from_secs(1),
+        };
+        let report = report(&[result]);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("board"));
+        assert_eq!(lines[1].split_whitespace().collect::<Vec<_>>(),
+                   vec!["tiny", "9x9", "10", "1.500", "2.000", "1000.000"]);
+        assert_eq!(run(&result.board, 2).rounds, 2);
+    }
+}
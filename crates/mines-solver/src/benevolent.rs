@@ -0,0 +1,162 @@
+#![warn(missing_docs)]
+//! [Re-exported] The benevolent variant, where a forced guess never
+//! loses.
+//!
+//! A `BenevolentBoard` is the opposite of a `KaboomBoard`. When a hidden
+//! `Tile` is revealed while the `solver` can't prove any hidden `Tile`
+//! safe, so the user had no choice but to guess, it looks for a layout
+//! that agrees with everything the user can see and has no bomb on that
+//! `Tile`. If there is one, the bombs are quietly moved there, and the
+//! reveal succeeds. A guess made while there was a safe move, or on a
+//! `Tile` that is provably a bomb, gets no help. The first reveal is
+//! always safe anyway.
+//!
+//! As with Kaboom, moving the bombs never changes anything the user has
+//! seen, flags are not trusted, and the search gives up after
+//! `SEARCH_LIMIT` steps.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate mines_core;
+//! # extern crate mines_solver;
+//! use mines_core::{Board, GameState};
+//! use mines_solver::BenevolentBoard;
+//!
+//! // Nothing has been revealed, so the user has to guess
+//! let b = Board::restore("1*1\n111\n...", "???\n???\n???").unwrap();
+//! let mut game = BenevolentBoard::new(b);
+//! game.reveal_tile(1).unwrap();
+//! assert_ne!(game.board().state(), GameState::Lost);
+//! assert!(!game.board().tiles[1].is_bomb);
+//! ```
+
+use mines_core::board::Board;
+use mines_core::board::FlagError;
+use mines_core::solver::{Solver, VisibleBoard};
+use mines_core::tile::TileState;
+
+use kaboom;
+
+/// A `Board` whose bombs move out of the way of forced guesses. See the
+/// `benevolent` module documentation.
+#[derive(Clone, Debug)]
+pub struct BenevolentBoard {
+    board: Board,
+}
+
+impl BenevolentBoard {
+    /// Plays the benevolent variant on `board`, which may already be
+    /// part way through a game.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `board` has anti-mines, which the
+    /// search doesn't understand.
+    pub fn new(board: Board) -> BenevolentBoard {
+        if board.num_anti_mines() > 0 {
+            panic!("Tried to play the benevolent variant on a Board with anti-mines!");
+        }
+        BenevolentBoard { board }
+    }
+
+    /// Returns the `Board`, with its bombs where they are now.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns the `Board`, ending the variant.
+    pub fn into_board(self) -> Board {
+        self.board
+    }
+
+    /// Reveals the `Tile` at `index`, first moving its bomb away if the
+    /// user had to guess and what they can see allows it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `Board::reveal_tile`. It is safe to discard this error; it is
+    /// only for the programmer.
+    pub fn reveal_tile(&mut self, index: usize) -> Result<(), &'static str> {
+        self.move_bomb_from(index);
+        self.board.reveal_tile(index)
+    }
+
+    /// Toggles a flag on the `Tile` at `index`, as `Board::flag_tile`
+    /// does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `Board::flag_tile`.
+    pub fn flag_tile(&mut self, index: usize) -> Result<(), FlagError> {
+        self.board.flag_tile(index)
+    }
+
+    /// Chords the revealed number at `index`, as `Board::chord_tile`
+    /// does. Chording trusts the user's flags, so it is never a forced
+    /// guess and gets no help.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `Board::chord_tile`. It is safe to discard this error; it is
+    /// only for the programmer.
+    pub fn chord_tile(&mut self, index: usize) -> Result<(), &'static str> {
+        self.board.chord_tile(index)
+    }
+
+    // Moves the bombs so that there isn't one at `index`, if the Tile is
+    // a hidden bomb, there was no safe move, and what the user can see
+    // allows it
+    fn move_bomb_from(&mut self, index: usize) {
+        let tile = &self.board.tiles[index];
+        if !self.board.is_generated() || !matches!(tile.state, TileState::Hidden) || !tile.is_bomb
+        {
+            return;
+        }
+        let view = VisibleBoard::from_board(&self.board);
+        let safe = Solver::default().solve(&view).safe;
+        if safe.iter().any(|&i| matches!(self.board.tiles[i].state, TileState::Hidden)) {
+            return;
+        }
+        let current: Vec<bool> = self.board.tiles.iter().map(|t| t.is_bomb).collect();
+        if let Some(layout) = kaboom::layout_with(&view, &current, index, false) {
+            // Nothing the user can see changes, so this isn't a move
+            for (tile, is_bomb) in self.board.tiles.iter_mut().zip(layout) {
+                tile.is_bomb = is_bomb;
+            }
+            self.board.count_adjacent_bombs();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mines_core::board::GameState;
+
+    #[test]
+    fn test_forced_guesses_win() {
+        // With nothing revealed, every reveal is a guess
+        for guess in 0..9 {
+            let b = Board::restore("1*1\n111\n...", "???\n???\n???").unwrap();
+            let mut game = BenevolentBoard::new(b);
+            game.reveal_tile(guess).unwrap();
+            assert!(!matches!(game.board().tiles[guess].state, TileState::Detonated));
+            assert_ne!(game.board().state(), GameState::Lost);
+            assert_eq!(game.board().tiles.iter().filter(|t| t.is_bomb).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_needless_guesses_lose() {
+        // The Tiles away from the 1 are safe, so guessing next to it is
+        // the user's own fault
+        let b = Board::restore("1*1\n111\n...", "1??\n???\n???").unwrap();
+        let mut game = BenevolentBoard::new(b);
+        game.reveal_tile(1).unwrap();
+        assert_eq!(game.board().state(), GameState::Lost);
+    }
+}
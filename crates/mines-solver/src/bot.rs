@@ -0,0 +1,226 @@
+#![warn(missing_docs)]
+//! Playing `Boards` automatically, with pluggable strategies.
+//!
+//! A `Strategy` looks at what a player could see (a `VisibleBoard`) and
+//! chooses the next `Action`, or gives up. `play` applies a strategy to a `Board`
+//! until the game is over, so that strategies can be compared without
+//! each one rebuilding the game loop.
+//!
+//! Two strategies are built in:
+//!
+//! * `Naive` reveals a random hidden `Tile` every time.
+//! * `Logical` reveals whatever the `solver` can prove safe, and only
+//!   guesses when it is stuck, picking the hidden `Tile` least likely
+//!   to be a bomb (see `analysis::safest_tile`).
+//!
+//! Neither reveals a flagged `Tile`. `Logical` takes the flag off a
+//! `Tile` the solver proves safe, and both take flags off once nothing
+//! else is left hidden, since the flags must then be wrong.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate mines_core;
+//! # extern crate mines_solver;
+//! use mines_core::{Board, GameState};
+//! use mines_solver::bot::{self, Logical};
+//!
+//! // A Board that never needs a guess is always won
+//! let mut b = Board::new_no_guess(9, 9, 10, 40).unwrap();
+//! let outcome = bot::play(&mut b, &mut Logical::new());
+//! assert_eq!(outcome.state, GameState::Won);
+//! ```
+
+use mines_core::action::Action;
+use mines_core::board::{Board, GameState};
+use mines_core::solver::{Solver, VisibleBoard, VisibleCell};
+use mines_core::util::SeededRng;
+
+use analysis;
+
+/// A way of choosing moves from what a player can see.
+pub trait Strategy {
+    /// Returns the next `Action` to take on the `Board` that `view`
+    /// shows, or `None` to give up. The game on it is not over yet.
+    fn choose_action(&mut self, view: &VisibleBoard) -> Option<Action>;
+}
+
+/// Reveals a random hidden `Tile` every time.
+#[derive(Clone, Debug)]
+pub struct Naive {
+    rng: SeededRng,
+}
+
+impl Naive {
+    /// Creates the strategy, choosing its `Tiles` deterministically from
+    /// `seed`.
+    pub fn new(seed: u64) -> Naive {
+        Naive { rng: SeededRng::new(seed) }
+    }
+}
+
+impl Strategy for Naive {
+    fn choose_action(&mut self, view: &VisibleBoard) -> Option<Action> {
+        let hidden = cells(view, VisibleCell::Hidden);
+        if !hidden.is_empty() {
+            return Some(Action::Reveal(hidden[self.rng.below(hidden.len())]));
+        }
+        let flagged = cells(view, VisibleCell::Flagged);
+        if !flagged.is_empty() {
+            return Some(Action::Flag(flagged[self.rng.below(flagged.len())]));
+        }
+        None
+    }
+}
+
+/// Reveals what the `solver` proves safe, and otherwise guesses the
+/// hidden `Tile` least likely to be a bomb.
+#[derive(Clone, Debug, Default)]
+pub struct Logical {
+    /// The solver used to find safe `Tiles`.
+    pub solver: Solver,
+}
+
+impl Logical {
+    /// Creates the strategy with the default `Solver`.
+    pub fn new() -> Logical {
+        Logical::default()
+    }
+}
+
+impl Strategy for Logical {
+    fn choose_action(&mut self, view: &VisibleBoard) -> Option<Action> {
+        if let Some(&i) = self.solver.solve(view).safe.first() {
+            return Some(match view.cells[i] {
+                VisibleCell::Flagged => Action::Flag(i),
+                _ => Action::Reveal(i),
+            });
+        }
+        let probabilities = analysis::probabilities(view);
+        let safest = |indices: Vec<usize>| {
+            indices.into_iter().fold(None, |best: Option<usize>, i| {
+                match best {
+                    Some(b) if probabilities[b] <= probabilities[i] => Some(b),
+                    _ => Some(i),
+                }
+            })
+        };
+        safest(cells(view, VisibleCell::Hidden))
+            .map(Action::Reveal)
+            .or_else(|| safest(cells(view, VisibleCell::Flagged)).map(Action::Flag))
+    }
+}
+
+// The indices of the cells of `view` that are `cell`
+fn cells(view: &VisibleBoard, cell: VisibleCell) -> Vec<usize> {
+    (0..view.cells.len()).filter(|&i| view.cells[i] == cell).collect()
+}
+
+/// How a game played by `play` went.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    /// How the game ended. This is `Playing` if the strategy forfeited.
+    pub state: GameState,
+    /// How many `Actions` the strategy took.
+    pub actions: usize,
+}
+
+/// Plays the game on `board` with `strategy` until it is won or lost.
+///
+/// A strategy that gives up, chooses an `Action` that fails, or takes
+/// more `Actions` than there are `Tiles` twice over, forfeits: `play`
+/// stops there, with the game still in progress.
+pub fn play<S: Strategy + ?Sized>(board: &mut Board, strategy: &mut S) -> Outcome {
+    let limit = board.tiles.len() * 2;
+    let mut actions = 0;
+    loop {
+        let state = board.state();
+        if state == GameState::Won || state == GameState::Lost {
+            return Outcome { state, actions };
+        }
+        if actions == limit {
+            break;
+        }
+        let action = match strategy.choose_action(&VisibleBoard::from_board(board)) {
+            Some(action) => action,
+            None => break,
+        };
+        actions += 1;
+        if board.apply(action).is_err() {
+            break;
+        }
+    }
+    Outcome {
+        state: GameState::Playing,
+        actions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_play() {
+        for seed in 0..10 {
+            let mut b = Board::new(9, 9, 10).with_seed(seed);
+            let outcome = play(&mut b, &mut Naive::new(seed));
+            assert!(outcome.state == GameState::Won || outcome.state == GameState::Lost);
+            assert_eq!(outcome.actions, b.move_count());
+
+            let mut b = Board::new(9, 9, 10).with_seed(seed);
+            let outcome = play(&mut b, &mut Logical::new());
+            assert_eq!(outcome.state, b.state());
+            assert!(outcome.state != GameState::Playing);
+        }
+    }
+
+    #[test]
+    fn test_forfeit() {
+        struct Stubborn;
+
+        impl Strategy for Stubborn {
+            fn choose_action(&mut self, _: &VisibleBoard) -> Option<Action> {
+                Some(Action::Chord(0))
+            }
+        }
+
+        struct Quitter;
+
+        impl Strategy for Quitter {
+            fn choose_action(&mut self, _: &VisibleBoard) -> Option<Action> {
+                None
+            }
+        }
+
+        let mut b = Board::from_layout("1*1\n111\n...").unwrap();
+        let outcome = play(&mut b, &mut Stubborn);
+        assert_eq!(outcome,
+                   Outcome {
+                       state: GameState::Playing,
+                       actions: 1,
+                   });
+        let outcome = play(&mut b, &mut Quitter);
+        assert_eq!(outcome,
+                   Outcome {
+                       state: GameState::Playing,
+                       actions: 0,
+                   });
+    }
+
+    #[test]
+    fn test_play_with_flags() {
+        // The flag on the left is wrong, and the one on the right isn't
+        let start = Board::restore("1*1\n111\n...", "!!?\n111\n...").unwrap();
+
+        let mut b = start.clone();
+        let outcome = play(&mut b, &mut Logical::new());
+        assert_eq!(outcome.state, GameState::Won);
+
+        for seed in 0..20 {
+            let mut b = start.clone();
+            let outcome = play(&mut b, &mut Naive::new(seed));
+            assert!(outcome.state != GameState::Playing, "seed {}", seed);
+        }
+    }
+}
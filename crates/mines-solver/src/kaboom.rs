@@ -0,0 +1,376 @@
+#![warn(missing_docs)]
+//! [Re-exported] The Kaboom variant, where every guess that could be a
+//! bomb is one.
+//!
+//! A `KaboomBoard` doesn't keep its bombs where they were first placed.
+//! Whenever a hidden `Tile` is revealed, it looks for a layout that
+//! agrees with everything the user can see and has a bomb on that
+//! `Tile`. If there is one, the bombs are quietly moved there, and the
+//! reveal loses. Only a `Tile` that is safe in every such layout (which
+//! includes everything the `solver` can prove) is revealed as safe, so
+//! the game can only be won by deduction, never by luck. The first
+//! reveal places the bombs as usual.
+//!
+//! Moving the bombs never changes anything the user has seen: every
+//! revealed number still counts the same bombs around it. Flags are
+//! not trusted, just as by the `solver`.
+//!
+//! Finding a layout is a search over the hidden `Tiles` next to
+//! revealed numbers. It gives up after `SEARCH_LIMIT` steps, in which
+//! case the bombs stay where they are.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate mines_core;
+//! # extern crate mines_solver;
+//! use mines_core::{Board, GameState};
+//! use mines_solver::KaboomBoard;
+//!
+//! // The 1 in the corner says one of its three hidden neighbours is a
+//! // bomb, so guessing any of them loses
+//! let b = Board::restore("1*1\n111\n...", "1??\n???\n???").unwrap();
+//! let mut game = KaboomBoard::new(b);
+//! game.reveal_tile(3).unwrap();
+//! assert_eq!(game.board().state(), GameState::Lost);
+//! assert!(game.board().tiles[3].is_bomb);
+//! ```
+
+use mines_core::board::{Board, FlagError};
+use mines_core::solver::{Solver, VisibleBoard, VisibleCell};
+use mines_core::tile::TileState;
+
+/// The most steps the search for a layout takes before giving up.
+pub const SEARCH_LIMIT: usize = 1_000_000;
+
+/// A `Board` whose bombs move to wherever the user guesses. See the
+/// `kaboom` module documentation.
+#[derive(Clone, Debug)]
+pub struct KaboomBoard {
+    board: Board,
+}
+
+impl KaboomBoard {
+    /// Plays the Kaboom variant on `board`, which may already be part
+    /// way through a game.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `board` has anti-mines, which the
+    /// search doesn't understand.
+    pub fn new(board: Board) -> KaboomBoard {
+        if board.num_anti_mines() > 0 {
+            panic!("Tried to play Kaboom on a Board with anti-mines!");
+        }
+        KaboomBoard { board }
+    }
+
+    /// Returns the `Board`, with its bombs where they are now.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns the `Board`, ending the variant.
+    pub fn into_board(self) -> Board {
+        self.board
+    }
+
+    /// Reveals the `Tile` at `index`, first moving a bomb onto it if
+    /// what the user can see allows one to be there.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `Board::reveal_tile`. It is safe to discard this error; it is
+    /// only for the programmer.
+    pub fn reveal_tile(&mut self, index: usize) -> Result<(), &'static str> {
+        self.move_bomb_to(index);
+        self.board.reveal_tile(index)
+    }
+
+    /// Toggles a flag on the `Tile` at `index`, as `Board::flag_tile`
+    /// does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `Board::flag_tile`.
+    pub fn flag_tile(&mut self, index: usize) -> Result<(), FlagError> {
+        self.board.flag_tile(index)
+    }
+
+    /// Chords the revealed number at `index`, as `Board::chord_tile`
+    /// does, first moving a bomb onto the first of its hidden
+    /// neighbours that can have one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error under the same conditions as
+    /// `Board::chord_tile`. It is safe to discard this error; it is
+    /// only for the programmer.
+    pub fn chord_tile(&mut self, index: usize) -> Result<(), &'static str> {
+        for n in self.board.adjacent_tile_indices(index) {
+            if matches!(self.board.tiles[n].state, TileState::Hidden) && self.move_bomb_to(n) {
+                break;
+            }
+        }
+        self.board.chord_tile(index)
+    }
+
+    // Moves the bombs so that there is one at `index`, if the Tile is
+    // hidden and what the user can see allows it. Returns whether there
+    // is now a bomb there
+    fn move_bomb_to(&mut self, index: usize) -> bool {
+        if !self.board.is_generated() ||
+           !matches!(self.board.tiles[index].state, TileState::Hidden) {
+            return false;
+        }
+        if self.board.tiles[index].is_bomb {
+            return true;
+        }
+        let current: Vec<bool> = self.board.tiles.iter().map(|t| t.is_bomb).collect();
+        match layout_with(&VisibleBoard::from_board(&self.board), &current, index, true) {
+            Some(layout) => {
+                // Nothing the user can see changes, so this isn't a move
+                for (tile, is_bomb) in self.board.tiles.iter_mut().zip(layout) {
+                    tile.is_bomb = is_bomb;
+                }
+                self.board.count_adjacent_bombs();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// The search through the hidden Tiles next to revealed numbers
+struct Search<'a> {
+    // The unknown Tiles the numbers say something about, in order
+    cells: Vec<usize>,
+    // For each number: its unknown neighbours (as positions in
+    // `cells`), and the fewest and most bombs they can hold
+    constraints: Vec<(Vec<usize>, usize, usize)>,
+    // For each position in `cells`, the constraints it is part of
+    touches: Vec<Vec<usize>>,
+    // For each constraint, the bombs placed and cells left so far
+    placed: Vec<usize>,
+    left: Vec<usize>,
+    assignment: Vec<bool>,
+    bombs: usize,
+    // The most bombs the cells can hold, and the fewest they must, for
+    // the rest to fit in the other unknown Tiles
+    most: usize,
+    fewest: usize,
+    current: &'a [bool],
+    steps: usize,
+}
+
+impl<'a> Search<'a> {
+    // Assigns `cells[position..]`, returning whether a consistent
+    // assignment was found
+    fn run(&mut self, position: usize) -> bool {
+        self.steps += 1;
+        if self.steps > SEARCH_LIMIT {
+            return false;
+        }
+        if position == self.cells.len() {
+            return self.bombs >= self.fewest;
+        }
+        // Trying the current layout first keeps the bombs that don't
+        // have to move where they are
+        let first = self.current[self.cells[position]];
+        for &is_bomb in &[first, !first] {
+            if self.assign(position, is_bomb) && self.run(position + 1) {
+                return true;
+            }
+            self.unassign(position, is_bomb);
+        }
+        false
+    }
+
+    // Assigns one cell, returning whether every constraint can still be
+    // met. Must be undone with `unassign` either way
+    fn assign(&mut self, position: usize, is_bomb: bool) -> bool {
+        self.assignment[position] = is_bomb;
+        if is_bomb {
+            self.bombs += 1;
+        }
+        let mut ok = self.bombs <= self.most;
+        for &c in &self.touches[position] {
+            self.left[c] -= 1;
+            if is_bomb {
+                self.placed[c] += 1;
+            }
+            let (_, min, max) = self.constraints[c];
+            ok &= self.placed[c] <= max && self.placed[c] + self.left[c] >= min;
+        }
+        ok
+    }
+
+    fn unassign(&mut self, position: usize, is_bomb: bool) {
+        if is_bomb {
+            self.bombs -= 1;
+        }
+        for &c in &self.touches[position] {
+            self.left[c] += 1;
+            if is_bomb {
+                self.placed[c] -= 1;
+            }
+        }
+    }
+}
+
+// A layout that agrees with `view` and has a bomb at `index` or not, as
+// `is_bomb` says, keeping as many of the bombs in `current` as it easily
+// can, or None if there is no such layout (or it couldn't be found in
+// time)
+pub(crate) fn layout_with(
+    view: &VisibleBoard,
+    current: &[bool],
+    index: usize,
+    is_bomb: bool,
+) -> Option<Vec<bool>> {
+    // None: unknown, Some(true): bomb, Some(false): safe
+    let mut known: Vec<Option<bool>> = view.cells
+        .iter()
+        .map(|cell| {
+            match *cell {
+                VisibleCell::Hidden | VisibleCell::Flagged => None,
+                VisibleCell::Revealed(_) | VisibleCell::Range(..) => Some(false),
+                VisibleCell::Mine => Some(true),
+            }
+        })
+        .collect();
+    let deductions = Solver::default().solve(view);
+    for &i in &deductions.safe {
+        known[i] = Some(false);
+    }
+    for &i in &deductions.mines {
+        known[i] = Some(true);
+    }
+    if known[index] == Some(!is_bomb) {
+        return None;
+    }
+    known[index] = Some(is_bomb);
+
+    let mut cells = Vec::new();
+    let mut position = vec![None; known.len()];
+    let mut constraints = Vec::new();
+    for (i, cell) in view.cells.iter().enumerate() {
+        if let Some((min, max)) = cell.bounds() {
+            let mut members = Vec::new();
+            let mut bombs = 0;
+            for &n in view.neighbors(i) {
+                match known[n] {
+                    Some(true) => bombs += 1,
+                    Some(false) => {}
+                    None => {
+                        let p = *position[n].get_or_insert_with(|| {
+                            cells.push(n);
+                            cells.len() - 1
+                        });
+                        members.push(p);
+                    }
+                }
+            }
+            if bombs > max || bombs + members.len() < min {
+                return None;
+            }
+            constraints.push((members, min.saturating_sub(bombs), max - bombs));
+        }
+    }
+
+    let found = known.iter().filter(|&&k| k == Some(true)).count();
+    let remaining = view.num_mines.checked_sub(found)?;
+    let interior: Vec<usize> = (0..known.len())
+        .filter(|&i| known[i].is_none() && position[i].is_none())
+        .collect();
+    let mut touches = vec![Vec::new(); cells.len()];
+    for (c, (members, _, _)) in constraints.iter().enumerate() {
+        for &p in members {
+            touches[p].push(c);
+        }
+    }
+    let mut search = Search {
+        placed: vec![0; constraints.len()],
+        left: constraints.iter().map(|(members, _, _)| members.len()).collect(),
+        assignment: vec![false; cells.len()],
+        bombs: 0,
+        most: remaining,
+        fewest: remaining.saturating_sub(interior.len()),
+        current,
+        steps: 0,
+        cells,
+        constraints,
+        touches,
+    };
+    if !search.run(0) {
+        return None;
+    }
+
+    let mut layout: Vec<bool> = known.iter().map(|&k| k == Some(true)).collect();
+    for (p, &i) in search.cells.iter().enumerate() {
+        layout[i] = search.assignment[p];
+    }
+    // The rest go on the interior, where they were if possible
+    let (mut keep, others): (Vec<usize>, Vec<usize>) =
+        interior.into_iter().partition(|&i| current[i]);
+    keep.extend(others);
+    for &i in keep.iter().take(remaining - search.bombs) {
+        layout[i] = true;
+    }
+    Some(layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mines_core::board::GameState;
+    use mines_core::solver;
+
+    #[test]
+    fn test_guesses_lose() {
+        // Either of the 1's hidden neighbours could be the bomb
+        for &guess in &[1, 3] {
+            let b = Board::restore("1*1\n111\n...", "1??\n???\n???").unwrap();
+            let mut game = KaboomBoard::new(b);
+            game.reveal_tile(guess).unwrap();
+            assert_eq!(game.board().state(), GameState::Lost);
+            assert_eq!(game.board().tiles.iter().filter(|t| t.is_bomb).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_deductions_are_honoured() {
+        // The 1-1 on the edge proves the corner safe
+        let b = Board::restore("1*1\n111\n...", "???\n111\n...").unwrap();
+        let safe = solver::solve(&b).safe;
+        assert_eq!(safe, vec![0, 2]);
+        let mut game = KaboomBoard::new(b);
+        game.reveal_tile(0).unwrap();
+        game.reveal_tile(2).unwrap();
+        assert_eq!(game.board().state(), GameState::Won);
+    }
+
+    #[test]
+    fn test_moved_bombs_agree_with_the_numbers() {
+        for seed in 0..10 {
+            let mut b = Board::new(9, 9, 10).with_seed(seed);
+            b.reveal_tile(40).unwrap();
+            let before = b.visible_string();
+            let mut game = KaboomBoard::new(b);
+            let guess = (0..81).find(|&i| {
+                matches!(game.board().tiles[i].state, TileState::Hidden) &&
+                !solver::solve(game.board()).safe.contains(&i)
+            });
+            if let Some(guess) = guess {
+                game.reveal_tile(guess).unwrap();
+                assert_eq!(game.board().state(), GameState::Lost);
+                let b = game.board();
+                assert_eq!(b.tiles.iter().filter(|t| t.is_bomb).count(), 10);
+                let restored = Board::restore(&b.layout_string(), &before).unwrap();
+                assert_eq!(restored.visible_string(), before);
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+/*!
+# mines-solver
+
+Analysis of **mines** `Boards` beyond what the deduction `solver` of
+`mines-core` can prove: mine probabilities, bots that play by them,
+benchmarks, and the experimental modes built on solving (benevolent and
+kaboom boards, proximity hints, and pattern practice).
+
+Most users should depend on `mines` with the `solver` feature instead,
+which re-exports this crate.
+
+## Features
+
+* `rayon`: Has `analysis` work out separate parts of the frontier in
+  parallel.
+
+* `experimental`: Builds the experimental modules of this crate.
+*/
+
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+extern crate mines_core;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+pub mod analysis;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod benevolent;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod bench;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod bot;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod kaboom;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod pattern;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod practice;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod proximity;
+
+#[cfg(feature = "experimental")]
+pub use benevolent::*;
+#[cfg(feature = "experimental")]
+pub use kaboom::*;
+#[cfg(feature = "experimental")]
+pub use proximity::*;
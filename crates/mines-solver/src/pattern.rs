@@ -0,0 +1,298 @@
+#![warn(missing_docs)]
+//! Recognising the classic patterns of Minesweeper logic.
+//!
+//! The `solver` proves what it can without saying how. This module
+//! looks for the handful of named patterns that players learn, so that
+//! teaching front-ends can point at one and say which it is:
+//!
+//! * `1-2-1`: three numbers in a line against a row of hidden `Tiles`.
+//!   The bombs are under the two 1s.
+//! * `1-2-2-1`: four numbers in a line against a row of hidden `Tiles`.
+//!   The bombs are under the two 2s.
+//! * `1-1 wall`: two 1s in a line, starting against a wall (the edge of
+//!   the `Board`, or a `Tile` that is already known). The `Tile` past
+//!   the second 1 is safe.
+//! * `1-2 wall`: a 1 then a 2 in a line, starting against a wall. The
+//!   `Tile` past the 2 is a bomb.
+//!
+//! A pattern only counts if its numbers have no unknown neighbours
+//! other than the row of `Tiles` they face, so that the pattern is the
+//! whole story. Revealed bombs are taken off the numbers first, and
+//! flags are not trusted.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate mines_core;
+//! # extern crate mines_solver;
+//! use mines_core::Board;
+//! use mines_solver::pattern::{self, PatternId};
+//!
+//! let b = Board::restore("1*2*1\n11211\n.....", "?????\n11211\n.....").unwrap();
+//! let found = pattern::find(&b);
+//! let one_two_one = found.iter().find(|m| m.id == PatternId::OneTwoOne).unwrap();
+//! assert_eq!(one_two_one.numbers, vec![6, 7, 8]);
+//! assert_eq!(one_two_one.mines, vec![1, 3]);
+//! assert_eq!(one_two_one.safe, vec![0, 2, 4]);
+//! ```
+
+use std::fmt;
+
+use mines_core::board::Board;
+use mines_core::solver::{VisibleBoard, VisibleCell};
+
+/// A named pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PatternId {
+    /// A 1, a 2, and a 1 in a line.
+    OneTwoOne,
+    /// A 1, two 2s, and a 1 in a line.
+    OneTwoTwoOne,
+    /// Two 1s in a line, starting against a wall.
+    OneOneWall,
+    /// A 1 then a 2 in a line, starting against a wall.
+    OneTwoWall,
+}
+
+impl PatternId {
+    /// Returns every pattern, in the order they are listed above.
+    pub fn all() -> &'static [PatternId] {
+        &[PatternId::OneTwoOne,
+          PatternId::OneTwoTwoOne,
+          PatternId::OneOneWall,
+          PatternId::OneTwoWall]
+    }
+
+    /// Returns the name players know the pattern by, such as `1-2-1`.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            PatternId::OneTwoOne => "1-2-1",
+            PatternId::OneTwoTwoOne => "1-2-2-1",
+            PatternId::OneOneWall => "1-1 wall",
+            PatternId::OneTwoWall => "1-2 wall",
+        }
+    }
+
+    // The numbers in the line, after taking off revealed bombs
+    fn values(&self) -> &'static [usize] {
+        match *self {
+            PatternId::OneTwoOne => &[1, 2, 1],
+            PatternId::OneTwoTwoOne => &[1, 2, 2, 1],
+            PatternId::OneOneWall => &[1, 1],
+            PatternId::OneTwoWall => &[1, 2],
+        }
+    }
+
+    fn needs_wall(&self) -> bool {
+        matches!(*self, PatternId::OneOneWall | PatternId::OneTwoWall)
+    }
+
+    // Where the mines and safe Tiles are in the facing row, counted
+    // from the Tile facing the first number
+    fn deductions(&self) -> (&'static [isize], &'static [isize]) {
+        match *self {
+            PatternId::OneTwoOne => (&[0, 2], &[-1, 1, 3]),
+            PatternId::OneTwoTwoOne => (&[1, 2], &[-1, 0, 3, 4]),
+            PatternId::OneOneWall => (&[], &[2]),
+            PatternId::OneTwoWall => (&[2], &[]),
+        }
+    }
+}
+
+impl fmt::Display for PatternId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Somewhere a pattern was found, and what it proves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternMatch {
+    /// Which pattern it is.
+    pub id: PatternId,
+    /// The indices of the numbers that make up the pattern, in
+    /// ascending order.
+    pub numbers: Vec<usize>,
+    /// The unknown `Tiles` the pattern proves safe, in ascending order.
+    pub safe: Vec<usize>,
+    /// The unknown `Tiles` the pattern proves to be bombs, in ascending
+    /// order.
+    pub mines: Vec<usize>,
+}
+
+/// Finds every pattern in what the user can see of `board`. See
+/// `find_in`.
+pub fn find(board: &Board) -> Vec<PatternMatch> {
+    find_in(&VisibleBoard::from_board(board))
+}
+
+/// Finds every pattern in `view` that proves something about an unknown
+/// `Tile`, ordered by their lowest number and then by `PatternId`.
+pub fn find_in(view: &VisibleBoard) -> Vec<PatternMatch> {
+    const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+    let mut found: Vec<PatternMatch> = Vec::new();
+    for start in 0..view.cells.len() {
+        for &along in &DIRECTIONS {
+            for &facing in &[(along.1, along.0), (-along.1, -along.0)] {
+                for &id in PatternId::all() {
+                    if let Some(m) = match_at(view, id, start, along, facing) {
+                        if !found.iter().any(|f| f.id == m.id && f.numbers == m.numbers) {
+                            found.push(m);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    found.sort_by_key(|m| (m.numbers[0], m.id));
+    found
+}
+
+// Checks for `id` with its first number at `start`, running `along` and
+// facing the row on the `facing` side
+fn match_at(view: &VisibleBoard,
+            id: PatternId,
+            start: usize,
+            along: (isize, isize),
+            facing: (isize, isize))
+            -> Option<PatternMatch> {
+    let x0 = (start % view.width) as isize;
+    let y0 = (start / view.width) as isize;
+    let at = |x: isize, y: isize| {
+        if x < 0 || y < 0 || x >= view.width as isize || y >= view.height as isize {
+            None
+        } else {
+            Some(y as usize * view.width + x as usize)
+        }
+    };
+    // The facing Tile `j` steps along from the first number
+    let facing_tile = |j: isize| at(x0 + along.0 * j + facing.0, y0 + along.1 * j + facing.1);
+    let unknown = |i: Option<usize>| i.is_some_and(|i| view.cells[i].is_unknown());
+
+    let mut numbers = Vec::new();
+    for (step, &value) in id.values().iter().enumerate() {
+        let step = step as isize;
+        let index = at(x0 + along.0 * step, y0 + along.1 * step)?;
+        let number = match view.cells[index] {
+            VisibleCell::Revealed(n) => n,
+            _ => return None,
+        };
+        let mut mines = 0;
+        for &n in view.neighbors(index) {
+            let dx = (n % view.width) as isize - (index % view.width) as isize;
+            let dy = (n / view.width) as isize - (index / view.width) as isize;
+            match view.cells[n] {
+                VisibleCell::Mine => mines += 1,
+                // Everything unknown must be in the facing row
+                ref cell if cell.is_unknown() && dx * facing.0 + dy * facing.1 != 1 => return None,
+                _ => {}
+            }
+        }
+        if number < mines || number - mines != value {
+            return None;
+        }
+        if !unknown(facing_tile(step)) {
+            return None;
+        }
+        numbers.push(index);
+    }
+    if id.needs_wall() && unknown(facing_tile(-1)) {
+        return None;
+    }
+
+    let (mine_steps, safe_steps) = id.deductions();
+    let mut mines = Vec::new();
+    for &j in mine_steps {
+        // A mine the pattern needs must still be unknown
+        let tile = facing_tile(j).filter(|&i| view.cells[i].is_unknown())?;
+        mines.push(tile);
+    }
+    let mut safe: Vec<usize> = safe_steps.iter()
+        .filter_map(|&j| facing_tile(j))
+        .filter(|&i| view.cells[i].is_unknown())
+        .collect();
+    if mines.is_empty() && safe.is_empty() {
+        return None;
+    }
+
+    numbers.sort_unstable();
+    mines.sort_unstable();
+    safe.sort_unstable();
+    Some(PatternMatch {
+        id,
+        numbers,
+        safe,
+        mines,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mines_core::solver;
+
+    fn found(id: PatternId,
+             numbers: Vec<usize>,
+             safe: Vec<usize>,
+             mines: Vec<usize>)
+             -> PatternMatch {
+        PatternMatch {
+            id,
+            numbers,
+            safe,
+            mines,
+        }
+    }
+
+    #[test]
+    fn test_find() {
+        struct Test {
+            layout: &'static str,
+            visible: &'static str,
+            expected: Vec<PatternMatch>,
+        }
+
+        let tests = [Test {
+                         layout: "1*2*1\n11211\n.....",
+                         visible: "?????\n11211\n.....",
+                         expected: vec![found(PatternId::OneOneWall, vec![5, 6], vec![2], vec![]),
+                                        found(PatternId::OneTwoOne, vec![6, 7, 8], vec![0, 2, 4],
+                                              vec![1, 3]),
+                                        found(PatternId::OneOneWall, vec![8, 9], vec![2], vec![])],
+                     },
+                     Test {
+                         layout: "1**1.\n1221.\n.....",
+                         visible: "?????\n1221.\n.....",
+                         expected: vec![found(PatternId::OneTwoTwoOne, vec![5, 6, 7, 8],
+                                              vec![0, 3, 4], vec![1, 2]),
+                                        found(PatternId::OneTwoWall, vec![5, 6], vec![], vec![2])],
+                     },
+                     Test {
+                         layout: "*2*1.\n1211.\n.....",
+                         visible: "?????\n1211.\n.....",
+                         expected: vec![found(PatternId::OneTwoOne, vec![5, 6, 7], vec![1, 3],
+                                              vec![0, 2]),
+                                        found(PatternId::OneTwoWall, vec![5, 6], vec![], vec![2])],
+                     },
+                     Test {
+                         // The last 1 has unknown neighbours below, so
+                         // the line isn't the whole story
+                         layout: "1*2*1\n11211\n.....",
+                         visible: "?????\n11211\n...??",
+                         expected: vec![found(PatternId::OneOneWall, vec![5, 6], vec![2], vec![])],
+                     }];
+
+        for test in &tests {
+            let b = Board::restore(test.layout, test.visible).unwrap();
+            assert_eq!(find(&b), test.expected, "{}", test.layout);
+
+            // Everything a pattern proves, the solver proves too
+            let deductions = solver::solve(&b);
+            for m in find(&b) {
+                assert!(m.safe.iter().all(|i| deductions.safe.contains(i)));
+                assert!(m.mines.iter().all(|i| deductions.mines.contains(i)));
+            }
+        }
+    }
+}
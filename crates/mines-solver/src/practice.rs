@@ -0,0 +1,65 @@
+#![warn(missing_docs)]
+//! Small `Boards` for drilling one pattern at a time.
+//!
+//! `board_for` makes a small `Board`, opens it up from the middle, and
+//! checks whether the named pattern (see the `pattern` module) shows up
+//! on the edge of the opening. If not, it tries again with the next
+//! layout from the seed, until one does. Everything is derived from the
+//! seed, so the same pattern and seed always give the same `Board`.
+//!
+//! # Examples
+//!
+//! ```
+//! use mines_solver::pattern::{self, PatternId};
+//! use mines_solver::practice;
+//!
+//! let b = practice::board_for(PatternId::OneTwoOne, 7);
+//! assert!(pattern::find(&b).iter().any(|m| m.id == PatternId::OneTwoOne));
+//! assert!(b.started_at().is_none());
+//! ```
+
+use mines_core::board::Board;
+use mines_core::util::SeededRng;
+
+use pattern::{self, PatternId};
+
+/// The width of a practice `Board`.
+pub const WIDTH: usize = 9;
+/// The height of a practice `Board`.
+pub const HEIGHT: usize = 9;
+/// The number of bombs on a practice `Board`.
+pub const MINES: usize = 14;
+
+/// Returns a small `Board` on which `id` can be seen on the edge of
+/// the opening, which has already been revealed. The clock starts on
+/// the next reveal, and the move counts start from zero.
+pub fn board_for(id: PatternId, seed: u64) -> Board {
+    let mut seeds = SeededRng::new(seed);
+    let start = (HEIGHT / 2) * WIDTH + WIDTH / 2;
+    loop {
+        let mut board = Board::new(WIDTH, HEIGHT, MINES).with_seed(seeds.next_u64());
+        // The first reveal is always safe, so this can't fail
+        let _ = board.reveal_tile(start);
+        if pattern::find(&board).iter().any(|m| m.id == id) {
+            board.forget_moves();
+            return board;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_for() {
+        for &id in PatternId::all() {
+            for seed in 0..3 {
+                let b = board_for(id, seed);
+                assert!(pattern::find(&b).iter().any(|m| m.id == id), "{} {}", id, seed);
+                assert_eq!(b.layout_string(), board_for(id, seed).layout_string());
+                assert_eq!(b.move_count(), 0);
+            }
+        }
+    }
+}
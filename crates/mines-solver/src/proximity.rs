@@ -0,0 +1,213 @@
+#![warn(missing_docs)]
+//! [Re-exported] The hot/cold variant, where revealed `Tiles` only give
+//! a rough idea of how many bombs are nearby.
+//!
+//! The `Board` itself is unchanged and still knows the exact numbers;
+//! the variant only changes what the user is shown. Each revealed
+//! `Tile` reports a `Proximity` band instead of its number, and
+//! `VisibleBoard::from_board_proximity` captures that coarser view so
+//! that the `solver` and `analysis` can work with it.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate mines_core;
+//! # extern crate mines_solver;
+//! use mines_core::{Board, GridFormat};
+//! use mines_solver::{BoardProximity, Proximity, VisibleProximity};
+//! use mines_core::solver::{self, VisibleBoard};
+//!
+//! let b = Board::restore("*2*\n121\n...", "???\n121\n...").unwrap();
+//! assert_eq!(b.proximity(3), Some(Proximity::Warm));
+//! assert_eq!(b.render_proximity(&GridFormat::compact()), "???\n+++\n...\n");
+//!
+//! // With only bands to go on, the 1-2-1 pattern can't be read
+//! let view = VisibleBoard::from_board_proximity(&b);
+//! assert!(solver::Solver::default().solve(&view).is_empty());
+//! ```
+
+use std::fmt;
+
+use mines_core::board::Board;
+use mines_core::render::GridFormat;
+use mines_core::solver::{VisibleBoard, VisibleCell};
+use mines_core::tile::TileState;
+
+/// How close a revealed `Tile` is to bombs, in coarse bands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Proximity {
+    /// No adjacent bombs.
+    Cold,
+    /// One or two adjacent bombs.
+    Warm,
+    /// Three or more adjacent bombs.
+    Hot,
+}
+
+impl Proximity {
+    /// Returns the band that `adjacent_bombs` falls into.
+    pub fn of(adjacent_bombs: usize) -> Proximity {
+        match adjacent_bombs {
+            0 => Proximity::Cold,
+            1 | 2 => Proximity::Warm,
+            _ => Proximity::Hot,
+        }
+    }
+
+    /// Returns the least and most adjacent bombs in this band,
+    /// inclusive.
+    pub fn bounds(&self) -> (usize, usize) {
+        match *self {
+            Proximity::Cold => (0, 0),
+            Proximity::Warm => (1, 2),
+            Proximity::Hot => (3, 8),
+        }
+    }
+}
+
+/// Writes `.` for `Cold`, `+` for `Warm`, and `#` for `Hot`.
+impl fmt::Display for Proximity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            Proximity::Cold => ".",
+            Proximity::Warm => "+",
+            Proximity::Hot => "#",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Proximity hints about a `Board`. See the `proximity` module documentation.
+pub trait BoardProximity {
+    /// Returns the `Proximity` shown on the `Tile` at `index`, or
+    /// `None` if it is not a revealed safe `Tile`.
+    fn proximity(&self, index: usize) -> Option<Proximity>;
+
+    /// Renders what the user can see in the hot/cold variant: the same
+    /// as `render_visible`, but with revealed numbers replaced by their
+    /// `Proximity`.
+    fn render_proximity(&self, format: &GridFormat) -> String;
+}
+
+impl BoardProximity for Board {
+    fn proximity(&self, index: usize) -> Option<Proximity> {
+        let tile = &self.tiles[index];
+        match tile.state {
+            // Anti-mines can make the number negative; it is just as
+            // far from 0 either way
+            TileState::Revealed if !tile.is_bomb => {
+                Some(Proximity::of(tile.adjacent_bombs.unsigned_abs()))
+            }
+            _ => None,
+        }
+    }
+
+    fn render_proximity(&self, format: &GridFormat) -> String {
+        let cells: Vec<String> = (0..self.tiles.len())
+            .map(|i| {
+                match self.proximity(i) {
+                    Some(proximity) => format!("{}", proximity),
+                    None => format!("{}", self.tiles[i]),
+                }
+            })
+            .collect();
+        format.render(&cells, self.width)
+    }
+}
+
+/// Solving in the hot/cold variant. See the `proximity` module
+/// documentation.
+pub trait VisibleProximity {
+    /// Captures what the user can currently see of `board` in the
+    /// hot/cold variant, where every revealed number is only known to
+    /// be within its `Proximity` band.
+    fn from_board_proximity(board: &Board) -> VisibleBoard;
+}
+
+impl VisibleProximity for VisibleBoard {
+    fn from_board_proximity(board: &Board) -> VisibleBoard {
+        let mut view = VisibleBoard::from_board(board);
+        for (i, cell) in view.cells.iter_mut().enumerate() {
+            if let Some(proximity) = board.proximity(i) {
+                let (min, max) = proximity.bounds();
+                *cell = VisibleCell::Range(min, max);
+            }
+        }
+        view
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mines_core::solver::{Deductions, Solver};
+    use analysis;
+
+    #[test]
+    fn test_bands() {
+        for n in 0..9 {
+            let (min, max) = Proximity::of(n).bounds();
+            assert!(min <= n && n <= max);
+        }
+    }
+
+    #[test]
+    fn test_solving_with_bands() {
+        struct Test {
+            layout: &'static str,
+            visible: &'static str,
+            expected: Deductions,
+        }
+
+        let tests = [Test {
+                         // A cold tile still clears its neighbours
+                         layout: "1*1..\n11111\n...1*",
+                         visible: "?????\n?????\n..???",
+                         expected: Deductions {
+                             safe: vec![5, 6, 7, 12],
+                             mines: vec![],
+                         },
+                     },
+                     Test {
+                         // Warm against a wall says little, but the total
+                         // mine count still pins the mine down
+                         layout: "1*1\n111\n...",
+                         visible: "???\n111\n...",
+                         expected: Deductions {
+                             safe: vec![0, 2],
+                             mines: vec![1],
+                         },
+                     },
+                     Test {
+                         // The warm row and the total count fill in the rest
+                         layout: "***\n*5*\n121",
+                         visible: "???\n?5?\n121",
+                         expected: Deductions {
+                             safe: vec![],
+                             mines: vec![0, 1, 2, 3, 5],
+                         },
+                     }];
+
+        for test in &tests {
+            let board = Board::restore(test.layout, test.visible).unwrap();
+            let view = VisibleBoard::from_board_proximity(&board);
+            assert_eq!(Solver::default().solve(&view), test.expected);
+        }
+    }
+
+    #[test]
+    fn test_probabilities_with_bands() {
+        // Three warm tiles under three unknowns, with two mines: any
+        // two of the three will do
+        let mut cells = vec![VisibleCell::Revealed(0); 9];
+        for i in 0..3 {
+            cells[i] = VisibleCell::Hidden;
+            cells[i + 3] = VisibleCell::Range(1, 2);
+        }
+        let view = VisibleBoard::new(3, 3, 2, cells);
+        let probabilities = analysis::probabilities(&view);
+        for p in &probabilities[0..3] {
+            assert!((p - 2.0 / 3.0).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Generates a `Board` and writes out its layout.
+//!
+//! ```text
+//! mines-gen [--width W] [--height H] [--mines M] [--seed S]
+//!           [--first-click X,Y] [--format text|json|mbf]
+//! ```
+//!
+//! The `Board` is beginner-sized unless told otherwise, and is generated
+//! as if (X, Y) were revealed first, the centre by default. The same
+//! seed always gives the same layout; without one, a seed is picked at
+//! random, and the JSON output says which.
+//!
+//! `text` is the layout format of the `layout` module. `json` is an
+//! object with the settings and the layout, one string per row:
+//!
+//! ```text
+//! {"width":9,"height":9,"mines":10,"seed":42,"first_click":[4,4],
+//!  "layout":["1*1......", ...]}
+//! ```
+//!
+//! `mbf` is the binary MBF format of the `mbf` module, for board
+//! sharing tools.
+
+extern crate mines;
+extern crate rand;
+
+use std::env;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::process;
+
+use mines::{Board, Difficulty};
+use rand::Rng;
+
+struct Options {
+    width: usize,
+    height: usize,
+    mines: usize,
+    seed: u64,
+    first_click: Option<(usize, usize)>,
+    format: Format,
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Text,
+    Json,
+    Mbf,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = parse_args(&args).unwrap_or_else(|| usage());
+
+    let mut board = Board::builder()
+        .width(options.width)
+        .height(options.height)
+        .mines(options.mines)
+        .seed(options.seed)
+        .build()
+        .unwrap_or_else(|err| fail(err));
+    let first_click = options.first_click.unwrap_or((board.width / 2, board.height / 2));
+    if first_click.0 >= board.width || first_click.1 >= board.height {
+        fail("the first click is off the board");
+    }
+    let index = board.linear_coords(first_click);
+    // The Board has a seed, so that is what places the mines
+    board.generate_with_rng(index, &mut rand::thread_rng());
+
+    match options.format {
+        Format::Text => print!("{}", board.layout_string()),
+        Format::Json => println!("{}", json(&board, options.seed, first_click)),
+        Format::Mbf => {
+            let bytes = board.to_mbf().unwrap_or_else(|err| fail(err));
+            if let Err(err) = io::stdout().write_all(&bytes) {
+                fail(err);
+            }
+        }
+    }
+}
+
+// Reads `--name value` pairs
+fn parse_args(args: &[String]) -> Option<Options> {
+    let (width, height, mines) = Difficulty::Beginner.dimensions()?;
+    let mut options = Options {
+        width,
+        height,
+        mines,
+        seed: rand::thread_rng().next_u64(),
+        first_click: None,
+        format: Format::Text,
+    };
+    for pair in args.chunks(2) {
+        let (name, value) = match pair {
+            [name, value] => (name.as_str(), value.as_str()),
+            _ => return None,
+        };
+        match name {
+            "--width" => options.width = value.parse().ok()?,
+            "--height" => options.height = value.parse().ok()?,
+            "--mines" => options.mines = value.parse().ok()?,
+            "--seed" => options.seed = value.parse().ok()?,
+            "--first-click" => {
+                let mut coords = value.splitn(2, ',');
+                let x = coords.next()?.trim().parse().ok()?;
+                let y = coords.next()?.trim().parse().ok()?;
+                options.first_click = Some((x, y));
+            }
+            "--format" => {
+                options.format = match value {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    "mbf" => Format::Mbf,
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(options)
+}
+
+// Writes the settings and layout of `board` as JSON. The layout format
+// only uses characters that need no escaping
+fn json(board: &Board, seed: u64, first_click: (usize, usize)) -> String {
+    let rows: Vec<String> =
+        board.layout_string().lines().map(|row| format!("\"{}\"", row)).collect();
+    format!("{{\"width\":{},\"height\":{},\"mines\":{},\"seed\":{},\"first_click\":[{},{}],\
+             \"layout\":[{}]}}",
+            board.width,
+            board.height,
+            board.num_mines,
+            seed,
+            first_click.0,
+            first_click.1,
+            rows.join(","))
+}
+
+fn fail<E: Display>(err: E) -> ! {
+    eprintln!("mines-gen: {}", err);
+    process::exit(1);
+}
+
+fn usage() -> ! {
+    eprintln!("usage: mines-gen [--width W] [--height H] [--mines M] [--seed S]");
+    eprintln!("                 [--first-click X,Y] [--format text|json|mbf]");
+    process::exit(2);
+}
@@ -0,0 +1,93 @@
+//! Reads what the user can see of a `Board` from stdin, and prints what
+//! can be worked out from it.
+//!
+//! ```text
+//! mines-solve MINES < board.txt
+//! ```
+//!
+//! The `Board` is written in the visible format of the `layout` module,
+//! which is also what `Board`'s `Display` prints, and MINES is the
+//! number of mines on it. The `Tiles` the solver proves safe and those
+//! it proves to be mines are listed by their (x, y) coordinates,
+//! followed by the `Board` with every unknown `Tile` replaced by the
+//! percentage chance that it is a mine:
+//!
+//! ```text
+//! $ printf '???\n111\n...\n' | mines-solve 1
+//! safe: (0, 0) (2, 0)
+//! mines: (1, 0)
+//!
+//!   0% 100%   0%
+//!    1    1    1
+//!    .    .    .
+//! ```
+
+extern crate mines;
+
+use std::env;
+use std::fmt::Display;
+use std::io::{self, Read};
+use std::process;
+
+use mines::analysis;
+use mines::solver::{Solver, VisibleBoard, VisibleCell};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let num_mines = match args.as_slice() {
+        [mines] => mines.parse().unwrap_or_else(|_| usage()),
+        _ => usage(),
+    };
+    let mut input = String::new();
+    if let Err(err) = io::stdin().read_to_string(&mut input) {
+        fail(err);
+    }
+    let view = VisibleBoard::parse(&input, num_mines).unwrap_or_else(|err| fail(err));
+
+    let deductions = Solver::default().solve(&view);
+    println!("safe:{}", coords(&view, &deductions.safe));
+    println!("mines:{}", coords(&view, &deductions.mines));
+    println!();
+    print!("{}", chances(&view));
+}
+
+// Lists `indices` as (x, y) coordinates, each after a space
+fn coords(view: &VisibleBoard, indices: &[usize]) -> String {
+    indices.iter().map(|&i| format!(" ({}, {})", i % view.width, i / view.width)).collect()
+}
+
+// Writes the visible Board with the chance of each unknown Tile being
+// a mine in place of the Tile
+fn chances(view: &VisibleBoard) -> String {
+    let probabilities = analysis::probabilities(view);
+    let cells: Vec<String> = view.cells
+        .iter()
+        .zip(probabilities)
+        .map(|(cell, p)| {
+            match *cell {
+                VisibleCell::Hidden | VisibleCell::Flagged => format!("{:.0}%", p * 100.0),
+                VisibleCell::Revealed(0) => String::from("."),
+                VisibleCell::Revealed(n) => n.to_string(),
+                VisibleCell::Range(min, max) => format!("{}-{}", min, max),
+                VisibleCell::Mine => String::from("*"),
+            }
+        })
+        .collect();
+    let mut out = String::new();
+    for row in cells.chunks(view.width) {
+        let row: Vec<String> = row.iter().map(|cell| format!("{:>4}", cell)).collect();
+        out.push_str(&row.join(" "));
+        out.push('\n');
+    }
+    out
+}
+
+fn fail<E: Display>(err: E) -> ! {
+    eprintln!("mines-solve: {}", err);
+    process::exit(1);
+}
+
+fn usage() -> ! {
+    eprintln!("usage: mines-solve MINES < board.txt");
+    process::exit(2);
+}
@@ -0,0 +1,62 @@
+//! Command-line tools for the `mines` crate.
+//!
+//! ```text
+//! mines play [LEVEL | WIDTH HEIGHT MINES]    play in the terminal
+//! mines bench [ROUNDS]                       time the standard benchmark boards
+//! ```
+//!
+//! `mines play` needs the `tui` feature. LEVEL is `beginner` (the
+//! default), `intermediate`, or `expert`.
+
+#[cfg(feature = "tui")]
+#[macro_use]
+extern crate crossterm;
+extern crate mines;
+
+#[cfg(feature = "tui")]
+mod play;
+
+use std::env;
+use std::process;
+
+use mines::bench;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(|s| s.as_str()) {
+        Some("play") => run_play(&args[1..]),
+        Some("bench") => run_bench(&args[1..]),
+        _ => usage(),
+    }
+}
+
+#[cfg(feature = "tui")]
+fn run_play(args: &[String]) {
+    play::run(args)
+}
+
+#[cfg(not(feature = "tui"))]
+fn run_play(_: &[String]) {
+    eprintln!("mines: built without the tui feature, so there is no game to play");
+    process::exit(2);
+}
+
+fn run_bench(args: &[String]) {
+    let rounds = match args {
+        [] => 5,
+        [rounds] => rounds.parse().unwrap_or_else(|_| usage()),
+        _ => usage(),
+    };
+    let mut results = Vec::new();
+    for board in bench::boards() {
+        eprintln!("timing {}...", board.name);
+        results.push(bench::run(&board, rounds));
+    }
+    print!("{}", bench::report(&results));
+}
+
+fn usage() -> ! {
+    eprintln!("usage: mines play [LEVEL | WIDTH HEIGHT MINES]");
+    eprintln!("       mines bench [ROUNDS]");
+    process::exit(2);
+}
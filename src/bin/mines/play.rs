@@ -0,0 +1,224 @@
+//! `mines play`: Minesweeper in the terminal.
+//!
+//! The arrow keys (or h, j, k, and l) move the cursor, space or enter
+//! reveals the `Tile` under it (or chords it, if it is already
+//! revealed), f flags it, n starts a new game, and q or escape quits.
+
+use std::io::{self, Write};
+use std::process;
+use std::time::Duration;
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use mines::{Board, BoardBuilder, Difficulty, GameState, TileView};
+
+const HELP: &str = "arrows move  space reveal  f flag  n new game  q quit";
+
+/// Plays games of the size given in `args` until the user quits.
+pub fn run(args: &[String]) {
+    let (width, height, mines) = parse_size(args).unwrap_or_else(|| super::usage());
+    let builder = Board::builder()
+        .width(width)
+        .height(height)
+        .mines(mines);
+    if let Err(err) = builder.build() {
+        eprintln!("mines: {}", err);
+        process::exit(2);
+    }
+    if let Err(err) = play(builder) {
+        eprintln!("mines: {}", err);
+        process::exit(1);
+    }
+}
+
+// Reads `[LEVEL | WIDTH HEIGHT MINES]`
+fn parse_size(args: &[String]) -> Option<(usize, usize, usize)> {
+    match args {
+        [] => Difficulty::Beginner.dimensions(),
+        [level] => {
+            match level.as_str() {
+                "beginner" => Difficulty::Beginner.dimensions(),
+                "intermediate" => Difficulty::Intermediate.dimensions(),
+                "expert" => Difficulty::Expert.dimensions(),
+                _ => None,
+            }
+        }
+        [width, height, mines] => {
+            Some((width.parse().ok()?, height.parse().ok()?, mines.parse().ok()?))
+        }
+        _ => None,
+    }
+}
+
+fn play(builder: BoardBuilder) -> io::Result<()> {
+    let _screen = Screen::enter()?;
+    let mut out = io::stdout();
+    let mut game = Game::new(builder);
+    loop {
+        game.draw(&mut out)?;
+        // Wake up now and then even without a key, to keep the timer
+        // ticking
+        if !event::poll(Duration::from_millis(200))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !game.handle(key) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+// Holds the terminal in raw mode on the alternate screen, and puts it
+// back when dropped, even on a panic
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Screen> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct Game {
+    builder: BoardBuilder,
+    board: Board,
+    cursor: (usize, usize),
+    // How long the game took, once it is over, so that the timer stops
+    finished_in: Option<Duration>,
+}
+
+impl Game {
+    fn new(builder: BoardBuilder) -> Game {
+        let board = builder.build().expect("the settings were checked before playing");
+        Game {
+            cursor: (board.width / 2, board.height / 2),
+            builder,
+            board,
+            finished_in: None,
+        }
+    }
+
+    // Acts on `key`, returning false if the user wants to quit
+    fn handle(&mut self, key: KeyEvent) -> bool {
+        let (x, y) = self.cursor;
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Left | KeyCode::Char('h') => self.cursor.0 = x.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.cursor.0 = (x + 1).min(self.board.width - 1)
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.cursor.1 = y.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.cursor.1 = (y + 1).min(self.board.height - 1)
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => self.open(),
+            KeyCode::Char('f') => {
+                // Flagging what can't be flagged just does nothing
+                let _ = self.board.flag_tile(self.index());
+            }
+            KeyCode::Char('n') => *self = Game::new(self.builder.clone()),
+            _ => {}
+        }
+        true
+    }
+
+    fn index(&self) -> usize {
+        self.board.linear_coords(self.cursor)
+    }
+
+    // Reveals the Tile under the cursor, or chords it if it is already
+    // revealed
+    fn open(&mut self) {
+        let index = self.index();
+        let _ = match self.board.tile_view(index) {
+            TileView::Number(_) => self.board.chord_tile(index),
+            _ => self.board.reveal_tile(index),
+        };
+        if self.finished_in.is_none() && self.is_over() {
+            self.finished_in = Some(self.board.elapsed());
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        matches!(self.board.state(), GameState::Won | GameState::Lost)
+    }
+
+    fn draw<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let time = self.finished_in.unwrap_or_else(|| self.board.elapsed());
+        let header = format!("Mines: {:<5} Time: {}", self.board.mines_remaining(), time.as_secs());
+        // Everything is drawn over the last frame, rather than clearing
+        // the screen and flickering
+        queue!(out, MoveTo(0, 0), Print(header), Clear(ClearType::UntilNewLine))?;
+        for y in 0..self.board.height {
+            queue!(out, MoveTo(0, y as u16 + 2))?;
+            for x in 0..self.board.width {
+                let view = self.board.tile_view(self.board.linear_coords((x, y)));
+                let (glyph, color) = look(view);
+                if (x, y) == self.cursor {
+                    queue!(out, SetAttribute(Attribute::Reverse))?;
+                }
+                queue!(out,
+                       SetForegroundColor(color),
+                       Print(glyph),
+                       ResetColor,
+                       SetAttribute(Attribute::Reset),
+                       Print(' '))?;
+            }
+        }
+        let status = match self.board.state() {
+            GameState::Won => "You won! n for a new game, q to quit",
+            GameState::Lost => "Boom! n for a new game, q to quit",
+            GameState::Ready | GameState::Playing => HELP,
+        };
+        queue!(out,
+               MoveTo(0, self.board.height as u16 + 3),
+               Print(status),
+               Clear(ClearType::UntilNewLine))?;
+        out.flush()
+    }
+}
+
+// How a Tile is drawn: the same characters as `Board::visible_string`,
+// coloured like the classic game
+fn look(view: TileView) -> (char, Color) {
+    match view {
+        TileView::Hidden => ('?', Color::DarkGrey),
+        TileView::Flagged => ('!', Color::Red),
+        TileView::Number(0) => ('.', Color::DarkGrey),
+        TileView::Number(n) => {
+            let color = match n {
+                1 => Color::Blue,
+                2 => Color::Green,
+                3 => Color::Red,
+                4 => Color::DarkBlue,
+                5 => Color::DarkRed,
+                6 => Color::Cyan,
+                7 => Color::Magenta,
+                _ => Color::Grey,
+            };
+            // Anti-mines can push a number below zero
+            let glyph = if n < 0 {
+                '-'
+            } else {
+                ::std::char::from_digit(n as u32, 10).unwrap_or('+')
+            };
+            (glyph, color)
+        }
+        TileView::Detonated => ('*', Color::Red),
+        TileView::Exposed => ('#', Color::White),
+        TileView::WrongFlag => ('X', Color::Red),
+        TileView::OutOfPlay => (' ', Color::Reset),
+    }
+}
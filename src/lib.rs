@@ -15,24 +15,126 @@ you will need to create a front-end for actually starting and playing
 a game of Minesweeper. Of particular interest to the programmer:
 
 * Each `Tile` has a state that can be queried, so that your program
-knows how to represent it to the user. For example, a `Tile` that has
-`TileState::Flagged` will be represented by a '!'  when printed with
-`Display`. Your program, then, can choose to represent that `Tile`
-with a specific sprite depending on its state.
+  knows how to represent it to the user. For example, a `Tile` that has
+  `TileState::Flagged` will be represented by a '!'  when printed with
+  `Display`. Your program, then, can choose to represent that `Tile`
+  with a specific sprite depending on its state.
 
 * The `Tiles` of any given `Board` are contained within a
-one-dimensional `Vec`, and must be accessed as such. For example: the
-very first tile (top-left corner) would be at 0. Convenience methods
-are provided so that you can access a `Tile` with an (x, y) coordinate
-pair, and vice-versa.
+  one-dimensional `Vec`, and must be accessed as such. For example: the
+  very first tile (top-left corner) would be at 0. Convenience methods
+  are provided so that you can access a `Tile` with an (x, y) coordinate
+  pair, and vice-versa.
 
 * When instantiating a new `Board`, note that no bombs/tile values
-will be placed *until* the first time a `Tile` is revealed via
-`Board::reveal_tile()`.
+  will be placed *until* the first time a `Tile` is revealed via
+  `Board::reveal_tile()`.
+
+## Crates
+
+**mines** is a facade over a workspace of smaller crates, so that
+consumers who only need a `Board` don't build everything else:
+
+* `mines-core`: `Board` and `Tile` themselves, their `topology`,
+  building, generating, and saving boards, rendering them as text,
+  and the `solver` that hints and no-guess generation are built on.
+  Always included.
+
+* `mines-solver` (the `solver` feature, on by default): mine
+  probabilities (`analysis`), and the experimental modules built on
+  solving.
+
+* `mines-render` (the `render` feature): drawing `Boards` as
+  pictures (`raster`).
+
+* `mines-net` (the `net` feature, on by default): share codes and
+  links, commitments, daily boards, and multiplayer and versus games.
+
+Everything is re-exported from the root of this crate, and each module
+keeps its path (`mines::solver`, `mines::code`, and so on). Methods
+that the optional crates add to `Board` come from extension traits,
+such as `BoardCode` for `Board::to_code`, which `use mines::*` brings
+into scope.
+
+## Optional Features
+
+* `solver`, `render`, and `net`: Include the crates above.
+
+* `serde`: Implements `Serialize` and `Deserialize` for `Board`,
+  `Tile`, and `TileState`, so that games can be persisted and
+  restored.
+
+* `rayon`: Counts the numbers of very large `Boards` (a million
+  `Tiles` or more) on every core when they are generated, and has
+  `analysis` work out separate parts of the frontier in parallel.
+
+* `wasm`: Adds `WasmBoard`, bindings that let JavaScript play on a
+  `Board` through `wasm-bindgen`. See the `wasm` module.
+
+* `image`: The same as `render`, which adds `BoardRaster::to_image`
+  and `BoardRaster::render_png`, for drawing `Boards` as pictures.
+
+* `tui`: Adds `mines play`, a Minesweeper game for the terminal, to
+  the `mines` binary. It is also the simplest complete front-end for
+  the crate to read.
+
+* `debug-invariants`: Checks that the `Board` is still consistent
+  (with `Board::validate`) after every move, undo, and redo, panicking
+  with a description of what is wrong and a dump of the `Board` if
+  not. Meant for catching bugs during development; nothing is checked
+  without it.
+
+* `history`: Adds `HistoryStore`, a single-file, append-only history
+  of games with simple queries.
+
+* `experimental` (on by default): Builds the modules that are still
+  finding their shape. See below.
+
+## Stability
+
+The crate is split into two tiers:
+
+* The stable core: `Board` and `Tile` themselves, their `topology`,
+  building, generating, saving, and sharing boards (`builder`,
+  `generate`, `layout`, `save`, `code`, `link`, `dump`), rendering and
+  `view`s, the clock, undo, actions, listeners, statistics, replays,
+  validation, the solver, and `analysis`. These only change in
+  incompatible ways with the crate's major version.
+
+* Experimental modules, which are only built with the `experimental`
+  feature: `adaptive`, `benevolent`, `bench`, `bot`, `cascade`,
+  `commit`, `daily`, `graph`, `infinite`, `kaboom`, `liar`,
+  `multiplayer`, `packed`, `pattern`, `practice`, `proximity`,
+  `shrink`, and `versus`. Any release may change them. The `mines`
+  binary needs them too.
+
+Production users who want only `mines-core` can turn off the default
+features:
+
+```toml
+[dependencies.mines]
+git = "https://github.com/ben01189998819991197253/mines-rs.git"
+default-features = false
+```
 */
 
-pub mod board;
-pub mod tile;
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+extern crate mines_core;
+#[cfg(feature = "net")]
+extern crate mines_net;
+#[cfg(feature = "render")]
+extern crate mines_render;
+#[cfg(feature = "solver")]
+extern crate mines_solver;
 
-pub use board::*;
-pub use tile::*;
+pub use mines_core::*;
+#[cfg(feature = "net")]
+#[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+pub use mines_net::*;
+#[cfg(feature = "render")]
+#[cfg_attr(docsrs, doc(cfg(feature = "render")))]
+pub use mines_render::*;
+#[cfg(feature = "solver")]
+#[cfg_attr(docsrs, doc(cfg(feature = "solver")))]
+pub use mines_solver::*;